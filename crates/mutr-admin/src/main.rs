@@ -0,0 +1,468 @@
+//! `mutr-admin`: operator CLI for the MutantRolls CLR.
+//!
+//! Each subcommand builds one authority instruction with `mutr-clr-client` and sends it
+//! signed by `--keypair`, which must be the CLR authority. `--dry-run` simulates the
+//! transaction instead and prints its result, compute units and logs.
+//!
+//! ```text
+//! mutr-admin --url devnet update-config --max-payout-bps 200 --dry-run
+//! mutr-admin treasury propose --amount 1000000 --recipient <MUTR account>
+//! ```
+
+use std::error::Error;
+use std::sync::Arc;
+
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{read_keypair_file, Keypair};
+use anchor_client::Cluster;
+use clap::{Parser, Subcommand};
+use mutr_clr_client::mutr_clr::{
+    accounts, instruction, ConfigUpdate, FeeSplit, InitConfig, SpendProposal,
+};
+use mutr_clr_client::{clr_instruction, pda, ClrClient};
+
+#[derive(Parser)]
+#[command(name = "mutr-admin", about = "Operate the MutantRolls CLR")]
+struct Cli {
+    /// Cluster moniker or RPC URL
+    #[arg(long, short = 'u', global = true, default_value = "localnet")]
+    url: Cluster,
+
+    /// Authority keypair; also pays fees and rent
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+
+    /// Simulate the transaction instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the CLR's global state over existing mints and vault
+    Initialize {
+        #[arg(long)]
+        mutr_mint: Pubkey,
+        /// xMUTR mint, its mint authority already set to the state PDA
+        #[arg(long)]
+        xmutr_mint: Pubkey,
+        /// MUTR account owned by the state PDA
+        #[arg(long)]
+        clr_vault: Pubkey,
+        #[arg(long)]
+        stake_fee_bps: u16,
+        #[arg(long)]
+        unstake_fee_bps: u16,
+        #[arg(long)]
+        lower_threshold: u64,
+        #[arg(long)]
+        upper_threshold: u64,
+        #[arg(long, default_value_t = 0)]
+        exit_penalty_max_bps: u16,
+        #[arg(long, default_value_t = 0)]
+        exit_penalty_decay_secs: i64,
+    },
+    /// Change fee and threshold parameters; unset flags keep their value
+    UpdateConfig(ConfigArgs),
+    /// Register a game signer allowed to open and resolve bets
+    RegisterGame {
+        #[arg(long)]
+        game: Pubkey,
+        #[arg(long)]
+        max_multiplier_bps: u32,
+        #[arg(long)]
+        bet_ttl_slots: u64,
+    },
+    /// Stop a game from taking bets
+    Pause {
+        #[arg(long)]
+        game: Pubkey,
+    },
+    /// Let a paused game take bets again
+    Unpause {
+        #[arg(long)]
+        game: Pubkey,
+    },
+    /// Book house profit for stakers
+    RecordProfit {
+        #[arg(long)]
+        amount: u64,
+        /// Sign as a listed operator rather than the authority
+        #[arg(long)]
+        operator: bool,
+    },
+    /// Queue, cancel or execute timelocked treasury spends
+    #[command(subcommand)]
+    Treasury(TreasuryCommand),
+    /// Grow the global state, or another config account, to the current layout size
+    ExpandAccount {
+        /// Account to grow; the global state when unset
+        #[arg(long)]
+        account: Option<Pubkey>,
+    },
+    /// Name the guardian allowed to raise the pause flags
+    SetGuardian {
+        #[arg(long)]
+        guardian: Pubkey,
+    },
+    /// Set the CLR-wide pause flags, clearing the unset ones
+    SetPauseFlags {
+        /// Bits: 1 staking, 2 withdrawals, 4 betting; 7 is the circuit breaker
+        #[arg(long)]
+        flags: u8,
+    },
+    /// Raise pause flags, signing as the guardian
+    GuardianPause {
+        /// Bits: 1 staking, 2 withdrawals, 4 betting; 7 is the circuit breaker
+        #[arg(long)]
+        flags: u8,
+    },
+    /// Replace the operator keys allowed to book profit
+    SetOperators {
+        /// Comma-separated operator keys; empty revokes them all
+        #[arg(long, value_delimiter = ',')]
+        operators: Vec<Pubkey>,
+    },
+}
+
+#[derive(clap::Args)]
+struct ConfigArgs {
+    #[arg(long)]
+    stake_fee_bps: Option<u16>,
+    #[arg(long)]
+    unstake_fee_bps: Option<u16>,
+    #[arg(long)]
+    lower_threshold: Option<u64>,
+    #[arg(long)]
+    upper_threshold: Option<u64>,
+    #[arg(long)]
+    exit_penalty_max_bps: Option<u16>,
+    #[arg(long)]
+    exit_penalty_decay_secs: Option<i64>,
+    /// Fee split as CLR,BURN,TREASURY bps summing to 10000
+    #[arg(long, value_delimiter = ',', num_args = 3)]
+    fee_split: Option<Vec<u16>>,
+    #[arg(long)]
+    profit_epoch_secs: Option<i64>,
+    #[arg(long)]
+    drip_slots: Option<u64>,
+    #[arg(long)]
+    keeper_fee_bps: Option<u16>,
+    #[arg(long)]
+    max_payout_bps: Option<u16>,
+    /// Share of the CLR vault above which prizes are streamed; 0 for no streaming
+    #[arg(long)]
+    prize_vesting_threshold_bps: Option<u16>,
+    /// Share of a streamed prize paid at once
+    #[arg(long)]
+    prize_upfront_bps: Option<u16>,
+    /// Slots the rest of a streamed prize releases over
+    #[arg(long)]
+    prize_vesting_slots: Option<u32>,
+    #[arg(long)]
+    max_daily_wager: Option<u64>,
+    #[arg(long)]
+    min_stake: Option<u64>,
+    #[arg(long)]
+    min_unstake: Option<u64>,
+    /// Slots a position that joined just before a profit distribution waits to claim
+    #[arg(long)]
+    claim_lockup_slots: Option<u64>,
+    /// Profit epochs each rollover averages over; 0 or 1 for no smoothing
+    #[arg(long)]
+    smoothing_epochs: Option<u8>,
+}
+
+impl From<ConfigArgs> for ConfigUpdate {
+    fn from(args: ConfigArgs) -> Self {
+        ConfigUpdate {
+            stake_fee_bps: args.stake_fee_bps,
+            unstake_fee_bps: args.unstake_fee_bps,
+            lower_threshold: args.lower_threshold,
+            upper_threshold: args.upper_threshold,
+            exit_penalty_max_bps: args.exit_penalty_max_bps,
+            exit_penalty_decay_secs: args.exit_penalty_decay_secs,
+            fee_split: args.fee_split.map(|bps| FeeSplit {
+                clr_bps: bps[0],
+                burn_bps: bps[1],
+                treasury_bps: bps[2],
+            }),
+            profit_epoch_secs: args.profit_epoch_secs,
+            drip_slots: args.drip_slots,
+            keeper_fee_bps: args.keeper_fee_bps,
+            max_payout_bps: args.max_payout_bps,
+            prize_vesting_threshold_bps: args.prize_vesting_threshold_bps,
+            prize_upfront_bps: args.prize_upfront_bps,
+            prize_vesting_slots: args.prize_vesting_slots,
+            max_daily_wager: args.max_daily_wager,
+            min_stake: args.min_stake,
+            min_unstake: args.min_unstake,
+            claim_lockup_slots: args.claim_lockup_slots,
+            smoothing_epochs: args.smoothing_epochs,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum TreasuryCommand {
+    /// Queue a spend, executable after the treasury delay
+    Propose {
+        #[arg(long)]
+        amount: u64,
+        /// MUTR account receiving the spend
+        #[arg(long)]
+        recipient: Pubkey,
+    },
+    /// Drop a queued spend
+    Cancel {
+        #[arg(long)]
+        id: u64,
+    },
+    /// Pay out a queued spend whose delay has passed
+    Execute {
+        #[arg(long)]
+        id: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let keypair = match cli.keypair {
+        Some(path) => path,
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+    let payer = Arc::new(read_keypair_file(&keypair)?);
+    let client = ClrClient::new(cli.url, payer.clone(), CommitmentConfig::confirmed())?;
+
+    let ix = build(&client, cli.command).await?;
+    if cli.dry_run {
+        let result = client.simulate(vec![ix], &[]).await?;
+        match result.err {
+            Some(err) => println!("simulation failed: {err}"),
+            None => println!("simulation ok"),
+        }
+        if let Some(units) = result.units_consumed {
+            println!("compute units: {units}");
+        }
+        for log in result.logs.unwrap_or_default() {
+            println!("  {log}");
+        }
+    } else {
+        println!("{}", client.send(vec![ix], &[]).await?);
+    }
+    Ok(())
+}
+
+async fn build(
+    client: &ClrClient<Arc<Keypair>>,
+    command: Command,
+) -> Result<Instruction, Box<dyn Error>> {
+    let authority = client.payer();
+    let ix = match command {
+        Command::Initialize {
+            mutr_mint,
+            xmutr_mint,
+            clr_vault,
+            stake_fee_bps,
+            unstake_fee_bps,
+            lower_threshold,
+            upper_threshold,
+            exit_penalty_max_bps,
+            exit_penalty_decay_secs,
+        } => {
+            let token_program = client
+                .program()
+                .async_rpc()
+                .get_account(&mutr_mint)
+                .await?
+                .owner;
+            clr_instruction(
+                accounts::InitializeClr {
+                    state: pda::state(),
+                    mutr_mint,
+                    xmutr_mint,
+                    clr_vault,
+                    authority,
+                    system_program: anchor_client::solana_sdk::system_program::ID,
+                    token_program,
+                },
+                instruction::InitializeClr {
+                    config: InitConfig {
+                        mutr_mint,
+                        xmutr_mint,
+                        stake_fee_bps,
+                        unstake_fee_bps,
+                        lower_threshold,
+                        upper_threshold,
+                        exit_penalty_max_bps,
+                        exit_penalty_decay_secs,
+                    },
+                },
+            )
+        }
+        Command::UpdateConfig(args) => clr_instruction(
+            accounts::UpdateConfig {
+                state: pda::state(),
+                admin_log: pda::admin_log(),
+                authority,
+            },
+            instruction::UpdateConfig {
+                update: args.into(),
+            },
+        ),
+        Command::RegisterGame {
+            game,
+            max_multiplier_bps,
+            bet_ttl_slots,
+        } => clr_instruction(
+            accounts::RegisterGame {
+                state: pda::state(),
+                game_config: pda::game_config(&game),
+                game_ledger: pda::game_ledger(&game),
+                admin_log: pda::admin_log(),
+                authority,
+                system_program: anchor_client::solana_sdk::system_program::ID,
+            },
+            instruction::RegisterGame {
+                game,
+                max_multiplier_bps,
+                bet_ttl_slots,
+            },
+        ),
+        Command::Pause { game } => set_game_enabled(authority, &game, false),
+        Command::Unpause { game } => set_game_enabled(authority, &game, true),
+        Command::RecordProfit {
+            amount,
+            operator: true,
+        } => clr_instruction(
+            accounts::OperatorRecordProfit {
+                state: pda::state(),
+                stats: pda::stats(),
+                operators: pda::operators(),
+                operator: authority,
+            },
+            instruction::OperatorRecordProfit {
+                profit_amount: amount,
+            },
+        ),
+        Command::RecordProfit {
+            amount,
+            operator: false,
+        } => clr_instruction(
+            accounts::RecordProfit {
+                state: pda::state(),
+                stats: pda::stats(),
+                admin_log: pda::admin_log(),
+                authority,
+            },
+            instruction::RecordProfit {
+                profit_amount: amount,
+            },
+        ),
+        Command::Treasury(TreasuryCommand::Propose { amount, recipient }) => {
+            let id = client.global_state().await?.spend_proposal_count;
+            println!("spend proposal {id}");
+            clr_instruction(
+                accounts::ProposeSpend {
+                    state: pda::state(),
+                    spend_proposal: pda::spend_proposal(id),
+                    admin_log: pda::admin_log(),
+                    authority,
+                    payer: authority,
+                    system_program: anchor_client::solana_sdk::system_program::ID,
+                },
+                instruction::ProposeSpend { amount, recipient },
+            )
+        }
+        Command::Treasury(TreasuryCommand::Cancel { id }) => clr_instruction(
+            accounts::CancelSpend {
+                state: pda::state(),
+                spend_proposal: pda::spend_proposal(id),
+                admin_log: pda::admin_log(),
+                authority,
+            },
+            instruction::CancelSpend {},
+        ),
+        Command::Treasury(TreasuryCommand::Execute { id }) => {
+            let proposal: SpendProposal = client.account(&pda::spend_proposal(id)).await?;
+            let state = client.global_state().await?;
+            let clr = client.clr_accounts().await?;
+            clr_instruction(
+                accounts::ExecuteSpend {
+                    state: pda::state(),
+                    mutr_mint: clr.vaults.mutr_mint,
+                    spend_proposal: pda::spend_proposal(id),
+                    treasury_vault: clr.treasury_vault,
+                    recipient_mutr_account: proposal.recipient,
+                    authority: state.authority,
+                    token_program: clr.vaults.token_program,
+                },
+                instruction::ExecuteSpend {},
+            )
+        }
+        Command::ExpandAccount { account } => clr_instruction(
+            accounts::ExpandAccount {
+                state: pda::state(),
+                account: account.unwrap_or_else(pda::state),
+                admin_log: pda::admin_log(),
+                authority,
+                system_program: anchor_client::solana_sdk::system_program::ID,
+            },
+            instruction::ExpandAccount {},
+        ),
+        Command::SetGuardian { guardian } => clr_instruction(
+            accounts::SetGuardian {
+                state: pda::state(),
+                admin_log: pda::admin_log(),
+                authority,
+            },
+            instruction::SetGuardian { guardian },
+        ),
+        Command::SetPauseFlags { flags } => clr_instruction(
+            accounts::SetPauseFlags {
+                state: pda::state(),
+                admin_log: pda::admin_log(),
+                authority,
+            },
+            instruction::SetPauseFlags { flags },
+        ),
+        Command::GuardianPause { flags } => clr_instruction(
+            accounts::GuardianPause {
+                state: pda::state(),
+                admin_log: pda::admin_log(),
+                guardian: authority,
+            },
+            instruction::GuardianPause { flags },
+        ),
+        Command::SetOperators { operators } => clr_instruction(
+            accounts::SetOperators {
+                state: pda::state(),
+                operators: pda::operators(),
+                admin_log: pda::admin_log(),
+                authority,
+                system_program: anchor_client::solana_sdk::system_program::ID,
+            },
+            instruction::SetOperators { operators },
+        ),
+    };
+    Ok(ix)
+}
+
+/// Pausing a game disables its new bets; `set-pause-flags` halts the whole CLR.
+fn set_game_enabled(authority: Pubkey, game: &Pubkey, enabled: bool) -> Instruction {
+    clr_instruction(
+        accounts::SetGameEnabled {
+            state: pda::state(),
+            game_config: pda::game_config(game),
+            admin_log: pda::admin_log(),
+            authority,
+        },
+        instruction::SetGameEnabled { enabled },
+    )
+}
@@ -0,0 +1,32 @@
+//! Decoding of raw CLR account data.
+//!
+//! Borsh accounts go through their `AccountDeserialize`. Zero-copy accounts are copied
+//! out with `bytemuck::pod_read_unaligned` instead, since account data fetched over RPC
+//! carries no alignment guarantee and anchor's zero-copy decoder casts in place.
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::ZeroCopy;
+
+/// Decode a borsh account (e.g. `UserState`, `UserStats`), checking its discriminator.
+pub fn account<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Decode a zero-copy account (e.g. `GlobalState`, `GameConfig`, `Bet`), checking its
+/// discriminator and length.
+pub fn zero_copy<T: ZeroCopy>(data: &[u8]) -> Result<T> {
+    let disc = T::discriminator();
+    if data.len() < disc.len() {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if data[..disc.len()] != disc {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    let body = &data[disc.len()..];
+    let size = std::mem::size_of::<T>();
+    if body.len() < size {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(bytemuck::pod_read_unaligned(&body[..size]))
+}
@@ -0,0 +1,343 @@
+//! Builders for the staking instructions users send directly; the game-facing builders
+//! live in `mutr_clr_cpi` and are re-exported at the crate root.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+use mutr_clr::LeaderboardKind;
+
+use crate::{pda, ClrAccounts};
+
+/// Build a CLR instruction from its anchor accounts and args, for instructions without
+/// a builder here.
+pub fn clr_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: mutr_clr::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Stake `amount` MUTR into position `position_index`, opening it at `lock_tier` if new.
+#[derive(Clone, Debug, Default)]
+pub struct Stake {
+    pub user: Pubkey,
+    pub user_mutr_account: Pubkey,
+    pub user_xmutr_account: Pubkey,
+    pub amount: u64,
+    pub position_index: u32,
+    pub lock_tier: u8,
+    /// Offer the position to the stakers leaderboard
+    pub leaderboard: bool,
+    /// Record the new share price in the oracle
+    pub share_price_oracle: bool,
+    /// Pass the user's stats, spending their fee credit
+    pub user_stats: bool,
+    /// With `user_stats`: accrue staking points
+    pub loyalty: bool,
+    /// With `user_stats`: discount the fee by the user's VIP tier
+    pub vip: bool,
+    /// With `user_stats`: code of the referrer earning its cut
+    pub referrer_code: Option<String>,
+    /// Last slot the stake may land in
+    pub deadline_slot: Option<u64>,
+    /// Move the new xMUTR into the dividend pool; its vault must exist
+    pub join_dividends: bool,
+}
+
+impl Stake {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        let referrer = self.referrer_code.as_deref().map(pda::referrer);
+        clr_instruction(
+            mutr_clr::accounts::Stake {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: clr.vaults.mutr_mint,
+                xmutr_mint: clr.xmutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                treasury_vault: clr.treasury_vault,
+                user_mutr_account: self.user_mutr_account,
+                user_xmutr_account: self.user_xmutr_account,
+                user_state: pda::user_state(&self.user, self.position_index),
+                user_positions: pda::user_positions(&self.user),
+                leaderboard: self
+                    .leaderboard
+                    .then(|| pda::leaderboard(LeaderboardKind::Stakers)),
+                share_price_oracle: self.share_price_oracle.then(pda::share_price_oracle),
+                loyalty_config: self.loyalty.then(pda::loyalty_config),
+                user_stats: self.user_stats.then(|| pda::user_stats(&self.user)),
+                vip_config: self.vip.then(pda::vip_config),
+                referral_config: referrer.map(|_| pda::referral_config()),
+                referrer,
+                referral_vault: referrer.map(|_| pda::referral_vault()),
+                dividend_vault: self.join_dividends.then(pda::dividend_vault),
+                user: self.user,
+                token_program: clr.vaults.token_program,
+                system_program: anchor_lang::system_program::ID,
+            },
+            mutr_clr::instruction::Stake {
+                amount: self.amount,
+                position_index: self.position_index,
+                lock_tier: self.lock_tier,
+                deadline_slot: self.deadline_slot,
+                join_dividends: self.join_dividends,
+            },
+        )
+    }
+}
+
+/// Burn `shares` xMUTR of position `position_index` for MUTR sent to a destination.
+#[derive(Clone, Debug, Default)]
+pub struct Unstake {
+    pub user: Pubkey,
+    pub user_xmutr_account: Pubkey,
+    /// Any account of the MUTR mint
+    pub destination_mutr_account: Pubkey,
+    pub shares: u64,
+    pub position_index: u32,
+    /// Record the new share price in the oracle
+    pub share_price_oracle: bool,
+    /// Pass the user's stats, spending their fee credit
+    pub user_stats: bool,
+    /// With `user_stats`: accrue staking points
+    pub loyalty: bool,
+    /// With `user_stats`: discount the fee by the user's VIP tier
+    pub vip: bool,
+    /// Last slot the unstake may land in
+    pub deadline_slot: Option<u64>,
+}
+
+impl Unstake {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::Unstake {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: clr.vaults.mutr_mint,
+                xmutr_mint: clr.xmutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                treasury_vault: clr.treasury_vault,
+                destination_mutr_account: self.destination_mutr_account,
+                user_xmutr_account: self.user_xmutr_account,
+                user_state: pda::user_state(&self.user, self.position_index),
+                loyalty_config: self.loyalty.then(pda::loyalty_config),
+                user_stats: self.user_stats.then(|| pda::user_stats(&self.user)),
+                vip_config: self.vip.then(pda::vip_config),
+                share_price_oracle: self.share_price_oracle.then(pda::share_price_oracle),
+                user: self.user,
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::Unstake {
+                shares: self.shares,
+                _position_index: self.position_index,
+                deadline_slot: self.deadline_slot,
+            },
+        )
+    }
+}
+
+/// Claim the dividends of position `position_index` to a destination.
+#[derive(Clone, Debug, Default)]
+pub struct ClaimRewards {
+    pub user: Pubkey,
+    /// Any account of the MUTR mint; one of the owner's when a delegate claims
+    pub destination_mutr_account: Pubkey,
+    pub position_index: u32,
+    /// The position's delegate, signing in the owner's place
+    pub delegate: Option<Pubkey>,
+}
+
+impl ClaimRewards {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::ClaimRewards {
+                state: pda::state(),
+                mutr_mint: clr.vaults.mutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                destination_mutr_account: self.destination_mutr_account,
+                user_state: pda::user_state(&self.user, self.position_index),
+                user: self.user,
+                caller: self.delegate.unwrap_or(self.user),
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::ClaimRewards {
+                _position_index: self.position_index,
+            },
+        )
+    }
+}
+
+/// Restake the pending dividends of position `position_index` as xMUTR.
+#[derive(Clone, Debug, Default)]
+pub struct CompoundRewards {
+    pub user: Pubkey,
+    pub user_xmutr_account: Pubkey,
+    pub position_index: u32,
+    /// The position's delegate, signing in the owner's place
+    pub delegate: Option<Pubkey>,
+}
+
+impl CompoundRewards {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::CompoundRewards {
+                state: pda::state(),
+                stats: pda::stats(),
+                xmutr_mint: clr.xmutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                user_xmutr_account: self.user_xmutr_account,
+                user_state: pda::user_state(&self.user, self.position_index),
+                user: self.user,
+                caller: self.delegate.unwrap_or(self.user),
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::CompoundRewards {
+                _position_index: self.position_index,
+            },
+        )
+    }
+}
+
+/// Keeper: close an ended profit epoch, earning the keeper fee.
+#[derive(Clone, Debug, Default)]
+pub struct EpochRollover {
+    pub keeper: Pubkey,
+    pub keeper_mutr_account: Pubkey,
+}
+
+impl EpochRollover {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::EpochRollover {
+                state: pda::state(),
+                mutr_mint: clr.vaults.mutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                keeper_mutr_account: self.keeper_mutr_account,
+                keeper: self.keeper,
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::EpochRollover {},
+        )
+    }
+}
+
+/// Keeper: release vested drip profit, earning the keeper fee.
+#[derive(Clone, Debug, Default)]
+pub struct Drip {
+    pub keeper: Pubkey,
+    pub keeper_mutr_account: Pubkey,
+}
+
+impl Drip {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::Drip {
+                state: pda::state(),
+                mutr_mint: clr.vaults.mutr_mint,
+                clr_vault: clr.vaults.clr_vault,
+                keeper_mutr_account: self.keeper_mutr_account,
+                keeper: self.keeper,
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::Drip {},
+        )
+    }
+}
+
+/// Keeper: release accrued emissions to the dividend pool, earning the keeper fee.
+#[derive(Clone, Debug, Default)]
+pub struct HarvestEmissions {
+    pub keeper: Pubkey,
+    pub keeper_mutr_account: Pubkey,
+    /// Record the new share price in the oracle
+    pub share_price_oracle: bool,
+}
+
+impl HarvestEmissions {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::HarvestEmissions {
+                state: pda::state(),
+                mutr_mint: clr.vaults.mutr_mint,
+                xmutr_mint: clr.xmutr_mint,
+                emission_schedule: pda::emission_schedule(),
+                emission_vault: clr.emission_vault,
+                clr_vault: clr.vaults.clr_vault,
+                keeper_mutr_account: self.keeper_mutr_account,
+                share_price_oracle: self.share_price_oracle.then(pda::share_price_oracle),
+                keeper: self.keeper,
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::HarvestEmissions {},
+        )
+    }
+}
+
+/// Keeper: fold rounding dust in the bonus vault into dividends.
+#[derive(Clone, Debug, Default)]
+pub struct SweepDust {
+    /// `GlobalState::bonus_vault`
+    pub bonus_vault: Pubkey,
+}
+
+impl SweepDust {
+    pub fn instruction(&self) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::SweepDust {
+                state: pda::state(),
+                bonus_vault: self.bonus_vault,
+            },
+            mutr_clr::instruction::SweepDust {},
+        )
+    }
+}
+
+/// Settle a randomness-backed bet from its revealed request. Anyone can send it.
+#[derive(Clone, Debug, Default)]
+pub struct ResolveBetWithRandomness {
+    pub bet: Pubkey,
+    pub game: Pubkey,
+    pub player: Pubkey,
+    /// Request committed at bet time
+    pub randomness_account: Pubkey,
+    pub player_mutr_account: Pubkey,
+    /// Offer a win to the winners leaderboard
+    pub leaderboard: bool,
+    /// Credit the wager to the player's stats
+    pub user_stats: bool,
+    /// With `user_stats`: earn loyalty points
+    pub loyalty: bool,
+    /// With `user_stats`: accrue rakeback
+    pub rakeback: bool,
+}
+
+impl ResolveBetWithRandomness {
+    pub fn instruction(&self, clr: &ClrAccounts) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::ResolveBetWithRandomness {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: clr.vaults.mutr_mint,
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                bet: self.bet,
+                randomness_account: self.randomness_account,
+                clr_vault: clr.vaults.clr_vault,
+                player_mutr_account: self.player_mutr_account,
+                usd_pricing: None,
+                price_update: None,
+                leaderboard: self
+                    .leaderboard
+                    .then(|| pda::leaderboard(LeaderboardKind::Winners)),
+                season: None,
+                season_player: None,
+                season_leaderboard: None,
+                loyalty_config: self.loyalty.then(pda::loyalty_config),
+                user_stats: self.user_stats.then(|| pda::user_stats(&self.player)),
+                rakeback_config: self.rakeback.then(pda::rakeback_config),
+                token_program: clr.vaults.token_program,
+            },
+            mutr_clr::instruction::ResolveBetWithRandomness {},
+        )
+    }
+}
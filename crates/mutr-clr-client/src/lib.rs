@@ -0,0 +1,212 @@
+//! Rust client SDK for the MutantRolls CLR.
+//!
+//! Off-chain callers (the admin CLI, keepers, bots and tests) build instructions with
+//! the typed builders here and in `mutr_clr_cpi`, derive the CLR's PDAs with `pda`,
+//! decode fetched accounts with `decode`, and talk to a cluster through `ClrClient`, a
+//! thin async wrapper over an `anchor_client::Program`.
+//!
+//! ```ignore
+//! let client = ClrClient::new(Cluster::Devnet, Rc::new(payer), CommitmentConfig::confirmed())?;
+//! let clr = client.clr_accounts().await?;
+//! let claim = ClaimRewards { user, destination_mutr_account, ..Default::default() };
+//! let ix = claim.instruction(&clr);
+//! client.send(vec![ix], &[]).await?;
+//! ```
+
+use std::mem::offset_of;
+use std::ops::Deref;
+
+use anchor_client::solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use anchor_client::solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use anchor_client::solana_client::rpc_response::RpcSimulateTransactionResult;
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::signature::{Signature, Signer};
+use anchor_client::{Client, ClientError, Cluster, Program, RequestBuilder};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, Discriminator, ZeroCopy};
+use mutr_clr::{Bet, BetStatus, GameConfig, GlobalState, Stats, UserState, UserStats};
+use solana_account_decoder::UiAccountEncoding;
+
+pub mod decode;
+pub mod instructions;
+pub mod pda;
+
+pub use anchor_client;
+pub use instructions::*;
+pub use mutr_clr;
+pub use mutr_clr_cpi::{
+    ClrInstruction, ClrVaults, ContributeJackpot, PlaceBet, ResolveBet, SendPrize, CLR_PROGRAM_ID,
+};
+
+/// CLR-wide accounts recorded in `GlobalState`, as the staking builders need them.
+#[derive(Clone, Copy, Debug)]
+pub struct ClrAccounts {
+    pub vaults: ClrVaults,
+    pub xmutr_mint: Pubkey,
+    pub treasury_vault: Pubkey,
+    /// Default until emissions are initialized
+    pub emission_vault: Pubkey,
+}
+
+pub struct ClrClient<C> {
+    program: Program<C>,
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> ClrClient<C> {
+    /// Connect to the CLR on `cluster`, paying fees with `payer`.
+    #[allow(clippy::result_large_err)] // anchor_client's error type
+    pub fn new(
+        cluster: Cluster,
+        payer: C,
+        commitment: CommitmentConfig,
+    ) -> Result<Self, ClientError> {
+        let client = Client::new_with_options(cluster, payer, commitment);
+        Ok(Self {
+            program: client.program(mutr_clr::ID)?,
+        })
+    }
+
+    /// The underlying program handle, for requests this wrapper doesn't cover.
+    pub fn program(&self) -> &Program<C> {
+        &self.program
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.program.payer()
+    }
+
+    async fn data(&self, address: &Pubkey) -> Result<Vec<u8>, ClientError> {
+        Ok(self.program.async_rpc().get_account_data(address).await?)
+    }
+
+    /// Fetch and decode a borsh account.
+    pub async fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T, ClientError> {
+        Ok(decode::account(&self.data(address).await?)?)
+    }
+
+    /// Fetch and decode a zero-copy account.
+    pub async fn zero_copy<T: ZeroCopy>(&self, address: &Pubkey) -> Result<T, ClientError> {
+        Ok(decode::zero_copy(&self.data(address).await?)?)
+    }
+
+    pub async fn global_state(&self) -> Result<GlobalState, ClientError> {
+        self.zero_copy(&pda::state()).await
+    }
+
+    pub async fn stats(&self) -> Result<Stats, ClientError> {
+        self.zero_copy(&pda::stats()).await
+    }
+
+    pub async fn game_config(&self, game: &Pubkey) -> Result<GameConfig, ClientError> {
+        self.zero_copy(&pda::game_config(game)).await
+    }
+
+    pub async fn bet(&self, player: &Pubkey, nonce: u64) -> Result<Bet, ClientError> {
+        self.zero_copy(&pda::bet(player, nonce)).await
+    }
+
+    /// Every bet still awaiting its result, expired or not.
+    pub async fn pending_bets(&self) -> Result<Vec<(Pubkey, Bet)>, ClientError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize((8 + Bet::LEN) as u64),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, Bet::DISCRIMINATOR.to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    8 + offset_of!(Bet, status),
+                    vec![BetStatus::Pending as u8],
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = self
+            .program
+            .async_rpc()
+            .get_program_accounts_with_config(&mutr_clr::ID, config)
+            .await?;
+        let mut bets = Vec::with_capacity(accounts.len());
+        for (address, account) in accounts {
+            bets.push((address, decode::zero_copy(&account.data)?));
+        }
+        Ok(bets)
+    }
+
+    pub async fn user_state(
+        &self,
+        owner: &Pubkey,
+        position_index: u32,
+    ) -> Result<UserState, ClientError> {
+        self.account(&pda::user_state(owner, position_index)).await
+    }
+
+    pub async fn user_stats(&self, owner: &Pubkey) -> Result<UserStats, ClientError> {
+        self.account(&pda::user_stats(owner)).await
+    }
+
+    /// Read the CLR-wide accounts from `GlobalState`, taking the token program from the
+    /// owner of the MUTR mint.
+    pub async fn clr_accounts(&self) -> Result<ClrAccounts, ClientError> {
+        let state = self.global_state().await?;
+        let mint = self
+            .program
+            .async_rpc()
+            .get_account(&state.mutr_mint)
+            .await?;
+        Ok(ClrAccounts {
+            vaults: ClrVaults {
+                mutr_mint: state.mutr_mint,
+                clr_vault: state.clr_vault,
+                token_program: mint.owner,
+            },
+            xmutr_mint: state.xmutr_mint,
+            treasury_vault: state.treasury_vault,
+            emission_vault: state.emission_vault,
+        })
+    }
+
+    fn request<'a>(
+        &'a self,
+        instructions: Vec<Instruction>,
+        signers: &[&'a dyn Signer],
+    ) -> RequestBuilder<'a, C> {
+        let mut request = self.program.request();
+        for ix in instructions {
+            request = request.instruction(ix);
+        }
+        for signer in signers {
+            request = request.signer(*signer);
+        }
+        request
+    }
+
+    /// Send `instructions` in one transaction signed by the payer and `signers`.
+    pub async fn send(
+        &self,
+        instructions: Vec<Instruction>,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature, ClientError> {
+        self.request(instructions, signers).send().await
+    }
+
+    /// Simulate the transaction `send` would submit, for dry runs.
+    pub async fn simulate(
+        &self,
+        instructions: Vec<Instruction>,
+        signers: &[&dyn Signer],
+    ) -> Result<RpcSimulateTransactionResult, ClientError> {
+        let tx = self
+            .request(instructions, signers)
+            .signed_transaction()
+            .await?;
+        Ok(self
+            .program
+            .async_rpc()
+            .simulate_transaction(&tx)
+            .await?
+            .value)
+    }
+}
@@ -0,0 +1,103 @@
+//! Addresses of the CLR's program-derived accounts, extending `mutr_clr_cpi::pda` with
+//! the accounts only off-chain callers touch.
+
+use anchor_lang::prelude::Pubkey;
+use mutr_clr::LeaderboardKind;
+
+pub use mutr_clr_cpi::pda::*;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &mutr_clr::ID).0
+}
+
+/// Index of `owner`'s open staking positions
+pub fn user_positions(owner: &Pubkey) -> Pubkey {
+    find(&[b"user_positions", owner.as_ref()])
+}
+
+pub fn leaderboard(kind: LeaderboardKind) -> Pubkey {
+    find(&[b"leaderboard", &[kind as u8]])
+}
+
+pub fn loyalty_config() -> Pubkey {
+    find(&[b"loyalty_config"])
+}
+
+pub fn vip_config() -> Pubkey {
+    find(&[b"vip_config"])
+}
+
+pub fn referral_config() -> Pubkey {
+    find(&[b"referral_config"])
+}
+
+pub fn referral_vault() -> Pubkey {
+    find(&[b"referral_vault"])
+}
+
+/// xMUTR escrow of the dividend pool
+pub fn dividend_vault() -> Pubkey {
+    find(&[b"dividend_vault"])
+}
+
+/// xMUTR escrow of positions awaiting their new owner
+pub fn transfer_escrow() -> Pubkey {
+    find(&[b"transfer_escrow"])
+}
+
+/// `Referrer` registered under `code`
+pub fn referrer(code: &str) -> Pubkey {
+    find(&[b"referrer", code.as_bytes()])
+}
+
+/// Queued treasury spend `id`
+pub fn spend_proposal(id: u64) -> Pubkey {
+    find(&[b"spend", &id.to_le_bytes()])
+}
+
+pub fn emission_schedule() -> Pubkey {
+    find(&[b"emission_schedule"])
+}
+
+pub fn rakeback_config() -> Pubkey {
+    find(&[b"rakeback_config"])
+}
+
+/// Protocol treasury vault
+pub fn treasury_vault() -> Pubkey {
+    find(&[b"treasury_vault"])
+}
+
+pub fn share_price_oracle() -> Pubkey {
+    find(&[b"share_price_oracle"])
+}
+
+/// Ring buffer of admin actions
+pub fn admin_log() -> Pubkey {
+    find(&[b"admin_log"])
+}
+
+/// Keys allowed to book profit besides the authority
+pub fn operators() -> Pubkey {
+    find(&[b"operators"])
+}
+
+/// Multisig `id` of `creator`
+pub fn multisig(creator: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"multisig", creator.as_ref(), &id.to_le_bytes()])
+}
+
+/// Signer PDA of `multisig`, the key handed the authority role
+pub fn multisig_signer(multisig: &Pubkey) -> Pubkey {
+    find(&[b"multisig_signer", multisig.as_ref()])
+}
+
+/// Transaction `id` proposed to `multisig`
+pub fn multisig_transaction(multisig: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"multisig_tx", multisig.as_ref(), &id.to_le_bytes()])
+}
+
+/// Holder snapshot `id` of `creator`
+pub fn snapshot(creator: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"snapshot", creator.as_ref(), &id.to_le_bytes()])
+}
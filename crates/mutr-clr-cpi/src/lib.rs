@@ -0,0 +1,388 @@
+//! Typed instruction builders for integrating a game program with the MutantRolls CLR.
+//!
+//! Each builder takes the accounts a caller actually chooses, derives the CLR's PDAs
+//! (see `pda`) and produces the `Instruction` with its account metas in the order the
+//! CLR expects. Optional CLR accounts are `None` unless set. With the `cpi` feature,
+//! `ClrInstruction::invoke_signed` sends the instruction from an on-chain program,
+//! signing as the game's PDA.
+//!
+//! ```ignore
+//! let vaults = ClrVaults { mutr_mint, clr_vault, token_program: spl_token::ID };
+//! SendPrize { game, winner_mutr_account, amount }
+//!     .invoke_signed(&vaults, &account_infos, &[game_authority_seeds])?;
+//! ```
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+
+pub mod pda;
+
+pub use mutr_clr;
+pub use mutr_clr::ID as CLR_PROGRAM_ID;
+
+/// MUTR accounts recorded in the CLR's `GlobalState`, shared by every builder.
+#[derive(Clone, Copy, Debug)]
+pub struct ClrVaults {
+    pub mutr_mint: Pubkey,
+    pub clr_vault: Pubkey,
+    /// Token program owning the MUTR mint
+    pub token_program: Pubkey,
+}
+
+pub trait ClrInstruction {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction;
+
+    /// CPI into the CLR. `account_infos` must hold every account of the instruction;
+    /// `signer_seeds` sign for the game (or player) PDA.
+    #[cfg(feature = "cpi")]
+    fn invoke_signed(
+        &self,
+        vaults: &ClrVaults,
+        account_infos: &[AccountInfo],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        anchor_lang::solana_program::program::invoke_signed(
+            &self.instruction(vaults),
+            account_infos,
+            signer_seeds,
+        )
+        .map_err(Into::into)
+    }
+}
+
+fn clr_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: mutr_clr::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Game: pay `amount` MUTR from the CLR vault to a winner.
+#[derive(Clone, Debug, Default)]
+pub struct SendPrize {
+    /// Registered game signer
+    pub game: Pubkey,
+    pub winner_mutr_account: Pubkey,
+    pub amount: u64,
+}
+
+impl ClrInstruction for SendPrize {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::SendPrize {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: vaults.mutr_mint,
+                clr_vault: vaults.clr_vault,
+                winner_mutr_account: self.winner_mutr_account,
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                game: self.game,
+                token_program: vaults.token_program,
+            },
+            mutr_clr::instruction::SendPrize {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+/// Game: pay a prize over the vesting threshold, part now and the rest streamed to the
+/// winner's `PrizeVesting` account.
+#[derive(Clone, Debug, Default)]
+pub struct SendVestedPrize {
+    /// Registered game signer
+    pub game: Pubkey,
+    pub winner: Pubkey,
+    /// The winner's MUTR account, paid the part released at once
+    pub winner_mutr_account: Pubkey,
+    /// Funds the vesting account's rent when the winner has none yet
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+impl ClrInstruction for SendVestedPrize {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::SendVestedPrize {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: vaults.mutr_mint,
+                clr_vault: vaults.clr_vault,
+                winner_mutr_account: self.winner_mutr_account,
+                prize_vesting: pda::prize_vesting(&self.winner),
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                game: self.game,
+                payer: self.payer,
+                token_program: vaults.token_program,
+                system_program: anchor_lang::system_program::ID,
+            },
+            mutr_clr::instruction::SendVestedPrize {
+                winner: self.winner,
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+/// Game: pay `amount` MUTR from the CLR vault to a winner, through `send_vested_prize`
+/// when the prize is over the CLR's vesting threshold and `send_prize` otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct PayPrize {
+    /// Registered game signer
+    pub game: Pubkey,
+    pub winner: Pubkey,
+    pub winner_mutr_account: Pubkey,
+    /// Funds the winner's `PrizeVesting` when a streamed prize opens it
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+impl PayPrize {
+    /// `send_vested_prize` when `vested`, `send_prize` otherwise.
+    pub fn instruction(&self, vaults: &ClrVaults, vested: bool) -> Instruction {
+        if vested {
+            SendVestedPrize {
+                game: self.game,
+                winner: self.winner,
+                winner_mutr_account: self.winner_mutr_account,
+                payer: self.payer,
+                amount: self.amount,
+            }
+            .instruction(vaults)
+        } else {
+            SendPrize {
+                game: self.game,
+                winner_mutr_account: self.winner_mutr_account,
+                amount: self.amount,
+            }
+            .instruction(vaults)
+        }
+    }
+
+    /// CPI into the CLR, streaming the prize when it is over the vesting threshold for
+    /// `clr_state` and a CLR vault holding `clr_vault_balance`. `account_infos` must
+    /// hold the accounts of both instructions.
+    #[cfg(feature = "cpi")]
+    pub fn invoke_signed(
+        &self,
+        vaults: &ClrVaults,
+        clr_state: &AccountLoader<mutr_clr::GlobalState>,
+        clr_vault_balance: u64,
+        account_infos: &[AccountInfo],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let vested = mutr_clr::prize_vesting::needs_vesting(
+            &*clr_state.load()?,
+            clr_vault_balance,
+            self.amount,
+        );
+        anchor_lang::solana_program::program::invoke_signed(
+            &self.instruction(vaults, vested),
+            account_infos,
+            signer_seeds,
+        )
+        .map_err(Into::into)
+    }
+}
+
+/// Escrow a wager in the CLR vault and open a `Bet` for a registered game.
+#[derive(Clone, Debug, Default)]
+pub struct PlaceBet {
+    /// Registered game the bet is for
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub player_mutr_account: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub multiplier_bps: u32,
+    /// Fresh request, for games with a randomness provider
+    pub randomness_account: Option<Pubkey>,
+    /// Jackpot vault; required once the jackpot exists
+    pub jackpot_vault: Option<Pubkey>,
+    /// Required by the CLR daily wager and rate limits when they are on
+    pub user_stats: Option<Pubkey>,
+    /// Key signing in the player's place under a CLR session
+    pub session_key: Option<Pubkey>,
+    /// Last slot the bet may land in
+    pub deadline_slot: Option<u64>,
+}
+
+impl ClrInstruction for PlaceBet {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::PlaceBet {
+                state: pda::state(),
+                mutr_mint: vaults.mutr_mint,
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                bet: pda::bet(&self.player, self.nonce),
+                clr_vault: vaults.clr_vault,
+                player_mutr_account: self.player_mutr_account,
+                randomness_account: self.randomness_account,
+                jackpot: self.jackpot_vault.map(|_| pda::jackpot()),
+                jackpot_vault: self.jackpot_vault,
+                usd_pricing: None,
+                price_update: None,
+                user_stats: self.user_stats,
+                referral_config: None,
+                referrer: None,
+                referral_vault: None,
+                player_state: pda::user_state(&self.player, 0),
+                session: self
+                    .session_key
+                    .map(|session_key| pda::session(&self.player, &session_key)),
+                player: self.player,
+                payer: self.session_key.unwrap_or(self.player),
+                token_program: vaults.token_program,
+                system_program: anchor_lang::system_program::ID,
+            },
+            mutr_clr::instruction::PlaceBet {
+                nonce: self.nonce,
+                amount: self.amount,
+                multiplier_bps: self.multiplier_bps,
+                deadline_slot: self.deadline_slot,
+            },
+        )
+    }
+}
+
+/// Game: settle a pending bet, paying `amount * multiplier` on a win.
+#[derive(Clone, Debug, Default)]
+pub struct ResolveBet {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub nonce: u64,
+    pub player_mutr_account: Pubkey,
+    pub won: bool,
+    /// The player's stats; credits the wager toward loyalty points and VIP tiers
+    pub user_stats: Option<Pubkey>,
+}
+
+impl ClrInstruction for ResolveBet {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::ResolveBet {
+                state: pda::state(),
+                stats: pda::stats(),
+                mutr_mint: vaults.mutr_mint,
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                bet: pda::bet(&self.player, self.nonce),
+                clr_vault: vaults.clr_vault,
+                player_mutr_account: self.player_mutr_account,
+                usd_pricing: None,
+                price_update: None,
+                leaderboard: None,
+                season: None,
+                season_player: None,
+                season_leaderboard: None,
+                loyalty_config: None,
+                user_stats: self.user_stats,
+                rakeback_config: None,
+                game: self.game,
+                token_program: vaults.token_program,
+            },
+            mutr_clr::instruction::ResolveBet { won: self.won },
+        )
+    }
+}
+
+/// Game: run a wager the game escrowed itself through the CLR's player checks.
+#[derive(Clone, Debug, Default)]
+pub struct RecordGameWager {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    /// Required by the CLR daily wager and rate limits when they are on
+    pub user_stats: Option<Pubkey>,
+    pub amount: u64,
+}
+
+impl ClrInstruction for RecordGameWager {
+    fn instruction(&self, _vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::RecordGameWager {
+                state: pda::state(),
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                player_state: pda::user_state(&self.player, 0),
+                user_stats: self.user_stats,
+                player: self.player,
+                game: self.game,
+            },
+            mutr_clr::instruction::RecordGameWager {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+/// Game: book what the game paid winners out of its own escrow.
+#[derive(Clone, Debug, Default)]
+pub struct RecordGamePayout {
+    pub game: Pubkey,
+    pub amount: u64,
+}
+
+impl ClrInstruction for RecordGamePayout {
+    fn instruction(&self, _vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::RecordGamePayout {
+                game_config: pda::game_config(&self.game),
+                game_ledger: pda::game_ledger(&self.game),
+                game: self.game,
+            },
+            mutr_clr::instruction::RecordGamePayout {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+/// Game: move the jackpot's share of a wager taken into the CLR vault.
+#[derive(Clone, Debug, Default)]
+pub struct ContributeJackpot {
+    pub game: Pubkey,
+    pub jackpot_vault: Pubkey,
+    pub wager: u64,
+}
+
+impl ClrInstruction for ContributeJackpot {
+    fn instruction(&self, vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::ContributeJackpot {
+                state: pda::state(),
+                mutr_mint: vaults.mutr_mint,
+                jackpot: pda::jackpot(),
+                clr_vault: vaults.clr_vault,
+                jackpot_vault: self.jackpot_vault,
+                game_config: pda::game_config(&self.game),
+                game: self.game,
+                token_program: vaults.token_program,
+            },
+            mutr_clr::instruction::ContributeJackpot { wager: self.wager },
+        )
+    }
+}
+
+/// Game: take the next event sequence number, returned as the instruction's return data.
+#[derive(Clone, Debug, Default)]
+pub struct NextEventNonce {
+    pub game: Pubkey,
+}
+
+impl ClrInstruction for NextEventNonce {
+    fn instruction(&self, _vaults: &ClrVaults) -> Instruction {
+        clr_instruction(
+            mutr_clr::accounts::NextEventNonce {
+                state: pda::state(),
+                game_config: pda::game_config(&self.game),
+                game: self.game,
+            },
+            mutr_clr::instruction::NextEventNonce {},
+        )
+    }
+}
@@ -0,0 +1,53 @@
+//! Addresses of the CLR's program-derived accounts.
+
+use anchor_lang::prelude::Pubkey;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &mutr_clr::ID).0
+}
+
+/// `GlobalState`; also the authority of the CLR vaults
+pub fn state() -> Pubkey {
+    find(&[b"state"])
+}
+
+pub fn stats() -> Pubkey {
+    find(&[b"stats"])
+}
+
+/// `GameConfig` of a registered game signer
+pub fn game_config(game: &Pubkey) -> Pubkey {
+    find(&[b"game", game.as_ref()])
+}
+
+/// `GameLedger` of a registered game signer
+pub fn game_ledger(game: &Pubkey) -> Pubkey {
+    find(&[b"game_ledger", game.as_ref()])
+}
+
+pub fn bet(player: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"bet", player.as_ref(), &nonce.to_le_bytes()])
+}
+
+pub fn jackpot() -> Pubkey {
+    find(&[b"jackpot"])
+}
+
+/// `PrizeVesting` streaming a large prize to `winner`
+pub fn prize_vesting(winner: &Pubkey) -> Pubkey {
+    find(&[b"prize_vesting", winner.as_ref()])
+}
+
+pub fn user_stats(owner: &Pubkey) -> Pubkey {
+    find(&[b"user_stats", owner.as_ref()])
+}
+
+/// Staking position `position_index` of `owner`; position 0 also holds their
+/// responsible-gaming limits
+pub fn user_state(owner: &Pubkey, position_index: u32) -> Pubkey {
+    find(&[b"user_state", owner.as_ref(), &position_index.to_le_bytes()])
+}
+
+pub fn session(owner: &Pubkey, session_key: &Pubkey) -> Pubkey {
+    find(&[b"session", owner.as_ref(), session_key.as_ref()])
+}
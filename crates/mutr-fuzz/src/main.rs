@@ -0,0 +1,126 @@
+//! `mutr-fuzz`: property-based invariant fuzzing of the CLR's staking accounting.
+//!
+//! Generates random CLR parameters and sequences of stake, unstake, join, leave,
+//! profit, prize, claim, drip and wait operations, runs them through the host-side
+//! model in `model` and checks after every step that
+//!
+//! - the xMUTR held by positions adds up to the supply and covers each position's shares;
+//! - `total_dividend_shares` equals the summed dividend weights of all positions;
+//! - the vault keeps covering every position's pending rewards plus the undripped
+//!   profit, unless a prize cuts into them;
+//! - vault per xMUTR never falls except when prizes or rewards are paid out.
+//!
+//! A violation is shrunk to a minimal sequence, printed, and exits with status 1.
+
+mod model;
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use mutr_clr::FeeSplit;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestError, TestRunner};
+
+use model::{Clr, Op, Params};
+
+const USERS: usize = 3;
+const MAX_AMOUNT: u64 = 1_000_000_000_000_000;
+
+#[derive(Parser)]
+#[command(name = "mutr-fuzz", about = "Fuzz the CLR accounting invariants")]
+struct Cli {
+    /// Random sequences to run
+    #[arg(long, default_value_t = 1_000)]
+    cases: u32,
+
+    /// Most operations per sequence
+    #[arg(long, default_value_t = 64)]
+    steps: usize,
+}
+
+fn params() -> impl Strategy<Value = Params> {
+    (
+        0..=1_000u16,
+        0..=1_000u16,
+        0..=1_000u16,
+        0..=30 * 86_400i64,
+        0..=10_000u16,
+        0..=10_000u16,
+        prop_oneof![Just(0u64), 1..=1_000u64],
+    )
+        .prop_map(
+            |(stake_fee, unstake_fee, exit_penalty, decay, burn, treasury, drip_slots)| {
+                let treasury = treasury.min(10_000 - burn);
+                Params {
+                    stake_fee_bps: stake_fee,
+                    unstake_fee_bps: unstake_fee,
+                    exit_penalty_max_bps: exit_penalty,
+                    exit_penalty_decay_secs: decay,
+                    fee_split: FeeSplit {
+                        clr_bps: 10_000 - burn - treasury,
+                        burn_bps: burn,
+                        treasury_bps: treasury,
+                    },
+                    drip_slots,
+                }
+            },
+        )
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let user = 0..USERS;
+    let amount = 1..=MAX_AMOUNT;
+    let bps = 1..=10_000u16;
+    prop_oneof![
+        3 => (user.clone(), amount.clone()).prop_map(|(user, amount)| Op::Stake { user, amount }),
+        2 => (user.clone(), bps.clone()).prop_map(|(user, bps)| Op::Unstake { user, bps }),
+        2 => (user.clone(), bps.clone()).prop_map(|(user, bps)| Op::Join { user, bps }),
+        2 => (user.clone(), bps).prop_map(|(user, bps)| Op::Leave { user, bps }),
+        2 => amount.clone().prop_map(|amount| Op::Profit { amount }),
+        1 => amount.prop_map(|amount| Op::Prize { amount }),
+        1 => user.prop_map(|user| Op::Claim { user }),
+        1 => Just(Op::Drip),
+        1 => (0..=86_400i64, 0..=1_000u64).prop_map(|(secs, slots)| Op::Wait { secs, slots }),
+    ]
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut runner = TestRunner::new(Config {
+        cases: cli.cases,
+        failure_persistence: None,
+        ..Config::default()
+    });
+
+    let result = runner.run(&(params(), vec(op(), 1..=cli.steps)), |(params, ops)| {
+        let mut clr = Clr::new(&params, USERS);
+        for (i, op) in ops.iter().enumerate() {
+            let before = clr.clone();
+            if clr.apply(op) {
+                clr.check(&before, op)
+                    .map_err(|err| TestCaseError::fail(format!("step {i} ({op:?}): {err}")))?;
+            }
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => {
+            println!("{} cases passed", cli.cases);
+            ExitCode::SUCCESS
+        }
+        Err(TestError::Fail(reason, (params, ops))) => {
+            eprintln!("invariant violated: {reason}");
+            eprintln!("{params:?}");
+            for op in ops {
+                eprintln!("  {op:?}");
+            }
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Host-side model of the CLR's staking accounting.
+//!
+//! `Clr` holds a `GlobalState`, the CLR vault balance, the xMUTR supply and a set of
+//! positions, and applies each operation the way its instruction handler does, through
+//! the program's own math (`shares_for_deposit`, `amount_for_shares`, `apply_fee`,
+//! `settle_user_rewards`, ...). Token CPIs become balance updates; the vault is read
+//! before CPIs land, as the handlers read their unreloaded accounts. An operation the
+//! program would reject leaves the model untouched.
+
+use anchor_lang::prelude::*;
+use mutr_clr::{
+    amount_for_shares, apply_fee, blend_joined_at, checkpoint_reward_debt, dividend_weight,
+    drip_due, exit_penalty_bps, pending_rewards, release_profit, reward_debt_for,
+    settle_user_rewards, shares_for_deposit, FeeSplit, GlobalState, MutrError, UserState,
+    VIRTUAL_ASSETS, VIRTUAL_SHARES,
+};
+
+/// Share-denominated operations act on a fraction of the position, in bps.
+#[derive(Clone, Debug)]
+pub enum Op {
+    Stake {
+        user: usize,
+        amount: u64,
+    },
+    Unstake {
+        user: usize,
+        bps: u16,
+    },
+    Join {
+        user: usize,
+        bps: u16,
+    },
+    Leave {
+        user: usize,
+        bps: u16,
+    },
+    /// Lost wagers land in the vault and are recorded as profit
+    Profit {
+        amount: u64,
+    },
+    Prize {
+        amount: u64,
+    },
+    Claim {
+        user: usize,
+    },
+    Drip,
+    Wait {
+        secs: i64,
+        slots: u64,
+    },
+}
+
+impl Op {
+    /// Payouts lower the share price by design; every other operation must not.
+    pub fn pays_out(&self) -> bool {
+        matches!(self, Op::Prize { .. } | Op::Claim { .. })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub stake_fee_bps: u16,
+    pub unstake_fee_bps: u16,
+    pub exit_penalty_max_bps: u16,
+    pub exit_penalty_decay_secs: i64,
+    pub fee_split: FeeSplit,
+    pub drip_slots: u64,
+}
+
+#[derive(Clone)]
+pub struct Clr {
+    pub state: GlobalState,
+    pub vault: u64,
+    pub supply: u64,
+    pub positions: Vec<UserState>,
+    /// xMUTR held by each position's owner
+    pub held: Vec<u64>,
+    pub now: i64,
+    pub slot: u64,
+}
+
+impl Clr {
+    pub fn new(params: &Params, users: usize) -> Self {
+        let mut state: GlobalState = bytemuck::Zeroable::zeroed();
+        state.stake_fee_bps = params.stake_fee_bps;
+        state.unstake_fee_bps = params.unstake_fee_bps;
+        state.exit_penalty_max_bps = params.exit_penalty_max_bps;
+        state.exit_penalty_decay_secs = params.exit_penalty_decay_secs;
+        state.fee_split = params.fee_split;
+        state.drip_slots = params.drip_slots;
+
+        let mut position =
+            UserState::deserialize(&mut &[0u8; UserState::LEN][..]).expect("zeroed UserState");
+        position.boost_bps = 10_000;
+        Self {
+            state,
+            vault: 0,
+            supply: 0,
+            positions: vec![position; users],
+            held: vec![0; users],
+            now: 1_700_000_000,
+            slot: 1,
+        }
+    }
+
+    /// Apply `op`; returns false, leaving the model unchanged, when the program would
+    /// reject it.
+    pub fn apply(&mut self, op: &Op) -> bool {
+        let mut next = self.clone();
+        let applied = next.step(op).is_ok();
+        if applied {
+            *self = next;
+        }
+        applied
+    }
+
+    fn step(&mut self, op: &Op) -> Result<()> {
+        match *op {
+            Op::Stake { user, amount } => self.stake(user, amount),
+            Op::Unstake { user, bps } => {
+                let shares = fraction(self.positions[user].staked_shares, bps);
+                self.unstake(user, shares)
+            }
+            Op::Join { user, bps } => {
+                let shares = fraction(self.positions[user].staked_shares, bps);
+                self.join(user, shares)
+            }
+            Op::Leave { user, bps } => {
+                let shares = fraction(self.positions[user].dividend_shares, bps);
+                self.leave(user, shares)
+            }
+            Op::Profit { amount } => {
+                self.vault = self
+                    .vault
+                    .checked_add(amount)
+                    .ok_or(MutrError::MathOverflow)?;
+                release_profit(&mut self.state, amount, self.slot)
+            }
+            Op::Prize { amount } => {
+                require!(amount > 0, MutrError::InvalidAmount);
+                self.vault = self
+                    .vault
+                    .checked_sub(amount)
+                    .ok_or(ProgramError::InsufficientFunds)?;
+                Ok(())
+            }
+            Op::Claim { user } => self.claim(user),
+            Op::Drip => {
+                require!(self.state.drip_remaining > 0, MutrError::NothingToDrip);
+                drip_due(&mut self.state, self.slot, 0).map(|_| ())
+            }
+            Op::Wait { secs, slots } => {
+                self.now += secs;
+                self.slot += slots;
+                Ok(())
+            }
+        }
+    }
+
+    fn stake(&mut self, user: usize, amount: u64) -> Result<()> {
+        require!(amount > 0, MutrError::InvalidAmount);
+        let vault_before = self.vault;
+        self.vault = self
+            .vault
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+
+        let net_amount = apply_fee(amount, self.state.stake_fee_bps)?;
+        let (burn_cut, treasury_cut) = self.state.fee_split.portions(amount - net_amount)?;
+        self.vault -= burn_cut + treasury_cut;
+
+        let shares = shares_for_deposit(net_amount, self.supply, vault_before)?;
+        require!(shares > 0, MutrError::ZeroShares);
+        self.mint(user, shares)?;
+        let position = &mut self.positions[user];
+        position.staked_shares = position
+            .staked_shares
+            .checked_add(shares)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    fn unstake(&mut self, user: usize, shares: u64) -> Result<()> {
+        require!(shares > 0, MutrError::InvalidAmount);
+        let position = &mut self.positions[user];
+        let covered = shares
+            .checked_add(position.dividend_shares)
+            .ok_or(MutrError::MathOverflow)?;
+        require!(
+            position.staked_shares >= covered,
+            MutrError::InsufficientShares
+        );
+        position.staked_shares -= shares;
+
+        // Priced on the supply read before the burn
+        let value = amount_for_shares(shares, self.vault, self.supply)?;
+        self.held[user] -= shares;
+        self.supply -= shares;
+
+        let net_amount = apply_fee(value, self.state.unstake_fee_bps)?;
+        let (burn_cut, treasury_cut) = self.state.fee_split.portions(value - net_amount)?;
+        self.vault = self
+            .vault
+            .checked_sub(burn_cut + treasury_cut + net_amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    fn join(&mut self, user: usize, shares: u64) -> Result<()> {
+        require!(shares > 0, MutrError::InvalidAmount);
+        let position = &mut self.positions[user];
+        require!(
+            position.staked_shares >= shares,
+            MutrError::InsufficientShares
+        );
+
+        settle_user_rewards(&self.state, position)?;
+        let weight_before = dividend_weight(position)?;
+        position.joined_at = blend_joined_at(
+            position.joined_at,
+            position.dividend_shares,
+            self.now,
+            shares,
+        )?;
+        position.staked_shares -= shares;
+        position.dividend_shares = position
+            .dividend_shares
+            .checked_add(shares)
+            .ok_or(MutrError::MathOverflow)?;
+
+        let weight_after = dividend_weight(position)?;
+        self.state.total_dividend_shares = self
+            .state
+            .total_dividend_shares
+            .checked_add(weight_after - weight_before)
+            .ok_or(MutrError::MathOverflow)?;
+        checkpoint_reward_debt(&self.state, position)
+    }
+
+    fn leave(&mut self, user: usize, shares: u64) -> Result<()> {
+        require!(shares > 0, MutrError::InvalidAmount);
+        let position = &mut self.positions[user];
+        require!(
+            position.dividend_shares >= shares,
+            MutrError::InsufficientShares
+        );
+
+        settle_user_rewards(&self.state, position)?;
+        let weight_before = dividend_weight(position)?;
+        let fee_bps = exit_penalty_bps(&self.state, position, self.now)?;
+        let net_shares = apply_fee(shares, fee_bps)?;
+        position.dividend_shares -= shares;
+        position.staked_shares = position
+            .staked_shares
+            .checked_add(net_shares)
+            .ok_or(MutrError::MathOverflow)?;
+
+        let weight_after = dividend_weight(position)?;
+        self.state.total_dividend_shares = self
+            .state
+            .total_dividend_shares
+            .checked_sub(weight_before - weight_after)
+            .ok_or(MutrError::MathOverflow)?;
+        checkpoint_reward_debt(&self.state, position)?;
+
+        // Priced on the supply read before the fee xMUTR is burned
+        let fee_shares = shares - net_shares;
+        let fee_value = if self.supply == 0 {
+            0
+        } else {
+            amount_for_shares(fee_shares, self.vault, self.supply)?
+        };
+        self.held[user] -= fee_shares;
+        self.supply -= fee_shares;
+        let (burn_cut, treasury_cut) = self.state.fee_split.portions(fee_value)?;
+        self.vault = self
+            .vault
+            .checked_sub(burn_cut + treasury_cut)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    fn claim(&mut self, user: usize) -> Result<()> {
+        let position = &mut self.positions[user];
+        let pending = pending_rewards(&self.state, position)?;
+        if pending == 0 {
+            return Ok(());
+        }
+        position.pending_rewards = 0;
+        position.reward_debt = reward_debt_for(&self.state, position)?;
+        self.vault = self
+            .vault
+            .checked_sub(pending)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    fn mint(&mut self, user: usize, shares: u64) -> Result<()> {
+        self.supply = self
+            .supply
+            .checked_add(shares)
+            .ok_or(MutrError::MathOverflow)?;
+        self.held[user] += shares;
+        Ok(())
+    }
+
+    /// Check the invariants after `op` took the model from `before` to `self`.
+    pub fn check(&self, before: &Clr, op: &Op) -> std::result::Result<(), String> {
+        if self.held.iter().sum::<u64>() != self.supply {
+            return Err(format!("held xMUTR != supply {}", self.supply));
+        }
+        for (i, position) in self.positions.iter().enumerate() {
+            if position.staked_shares as u128 + position.dividend_shares as u128
+                > self.held[i] as u128
+            {
+                return Err(format!("position {i} counts more shares than it holds"));
+            }
+        }
+
+        let mut weights = 0u128;
+        for position in &self.positions {
+            weights += dividend_weight(position).map_err(|e| e.to_string())?;
+        }
+        if weights != self.state.total_dividend_shares {
+            return Err(format!(
+                "total_dividend_shares {} != summed weights {weights}",
+                self.state.total_dividend_shares
+            ));
+        }
+
+        // Prizes may cut into owed rewards; nothing else may leave the vault short
+        let owed = self.owed()?;
+        if (self.vault as u128) < owed
+            && !matches!(op, Op::Prize { .. })
+            && before.owed()? <= before.vault as u128
+        {
+            return Err(format!("vault {} below owed rewards {owed}", self.vault));
+        }
+
+        // The price shares trade at, (vault + VIRTUAL_ASSETS) / (supply + VIRTUAL_SHARES),
+        // must not fall
+        let virtual_vault = |clr: &Self| clr.vault as u128 + VIRTUAL_ASSETS as u128;
+        let virtual_supply = |clr: &Self| clr.supply as u128 + VIRTUAL_SHARES as u128;
+        if !op.pays_out()
+            && before.supply > 0
+            && self.supply > 0
+            && virtual_vault(self) * virtual_supply(before)
+                < virtual_vault(before) * virtual_supply(self)
+        {
+            return Err(format!(
+                "share price fell from {}/{} to {}/{}",
+                before.vault, before.supply, self.vault, self.supply
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Clr {
+    /// Pending rewards of every position plus the profit still dripping to them.
+    fn owed(&self) -> std::result::Result<u128, String> {
+        let mut owed = self.state.drip_remaining as u128;
+        for position in &self.positions {
+            owed += pending_rewards(&self.state, position).map_err(|e| e.to_string())? as u128;
+        }
+        Ok(owed)
+    }
+}
+
+fn fraction(shares: u64, bps: u16) -> u64 {
+    (shares as u128 * bps as u128 / 10_000) as u64
+}
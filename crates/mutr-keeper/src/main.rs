@@ -0,0 +1,263 @@
+//! `mutr-keeper`: daemon cranking the CLR's permissionless instructions.
+//!
+//! Every `--interval-secs` it reads `GlobalState` and sends whichever cranks are due:
+//! `epoch_rollover` once the profit epoch has ended, `drip` while profit is vesting and
+//! `harvest_emissions` while emissions accrue, collecting their keeper fees into
+//! `--keeper-mutr-account`, and `sweep_dust` when the bonus vault holds dust beyond what
+//! it owes. It also retries `resolve_bet_with_randomness` on every pending
+//! randomness-backed bet until its request is revealed; a send that fails preflight
+//! costs nothing and is tried again next tick. USD bets are left to their
+//! game, which holds the price update a win needs.
+//!
+//! There is no `process_withdrawals` crank: the CLR has no withdrawal queue, and
+//! `unstake` pays out in the transaction that burns the shares.
+//!
+//! Each transaction carries a compute unit price of `--priority-fee` micro-lamports.
+//! Sent and failed counts per crank are logged after every tick (`RUST_LOG` sets the
+//! level, `info` by default).
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::compute_budget::ComputeBudgetInstruction;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{read_keypair_file, Keypair};
+use anchor_client::{ClientError, Cluster};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use clap::Parser;
+use mutr_clr_client::mutr_clr::{LeaderboardKind, MAX_SWEEP_DUST};
+use mutr_clr_client::{
+    pda, ClrAccounts, ClrClient, Drip, EpochRollover, HarvestEmissions, ResolveBetWithRandomness,
+    SweepDust,
+};
+
+#[derive(Parser)]
+#[command(name = "mutr-keeper", about = "Crank the MutantRolls CLR")]
+struct Cli {
+    /// Cluster moniker or RPC URL
+    #[arg(long, short = 'u', default_value = "localnet")]
+    url: Cluster,
+
+    /// Keeper keypair; pays fees
+    #[arg(long, short = 'k')]
+    keypair: Option<String>,
+
+    /// MUTR account receiving the keeper fees
+    #[arg(long)]
+    keeper_mutr_account: Pubkey,
+
+    #[arg(long, default_value_t = 10)]
+    interval_secs: u64,
+
+    /// Compute unit price, in micro-lamports
+    #[arg(long, default_value_t = 0)]
+    priority_fee: u64,
+
+    /// Compute unit limit per transaction; the runtime default when unset
+    #[arg(long)]
+    compute_unit_limit: Option<u32>,
+
+    /// Leave randomness-backed bets to their games
+    #[arg(long)]
+    no_resolve: bool,
+}
+
+#[derive(Default)]
+struct Counts {
+    sent: u64,
+    failed: u64,
+}
+
+struct Keeper {
+    client: ClrClient<Arc<Keypair>>,
+    clr: ClrAccounts,
+    keeper_mutr_account: Pubkey,
+    budget: Vec<Instruction>,
+    resolve_bets: bool,
+    metrics: BTreeMap<&'static str, Counts>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+    let keypair = match cli.keypair {
+        Some(path) => path,
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+    let payer = Arc::new(read_keypair_file(&keypair)?);
+    let client = ClrClient::new(cli.url, payer, CommitmentConfig::confirmed())?;
+
+    let mut budget = vec![ComputeBudgetInstruction::set_compute_unit_price(
+        cli.priority_fee,
+    )];
+    if let Some(limit) = cli.compute_unit_limit {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    let mut keeper = Keeper {
+        clr: client.clr_accounts().await?,
+        client,
+        keeper_mutr_account: cli.keeper_mutr_account,
+        budget,
+        resolve_bets: !cli.no_resolve,
+        metrics: BTreeMap::new(),
+    };
+    log::info!("keeping CLR {} as {}", pda::state(), keeper.client.payer());
+
+    let mut interval = tokio::time::interval(Duration::from_secs(cli.interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(err) = keeper.tick().await {
+            log::error!("tick failed: {err}");
+        }
+        keeper.log_metrics();
+    }
+}
+
+impl Keeper {
+    async fn tick(&mut self) -> Result<(), ClientError> {
+        let state = self.client.global_state().await?;
+        let slot = self.client.program().async_rpc().get_slot().await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let keeper = self.client.payer();
+
+        if state.profit_epoch_secs > 0
+            && now
+                >= state
+                    .profit_epoch_start
+                    .saturating_add(state.profit_epoch_secs)
+        {
+            let ix = EpochRollover {
+                keeper,
+                keeper_mutr_account: self.keeper_mutr_account,
+            }
+            .instruction(&self.clr);
+            self.send("epoch_rollover", ix).await;
+        }
+        if state.drip_remaining > 0
+            && state.total_dividend_shares > 0
+            && slot.min(state.drip_end_slot) > state.drip_last_slot
+        {
+            let ix = Drip {
+                keeper,
+                keeper_mutr_account: self.keeper_mutr_account,
+            }
+            .instruction(&self.clr);
+            self.send("drip", ix).await;
+        }
+        if state.emission_vault != Pubkey::default()
+            && state.emission_last_slot < slot.min(state.emission_end_slot)
+        {
+            let oracle = self
+                .client
+                .program()
+                .async_rpc()
+                .get_multiple_accounts(&[pda::share_price_oracle()])
+                .await?;
+            let ix = HarvestEmissions {
+                keeper,
+                keeper_mutr_account: self.keeper_mutr_account,
+                share_price_oracle: oracle[0].is_some(),
+            }
+            .instruction(&self.clr);
+            self.send("harvest_emissions", ix).await;
+        }
+        if state.bonus_vault != Pubkey::default() && state.total_dividend_shares > 0 {
+            let balance = self
+                .client
+                .program()
+                .async_rpc()
+                .get_token_account_balance(&state.bonus_vault)
+                .await?;
+            let surplus = balance
+                .amount
+                .parse::<u64>()
+                .unwrap_or_default()
+                .saturating_sub(state.bonus_owed);
+            if surplus > 0 && surplus <= MAX_SWEEP_DUST {
+                let ix = SweepDust {
+                    bonus_vault: state.bonus_vault,
+                }
+                .instruction();
+                self.send("sweep_dust", ix).await;
+            }
+        }
+        if self.resolve_bets {
+            self.resolve_pending_bets(slot).await?;
+        }
+        Ok(())
+    }
+
+    async fn resolve_pending_bets(&mut self, slot: u64) -> Result<(), ClientError> {
+        let bets = self.client.pending_bets().await?;
+        let rpc = self.client.program().async_rpc();
+        let configs = rpc
+            .get_multiple_accounts(&[
+                pda::leaderboard(LeaderboardKind::Winners),
+                pda::loyalty_config(),
+                pda::rakeback_config(),
+            ])
+            .await?;
+        let [leaderboard, loyalty, rakeback] = [0, 1, 2].map(|i| configs[i].is_some());
+
+        for (address, bet) in bets {
+            if !bet.uses_randomness() || bet.usd_cents > 0 || slot > bet.expiry_slot {
+                continue;
+            }
+            let user_stats = rpc
+                .get_account_data(&pda::user_stats(&bet.player))
+                .await
+                .is_ok();
+            let ix = ResolveBetWithRandomness {
+                bet: address,
+                game: bet.game,
+                player: bet.player,
+                randomness_account: bet.randomness_account,
+                player_mutr_account: get_associated_token_address_with_program_id(
+                    &bet.player,
+                    &self.clr.vaults.mutr_mint,
+                    &self.clr.vaults.token_program,
+                ),
+                leaderboard,
+                user_stats,
+                loyalty: user_stats && loyalty,
+                rakeback: user_stats && rakeback,
+            }
+            .instruction(&self.clr);
+            self.send("resolve_bet_with_randomness", ix).await;
+        }
+        Ok(())
+    }
+
+    async fn send(&mut self, crank: &'static str, ix: Instruction) {
+        let mut instructions = self.budget.clone();
+        instructions.push(ix);
+        let counts = self.metrics.entry(crank).or_default();
+        match self.client.send(instructions, &[]).await {
+            Ok(signature) => {
+                counts.sent += 1;
+                log::info!("{crank}: {signature}");
+            }
+            Err(err) => {
+                counts.failed += 1;
+                log::warn!("{crank} failed: {err}");
+            }
+        }
+    }
+
+    fn log_metrics(&self) {
+        for (crank, counts) in &self.metrics {
+            log::info!(
+                "metrics {crank} sent={} failed={}",
+                counts.sent,
+                counts.failed
+            );
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! `mutr-localnet`: bootstrap a local test validator with a working CLR.
+//!
+//! Starts `solana-test-validator` with the CLR program loaded (or uses one already
+//! running with `--no-validator`), then:
+//!
+//! 1. creates the MUTR mint, owned by the local keypair, and the xMUTR mint, whose
+//!    authority is the CLR state PDA;
+//! 2. initializes the CLR with its stats and treasury, and seeds the bankroll;
+//! 3. registers a mock game signer that resolves its own bets;
+//! 4. funds `--wallets` test wallets with SOL and MUTR.
+//!
+//! The game and wallet keypairs are written to `--out` along with `localnet.env`,
+//! listing every address the frontend and scripts need.
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_client::solana_sdk::native_token::LAMPORTS_PER_SOL;
+use anchor_client::solana_sdk::program_pack::Pack;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair};
+use anchor_client::solana_sdk::signer::Signer;
+use anchor_client::solana_sdk::system_instruction;
+use anchor_client::solana_sdk::system_program;
+use anchor_client::Cluster;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction as ata_instruction;
+use anchor_spl::token::spl_token;
+use clap::Parser;
+use mutr_clr_client::mutr_clr::{accounts, instruction, InitConfig};
+use mutr_clr_client::{clr_instruction, pda, ClrClient, CLR_PROGRAM_ID};
+
+const RPC_URL: &str = "http://127.0.0.1:8899";
+const WS_URL: &str = "ws://127.0.0.1:8900";
+
+#[derive(Parser)]
+#[command(
+    name = "mutr-localnet",
+    about = "Bootstrap a local MutantRolls environment"
+)]
+struct Cli {
+    /// Keypair paying for the setup and holding the CLR authority and MUTR mint
+    #[arg(long, short = 'k')]
+    keypair: Option<String>,
+
+    /// Use the validator already running on the default ports
+    #[arg(long)]
+    no_validator: bool,
+
+    /// CLR program loaded into the started validator
+    #[arg(long, default_value = "target/deploy/mutr_clr.so")]
+    program: PathBuf,
+
+    /// Directory for the ledger, keypairs and `localnet.env`
+    #[arg(long, default_value = ".localnet")]
+    out: PathBuf,
+
+    #[arg(long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Test wallets to create and fund
+    #[arg(long, default_value_t = 3)]
+    wallets: usize,
+
+    /// Whole MUTR minted to each test wallet
+    #[arg(long, default_value_t = 100_000)]
+    wallet_mutr: u64,
+
+    /// Whole MUTR seeding the CLR vault
+    #[arg(long, default_value_t = 1_000_000)]
+    bankroll: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    std::fs::create_dir_all(&cli.out)?;
+    let mut validator = if cli.no_validator {
+        None
+    } else {
+        Some(start_validator(&cli.program, &cli.out.join("ledger"))?)
+    };
+
+    let keypair = match &cli.keypair {
+        Some(path) => path.clone(),
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+    let payer = Arc::new(read_keypair_file(&keypair)?);
+    let cluster = Cluster::Custom(RPC_URL.to_string(), WS_URL.to_string());
+    let client = ClrClient::new(cluster, payer.clone(), CommitmentConfig::confirmed())?;
+    wait_for_rpc(&client).await?;
+    airdrop(&client, &payer.pubkey(), 100).await?;
+
+    let env = bootstrap(&client, &cli).await?;
+    std::fs::write(cli.out.join("localnet.env"), &env)?;
+    print!("{env}");
+
+    if let Some(validator) = &mut validator {
+        println!("validator running at {RPC_URL}; Ctrl-C to stop");
+        validator.wait()?;
+    }
+    Ok(())
+}
+
+fn start_validator(program: &Path, ledger: &Path) -> Result<Child, Box<dyn Error>> {
+    let program_id = CLR_PROGRAM_ID.to_string();
+    Ok(Command::new("solana-test-validator")
+        .arg("--reset")
+        .arg("--quiet")
+        .arg("--ledger")
+        .arg(ledger)
+        .args(["--bpf-program", &program_id])
+        .arg(program)
+        .spawn()?)
+}
+
+async fn wait_for_rpc(client: &ClrClient<Arc<Keypair>>) -> Result<(), Box<dyn Error>> {
+    let rpc = client.program().async_rpc();
+    for _ in 0..60 {
+        if rpc.get_health().await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    Err("validator did not come up".into())
+}
+
+async fn airdrop(
+    client: &ClrClient<Arc<Keypair>>,
+    to: &Pubkey,
+    sol: u64,
+) -> Result<(), Box<dyn Error>> {
+    let rpc = client.program().async_rpc();
+    let signature = rpc.request_airdrop(to, sol * LAMPORTS_PER_SOL).await?;
+    while !rpc.confirm_transaction(&signature).await? {
+        tokio::time::sleep(Duration::from_millis(400)).await;
+    }
+    Ok(())
+}
+
+/// Set up the mints, CLR, mock game and wallets; returns the `localnet.env` contents.
+async fn bootstrap(client: &ClrClient<Arc<Keypair>>, cli: &Cli) -> Result<String, Box<dyn Error>> {
+    let authority = client.payer();
+    let state = pda::state();
+    let unit = 10u64.pow(cli.decimals as u32);
+
+    let mutr_mint = Keypair::new();
+    let xmutr_mint = Keypair::new();
+    let clr_vault = get_associated_token_address(&state, &mutr_mint.pubkey());
+    let mut ixs = create_mint(client, &mutr_mint.pubkey(), &authority, cli.decimals).await?;
+    ixs.extend(create_mint(client, &xmutr_mint.pubkey(), &state, cli.decimals).await?);
+    ixs.push(ata_instruction::create_associated_token_account_idempotent(
+        &authority,
+        &state,
+        &mutr_mint.pubkey(),
+        &spl_token::ID,
+    ));
+    client.send(ixs, &[&mutr_mint, &xmutr_mint]).await?;
+
+    let initialize = clr_instruction(
+        accounts::InitializeClr {
+            state,
+            mutr_mint: mutr_mint.pubkey(),
+            xmutr_mint: xmutr_mint.pubkey(),
+            clr_vault,
+            authority,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+        },
+        instruction::InitializeClr {
+            config: InitConfig {
+                mutr_mint: mutr_mint.pubkey(),
+                xmutr_mint: xmutr_mint.pubkey(),
+                stake_fee_bps: 300,
+                unstake_fee_bps: 300,
+                lower_threshold: 0,
+                upper_threshold: u64::MAX,
+                exit_penalty_max_bps: 400,
+                exit_penalty_decay_secs: 7 * 24 * 60 * 60,
+            },
+        },
+    );
+    let initialize_admin_log = clr_instruction(
+        accounts::InitializeAdminLog {
+            state,
+            admin_log: pda::admin_log(),
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeAdminLog {},
+    );
+    let initialize_stats = clr_instruction(
+        accounts::InitializeStats {
+            state,
+            stats: pda::stats(),
+            clr_vault,
+            xmutr_mint: xmutr_mint.pubkey(),
+            admin_log: pda::admin_log(),
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeStats { unique_stakers: 0 },
+    );
+    let initialize_oracle = clr_instruction(
+        accounts::InitializeSharePriceOracle {
+            state,
+            share_price_oracle: pda::share_price_oracle(),
+            clr_vault,
+            xmutr_mint: xmutr_mint.pubkey(),
+            admin_log: pda::admin_log(),
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeSharePriceOracle {},
+    );
+    let initialize_treasury = clr_instruction(
+        accounts::InitializeTreasury {
+            state,
+            mutr_mint: mutr_mint.pubkey(),
+            treasury_vault: pda::treasury_vault(),
+            admin_log: pda::admin_log(),
+            authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeTreasury {
+            treasury_spend_delay_secs: 0,
+        },
+    );
+    let seed_bankroll = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mutr_mint.pubkey(),
+        &clr_vault,
+        &authority,
+        &[],
+        cli.bankroll * unit,
+    )?;
+    client
+        .send(
+            vec![
+                initialize,
+                initialize_admin_log,
+                initialize_stats,
+                initialize_oracle,
+                initialize_treasury,
+                seed_bankroll,
+            ],
+            &[],
+        )
+        .await?;
+
+    let game = Keypair::new();
+    let register_game = clr_instruction(
+        accounts::RegisterGame {
+            state,
+            game_config: pda::game_config(&game.pubkey()),
+            game_ledger: pda::game_ledger(&game.pubkey()),
+            admin_log: pda::admin_log(),
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterGame {
+            game: game.pubkey(),
+            max_multiplier_bps: 1_000_000,
+            bet_ttl_slots: 150,
+        },
+    );
+    client.send(vec![register_game], &[]).await?;
+    airdrop(client, &game.pubkey(), 10).await?;
+    write_keypair_file(&game, cli.out.join("game.json"))?;
+
+    let mut env = String::new();
+    writeln!(env, "CLR_PROGRAM_ID={CLR_PROGRAM_ID}")?;
+    writeln!(env, "CLR_STATE={state}")?;
+    writeln!(env, "MUTR_MINT={}", mutr_mint.pubkey())?;
+    writeln!(env, "XMUTR_MINT={}", xmutr_mint.pubkey())?;
+    writeln!(env, "CLR_VAULT={clr_vault}")?;
+    writeln!(env, "TREASURY_VAULT={}", pda::treasury_vault())?;
+    writeln!(env, "MOCK_GAME={}", game.pubkey())?;
+
+    for i in 0..cli.wallets {
+        let wallet = Keypair::new();
+        airdrop(client, &wallet.pubkey(), 10).await?;
+        let mutr_account = get_associated_token_address(&wallet.pubkey(), &mutr_mint.pubkey());
+        let xmutr_account = get_associated_token_address(&wallet.pubkey(), &xmutr_mint.pubkey());
+        let ixs = vec![
+            ata_instruction::create_associated_token_account_idempotent(
+                &authority,
+                &wallet.pubkey(),
+                &mutr_mint.pubkey(),
+                &spl_token::ID,
+            ),
+            ata_instruction::create_associated_token_account_idempotent(
+                &authority,
+                &wallet.pubkey(),
+                &xmutr_mint.pubkey(),
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &mutr_mint.pubkey(),
+                &mutr_account,
+                &authority,
+                &[],
+                cli.wallet_mutr * unit,
+            )?,
+        ];
+        client.send(ixs, &[]).await?;
+        write_keypair_file(&wallet, cli.out.join(format!("wallet-{i}.json")))?;
+        writeln!(env, "WALLET_{i}={}", wallet.pubkey())?;
+        writeln!(env, "WALLET_{i}_MUTR={mutr_account}")?;
+        writeln!(env, "WALLET_{i}_XMUTR={xmutr_account}")?;
+    }
+    Ok(env)
+}
+
+async fn create_mint(
+    client: &ClrClient<Arc<Keypair>>,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    decimals: u8,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let payer = client.payer();
+    let rent = client
+        .program()
+        .async_rpc()
+        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+        .await?;
+    Ok(vec![
+        system_instruction::create_account(
+            &payer,
+            mint,
+            rent,
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(&spl_token::ID, mint, authority, None, decimals)?,
+    ])
+}
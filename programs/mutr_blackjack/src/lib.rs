@@ -0,0 +1,1188 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+use mutr_clr_cpi::{ClrVaults, PayPrize};
+
+declare_id!("B1ackjackRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Cards in the single deck
+pub const DECK_SIZE: u8 = 52;
+
+/// Most cards a hand can hold before reaching 21 (four aces, four twos, three threes)
+pub const MAX_HAND_CARDS: usize = 11;
+
+/// Hands a player can hold after splitting; re-splitting is not offered
+pub const MAX_HANDS: usize = 2;
+
+/// Largest return on a single wager: split into two doubled hands that both win
+pub const MAX_RETURN_MULTIPLE: u64 = 8;
+
+/// Single-deck blackjack, European no-hole-card rules: the dealer stands on all 17s,
+/// draws the second card only once the player is done, and a dealer blackjack takes
+/// doubled and split wagers too. Blackjack pays 3:2.
+///
+/// Every card comes from VRF randomness: each player action that needs a card commits a
+/// fresh request, and `draw` deals from the remaining deck once it is revealed.
+#[program]
+pub mod mutr_blackjack {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR, which pays every win through `send_prize`.
+    pub fn initialize_blackjack(
+        ctx: Context<InitializeBlackjack>,
+        min_bet: u64,
+        max_bet: u64,
+        hand_ttl_slots: u64,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            min_bet > 0
+                && min_bet <= max_bet
+                && hand_ttl_slots > 0
+                && randomness_provider != RandomnessProvider::None,
+            BlackjackError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.hand_ttl_slots = hand_ttl_slots;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: change the bet limits and hand expiry.
+    pub fn update_blackjack_config(
+        ctx: Context<UpdateBlackjackConfig>,
+        min_bet: u64,
+        max_bet: u64,
+        hand_ttl_slots: u64,
+    ) -> Result<()> {
+        require!(
+            min_bet > 0 && min_bet <= max_bet && hand_ttl_slots > 0,
+            BlackjackError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.hand_ttl_slots = hand_ttl_slots;
+        Ok(())
+    }
+
+    /// Wager `amount` on a new hand. The wager goes straight into the CLR vault and the
+    /// opening cards are dealt by `draw` once the accompanying request is revealed.
+    pub fn start_hand(ctx: Context<StartHand>, nonce: u64, amount: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            amount >= config.min_bet && amount <= config.max_bet,
+            BlackjackError::BetOutOfRange
+        );
+        let max_return = amount
+            .checked_mul(MAX_RETURN_MULTIPLE)
+            .ok_or(BlackjackError::MathOverflow)?;
+        mutr_clr::risk::check_payout(
+            &*ctx.accounts.clr_state.load()?,
+            ctx.accounts.clr_vault.amount,
+            max_return,
+        )?;
+
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let amount = transfer_to_clr(
+            &ctx.accounts.player_mutr_account,
+            &mut ctx.accounts.clr_vault,
+            &ctx.accounts.mutr_mint,
+            &ctx.accounts.player,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        record_wager(
+            &ctx.accounts.clr_program,
+            mutr_clr::cpi::accounts::RecordGameWager {
+                state: ctx.accounts.clr_state.to_account_info(),
+                game_config: ctx.accounts.clr_game_config.to_account_info(),
+                game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+                player_state: ctx.accounts.clr_player_state.to_account_info(),
+                user_stats: ctx
+                    .accounts
+                    .clr_user_stats
+                    .as_ref()
+                    .map(|stats| stats.to_account_info()),
+                player: ctx.accounts.player.to_account_info(),
+                game: ctx.accounts.game_authority.to_account_info(),
+            },
+            config.authority_bump,
+            amount,
+        )?;
+
+        let hand = &mut ctx.accounts.hand;
+        hand.player = ctx.accounts.player.key();
+        hand.nonce = nonce;
+        hand.hands[0].wager = amount;
+        hand.hand_count = 1;
+        hand.bump = ctx.bumps.hand;
+        hand.request(
+            PendingAction::Deal,
+            ctx.accounts.randomness_account.key(),
+            slot,
+            config.hand_ttl_slots,
+        )
+    }
+
+    /// Take another card on the active hand.
+    pub fn hit(ctx: Context<PlayerAction>) -> Result<()> {
+        let randomness_account = ctx
+            .accounts
+            .randomness_account
+            .as_ref()
+            .ok_or(BlackjackError::InvalidRandomness)?;
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            ctx.accounts.config.randomness_provider,
+            randomness_account,
+            slot,
+        )?;
+
+        ctx.accounts.hand.request(
+            PendingAction::Hit,
+            randomness_account.key(),
+            slot,
+            ctx.accounts.config.hand_ttl_slots,
+        )
+    }
+
+    /// Keep the active hand. Standing on the last hand hands over to the dealer, which
+    /// needs a fresh request; otherwise play moves on to the next split hand.
+    pub fn stand(ctx: Context<PlayerAction>) -> Result<()> {
+        let hand = &mut ctx.accounts.hand;
+        let active = hand.active_hand as usize;
+        hand.hands[active].done = true;
+        if hand.next_hand() {
+            return Ok(());
+        }
+
+        let randomness_account = ctx
+            .accounts
+            .randomness_account
+            .as_ref()
+            .ok_or(BlackjackError::InvalidRandomness)?;
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            ctx.accounts.config.randomness_provider,
+            randomness_account,
+            slot,
+        )?;
+
+        hand.request(
+            PendingAction::DealerPlay,
+            randomness_account.key(),
+            slot,
+            ctx.accounts.config.hand_ttl_slots,
+        )
+    }
+
+    /// Double the wager on a two-card hand and take exactly one more card.
+    pub fn double_down(ctx: Context<RaiseWager>) -> Result<()> {
+        let hand = &ctx.accounts.hand;
+        let active = &hand.hands[hand.active_hand as usize];
+        require!(active.cards.count == 2, BlackjackError::ActionNotAllowed);
+        let amount = active.wager;
+
+        ctx.accounts.hand.raised = ctx.accounts.raise(amount)?;
+        ctx.accounts.hand.request_raised(
+            PendingAction::Double,
+            &ctx.accounts.config,
+            &ctx.accounts.randomness_account,
+        )
+    }
+
+    /// Split a pair of equal-valued opening cards into two hands, matching the wager on
+    /// the second. Split aces take one card each.
+    pub fn split(ctx: Context<RaiseWager>) -> Result<()> {
+        let hand = &ctx.accounts.hand;
+        let first = &hand.hands[0];
+        require!(
+            hand.hand_count == 1
+                && first.cards.count == 2
+                && card_value(first.cards.cards[0]) == card_value(first.cards.cards[1]),
+            BlackjackError::ActionNotAllowed
+        );
+        let amount = first.wager;
+
+        ctx.accounts.hand.raised = ctx.accounts.raise(amount)?;
+        ctx.accounts.hand.request_raised(
+            PendingAction::Split,
+            &ctx.accounts.config,
+            &ctx.accounts.randomness_account,
+        )
+    }
+
+    /// Permissionless: apply the pending action with the revealed randomness. Once the
+    /// player is done the dealer plays out in the same call, the hand is settled from
+    /// the CLR and its account is closed back to the player.
+    pub fn draw(ctx: Context<Draw>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        require!(
+            slot <= ctx.accounts.hand.expiry_slot,
+            BlackjackError::HandExpired
+        );
+
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            ctx.accounts.hand.request_slot,
+        )?;
+
+        let hand = &mut ctx.accounts.hand;
+        hand.apply(&value)?;
+        if hand.next_hand() {
+            hand.pending = PendingAction::None;
+            hand.expiry_slot = slot
+                .checked_add(ctx.accounts.config.hand_ttl_slots)
+                .ok_or(BlackjackError::MathOverflow)?;
+            return Ok(());
+        }
+
+        hand.play_dealer(&value)?;
+        let payout = hand.payout()?;
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(HandFinished {
+            event_nonce,
+            player: hand.player,
+            hand: hand.key(),
+            nonce: hand.nonce,
+            hands: hand.hands,
+            hand_count: hand.hand_count,
+            dealer: hand.dealer,
+            wagered: hand.wagered()?,
+            payout,
+            expired: false,
+        });
+
+        let wagered = ctx.accounts.hand.wagered()?;
+        ctx.accounts
+            .contribute_to_jackpot(ctx.accounts.config.authority_bump, wagered)?;
+        if payout > 0 {
+            send_from_clr(
+                &ctx.accounts.clr_accounts,
+                ctx.accounts.config.authority_bump,
+                payout,
+            )?;
+        }
+        ctx.accounts
+            .hand
+            .close(ctx.accounts.player.to_account_info())
+    }
+
+    /// Permissionless: close a hand that went past its expiry slot. A hand whose opening
+    /// cards were never dealt is refunded; a hand abandoned mid-play is forfeited, as
+    /// refunding it would let a player hold back a reveal they dislike.
+    pub fn close_expired_hand(ctx: Context<CloseExpiredHand>) -> Result<()> {
+        let hand = &ctx.accounts.hand;
+        require!(
+            Clock::get()?.slot > hand.expiry_slot,
+            BlackjackError::HandNotExpired
+        );
+
+        let refund = if hand.pending == PendingAction::Deal {
+            hand.hands[0].wager
+        } else {
+            0
+        };
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(HandFinished {
+            event_nonce,
+            player: hand.player,
+            hand: hand.key(),
+            nonce: hand.nonce,
+            hands: hand.hands,
+            hand_count: hand.hand_count,
+            dealer: hand.dealer,
+            wagered: hand.wagered()?,
+            payout: refund,
+            expired: true,
+        });
+
+        if refund == 0 {
+            return Ok(());
+        }
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            refund,
+        )
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Blackjack value of a card, counting aces as 1.
+pub fn card_value(card: u8) -> u8 {
+    match card % 13 {
+        0 => 1,
+        rank @ 1..=9 => rank + 1,
+        _ => 10,
+    }
+}
+
+/// Move a wager from the player into the CLR vault, returning what reached the vault
+/// net of any transfer fee.
+fn transfer_to_clr<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    clr_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    mutr_mint: &InterfaceAccount<'info, Mint>,
+    player: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<u64> {
+    let clr_vault_before = clr_vault.amount;
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mutr_mint.to_account_info(),
+        to: clr_vault.to_account_info(),
+        authority: player.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, mutr_mint.decimals)?;
+    clr_vault.reload()?;
+    Ok(clr_vault
+        .amount
+        .checked_sub(clr_vault_before)
+        .ok_or(BlackjackError::MathOverflow)?)
+}
+
+/// Pay `amount` from the CLR vault to the player, streamed when it is over the CLR's
+/// vesting threshold (see `mutr_clr_cpi::PayPrize`).
+fn send_from_clr(accounts: &ClrPrize, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let vaults = ClrVaults {
+        mutr_mint: accounts.mutr_mint.key(),
+        clr_vault: accounts.clr_vault.key(),
+        token_program: accounts.token_program.key(),
+    };
+    PayPrize {
+        game: accounts.game_authority.key(),
+        winner: accounts.player_mutr_account.owner,
+        winner_mutr_account: accounts.player_mutr_account.key(),
+        payer: accounts.payer.key(),
+        amount,
+    }
+    .invoke_signed(
+        &vaults,
+        &accounts.clr_state,
+        accounts.clr_vault.amount,
+        &accounts.to_account_infos(),
+        &[authority_seeds],
+    )
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+/// Run a wager through the CLR's player checks, signed by the game authority PDA.
+fn record_wager<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    cpi_accounts: mutr_clr::cpi::accounts::RecordGameWager<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    mutr_clr::cpi::record_game_wager(cpi_ctx, amount)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct BlackjackConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Slots a hand may wait on a reveal or a player decision before it can be closed
+    pub hand_ttl_slots: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl BlackjackConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 8  // min_bet
+        + 8  // max_bet
+        + 8  // hand_ttl_slots
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// Cards held by the player or the dealer, as deck indices `0..52` (rank = index % 13,
+/// ace first).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cards {
+    pub cards: [u8; MAX_HAND_CARDS],
+    pub count: u8,
+}
+
+impl Cards {
+    pub const LEN: usize = MAX_HAND_CARDS // cards
+        + 1; // count
+
+    fn push(&mut self, card: u8) -> Result<()> {
+        let index = self.count as usize;
+        require!(index < MAX_HAND_CARDS, BlackjackError::HandFull);
+        self.cards[index] = card;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Best total, counting one ace as 11 when that does not bust.
+    pub fn total(&self) -> u8 {
+        let cards = &self.cards[..self.count as usize];
+        let hard: u8 = cards.iter().map(|&card| card_value(card)).sum();
+        if hard <= 11 && cards.iter().any(|&card| card_value(card) == 1) {
+            hard + 10
+        } else {
+            hard
+        }
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.total() > 21
+    }
+
+    pub fn is_blackjack(&self) -> bool {
+        self.count == 2 && self.total() == 21
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlayerHand {
+    pub cards: Cards,
+    /// Wager riding on this hand, doubled included
+    pub wager: u64,
+    pub doubled: bool,
+    /// The player can no longer act on this hand
+    pub done: bool,
+}
+
+impl PlayerHand {
+    pub const LEN: usize = Cards::LEN // cards
+        + 8  // wager
+        + 1  // doubled
+        + 1; // done
+}
+
+/// What the pending randomness request will be used for.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PendingAction {
+    /// Waiting on a player decision
+    #[default]
+    None,
+    /// Two cards to the player, the up card to the dealer
+    Deal,
+    Hit,
+    Double,
+    Split,
+    /// The player is done; only the dealer draws
+    DealerPlay,
+}
+
+/// One blackjack hand, from the opening deal to settlement.
+#[account]
+pub struct HandSession {
+    pub player: Pubkey,
+    pub nonce: u64,
+    pub hands: [PlayerHand; MAX_HANDS],
+    pub hand_count: u8,
+    /// Hand the player is acting on
+    pub active_hand: u8,
+    pub dealer: Cards,
+    /// Bit `i` is set once deck card `i` has been dealt
+    pub dealt_mask: u64,
+    pub pending: PendingAction,
+    /// Request the pending action will be resolved with
+    pub randomness_account: Pubkey,
+    pub request_slot: u64,
+    pub expiry_slot: u64,
+    /// Wager the pending double or split added, as it reached the CLR vault
+    pub raised: u64,
+    pub bump: u8,
+}
+
+impl HandSession {
+    pub const LEN: usize = 32 // player
+        + 8  // nonce
+        + PlayerHand::LEN * MAX_HANDS // hands
+        + 1  // hand_count
+        + 1  // active_hand
+        + Cards::LEN // dealer
+        + 8  // dealt_mask
+        + 1  // pending
+        + 32 // randomness_account
+        + 8  // request_slot
+        + 8  // expiry_slot
+        + 8  // raised
+        + 1; // bump
+
+    /// Record a player action waiting on `randomness_account`.
+    fn request(
+        &mut self,
+        action: PendingAction,
+        randomness_account: Pubkey,
+        slot: u64,
+        ttl_slots: u64,
+    ) -> Result<()> {
+        self.pending = action;
+        self.randomness_account = randomness_account;
+        self.request_slot = slot;
+        self.expiry_slot = slot
+            .checked_add(ttl_slots)
+            .ok_or(BlackjackError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Check a fresh request and record a double or split waiting on it.
+    fn request_raised(
+        &mut self,
+        action: PendingAction,
+        config: &BlackjackConfig,
+        randomness_account: &AccountInfo,
+    ) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(config.randomness_provider, randomness_account, slot)?;
+        self.request(
+            action,
+            randomness_account.key(),
+            slot,
+            config.hand_ttl_slots,
+        )
+    }
+
+    /// Deal a card nobody has seen yet from the rest of the deck.
+    fn deal_card(&mut self, value: &[u8; 32]) -> u8 {
+        let dealt = self.dealt_mask.count_ones() as u8;
+        let hash = keccak::hashv(&[value.as_ref(), &[dealt]]).to_bytes();
+        let mut draw_bytes = [0u8; 8];
+        draw_bytes.copy_from_slice(&hash[..8]);
+        let mut draw = u64::from_le_bytes(draw_bytes) % (DECK_SIZE - dealt) as u64;
+
+        for card in 0..DECK_SIZE {
+            if self.dealt_mask & (1 << card) != 0 {
+                continue;
+            }
+            if draw == 0 {
+                self.dealt_mask |= 1 << card;
+                return card;
+            }
+            draw -= 1;
+        }
+        unreachable!("fewer cards dealt than the deck holds")
+    }
+
+    /// Deal the cards the pending action calls for.
+    fn apply(&mut self, value: &[u8; 32]) -> Result<()> {
+        let active = self.active_hand as usize;
+        match self.pending {
+            PendingAction::None => return err!(BlackjackError::NoPendingAction),
+            PendingAction::Deal => {
+                for _ in 0..2 {
+                    let card = self.deal_card(value);
+                    self.hands[0].cards.push(card)?;
+                }
+                let card = self.deal_card(value);
+                self.dealer.push(card)?;
+            }
+            PendingAction::Hit => {
+                let card = self.deal_card(value);
+                self.hands[active].cards.push(card)?;
+            }
+            PendingAction::Double => {
+                let card = self.deal_card(value);
+                let hand = &mut self.hands[active];
+                hand.cards.push(card)?;
+                hand.wager = hand
+                    .wager
+                    .checked_add(self.raised)
+                    .ok_or(BlackjackError::MathOverflow)?;
+                hand.doubled = true;
+                hand.done = true;
+            }
+            PendingAction::Split => {
+                let moved = self.hands[0].cards.cards[1];
+                self.hands[0].cards.count = 1;
+                self.hands[1].cards.push(moved)?;
+                self.hands[1].wager = self.raised;
+                self.hand_count = 2;
+
+                let split_aces = card_value(moved) == 1;
+                for index in 0..MAX_HANDS {
+                    let card = self.deal_card(value);
+                    let hand = &mut self.hands[index];
+                    hand.cards.push(card)?;
+                    hand.done = split_aces;
+                }
+            }
+            PendingAction::DealerPlay => {}
+        }
+
+        for hand in self.hands[..self.hand_count as usize].iter_mut() {
+            if hand.cards.total() >= 21 {
+                hand.done = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move to the first hand the player can still act on. Returns `false` once the
+    /// player is done with every hand.
+    fn next_hand(&mut self) -> bool {
+        while (self.active_hand as usize) < self.hand_count as usize {
+            if !self.hands[self.active_hand as usize].done {
+                return true;
+            }
+            self.active_hand += 1;
+        }
+        false
+    }
+
+    /// Only a natural on the sole hand counts as blackjack; 21 after a split does not.
+    fn is_natural(&self, index: usize) -> bool {
+        self.hand_count == 1 && self.hands[index].cards.is_blackjack()
+    }
+
+    /// Draw the dealer's cards: nothing when every hand busted, just the second card
+    /// against a player blackjack, otherwise up to a standing 17.
+    fn play_dealer(&mut self, value: &[u8; 32]) -> Result<()> {
+        let hands = &self.hands[..self.hand_count as usize];
+        if hands.iter().all(|hand| hand.cards.is_bust()) {
+            return Ok(());
+        }
+        let natural = self.is_natural(0);
+
+        loop {
+            let card = self.deal_card(value);
+            self.dealer.push(card)?;
+            if natural || self.dealer.total() >= 17 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// What the finished hand returns to the player, wagers included.
+    fn payout(&self) -> Result<u64> {
+        let dealer_total = self.dealer.total();
+        let dealer_blackjack = self.dealer.is_blackjack();
+
+        let mut payout: u64 = 0;
+        for (index, hand) in self.hands[..self.hand_count as usize].iter().enumerate() {
+            let total = hand.cards.total();
+            let natural = self.is_natural(index);
+            let wager = hand.wager as u128;
+
+            let hand_payout = if total > 21 {
+                0
+            } else if natural && dealer_blackjack {
+                wager
+            } else if natural {
+                wager * 5 / 2
+            } else if dealer_blackjack {
+                0
+            } else if dealer_total > 21 || total > dealer_total {
+                wager * 2
+            } else if total == dealer_total {
+                wager
+            } else {
+                0
+            };
+            payout = payout
+                .checked_add(u64::try_from(hand_payout).map_err(|_| BlackjackError::MathOverflow)?)
+                .ok_or(BlackjackError::MathOverflow)?;
+        }
+        Ok(payout)
+    }
+
+    /// Total staked on the hand, doubles and splits included.
+    pub fn wagered(&self) -> Result<u64> {
+        self.hands[..self.hand_count as usize]
+            .iter()
+            .try_fold(0u64, |total, hand| total.checked_add(hand.wager))
+            .ok_or_else(|| error!(BlackjackError::MathOverflow))
+    }
+}
+
+/// A finished hand, for the frontend.
+#[event]
+pub struct HandFinished {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub hand: Pubkey,
+    pub nonce: u64,
+    pub hands: [PlayerHand; MAX_HANDS],
+    pub hand_count: u8,
+    pub dealer: Cards,
+    pub wagered: u64,
+    pub payout: u64,
+    /// Closed past its expiry: refunded before the deal, forfeited after
+    pub expired: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeBlackjack<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BlackjackConfig::LEN,
+        seeds = [b"blackjack_config"],
+        bump
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    /// CHECK: PDA signer registered as the blackjack game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBlackjackConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"blackjack_config"],
+        bump = config.bump,
+        has_one = authority @ BlackjackError::Unauthorized
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct StartHand<'info> {
+    #[account(
+        seeds = [b"blackjack_config"],
+        bump = config.bump,
+        has_one = clr_state @ BlackjackError::InvalidClrState
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    /// CHECK: PDA signer registered as the blackjack game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Hands are only dealt while the game is enabled in the CLR
+    #[account(
+        mut,
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ BlackjackError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ BlackjackError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + HandSession::LEN,
+        seeds = [b"hand", player.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub hand: Account<'info, HandSession>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlayerAction<'info> {
+    #[account(
+        seeds = [b"blackjack_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    /// The player may only act between reveals, on a hand still in play
+    #[account(
+        mut,
+        has_one = player @ BlackjackError::InvalidHand,
+        constraint = hand.pending == PendingAction::None @ BlackjackError::ActionPending,
+        constraint = !hand.hands[hand.active_hand as usize].done @ BlackjackError::ActionNotAllowed
+    )]
+    pub hand: Account<'info, HandSession>,
+
+    /// CHECK: fresh request for the cards this action needs; parsed by `randomness`
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RaiseWager<'info> {
+    #[account(
+        seeds = [b"blackjack_config"],
+        bump = config.bump,
+        has_one = clr_state @ BlackjackError::InvalidClrState
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    /// The player may only act between reveals, on a hand still in play
+    #[account(
+        mut,
+        has_one = player @ BlackjackError::InvalidHand,
+        constraint = hand.pending == PendingAction::None @ BlackjackError::ActionPending,
+        constraint = !hand.hands[hand.active_hand as usize].done @ BlackjackError::ActionNotAllowed
+    )]
+    pub hand: Account<'info, HandSession>,
+
+    /// CHECK: PDA signer registered as the blackjack game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CLR config of the game; checked by the CLR
+    #[account(mut)]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ BlackjackError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: fresh request for the cards this action needs; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    pub player: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RaiseWager<'info> {
+    /// Stake another `amount` on the hand, returning what reached the vault.
+    fn raise(&mut self, amount: u64) -> Result<u64> {
+        let amount = transfer_to_clr(
+            &self.player_mutr_account,
+            &mut self.clr_vault,
+            &self.mutr_mint,
+            &self.player,
+            &self.token_program,
+            amount,
+        )?;
+        record_wager(
+            &self.clr_program,
+            mutr_clr::cpi::accounts::RecordGameWager {
+                state: self.clr_state.to_account_info(),
+                game_config: self.clr_game_config.to_account_info(),
+                game_ledger: self.clr_game_ledger.to_account_info(),
+                player_state: self.clr_player_state.to_account_info(),
+                user_stats: self
+                    .clr_user_stats
+                    .as_ref()
+                    .map(|stats| stats.to_account_info()),
+                player: self.player.to_account_info(),
+                game: self.game_authority.to_account_info(),
+            },
+            self.config.authority_bump,
+            amount,
+        )?;
+        Ok(amount)
+    }
+}
+
+/// CLR accounts for paying a player through `send_prize` or `send_vested_prize`.
+#[derive(Accounts)]
+pub struct ClrPrize<'info> {
+    /// CHECK: PDA signer registered as the blackjack game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the player's CLR `PrizeVesting`; created and checked by the CLR when a prize
+    /// is over its vesting threshold
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    /// Funds the player's `PrizeVesting` when a prize opens it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Draw<'info> {
+    #[account(
+        seeds = [b"blackjack_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ BlackjackError::InvalidClrState
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ BlackjackError::InvalidHand,
+        constraint = clr_accounts.player_mutr_account.owner == hand.player @ BlackjackError::InvalidHand
+    )]
+    pub hand: Account<'info, HandSession>,
+
+    /// CHECK: must be the request committed for the pending action; parsed by `randomness`
+    #[account(address = hand.randomness_account @ BlackjackError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: receives the hand account's rent once it is settled
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+impl<'info> Draw<'info> {
+    /// Feed the CLR progressive jackpot its share of the hand's stake.
+    /// The jackpot accounts are required once the jackpot exists.
+    fn contribute_to_jackpot(&self, authority_bump: u8, wager: u64) -> Result<()> {
+        let (Some(jackpot), Some(jackpot_vault)) = (&self.clr_jackpot, &self.clr_jackpot_vault)
+        else {
+            require!(
+                self.clr_accounts.clr_state.load()?.jackpot_enabled == 0,
+                BlackjackError::MissingJackpotAccounts
+            );
+            return Ok(());
+        };
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ContributeJackpot {
+            state: self.clr_accounts.clr_state.to_account_info(),
+            mutr_mint: self.clr_accounts.mutr_mint.to_account_info(),
+            jackpot: jackpot.to_account_info(),
+            clr_vault: self.clr_accounts.clr_vault.to_account_info(),
+            jackpot_vault: jackpot_vault.to_account_info(),
+            game_config: self.clr_accounts.clr_game_config.to_account_info(),
+            game: self.clr_accounts.game_authority.to_account_info(),
+            token_program: self.clr_accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_accounts.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::contribute_jackpot(cpi_ctx, wager)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredHand<'info> {
+    #[account(
+        seeds = [b"blackjack_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ BlackjackError::InvalidClrState
+    )]
+    pub config: Account<'info, BlackjackConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ BlackjackError::InvalidHand,
+        constraint = clr_accounts.player_mutr_account.owner == hand.player @ BlackjackError::InvalidHand,
+        close = player
+    )]
+    pub hand: Account<'info, HandSession>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    /// CHECK: receives the hand account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum BlackjackError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Hand does not belong to the player")]
+    InvalidHand,
+    #[msg("Action not allowed on this hand")]
+    ActionNotAllowed,
+    #[msg("Waiting on a randomness reveal")]
+    ActionPending,
+    #[msg("No action is waiting on randomness")]
+    NoPendingAction,
+    #[msg("Hand cannot hold more cards")]
+    HandFull,
+    #[msg("Hand has expired")]
+    HandExpired,
+    #[msg("Hand has not expired")]
+    HandNotExpired,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpotAccounts,
+}
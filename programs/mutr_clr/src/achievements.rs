@@ -0,0 +1,274 @@
+//! Soulbound achievement NFTs.
+//!
+//! A player's `UserStats` (see `loyalty`) track the milestones they have reached:
+//! `FirstStake` is reached by their first `stake` (or a `stake_on_behalf` naming them)
+//! made with the stats passed, `JackpotHit` by a `hit_jackpot` paying them with the stats
+//! passed, and `Wagered` once their lifetime wagers reach the `wager_milestone` the
+//! authority sets in the `AchievementConfig` with `set_achievement_config`.
+//!
+//! For each milestone reached, the player can call `mint_achievement` once to mint a 1/1
+//! Metaplex NFT at a PDA of their own. The token account is frozen before the master
+//! edition takes over the freeze authority, so the achievement can never leave it.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{
+    self, mpl_token_metadata::types::DataV2, CreateMasterEditionV3, CreateMetadataAccountsV3,
+    Metadata,
+};
+use anchor_spl::token_interface::{
+    self, FreezeAccount, Mint, MintTo, TokenAccount, TokenInterface,
+};
+
+use crate::*;
+
+/// Metaplex symbol of achievement NFTs
+pub const ACHIEVEMENT_SYMBOL: &str = "MUTRA";
+/// Longest achievement base URI, in bytes
+pub const MAX_ACHIEVEMENT_URI_LEN: usize = 180;
+
+pub fn set_milestones(
+    ctx: Context<SetAchievementConfig>,
+    wager_milestone: u64,
+    base_uri: String,
+) -> Result<()> {
+    require!(
+        wager_milestone > 0 && base_uri.len() <= MAX_ACHIEVEMENT_URI_LEN,
+        MutrError::InvalidConfig
+    );
+    let config = &mut ctx.accounts.achievement_config;
+    config.wager_milestone = wager_milestone;
+    config.base_uri = base_uri;
+    config.bump = ctx.bumps.achievement_config;
+    Ok(())
+}
+
+pub fn mint(ctx: Context<MintAchievement>, milestone: Milestone) -> Result<()> {
+    let config = &ctx.accounts.achievement_config;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let reached = match milestone {
+        Milestone::Wagered => user_stats.wagered >= config.wager_milestone,
+        _ => user_stats.milestones & milestone.bit() != 0,
+    };
+    require!(reached, MutrError::MilestoneNotReached);
+    require!(
+        user_stats.achievements & milestone.bit() == 0,
+        MutrError::AchievementMinted
+    );
+    user_stats.achievements |= milestone.bit();
+
+    let state_bump = ctx.accounts.state.load()?.bump;
+    let state_seeds: &[&[u8]] = &[b"state", &[state_bump]];
+    let signer_seeds = &[state_seeds];
+
+    // 1) Mint the achievement and freeze it in the player's account
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.achievement_mint.to_account_info(),
+        to: ctx.accounts.user_achievement_account.to_account_info(),
+        authority: ctx.accounts.state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::mint_to(cpi_ctx, 1)?;
+
+    let cpi_accounts = FreezeAccount {
+        account: ctx.accounts.user_achievement_account.to_account_info(),
+        mint: ctx.accounts.achievement_mint.to_account_info(),
+        authority: ctx.accounts.state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::freeze_account(cpi_ctx)?;
+
+    // 2) Metaplex metadata + master edition (max supply 0 makes it a 1/1)
+    let cpi_accounts = CreateMetadataAccountsV3 {
+        metadata: ctx.accounts.metadata.to_account_info(),
+        mint: ctx.accounts.achievement_mint.to_account_info(),
+        mint_authority: ctx.accounts.state.to_account_info(),
+        payer: ctx.accounts.user.to_account_info(),
+        update_authority: ctx.accounts.state.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    let data = DataV2 {
+        name: milestone.name().to_string(),
+        symbol: ACHIEVEMENT_SYMBOL.to_string(),
+        uri: format!("{}/{}.json", config.base_uri, milestone as u8),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    metadata::create_metadata_accounts_v3(cpi_ctx, data, false, true, None)?;
+
+    let cpi_accounts = CreateMasterEditionV3 {
+        edition: ctx.accounts.master_edition.to_account_info(),
+        mint: ctx.accounts.achievement_mint.to_account_info(),
+        update_authority: ctx.accounts.state.to_account_info(),
+        mint_authority: ctx.accounts.state.to_account_info(),
+        payer: ctx.accounts.user.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    metadata::create_master_edition_v3(cpi_ctx, Some(0))
+}
+
+/// Mark an event milestone reached in the player's stats, when they are passed.
+pub(crate) fn reach(user_stats: &mut Option<Account<'_, UserStats>>, milestone: Milestone) {
+    if let Some(user_stats) = user_stats {
+        user_stats.milestones |= milestone.bit();
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct AchievementConfig {
+    /// Lifetime MUTR wagered that reaches the `Wagered` milestone
+    pub wager_milestone: u64,
+    /// Metadata of milestone `m` is served at `{base_uri}/{m}.json`
+    pub base_uri: String,
+    pub bump: u8,
+}
+
+impl AchievementConfig {
+    pub const LEN: usize = 8 // wager_milestone
+        + 4 + MAX_ACHIEVEMENT_URI_LEN // base_uri
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Milestone {
+    FirstStake,
+    Wagered,
+    JackpotHit,
+}
+
+impl Milestone {
+    /// Bit of the milestone in `UserStats::milestones` and `UserStats::achievements`
+    pub fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Milestone::FirstStake => "MUTR First Stake",
+            Milestone::Wagered => "MUTR High Roller",
+            Milestone::JackpotHit => "MUTR Jackpot Winner",
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetAchievementConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + AchievementConfig::LEN,
+        seeds = [b"achievement_config"],
+        bump
+    )]
+    pub achievement_config: Account<'info, AchievementConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone: Milestone)]
+pub struct MintAchievement<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"achievement_config"],
+        bump = achievement_config.bump
+    )]
+    pub achievement_config: Account<'info, AchievementConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"achievement", user.key().as_ref(), &[milestone as u8]],
+        bump,
+        mint::decimals = 0,
+        mint::authority = state,
+        mint::freeze_authority = state
+    )]
+    pub achievement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = achievement_mint,
+        associated_token::authority = user
+    )]
+    pub user_achievement_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata PDA, validated by the token metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition PDA, validated by the token metadata program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
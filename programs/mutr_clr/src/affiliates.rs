@@ -0,0 +1,192 @@
+//! Affiliate revenue-share agreements.
+//!
+//! An affiliate is a referrer (see `referrals`) the authority has signed a custom deal
+//! with. `set_affiliate` records the agreement in an `Affiliate` PDA next to the
+//! referrer: a share of the revenue, the stake fees and house edge, its users generate,
+//! and how often it is paid. Only revenue generated after the agreement is signed counts.
+//!
+//! Once a payout is due, anyone can call `settle_affiliate`: the affiliate is paid
+//! `revenue_share_bps` of the revenue its referrer has tracked since the last settlement,
+//! from the treasury vault, and the next payout is scheduled one interval later. This
+//! comes on top of the referrer's own cut from the referral vault.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn set_terms(ctx: Context<SetAffiliate>, terms: AffiliateTerms) -> Result<()> {
+    require!(
+        terms.revenue_share_bps as u64 <= BPS_DENOMINATOR && terms.payout_interval_secs > 0,
+        MutrError::InvalidConfig
+    );
+    let now = Clock::get()?.unix_timestamp;
+    let affiliate = &mut ctx.accounts.affiliate;
+    if affiliate.referrer == Pubkey::default() {
+        affiliate.referrer = ctx.accounts.referrer.key();
+        affiliate.settled_revenue = ctx.accounts.referrer.revenue;
+        affiliate.lifetime_paid = 0;
+        affiliate.bump = ctx.bumps.affiliate;
+    }
+    affiliate.revenue_share_bps = terms.revenue_share_bps;
+    affiliate.payout_interval_secs = terms.payout_interval_secs;
+    affiliate.next_payout_ts = now
+        .checked_add(terms.payout_interval_secs)
+        .ok_or(MutrError::MathOverflow)?;
+    affiliate.active = terms.active;
+    Ok(())
+}
+
+/// Permissionless: pay an affiliate its share of the revenue since the last settlement.
+pub fn settle(ctx: Context<SettleAffiliate>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let revenue = ctx.accounts.referrer.revenue;
+    let affiliate = &mut ctx.accounts.affiliate;
+    require!(affiliate.active, MutrError::AffiliateInactive);
+    require!(now >= affiliate.next_payout_ts, MutrError::PayoutNotDue);
+
+    let new_revenue = revenue
+        .checked_sub(affiliate.settled_revenue)
+        .ok_or(MutrError::MathOverflow)?;
+    let amount = (new_revenue as u128 * affiliate.revenue_share_bps as u128
+        / BPS_DENOMINATOR as u128) as u64;
+    require!(amount > 0, MutrError::InvalidAmount);
+
+    affiliate.settled_revenue = revenue;
+    affiliate.lifetime_paid = affiliate
+        .lifetime_paid
+        .checked_add(amount)
+        .ok_or(MutrError::MathOverflow)?;
+    affiliate.next_payout_ts = now
+        .checked_add(affiliate.payout_interval_secs)
+        .ok_or(MutrError::MathOverflow)?;
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.treasury_vault.to_account_info(),
+        ctx.accounts.affiliate_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Affiliate {
+    /// `Referrer` whose users' revenue is shared
+    pub referrer: Pubkey,
+    pub revenue_share_bps: u16,
+    pub payout_interval_secs: i64,
+    pub next_payout_ts: i64,
+    /// Referrer revenue already settled (or generated before the agreement)
+    pub settled_revenue: u64,
+    pub lifetime_paid: u64,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl Affiliate {
+    pub const LEN: usize = 32 // referrer
+        + 2  // revenue_share_bps
+        + 8  // payout_interval_secs
+        + 8  // next_payout_ts
+        + 8  // settled_revenue
+        + 8  // lifetime_paid
+        + 1  // active
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AffiliateTerms {
+    pub revenue_share_bps: u16,
+    pub payout_interval_secs: i64,
+    /// An inactive affiliate keeps its tracking but cannot be settled
+    pub active: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetAffiliate<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"referrer", referrer.code.as_bytes()],
+        bump = referrer.bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Affiliate::LEN,
+        seeds = [b"affiliate", referrer.key().as_ref()],
+        bump
+    )]
+    pub affiliate: Account<'info, Affiliate>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAffiliate<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = treasury_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"referrer", referrer.code.as_bytes()],
+        bump = referrer.bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(
+        mut,
+        seeds = [b"affiliate", referrer.key().as_ref()],
+        bump = affiliate.bump
+    )]
+    pub affiliate: Account<'info, Affiliate>,
+
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payout account, owned by the referrer's owner
+    #[account(
+        mut,
+        constraint = affiliate_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = affiliate_mutr_account.owner == referrer.owner @ MutrError::InvalidRecipient
+    )]
+    pub affiliate_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
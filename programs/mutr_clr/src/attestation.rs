@@ -0,0 +1,75 @@
+//! Signed results from off-chain games.
+//!
+//! A game with a registered oracle key can have its bets settled by anyone presenting
+//! the oracle's Ed25519 signature over `bet || outcome`. The signature is checked by the
+//! native Ed25519 program in the instruction right before ours; we read that
+//! instruction back through the instructions sysvar and match its key and message.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::*;
+
+/// Size of one signature entry in the Ed25519 instruction header
+const ED25519_OFFSETS_LEN: usize = 14;
+/// `num_signatures` + padding
+const ED25519_HEADER_LEN: usize = 2;
+
+/// Message the oracle signs for a bet result: the bet address followed by 1 (won) or 0.
+pub fn result_message(bet: &Pubkey, won: bool) -> [u8; 33] {
+    let mut message = [0u8; 33];
+    message[..32].copy_from_slice(bet.as_ref());
+    message[32] = won as u8;
+    message
+}
+
+/// Require that the previous instruction is an Ed25519 verification of exactly one
+/// signature by `oracle` over `message`, with all data inline.
+pub fn verify_previous_ed25519(
+    instructions_sysvar: &AccountInfo,
+    oracle: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, MutrError::InvalidAttestation);
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        MutrError::InvalidAttestation
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        MutrError::InvalidAttestation
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_HEADER_LEN;
+    let signature_ix = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4) as usize;
+    let public_key_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // signature, key and message must all live in the Ed25519 instruction itself
+    require!(
+        signature_ix == u16::MAX && public_key_ix == u16::MAX && message_ix == u16::MAX,
+        MutrError::InvalidAttestation
+    );
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(MutrError::InvalidAttestation)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(MutrError::InvalidAttestation)?;
+    require!(
+        public_key == oracle.as_ref() && signed_message == message,
+        MutrError::InvalidAttestation
+    );
+    Ok(())
+}
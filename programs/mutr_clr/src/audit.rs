@@ -0,0 +1,114 @@
+//! On-chain audit log of admin actions.
+//!
+//! The `AdminLog` PDA keeps the last `ADMIN_LOG_SIZE` actions of the CLR authority in a
+//! ring buffer. Every authority-gated instruction, and the guardian's pause, requires
+//! the log and appends to it before doing anything else: the instruction's
+//! discriminator, the SHA-256 of its serialized arguments, the slot and the signer. The
+//! hash matches the instruction data after its first 8 bytes, so an auditor checks an
+//! entry against the transaction that made it. `initialize_clr` runs before the log can
+//! exist and is the one action not recorded; `initialize_admin_log` records itself.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::InstructionData;
+
+use crate::*;
+
+/// Entries kept by the log
+pub const ADMIN_LOG_SIZE: usize = 64;
+
+pub fn create_log(ctx: Context<InitializeAdminLog>) -> Result<()> {
+    let admin_log = &mut ctx.accounts.admin_log.load_init()?;
+    admin_log.bump = ctx.bumps.admin_log;
+    admin_log.append(entry(
+        &ctx.accounts.authority,
+        &crate::instruction::InitializeAdminLog {},
+    )?);
+    Ok(())
+}
+
+/// Append the admin instruction `ix`, rebuilt from the handler's arguments, to the log.
+pub(crate) fn record<I: InstructionData>(
+    admin_log: &AccountLoader<AdminLog>,
+    authority: &Signer,
+    ix: &I,
+) -> Result<()> {
+    admin_log.load_mut()?.append(entry(authority, ix)?);
+    Ok(())
+}
+
+fn entry<I: InstructionData>(authority: &Signer, ix: &I) -> Result<AdminLogEntry> {
+    let data = ix.data();
+    let mut instruction = [0u8; 8];
+    instruction.copy_from_slice(&data[..8]);
+    Ok(AdminLogEntry {
+        instruction,
+        params_hash: hash(&data[8..]).to_bytes(),
+        signer: authority.key(),
+        slot: Clock::get()?.slot,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[zero_copy]
+#[derive(Debug)]
+pub struct AdminLogEntry {
+    /// Anchor discriminator of the instruction
+    pub instruction: [u8; 8],
+    /// SHA-256 of the instruction's serialized arguments
+    pub params_hash: [u8; 32],
+    pub signer: Pubkey,
+    pub slot: u64,
+}
+
+/// Ring buffer of admin actions. Zero-copy so the array is not deserialized on every
+/// admin instruction.
+#[account(zero_copy)]
+pub struct AdminLog {
+    pub entries: [AdminLogEntry; ADMIN_LOG_SIZE],
+    /// Actions recorded since the log was created; the latest is at
+    /// `(count - 1) % ADMIN_LOG_SIZE`
+    pub count: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl AdminLog {
+    pub const LEN: usize = std::mem::size_of::<AdminLog>();
+
+    pub fn append(&mut self, entry: AdminLogEntry) {
+        self.entries[(self.count % ADMIN_LOG_SIZE as u64) as usize] = entry;
+        self.count += 1;
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeAdminLog<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminLog::LEN,
+        seeds = [b"admin_log"],
+        bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,1354 @@
+//! Game registry and native bet escrow.
+//!
+//! Games (a game program's signer PDA or an oracle key) are registered by the
+//! authority. Players escrow wagers into the CLR vault with `place_bet`, which opens a
+//! `Bet` PDA; the registered game settles it with `resolve_bet`, paying
+//! `amount * multiplier_bps / 10_000` on a win. Losing wagers stay in the vault.
+//! Revenue that carries no bankroll risk, such as a rake, is deposited by the game with
+//! `deposit_game_profit` and booked as profit; disabled games may still
+//! deposit so they can wind down.
+//! A bet the game never resolves before its expiry slot can be refunded by the player,
+//! less only the transfer fee; the house bears the jackpot share and referral cut it
+//! paid at open. A randomness-backed bet is forfeited instead: anyone can resolve it
+//! once the value is revealed, so only a player holding back a losing draw lets it
+//! expire.
+//! A session key can place bets in the player's place (see `sessions`).
+//! Games that escrow wagers themselves run each one through `record_game_wager`, which
+//! applies the same player checks as `place_bet` and books the wager without opening a
+//! `Bet`.
+//! A bet whose potential payout exceeds the bankroll risk limit is rejected (see `risk`),
+//! and so is a bet over the game's or the player's per-slot rate limit (see
+//! `rate_limit`).
+//! Wagers, payouts and refunds are booked in the game's ledger (see `game_ledger`).
+//! Once the progressive jackpot exists, every bet passes its accounts and feeds it its
+//! share (see `jackpot`).
+//! Passing the winners leaderboard offers a winning bet to it (see `leaderboard`), and
+//! passing the season accounts counts the bet toward the season (see `seasons`).
+//! Passing the player's `UserStats` credits the wager and its loyalty points (see
+//! `loyalty`) and, with the rakeback config, its rakeback (see `rakeback`).
+//! The player's self-exclusion and daily wager cap, and the protocol's daily wager limit,
+//! are enforced on every bet (see `responsible_gaming`).
+//! While USD limits are enabled, the bet and its payout must also fit them (see `pyth`).
+//! `place_bet_usd` sizes the bet in USD cents: the wager is converted to MUTR at the
+//! price when placed, and a win pays the MUTR value of the USD payout at the price when
+//! resolved, so the player carries no MUTR price risk while the bet is open.
+//!
+//! Games with a `RandomnessProvider` take their outcome from a committed randomness
+//! request instead of the game's word (see `randomness`). Off-chain games can register
+//! an oracle key whose signed results anyone may submit (see `attestation`).
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::*;
+
+pub fn register(
+    ctx: Context<RegisterGame>,
+    game: Pubkey,
+    max_multiplier_bps: u32,
+    bet_ttl_slots: u64,
+) -> Result<()> {
+    require!(
+        max_multiplier_bps as u64 >= BPS_DENOMINATOR && bet_ttl_slots > 0,
+        MutrError::InvalidConfig
+    );
+    let game_config = &mut ctx.accounts.game_config.load_init()?;
+    game_config.game = game;
+    game_config.enabled = 1;
+    game_config.max_multiplier_bps = max_multiplier_bps;
+    game_config.bet_ttl_slots = bet_ttl_slots;
+    game_config.randomness_provider = RandomnessProvider::None as u8;
+    game_config.bump = ctx.bumps.game_config;
+
+    let ledger = &mut ctx.accounts.game_ledger.load_init()?;
+    ledger.game = game;
+    ledger.created_slot = Clock::get()?.slot;
+    ledger.bump = ctx.bumps.game_ledger;
+    Ok(())
+}
+
+pub fn set_enabled(ctx: Context<SetGameEnabled>, enabled: bool) -> Result<()> {
+    ctx.accounts.game_config.load_mut()?.enabled = enabled as u8;
+    Ok(())
+}
+
+pub fn set_randomness(
+    ctx: Context<SetGameEnabled>,
+    provider: RandomnessProvider,
+    house_edge_bps: u16,
+) -> Result<()> {
+    require!(
+        (house_edge_bps as u64) < BPS_DENOMINATOR,
+        MutrError::InvalidConfig
+    );
+    let game_config = &mut ctx.accounts.game_config.load_mut()?;
+    game_config.randomness_provider = provider as u8;
+    game_config.house_edge_bps = house_edge_bps;
+    Ok(())
+}
+
+pub fn set_oracle(ctx: Context<SetGameEnabled>, oracle: Pubkey) -> Result<()> {
+    ctx.accounts.game_config.load_mut()?.oracle = oracle;
+    Ok(())
+}
+
+pub fn place(ctx: Context<PlaceBet>, nonce: u64, amount: u64, multiplier_bps: u32) -> Result<()> {
+    open_bet(ctx, nonce, amount, multiplier_bps, 0)
+}
+
+/// Place a bet sized in USD cents, escrowing its MUTR value at the current price.
+pub fn place_usd(
+    ctx: Context<PlaceBet>,
+    nonce: u64,
+    usd_cents: u64,
+    multiplier_bps: u32,
+) -> Result<()> {
+    require!(usd_cents > 0, MutrError::InvalidAmount);
+    let (Some(usd_pricing), Some(price_update)) =
+        (&ctx.accounts.usd_pricing, &ctx.accounts.price_update)
+    else {
+        return err!(MutrError::InvalidPriceFeed);
+    };
+    let amount = pyth::load_price(usd_pricing, price_update)?.cents_to_mutr(usd_cents)?;
+    open_bet(ctx, nonce, amount, multiplier_bps, usd_cents)
+}
+
+fn open_bet(
+    ctx: Context<PlaceBet>,
+    nonce: u64,
+    amount: u64,
+    multiplier_bps: u32,
+    usd_cents: u64,
+) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    sessions::authorize_bet(
+        &ctx.accounts.player,
+        &ctx.accounts.payer,
+        &mut ctx.accounts.session,
+        amount,
+    )?;
+    responsible_gaming::check_wager(&ctx.accounts.player_state, amount)?;
+    let game_config = *ctx.accounts.game_config.load()?;
+    require!(game_config.is_enabled(), MutrError::GameDisabled);
+    require!(
+        multiplier_bps > 0 && multiplier_bps <= game_config.max_multiplier_bps,
+        MutrError::InvalidMultiplier
+    );
+    let payout = bet_payout(amount, multiplier_bps)?;
+    let state = ctx.accounts.state.load()?;
+    guardian::check(&state, PAUSE_BETTING)?;
+    risk::check_payout(&state, ctx.accounts.clr_vault.amount, payout)?;
+    let usd_limits_enabled = state.usd_limits_enabled != 0;
+    let max_daily_wager = state.max_daily_wager;
+    drop(state);
+    responsible_gaming::check_daily_wager(&mut ctx.accounts.user_stats, max_daily_wager, amount)?;
+    if usd_limits_enabled {
+        let (Some(usd_pricing), Some(price_update)) =
+            (&ctx.accounts.usd_pricing, &ctx.accounts.price_update)
+        else {
+            return err!(MutrError::InvalidPriceFeed);
+        };
+        pyth::check_usd_limits(usd_pricing, price_update, amount, payout)?;
+    }
+
+    let slot = Clock::get()?.slot;
+    rate_limit::take_bet_token(
+        &mut *ctx.accounts.game_config.load_mut()?,
+        &mut ctx.accounts.user_stats,
+        slot,
+    )?;
+    let randomness_account = match &ctx.accounts.randomness_account {
+        Some(randomness_account) => {
+            randomness::check_fresh_request(
+                game_config.provider()?,
+                randomness_account,
+                slot,
+            )?;
+            randomness_account.key()
+        }
+        None => {
+            require!(
+                game_config.provider()? == RandomnessProvider::None,
+                MutrError::InvalidRandomness
+            );
+            Pubkey::default()
+        }
+    };
+
+    let clr_vault_before = ctx.accounts.clr_vault.amount;
+    let authority = match &ctx.accounts.session {
+        Some(session) => session.to_account_info(),
+        None => ctx.accounts.player.to_account_info(),
+    };
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.player_mutr_account.to_account_info(),
+        mint: ctx.accounts.mutr_mint.to_account_info(),
+        to: ctx.accounts.clr_vault.to_account_info(),
+        authority,
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    match &ctx.accounts.session {
+        // A session moves the wager as delegate of the player's account
+        Some(session) => {
+            let session_seeds: &[&[u8]] = &[
+                b"session",
+                session.owner.as_ref(),
+                session.session_key.as_ref(),
+                &[session.bump],
+            ];
+            token_interface::transfer_checked(
+                cpi_ctx.with_signer(&[session_seeds]),
+                amount,
+                ctx.accounts.mutr_mint.decimals,
+            )?;
+        }
+        None => {
+            token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?
+        }
+    }
+    // Refundable is what reached the vault; the house carries the jackpot and referral
+    // cuts taken below
+    let escrowed = received_amount(&mut ctx.accounts.clr_vault, clr_vault_before)?;
+
+    match (&mut ctx.accounts.jackpot, &ctx.accounts.jackpot_vault) {
+        (Some(jackpot), Some(jackpot_vault)) => jackpot::contribute(
+            jackpot,
+            jackpot_vault,
+            &ctx.accounts.state,
+            &ctx.accounts.clr_vault,
+            &ctx.accounts.mutr_mint,
+            &ctx.accounts.token_program,
+            amount,
+        )?,
+        _ => require!(
+            ctx.accounts.state.load()?.jackpot_enabled == 0,
+            MutrError::MissingJackpot
+        ),
+    }
+
+    // The referrer's cut of the house edge comes out of the escrowed wager
+    let house_edge =
+        (amount as u128 * game_config.house_edge_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let referral_cut = referrals::credit_referrer(
+        &ctx.accounts.referral_config,
+        &mut ctx.accounts.referrer,
+        &mut ctx.accounts.user_stats,
+        ctx.accounts.player.key(),
+        ReferralSource::HouseEdge,
+        house_edge,
+    )?;
+    referrals::move_cut(
+        &ctx.accounts.token_program,
+        &ctx.accounts.clr_vault,
+        &ctx.accounts.referral_vault,
+        &ctx.accounts.mutr_mint,
+        &ctx.accounts.state,
+        referral_cut,
+    )?;
+
+    let bet = &mut ctx.accounts.bet.load_init()?;
+    bet.game = game_config.game;
+    bet.player = ctx.accounts.player.key();
+    bet.nonce = nonce;
+    bet.amount = amount;
+    bet.multiplier_bps = multiplier_bps;
+    bet.placed_slot = slot;
+    bet.randomness_account = randomness_account;
+    bet.randomness_provider = game_config.randomness_provider;
+    bet.expiry_slot = bet
+        .placed_slot
+        .checked_add(game_config.bet_ttl_slots)
+        .ok_or(MutrError::MathOverflow)?;
+    bet.status = BetStatus::Pending as u8;
+    bet.payout = 0;
+    bet.usd_cents = usd_cents;
+    bet.escrowed = escrowed;
+    bet.bump = ctx.bumps.bet;
+    ctx.accounts.game_ledger.load_mut()?.record_wager(bet)
+}
+
+pub fn resolve(ctx: Context<ResolveBet>, won: bool) -> Result<()> {
+    require!(
+        !ctx.accounts.bet.load()?.uses_randomness(),
+        MutrError::InvalidRandomness
+    );
+    finish_settlement(
+        Settlement {
+            state: &ctx.accounts.state,
+            stats: &ctx.accounts.stats,
+            mutr_mint: &ctx.accounts.mutr_mint,
+            game_config: &ctx.accounts.game_config,
+            game_ledger: &ctx.accounts.game_ledger,
+            bet: &ctx.accounts.bet,
+            clr_vault: &mut ctx.accounts.clr_vault,
+            player_mutr_account: &ctx.accounts.player_mutr_account,
+            usd_pricing: ctx.accounts.usd_pricing.as_deref(),
+            price_update: ctx.accounts.price_update.as_ref(),
+            leaderboard: &ctx.accounts.leaderboard,
+            season: &mut ctx.accounts.season,
+            season_player: &mut ctx.accounts.season_player,
+            season_leaderboard: &ctx.accounts.season_leaderboard,
+            loyalty_config: &ctx.accounts.loyalty_config,
+            user_stats: &mut ctx.accounts.user_stats,
+            rakeback_config: &ctx.accounts.rakeback_config,
+            token_program: &ctx.accounts.token_program,
+        },
+        won,
+    )
+}
+
+/// Permissionless: settle a randomness-backed bet from its revealed value.
+pub fn resolve_with_randomness(ctx: Context<ResolveBetWithRandomness>) -> Result<()> {
+    let bet = *ctx.accounts.bet.load()?;
+    require!(bet.uses_randomness(), MutrError::InvalidRandomness);
+
+    let value = randomness::revealed_value(
+        bet.provider()?,
+        &ctx.accounts.randomness_account,
+        bet.placed_slot,
+    )?;
+    let won = randomness::roll_wins(
+        &value,
+        bet.multiplier_bps,
+        ctx.accounts.game_config.load()?.house_edge_bps,
+    )?;
+
+    finish_settlement(
+        Settlement {
+            state: &ctx.accounts.state,
+            stats: &ctx.accounts.stats,
+            mutr_mint: &ctx.accounts.mutr_mint,
+            game_config: &ctx.accounts.game_config,
+            game_ledger: &ctx.accounts.game_ledger,
+            bet: &ctx.accounts.bet,
+            clr_vault: &mut ctx.accounts.clr_vault,
+            player_mutr_account: &ctx.accounts.player_mutr_account,
+            usd_pricing: ctx.accounts.usd_pricing.as_deref(),
+            price_update: ctx.accounts.price_update.as_ref(),
+            leaderboard: &ctx.accounts.leaderboard,
+            season: &mut ctx.accounts.season,
+            season_player: &mut ctx.accounts.season_player,
+            season_leaderboard: &ctx.accounts.season_leaderboard,
+            loyalty_config: &ctx.accounts.loyalty_config,
+            user_stats: &mut ctx.accounts.user_stats,
+            rakeback_config: &ctx.accounts.rakeback_config,
+            token_program: &ctx.accounts.token_program,
+        },
+        won,
+    )
+}
+
+/// Permissionless: settle a bet from the game oracle's Ed25519-signed result.
+pub fn resolve_with_attestation(ctx: Context<ResolveBetWithAttestation>, won: bool) -> Result<()> {
+    let oracle = ctx.accounts.game_config.load()?.oracle;
+    require_keys_neq!(oracle, Pubkey::default(), MutrError::InvalidAttestation);
+    require!(
+        !ctx.accounts.bet.load()?.uses_randomness(),
+        MutrError::InvalidRandomness
+    );
+    attestation::verify_previous_ed25519(
+        &ctx.accounts.instructions_sysvar,
+        &oracle,
+        &attestation::result_message(&ctx.accounts.bet.key(), won),
+    )?;
+
+    finish_settlement(
+        Settlement {
+            state: &ctx.accounts.state,
+            stats: &ctx.accounts.stats,
+            mutr_mint: &ctx.accounts.mutr_mint,
+            game_config: &ctx.accounts.game_config,
+            game_ledger: &ctx.accounts.game_ledger,
+            bet: &ctx.accounts.bet,
+            clr_vault: &mut ctx.accounts.clr_vault,
+            player_mutr_account: &ctx.accounts.player_mutr_account,
+            usd_pricing: ctx.accounts.usd_pricing.as_deref(),
+            price_update: ctx.accounts.price_update.as_ref(),
+            leaderboard: &ctx.accounts.leaderboard,
+            season: &mut ctx.accounts.season,
+            season_player: &mut ctx.accounts.season_player,
+            season_leaderboard: &ctx.accounts.season_leaderboard,
+            loyalty_config: &ctx.accounts.loyalty_config,
+            user_stats: &mut ctx.accounts.user_stats,
+            rakeback_config: &ctx.accounts.rakeback_config,
+            token_program: &ctx.accounts.token_program,
+        },
+        won,
+    )
+}
+
+/// Settled bets stay on-chain as the audit record until the player closes them.
+pub fn close(ctx: Context<CloseBet>) -> Result<()> {
+    require!(
+        !ctx.accounts.bet.load()?.is_pending(),
+        MutrError::BetNotSettled
+    );
+    Ok(())
+}
+
+/// Run a wager a game escrowed itself through the player checks of `place_bet`.
+pub fn check_game_wager(ctx: Context<RecordGameWager>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    responsible_gaming::check_wager(&ctx.accounts.player_state, amount)?;
+    let state = ctx.accounts.state.load()?;
+    guardian::check(&state, PAUSE_BETTING)?;
+    let max_daily_wager = state.max_daily_wager;
+    drop(state);
+    responsible_gaming::check_daily_wager(&mut ctx.accounts.user_stats, max_daily_wager, amount)?;
+    rate_limit::take_bet_token(
+        &mut *ctx.accounts.game_config.load_mut()?,
+        &mut ctx.accounts.user_stats,
+        Clock::get()?.slot,
+    )?;
+    ctx.accounts.game_ledger.load_mut()?.book_wager(amount)
+}
+
+/// Transfer game revenue into the vault, signed by the game, and book it as profit
+/// (kept in the bankroll while the dividend pool is empty).
+pub fn deposit_profit(ctx: Context<DepositGameProfit>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let clr_vault_before = ctx.accounts.clr_vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.source.to_account_info(),
+        mint: ctx.accounts.mutr_mint.to_account_info(),
+        to: ctx.accounts.clr_vault.to_account_info(),
+        authority: ctx.accounts.game.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+
+    // Book only what arrived after any transfer fee
+    let received = received_amount(&mut ctx.accounts.clr_vault, clr_vault_before)?;
+    let dividend_shares = {
+        let mut state = ctx.accounts.state.load_mut()?;
+        // Nobody to pay: the deposit stays in the bankroll, behind the shares
+        if state.total_dividend_shares > 0 {
+            book_profit(&mut state, received)?;
+        }
+        state.total_dividend_shares
+    };
+
+    let stats = &mut ctx.accounts.stats.load_mut()?;
+    if dividend_shares > 0 {
+        stats.add_profit(received, dividend_shares)?;
+    }
+    stats.record_vault(ctx.accounts.clr_vault.amount)
+}
+
+/// Refund a bet left unresolved past its expiry slot and close it. What the wager
+/// brought into the CLR vault goes back; the jackpot share and referral cut taken at
+/// open stay where they went, at the house's expense. A randomness-backed bet is closed
+/// as lost without a refund.
+pub fn refund_expired(ctx: Context<RefundExpiredBet>) -> Result<()> {
+    let bet = *ctx.accounts.bet.load()?;
+    require!(bet.is_pending(), MutrError::BetNotPending);
+    require!(
+        Clock::get()?.slot > bet.expiry_slot,
+        MutrError::BetNotExpired
+    );
+
+    if bet.uses_randomness() {
+        // The wager stays in the vault and on the game's books as a loss
+        return Ok(());
+    }
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        ctx.accounts.player_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        bet.escrowed,
+    )?;
+    ctx.accounts.game_ledger.load_mut()?.record_refund(&bet)
+}
+
+/// The accounts `finish_settlement` works on, borrowed from whichever resolve
+/// instruction decided the bet.
+struct Settlement<'a, 'info> {
+    state: &'a AccountLoader<'info, GlobalState>,
+    stats: &'a AccountLoader<'info, Stats>,
+    mutr_mint: &'a InterfaceAccount<'info, Mint>,
+    game_config: &'a AccountLoader<'info, GameConfig>,
+    game_ledger: &'a AccountLoader<'info, GameLedger>,
+    bet: &'a AccountLoader<'info, Bet>,
+    clr_vault: &'a mut InterfaceAccount<'info, TokenAccount>,
+    player_mutr_account: &'a InterfaceAccount<'info, TokenAccount>,
+    usd_pricing: Option<&'a UsdPricing>,
+    price_update: Option<&'a UncheckedAccount<'info>>,
+    leaderboard: &'a Option<AccountLoader<'info, Leaderboard>>,
+    season: &'a mut Option<Account<'info, Season>>,
+    season_player: &'a mut Option<Account<'info, SeasonPlayer>>,
+    season_leaderboard: &'a Option<AccountLoader<'info, Leaderboard>>,
+    loyalty_config: &'a Option<Account<'info, LoyaltyConfig>>,
+    user_stats: &'a mut Option<Account<'info, UserStats>>,
+    rakeback_config: &'a Option<Account<'info, RakebackConfig>>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Settle the bet as won or lost, pay the player and book the result in the game
+/// ledger, leaderboards, season, loyalty, rakeback and stats. Shared by every resolve
+/// path once it has decided `won`.
+fn finish_settlement(accounts: Settlement, won: bool) -> Result<()> {
+    let payout = settle_bet(
+        &mut *accounts.bet.load_mut()?,
+        won,
+        accounts.usd_pricing,
+        accounts.price_update,
+    )?;
+    vault_transfer(
+        accounts.token_program.to_account_info(),
+        accounts.clr_vault.to_account_info(),
+        accounts.player_mutr_account.to_account_info(),
+        accounts.mutr_mint,
+        accounts.state.to_account_info(),
+        accounts.state.load()?.bump,
+        payout,
+    )?;
+    let bet = *accounts.bet.load()?;
+    accounts.game_ledger.load_mut()?.record_payout(&bet)?;
+    if let (true, Some(leaderboard)) = (payout > 0, accounts.leaderboard) {
+        leaderboard::offer_win(leaderboard, accounts.bet)?;
+    }
+    seasons::record_bet(
+        accounts.season,
+        accounts.season_player,
+        accounts.season_leaderboard,
+        &bet,
+    )?;
+    loyalty::record_wager(accounts.loyalty_config, accounts.user_stats, &bet)?;
+    rakeback::accrue(
+        accounts.rakeback_config,
+        accounts.user_stats,
+        accounts.game_config.load()?.house_edge_bps,
+        &bet,
+    )?;
+    stats::record_prize(accounts.stats, accounts.clr_vault, payout)
+}
+
+/// Mark a pending, unexpired bet as won or lost. Returns the payout owed; a USD bet
+/// is paid the MUTR value of its USD payout at the current price, capped at the MUTR
+/// payout `risk::check_payout` cleared when it was placed.
+fn settle_bet(
+    bet: &mut Bet,
+    won: bool,
+    usd_pricing: Option<&UsdPricing>,
+    price_update: Option<&UncheckedAccount>,
+) -> Result<u64> {
+    require!(bet.is_pending(), MutrError::BetNotPending);
+    require!(Clock::get()?.slot <= bet.expiry_slot, MutrError::BetExpired);
+
+    if won {
+        bet.payout = if bet.usd_cents > 0 {
+            let (Some(usd_pricing), Some(price_update)) = (usd_pricing, price_update) else {
+                return err!(MutrError::InvalidPriceFeed);
+            };
+            pyth::load_price(usd_pricing, price_update)?
+                .cents_to_mutr(bet_payout(bet.usd_cents, bet.multiplier_bps)?)?
+                .min(bet_payout(bet.amount, bet.multiplier_bps)?)
+        } else {
+            bet_payout(bet.amount, bet.multiplier_bps)?
+        };
+        bet.status = BetStatus::Won as u8;
+    } else {
+        bet.status = BetStatus::Lost as u8;
+    }
+    Ok(bet.payout)
+}
+
+/// `amount * multiplier_bps / 10_000`
+pub fn bet_payout(amount: u64, multiplier_bps: u32) -> Result<u64> {
+    let payout = (amount as u128)
+        .checked_mul(multiplier_bps as u128)
+        .ok_or(MutrError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(payout).map_err(|_| error!(MutrError::MathOverflow))
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// A game (or oracle) allowed to resolve bets and pay prizes from the CLR. Zero-copy,
+/// with fields grouped by alignment so the layout has no implicit padding.
+#[account(zero_copy)]
+pub struct GameConfig {
+    /// Signer that resolves this game's bets
+    pub game: Pubkey,
+    /// Ed25519 key whose signed results settle bets; default when unused
+    pub oracle: Pubkey,
+    /// Slots a bet may stay unresolved before the player can claim a refund
+    pub bet_ttl_slots: u64,
+    /// Slot the game's bet rate bucket was last refilled at (see `rate_limit`)
+    pub rate_updated_slot: u64,
+    /// Highest payout multiplier a bet may request, in bps (10_000 = 1x)
+    pub max_multiplier_bps: u32,
+    /// House edge applied to the win probability of randomness-backed bets
+    pub house_edge_bps: u16,
+    /// Game bet rate bucket: size (0 disables it), tokens regained per slot and tokens
+    /// left
+    pub rate_burst: u16,
+    pub rate_refill_per_slot: u16,
+    pub rate_tokens: u16,
+    /// Size and refill rate of the player bet rate buckets while betting on this game
+    pub player_rate_burst: u16,
+    pub player_rate_refill_per_slot: u16,
+    /// Non-zero while the game may take bets and pay prizes
+    pub enabled: u8,
+    /// Source of bet outcomes, a `RandomnessProvider`; `None` lets the game resolve
+    /// bets itself
+    pub randomness_provider: u8,
+    pub bump: u8,
+    pub _padding: [u8; 5],
+    /// Room for fields added later (see `migrations`)
+    pub _reserved: [u8; 64],
+}
+
+impl GameConfig {
+    pub const LEN: usize = std::mem::size_of::<GameConfig>();
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    pub fn provider(&self) -> Result<RandomnessProvider> {
+        RandomnessProvider::from_stored(self.randomness_provider)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BetStatus {
+    Pending,
+    Won,
+    Lost,
+}
+
+/// Escrowed wager awaiting (or recording) its result. Zero-copy, laid out like
+/// `GameConfig`.
+#[account(zero_copy)]
+pub struct Bet {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    /// Committed randomness request, default for game-resolved bets
+    pub randomness_account: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub placed_slot: u64,
+    /// Last slot the game may resolve the bet; refundable (or forfeited) afterwards
+    pub expiry_slot: u64,
+    pub payout: u64,
+    /// Size in USD cents for bets placed with `place_bet_usd`; 0 for MUTR bets
+    pub usd_cents: u64,
+    /// Part of the wager that reached the CLR vault, net of any transfer fee; what an
+    /// expired bet refunds
+    pub escrowed: u64,
+    /// Payout multiplier on a win, in bps
+    pub multiplier_bps: u32,
+    /// `RandomnessProvider` the request was opened with
+    pub randomness_provider: u8,
+    /// A `BetStatus`
+    pub status: u8,
+    pub bump: u8,
+    pub _padding: [u8; 1],
+}
+
+impl Bet {
+    pub const LEN: usize = std::mem::size_of::<Bet>();
+
+    pub fn uses_randomness(&self) -> bool {
+        self.randomness_provider != RandomnessProvider::None as u8
+    }
+
+    pub fn provider(&self) -> Result<RandomnessProvider> {
+        RandomnessProvider::from_stored(self.randomness_provider)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == BetStatus::Pending as u8
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(game: Pubkey)]
+pub struct RegisterGame<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GameConfig::LEN,
+        seeds = [b"game", game.as_ref()],
+        bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GameLedger::LEN,
+        seeds = [b"game_ledger", game.as_ref()],
+        bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGameEnabled<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game_config.load()?.game.as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct PlaceBet<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game_config.load()?.game.as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", game_config.load()?.game.as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Bet::LEN,
+        seeds = [b"bet", player.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = player_mutr_account.owner == player.key() @ MutrError::Unauthorized
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: randomness request, required for games with a randomness provider
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+
+    /// Progressive jackpot and its vault; required once the jackpot exists
+    #[account(
+        mut,
+        seeds = [b"jackpot"],
+        bump = jackpot.bump
+    )]
+    pub jackpot: Option<Account<'info, Jackpot>>,
+
+    #[account(mut)]
+    pub jackpot_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// USD limits; required with `price_update` while they are enabled
+    #[account(
+        seeds = [b"usd_pricing"],
+        bump = usd_pricing.bump
+    )]
+    pub usd_pricing: Option<Account<'info, UsdPricing>>,
+
+    /// CHECK: Pyth `PriceUpdateV2`, validated by `pyth::load_price`
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", player.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Referral config, the referrer and the referral vault; the referrer earns its cut
+    /// when they are passed with the player's stats (see `referrals`)
+    #[account(
+        seeds = [b"referral_config"],
+        bump = referral_config.bump
+    )]
+    pub referral_config: Option<Account<'info, ReferralConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.code.as_bytes()],
+        bump = referrer.bump
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_vault"],
+        bump
+    )]
+    pub referral_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the player's position 0, holding their self-exclusion and daily wager cap
+    /// when it exists; checked by `responsible_gaming::check_wager`
+    #[account(
+        mut,
+        seeds = [b"user_state", player.key().as_ref(), &0u32.to_le_bytes()],
+        bump
+    )]
+    pub player_state: UncheckedAccount<'info>,
+
+    /// Session whose key signs in the player's place (see `sessions`)
+    #[account(
+        mut,
+        seeds = [b"session", player.key().as_ref(), payer.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, Session>>,
+
+    /// CHECK: the bettor; must sign unless a session of theirs is passed, checked by
+    /// `sessions::authorize_bet`
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// Pays for the bet account: the player, or the session key
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveBet<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", game.key().as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", bet.load()?.player.as_ref(), &bet.load()?.nonce.to_le_bytes()],
+        bump = bet.load()?.bump,
+        constraint = bet.load()?.game == game.key() @ MutrError::Unauthorized
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = player_mutr_account.owner == bet.load()?.player @ MutrError::InvalidRecipient
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// USD pricing and price update; required to pay a winning USD bet
+    #[account(
+        seeds = [b"usd_pricing"],
+        bump = usd_pricing.bump
+    )]
+    pub usd_pricing: Option<Account<'info, UsdPricing>>,
+
+    /// CHECK: Pyth `PriceUpdateV2`, validated by `pyth::load_price`
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    /// Winners leaderboard; a winning bet is offered to it when passed
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[LeaderboardKind::Winners as u8]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Season, the player's season stats and the season leaderboard; the bet counts
+    /// toward the season when all three are passed
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    #[account(mut)]
+    pub season_player: Option<Account<'info, SeasonPlayer>>,
+
+    #[account(mut)]
+    pub season_leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Loyalty config and the player's stats; the wager is credited to the stats when
+    /// they are passed, and earns points when the config is too
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", bet.load()?.player.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Rakeback config; the bet's rakeback accrues to the player's stats when both are
+    /// passed
+    #[account(
+        seeds = [b"rakeback_config"],
+        bump = rakeback_config.bump
+    )]
+    pub rakeback_config: Option<Account<'info, RakebackConfig>>,
+
+    /// Registered game or oracle
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveBetWithRandomness<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"game", bet.load()?.game.as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", bet.load()?.game.as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", bet.load()?.player.as_ref(), &bet.load()?.nonce.to_le_bytes()],
+        bump = bet.load()?.bump
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    /// CHECK: must be the account committed at bet time; parsed in `randomness`
+    #[account(address = bet.load()?.randomness_account @ MutrError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = player_mutr_account.owner == bet.load()?.player @ MutrError::InvalidRecipient
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// USD pricing and price update; required to pay a winning USD bet
+    #[account(
+        seeds = [b"usd_pricing"],
+        bump = usd_pricing.bump
+    )]
+    pub usd_pricing: Option<Account<'info, UsdPricing>>,
+
+    /// CHECK: Pyth `PriceUpdateV2`, validated by `pyth::load_price`
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    /// Winners leaderboard; a winning bet is offered to it when passed
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[LeaderboardKind::Winners as u8]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Season, the player's season stats and the season leaderboard; the bet counts
+    /// toward the season when all three are passed
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    #[account(mut)]
+    pub season_player: Option<Account<'info, SeasonPlayer>>,
+
+    #[account(mut)]
+    pub season_leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Loyalty config and the player's stats; the wager is credited to the stats when
+    /// they are passed, and earns points when the config is too
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", bet.load()?.player.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Rakeback config; the bet's rakeback accrues to the player's stats when both are
+    /// passed
+    #[account(
+        seeds = [b"rakeback_config"],
+        bump = rakeback_config.bump
+    )]
+    pub rakeback_config: Option<Account<'info, RakebackConfig>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveBetWithAttestation<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"game", bet.load()?.game.as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", bet.load()?.game.as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", bet.load()?.player.as_ref(), &bet.load()?.nonce.to_le_bytes()],
+        bump = bet.load()?.bump
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    /// CHECK: instructions sysvar, read for the preceding Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = player_mutr_account.owner == bet.load()?.player @ MutrError::InvalidRecipient
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// USD pricing and price update; required to pay a winning USD bet
+    #[account(
+        seeds = [b"usd_pricing"],
+        bump = usd_pricing.bump
+    )]
+    pub usd_pricing: Option<Account<'info, UsdPricing>>,
+
+    /// CHECK: Pyth `PriceUpdateV2`, validated by `pyth::load_price`
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    /// Winners leaderboard; a winning bet is offered to it when passed
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[LeaderboardKind::Winners as u8]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Season, the player's season stats and the season leaderboard; the bet counts
+    /// toward the season when all three are passed
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    #[account(mut)]
+    pub season_player: Option<Account<'info, SeasonPlayer>>,
+
+    #[account(mut)]
+    pub season_leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Loyalty config and the player's stats; the wager is credited to the stats when
+    /// they are passed, and earns points when the config is too
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", bet.load()?.player.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Rakeback config; the bet's rakeback accrues to the player's stats when both are
+    /// passed
+    #[account(
+        seeds = [b"rakeback_config"],
+        bump = rakeback_config.bump
+    )]
+    pub rakeback_config: Option<Account<'info, RakebackConfig>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBet<'info> {
+    #[account(
+        mut,
+        seeds = [b"bet", player.key().as_ref(), &bet.load()?.nonce.to_le_bytes()],
+        bump = bet.load()?.bump,
+        close = player
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundExpiredBet<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", player.key().as_ref(), &bet.load()?.nonce.to_le_bytes()],
+        bump = bet.load()?.bump,
+        close = player
+    )]
+    pub bet: AccountLoader<'info, Bet>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", bet.load()?.game.as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RecordGameWager<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", game.key().as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    /// CHECK: the player's position 0, holding their self-exclusion and daily wager cap
+    /// when it exists; checked by `responsible_gaming::check_wager`
+    #[account(
+        mut,
+        seeds = [b"user_state", player.key().as_ref(), &0u32.to_le_bytes()],
+        bump
+    )]
+    pub player_state: UncheckedAccount<'info>,
+
+    /// Required while the protocol's daily wager limit is set or the game limits players
+    #[account(
+        mut,
+        seeds = [b"user_stats", player.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    pub player: Signer<'info>,
+
+    /// Registered game taking the wager
+    pub game: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositGameProfit<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Game-controlled account the profit is taken from
+    #[account(
+        mut,
+        constraint = source.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority, owner of `source`
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,38 @@
+//! Anti-sandwich lockup around profit distributions.
+//!
+//! Without a drip, profit lands in reward per share all at once when `record_profit`
+//! books it or the profit epoch rolls over, and emissions do when `harvest_emissions`
+//! runs, so a bot could join the dividend pool right before, then claim and leave right
+//! after. `GlobalState::last_profit_slot` records the slot of the last such distribution
+//! and each position records the slot it last joined at. A position that joined within
+//! `claim_lockup_slots` before a distribution cannot claim its rewards or leave the pool
+//! until `claim_lockup_slots` after it, which makes the sandwich hold its capital through
+//! the window. Positions that joined earlier are not affected; 0 disables the lockup.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Record the current slot as the last profit distribution.
+pub(crate) fn mark_distribution(state: &mut GlobalState) -> Result<()> {
+    state.last_profit_slot = Clock::get()?.slot;
+    Ok(())
+}
+
+/// Reject a claim or leave by a position that joined just before the last distribution,
+/// while its lockup runs.
+pub(crate) fn check(state: &GlobalState, user_state: &UserState) -> Result<()> {
+    let window = state.claim_lockup_slots;
+    // Positions that have not joined since the lockup existed read 0
+    if window == 0 || user_state.joined_slot == 0 {
+        return Ok(());
+    }
+    let joined_before_distribution = user_state.joined_slot <= state.last_profit_slot
+        && state.last_profit_slot - user_state.joined_slot < window;
+    require!(
+        !joined_before_distribution
+            || Clock::get()?.slot >= state.last_profit_slot.saturating_add(window),
+        MutrError::ClaimLocked
+    );
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Position delegates.
+//!
+//! An owner can name one delegate per position with `set_position_delegate`, so an
+//! auto-compounding service can work the position without holding the owner's key. The
+//! delegate may call `claim_rewards`, paying only into a MUTR account the owner holds,
+//! and `compound_rewards`, which restakes the pending MUTR rewards into the position
+//! fee-free. It can never unstake, leave the pool or send rewards elsewhere; setting the
+//! default key revokes it.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn set_delegate(ctx: Context<SetPositionDelegate>, delegate: Pubkey) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.check_movable()?;
+    user_state.delegate = delegate;
+    Ok(())
+}
+
+pub fn compound(ctx: Context<CompoundRewards>) -> Result<()> {
+    let state = *ctx.accounts.state.load()?;
+    guardian::check(&state, PAUSE_STAKING)?;
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.check_movable()?;
+    check_caller(user_state, &ctx.accounts.user, &ctx.accounts.caller)?;
+    // compounded shares can be unstaked at once, so they wait out the lockup like a claim
+    claim_lockup::check(&state, user_state)?;
+
+    let pending = pending_rewards(&state, user_state)?;
+    if pending == 0 {
+        return Ok(());
+    }
+    user_state.pending_rewards = 0;
+    user_state.reward_debt = reward_debt_for(&state, user_state)?;
+
+    // The rewards never leave the vault: price the shares as if they were claimed and
+    // staked again
+    let vault_without_rewards = state
+        .backing(ctx.accounts.clr_vault.amount)
+        .checked_sub(pending)
+        .ok_or(MutrError::MathOverflow)?;
+    let shares = shares_for_deposit(
+        pending,
+        ctx.accounts.xmutr_mint.supply,
+        vault_without_rewards,
+    )?;
+    require!(shares > 0, MutrError::ZeroShares);
+    user_state.staked_shares = user_state
+        .staked_shares
+        .checked_add(shares)
+        .ok_or(MutrError::MathOverflow)?;
+
+    let state_seeds: &[&[u8]] = &[b"state", &[state.bump]];
+    let signer_seeds = &[state_seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.xmutr_mint.to_account_info(),
+        to: ctx.accounts.user_xmutr_account.to_account_info(),
+        authority: ctx.accounts.state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::mint_to(cpi_ctx, shares)?;
+
+    stats::record_share_change(
+        &ctx.accounts.stats,
+        &mut ctx.accounts.clr_vault,
+        &mut ctx.accounts.xmutr_mint,
+        0,
+    )?;
+    deposit_caps::check(
+        &state,
+        &mut ctx.accounts.user_xmutr_account,
+        ctx.accounts.clr_vault.amount,
+        ctx.accounts.xmutr_mint.supply,
+    )
+}
+
+/// Accept the position's owner, or its delegate when one is set.
+pub(crate) fn check_caller(
+    user_state: &UserState,
+    owner: &UncheckedAccount,
+    caller: &Signer,
+) -> Result<()> {
+    require!(
+        caller.key() == owner.key()
+            || (user_state.delegate != Pubkey::default() && caller.key() == user_state.delegate),
+        MutrError::Unauthorized
+    );
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct SetPositionDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct CompoundRewards<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The owner's xMUTR account, credited with the compounded shares
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    /// CHECK: position owner, fixing the `user_state` address
+    pub user: UncheckedAccount<'info>,
+
+    /// The owner or the position's delegate
+    pub caller: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,135 @@
+//! Guarded launch deposit caps.
+//!
+//! While the programs are young the authority can cap the CLR vault (TVL) and the MUTR
+//! value of the xMUTR a single account holds. `stake` and `stake_on_behalf` check both
+//! once the new shares are minted. The caps follow a schedule: they hold at their start
+//! values until `cap_ramp_start`, rise linearly to their targets by `cap_ramp_end` and
+//! stay there, so the launch loosens without an admin transaction per step. A target of
+//! 0 lifts that cap at `cap_ramp_end`. The per-account cap counts the xMUTR account the
+//! shares are minted to; it is a guard rail, not a defence against a staker splitting
+//! deposits over wallets.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::*;
+
+pub fn set_caps(ctx: Context<SetDepositCaps>, schedule: DepositCapSchedule) -> Result<()> {
+    schedule.validate()?;
+    let mut state = ctx.accounts.state.load_mut()?;
+    state.tvl_cap = schedule.tvl_cap;
+    state.user_stake_cap = schedule.user_stake_cap;
+    state.tvl_cap_target = schedule.tvl_cap_target;
+    state.user_stake_cap_target = schedule.user_stake_cap_target;
+    state.cap_ramp_start = schedule.ramp_start;
+    state.cap_ramp_end = schedule.ramp_end;
+    Ok(())
+}
+
+/// Reject a stake that leaves the vault, or the staker's xMUTR, above its current cap.
+/// Call once the shares are minted and the vault and mint reloaded.
+pub(crate) fn check<'info>(
+    state: &GlobalState,
+    xmutr_account: &mut InterfaceAccount<'info, TokenAccount>,
+    vault_balance: u64,
+    xmutr_supply: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let tvl_cap = current_cap(state, state.tvl_cap, state.tvl_cap_target, now)?;
+    require!(
+        tvl_cap == 0 || vault_balance <= tvl_cap,
+        MutrError::TvlCapExceeded
+    );
+
+    let user_stake_cap = current_cap(
+        state,
+        state.user_stake_cap,
+        state.user_stake_cap_target,
+        now,
+    )?;
+    if user_stake_cap == 0 || xmutr_supply == 0 {
+        return Ok(());
+    }
+    xmutr_account.reload()?;
+    let staked = xmutr_account.amount as u128 * vault_balance as u128 / xmutr_supply as u128;
+    require!(
+        staked <= user_stake_cap as u128,
+        MutrError::UserStakeCapExceeded
+    );
+    Ok(())
+}
+
+/// The cap at `now` on its way from `start` to `target`; 0 for no cap.
+fn current_cap(state: &GlobalState, start: u64, target: u64, now: i64) -> Result<u64> {
+    if now >= state.cap_ramp_end {
+        return Ok(target);
+    }
+    if target == 0 || now <= state.cap_ramp_start {
+        return Ok(start);
+    }
+    let elapsed = (now - state.cap_ramp_start) as u128;
+    let duration = (state.cap_ramp_end - state.cap_ramp_start) as u128;
+    let raised = (target - start) as u128 * elapsed / duration;
+    start
+        .checked_add(raised as u64)
+        .ok_or(MutrError::MathOverflow.into())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Caps at `ramp_start` and at `ramp_end`; 0 for no cap.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct DepositCapSchedule {
+    /// Most MUTR the CLR vault may hold after a stake
+    pub tvl_cap: u64,
+    /// Most MUTR value of xMUTR the staker's account may hold after a stake
+    pub user_stake_cap: u64,
+    pub tvl_cap_target: u64,
+    pub user_stake_cap_target: u64,
+    /// Unix timestamps the caps start rising at and reach their targets by
+    pub ramp_start: i64,
+    pub ramp_end: i64,
+}
+
+impl DepositCapSchedule {
+    /// Caps may only rise along the schedule: an uncapped start cannot get a cap later.
+    pub fn validate(&self) -> Result<()> {
+        require!(self.ramp_end >= self.ramp_start, MutrError::InvalidConfig);
+        for (start, target) in [
+            (self.tvl_cap, self.tvl_cap_target),
+            (self.user_stake_cap, self.user_stake_cap_target),
+        ] {
+            require!(
+                target == 0 || (start != 0 && target >= start),
+                MutrError::InvalidConfig
+            );
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetDepositCaps<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,52 @@
+//! Dividend-pool escrow.
+//!
+//! Dividend shares used to be a number in `UserState` while their xMUTR stayed in the
+//! owner's wallet, free to be transferred or sold away from the position earning on it.
+//! Joining the pool now moves the xMUTR into the dividend vault, a token account at
+//! `[b"dividend_vault"]` owned by the state PDA, and leaving moves it back less the exit
+//! fee, whose xMUTR is burned. `UserState::escrowed_shares` counts the escrowed part of
+//! a position's dividend shares: shares that joined before the vault existed stay in
+//! the wallet and leave without a transfer, their exit fee burned from the wallet, so no
+//! position draws on xMUTR it never escrowed.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::*;
+
+/// Move `shares` xMUTR from the owner's wallet into the dividend vault.
+pub(crate) fn escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    user_xmutr_account: &InterfaceAccount<'info, TokenAccount>,
+    xmutr_mint: &InterfaceAccount<'info, Mint>,
+    dividend_vault: &InterfaceAccount<'info, TokenAccount>,
+    user: &Signer<'info>,
+    user_state: &mut UserState,
+    shares: u64,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: user_xmutr_account.to_account_info(),
+        mint: xmutr_mint.to_account_info(),
+        to: dividend_vault.to_account_info(),
+        authority: user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, shares, xmutr_mint.decimals)?;
+
+    user_state.escrowed_shares = user_state
+        .escrowed_shares
+        .checked_add(shares)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+/// Take `shares` leaving dividend shares, `fee_shares` of them forfeited, off the
+/// position's escrow. Returns how much escrowed xMUTR goes back to the owner and how much
+/// of the fee is burned from the vault rather than the wallet; the fee is charged to
+/// escrowed shares first.
+pub(crate) fn release(user_state: &mut UserState, shares: u64, fee_shares: u64) -> (u64, u64) {
+    let from_escrow = shares.min(user_state.escrowed_shares);
+    user_state.escrowed_shares -= from_escrow;
+    let fee_from_escrow = fee_shares.min(from_escrow);
+    (from_escrow - fee_from_escrow, fee_from_escrow)
+}
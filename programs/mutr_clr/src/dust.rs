@@ -0,0 +1,71 @@
+//! Bonus reward dust.
+//!
+//! Bonus tokens reach dividend shares through `acc_reward_per_share_2`, which rounds
+//! down, so a sliver of every funding is never claimable; tokens sent to the bonus vault
+//! outside `fund_bonus_rewards` are not claimable either. `GlobalState::bonus_owed`
+//! tracks what the dividend pool has been credited and not yet claimed, and anyone can
+//! call `sweep_dust` to credit the vault's surplus over it to the current dividend
+//! shares. Only surpluses up to `MAX_SWEEP_DUST` are swept: a larger one means the
+//! tracked balance does not cover the vault, as with bonus rewards funded before it
+//! existed, and is left alone rather than paid out twice.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::*;
+
+/// Largest vault surplus `sweep_dust` folds into dividends, in bonus-token base units
+pub const MAX_SWEEP_DUST: u64 = 1_000_000;
+
+pub fn sweep(ctx: Context<SweepDust>) -> Result<()> {
+    let mut state = ctx.accounts.state.load_mut()?;
+    let surplus = ctx
+        .accounts
+        .bonus_vault
+        .amount
+        .saturating_sub(state.bonus_owed);
+    require!(surplus > 0, MutrError::NothingToSweep);
+    require!(surplus <= MAX_SWEEP_DUST, MutrError::DustTooLarge);
+    credit_bonus(&mut state, surplus)
+}
+
+/// Fold `amount` bonus tokens into `acc_reward_per_share_2` and track the part the
+/// dividend shares can actually claim.
+pub(crate) fn credit_bonus(state: &mut GlobalState, amount: u64) -> Result<()> {
+    require!(state.total_dividend_shares > 0, MutrError::NoDividendShares);
+    let increment = (amount as u128)
+        .checked_mul(REWARD_PRECISION)
+        .ok_or(MutrError::MathOverflow)?
+        / state.total_dividend_shares;
+    state.acc_reward_per_share_2 = state
+        .acc_reward_per_share_2
+        .checked_add(increment)
+        .ok_or(MutrError::MathOverflow)?;
+
+    let credited = increment
+        .checked_mul(state.total_dividend_shares)
+        .ok_or(MutrError::MathOverflow)?
+        / REWARD_PRECISION;
+    state.bonus_owed = state
+        .bonus_owed
+        .checked_add(credited as u64)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SweepDust<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = bonus_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub bonus_vault: InterfaceAccount<'info, TokenAccount>,
+}
@@ -0,0 +1,265 @@
+//! Timelocked emergency withdrawal.
+//!
+//! For incident response the authority can move MUTR out of the CLR vault, but only to
+//! the recovery vault it registered beforehand with `set_recovery_vault`, and only
+//! `EMERGENCY_WITHDRAW_DELAY_SECS` after queueing the withdrawal with
+//! `queue_emergency_withdraw`. The queued amount, destination and execution time sit in
+//! the `EmergencyWithdrawal` account and the `EmergencyWithdrawQueued` event, so stakers
+//! see it coming and can unstake before anyone runs `execute_emergency_withdraw`. The
+//! delay is a constant, so no config change can shorten it. One withdrawal is queued at
+//! a time; registering another recovery vault drops it.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Time between queueing an emergency withdrawal and executing it
+pub const EMERGENCY_WITHDRAW_DELAY_SECS: i64 = 3 * SECONDS_PER_DAY;
+
+pub fn set_vault(ctx: Context<SetRecoveryVault>) -> Result<()> {
+    let emergency = &mut ctx.accounts.emergency_withdrawal;
+    emergency.recovery_vault = ctx.accounts.recovery_vault.key();
+    emergency.amount = 0;
+    emergency.executable_at = 0;
+    emergency.bump = ctx.bumps.emergency_withdrawal;
+    Ok(())
+}
+
+pub fn queue(ctx: Context<QueueEmergencyWithdraw>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let emergency = &mut ctx.accounts.emergency_withdrawal;
+    require!(
+        emergency.executable_at == 0,
+        MutrError::EmergencyWithdrawPending
+    );
+    emergency.amount = amount;
+    emergency.executable_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(EMERGENCY_WITHDRAW_DELAY_SECS)
+        .ok_or(MutrError::MathOverflow)?;
+
+    emit!(EmergencyWithdrawQueued {
+        event_nonce: next_nonce(&mut *ctx.accounts.state.load_mut()?)?,
+        amount,
+        recovery_vault: emergency.recovery_vault,
+        executable_at: emergency.executable_at,
+    });
+    Ok(())
+}
+
+pub fn cancel_withdraw(ctx: Context<CancelEmergencyWithdraw>) -> Result<()> {
+    let emergency = &mut ctx.accounts.emergency_withdrawal;
+    require!(emergency.executable_at != 0, MutrError::NoEmergencyWithdraw);
+    emergency.amount = 0;
+    emergency.executable_at = 0;
+    Ok(())
+}
+
+pub fn execute_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+    let emergency = &mut ctx.accounts.emergency_withdrawal;
+    require!(emergency.executable_at != 0, MutrError::NoEmergencyWithdraw);
+    require!(
+        Clock::get()?.unix_timestamp >= emergency.executable_at,
+        MutrError::EmergencyWithdrawNotReady
+    );
+    // the vault may have shrunk since the withdrawal was queued; streamed prizes stay
+    let amount = emergency.amount.min(
+        ctx.accounts
+            .state
+            .load()?
+            .backing(ctx.accounts.clr_vault.amount),
+    );
+    emergency.amount = 0;
+    emergency.executable_at = 0;
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        ctx.accounts.recovery_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )?;
+    stats::record_share_change(
+        &ctx.accounts.stats,
+        &mut ctx.accounts.clr_vault,
+        &mut ctx.accounts.xmutr_mint,
+        0,
+    )?;
+
+    emit!(EmergencyWithdrawExecuted {
+        event_nonce: next_nonce(&mut *ctx.accounts.state.load_mut()?)?,
+        amount,
+        recovery_vault: ctx.accounts.recovery_vault.key(),
+    });
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct EmergencyWithdrawal {
+    /// MUTR account the withdrawal pays into
+    pub recovery_vault: Pubkey,
+    /// MUTR queued for withdrawal
+    pub amount: u64,
+    /// Unix timestamp the queued withdrawal can run from, 0 for none queued
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+impl EmergencyWithdrawal {
+    pub const LEN: usize = 32 // recovery_vault
+        + 8  // amount
+        + 8  // executable_at
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetRecoveryVault<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + EmergencyWithdrawal::LEN,
+        seeds = [b"emergency_withdrawal"],
+        bump
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    #[account(
+        constraint = recovery_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = recovery_vault.key() != state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub recovery_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+
+    /// Rent payer for the account (separate so a PDA authority can register)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"emergency_withdrawal"],
+        bump = emergency_withdrawal.bump
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEmergencyWithdraw<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"emergency_withdrawal"],
+        bump = emergency_withdrawal.bump
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        seeds = [b"emergency_withdrawal"],
+        bump = emergency_withdrawal.bump,
+        has_one = recovery_vault @ MutrError::InvalidVault
+    )]
+    pub emergency_withdrawal: Account<'info, EmergencyWithdrawal>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub recovery_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub executor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,375 @@
+//! MUTR emissions to dividend-pool members, independent of game profits.
+//!
+//! A pre-funded emissions vault owned by the state PDA releases
+//! `emission_rate_per_slot` MUTR per slot until `emission_end_slot`. The permissionless
+//! `harvest_emissions` crank moves the accrued amount into the CLR vault and folds it
+//! into `acc_reward_per_share`, so emissions are claimed through `claim_rewards`.
+//!
+//! The `EmissionSchedule` account lists slot milestones at which the per-slot rate is
+//! halved; accrual applies them on-chain, splitting the elapsed range at each one.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Maximum number of halving milestones in an `EmissionSchedule`
+pub const MAX_EMISSION_HALVINGS: usize = 32;
+
+pub fn initialize(
+    ctx: Context<InitializeEmissions>,
+    emission_rate_per_slot: u64,
+    emission_end_slot: u64,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    require!(emission_end_slot >= slot, MutrError::InvalidConfig);
+
+    let state = &mut ctx.accounts.state.load_mut()?;
+    state.emission_vault = ctx.accounts.emission_vault.key();
+    state.emission_rate_per_slot = emission_rate_per_slot;
+    state.emission_end_slot = emission_end_slot;
+    state.emission_last_slot = slot;
+
+    let schedule = &mut ctx.accounts.emission_schedule;
+    schedule.halving_slots = Vec::new();
+    schedule.next_halving = 0;
+    schedule.bump = ctx.bumps.emission_schedule;
+    Ok(())
+}
+
+/// Replace the halving milestones. They must be strictly increasing and in the future.
+pub fn set_halvings(ctx: Context<SetEmissionHalvings>, halving_slots: Vec<u64>) -> Result<()> {
+    require!(
+        halving_slots.len() <= MAX_EMISSION_HALVINGS,
+        MutrError::InvalidConfig
+    );
+    let slot = Clock::get()?.slot;
+    let mut previous = slot;
+    for &milestone in &halving_slots {
+        require!(milestone > previous, MutrError::InvalidConfig);
+        previous = milestone;
+    }
+
+    let schedule = &mut ctx.accounts.emission_schedule;
+    schedule.halving_slots = halving_slots;
+    schedule.next_halving = 0;
+    Ok(())
+}
+
+/// Accrue emissions at the old rate, then switch to the new rate and end slot.
+pub fn update(
+    ctx: Context<UpdateEmissions>,
+    emission_rate_per_slot: u64,
+    emission_end_slot: u64,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    require!(emission_end_slot >= slot, MutrError::InvalidConfig);
+
+    let available = ctx.accounts.emission_vault.amount;
+    let (emitted, _) = accrue_emissions(
+        &mut *ctx.accounts.state.load_mut()?,
+        &mut ctx.accounts.emission_schedule,
+        slot,
+        available,
+        0,
+    )?;
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.emission_vault.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        emitted,
+    )?;
+
+    let state = &mut ctx.accounts.state.load_mut()?;
+    state.emission_rate_per_slot = emission_rate_per_slot;
+    state.emission_end_slot = emission_end_slot;
+    Ok(())
+}
+
+/// Permissionless: release emissions accrued since the last harvest. The caller
+/// earns `keeper_fee_bps` of the released amount.
+pub fn harvest(ctx: Context<HarvestEmissions>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let mut state = ctx.accounts.state.load_mut()?;
+    require!(
+        state.emission_last_slot < slot.min(state.emission_end_slot),
+        MutrError::NothingToHarvest
+    );
+
+    let available = ctx.accounts.emission_vault.amount;
+    let keeper_fee_bps = state.keeper_fee_bps;
+    let (distributed, keeper_fee) = accrue_emissions(
+        &mut state,
+        &mut ctx.accounts.emission_schedule,
+        slot,
+        available,
+        keeper_fee_bps,
+    )?;
+    claim_lockup::mark_distribution(&mut state)?;
+    let state_bump = state.bump;
+    // The transfers below borrow the state account as the vault authority
+    drop(state);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.emission_vault.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        state_bump,
+        distributed,
+    )?;
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.emission_vault.to_account_info(),
+        ctx.accounts.keeper_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        state_bump,
+        keeper_fee,
+    )?;
+
+    ctx.accounts.clr_vault.reload()?;
+    twap::observe(
+        &ctx.accounts.share_price_oracle,
+        ctx.accounts.clr_vault.amount,
+        ctx.accounts.xmutr_mint.supply,
+    )
+}
+
+/// Accrue emissions up to `slot`, halving the rate at every milestone passed and
+/// capping the total by what the vault still holds; `keeper_fee_bps` is held back for
+/// the caller. Slots that pass while the dividend pool is empty are skipped rather than
+/// back-paid. Returns `(distributed, keeper_fee)`, both to be moved out of the
+/// emissions vault.
+fn accrue_emissions(
+    state: &mut GlobalState,
+    schedule: &mut EmissionSchedule,
+    slot: u64,
+    available: u64,
+    keeper_fee_bps: u16,
+) -> Result<(u64, u64)> {
+    let until = slot.min(state.emission_end_slot);
+    if until <= state.emission_last_slot {
+        return Ok((0, 0));
+    }
+
+    let mut accrued: u64 = 0;
+    let mut from = state.emission_last_slot;
+    while let Some(&milestone) = schedule.halving_slots.get(schedule.next_halving as usize) {
+        if milestone > until {
+            break;
+        }
+        let segment = milestone.saturating_sub(from);
+        accrued = segment
+            .checked_mul(state.emission_rate_per_slot)
+            .and_then(|emitted| accrued.checked_add(emitted))
+            .ok_or(MutrError::MathOverflow)?;
+        from = from.max(milestone);
+        state.emission_rate_per_slot /= 2;
+        schedule.next_halving += 1;
+    }
+    accrued = (until - from)
+        .checked_mul(state.emission_rate_per_slot)
+        .and_then(|emitted| accrued.checked_add(emitted))
+        .ok_or(MutrError::MathOverflow)?;
+
+    state.emission_last_slot = until;
+    if state.total_dividend_shares == 0 {
+        return Ok((0, 0));
+    }
+
+    let amount = accrued.min(available);
+    let distributed = after_cut(amount, keeper_fee_bps)?;
+    distribute_profit(state, distributed)?;
+    Ok((distributed, amount - distributed))
+}
+
+/// Slot milestones at which the emission rate halves.
+#[account]
+pub struct EmissionSchedule {
+    pub halving_slots: Vec<u64>,
+    /// Index of the next milestone still to apply
+    pub next_halving: u8,
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    pub const LEN: usize = 4 + 8 * MAX_EMISSION_HALVINGS // halving_slots
+        + 1  // next_halving
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeEmissions<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// Pre-funded MUTR emissions vault; top it up with a plain token transfer
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"emission_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub emission_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmissionSchedule::LEN,
+        seeds = [b"emission_schedule"],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmissionHalvings<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"emission_schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEmissions<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = emission_vault @ MutrError::InvalidVault,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"emission_schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    #[account(mut)]
+    pub emission_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestEmissions<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = emission_vault @ MutrError::InvalidVault,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"emission_schedule"],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    #[account(mut)]
+    pub emission_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the keeper fee
+    #[account(
+        mut,
+        constraint = keeper_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub keeper_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Share price oracle; the new price is recorded when passed
+    #[account(
+        mut,
+        seeds = [b"share_price_oracle"],
+        bump = share_price_oracle.load()?.bump
+    )]
+    pub share_price_oracle: Option<AccountLoader<'info, SharePriceOracle>>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,94 @@
+//! Event sequence numbers.
+//!
+//! Game programs emit the protocol's events. Before emitting one, a game takes the next
+//! `event_nonce` from `GlobalState` with `next_event_nonce` and stamps it on the event,
+//! so the nonces of all games form one gapless sequence. Indexers detect a missed event
+//! as a gap and drop a replayed one as a repeat, however the RPC node reorders or
+//! re-delivers logs. The CLR's own events, below, draw from the same sequence.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+pub fn take_nonce(ctx: Context<NextEventNonce>) -> Result<u64> {
+    next_nonce(&mut *ctx.accounts.state.load_mut()?)
+}
+
+/// Advance the event sequence and return the new nonce.
+pub(crate) fn next_nonce(state: &mut GlobalState) -> Result<u64> {
+    state.event_nonce = state
+        .event_nonce
+        .checked_add(1)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(state.event_nonce)
+}
+
+// -----------------------------------------------------------------------------
+// Events
+// -----------------------------------------------------------------------------
+
+#[event]
+pub struct DividendPoolLeft {
+    pub event_nonce: u64,
+    pub user: Pubkey,
+    pub user_state: Pubkey,
+    /// Dividend shares withdrawn, fee included
+    pub shares: u64,
+    pub fee_bps: u16,
+    /// xMUTR forfeited as the exit fee
+    pub fee_shares: u64,
+    /// MUTR value of `fee_shares`, split per `fee_split`
+    pub fee_amount: u64,
+}
+
+/// The forfeited xMUTR of a dividend-pool exit fee was burned.
+#[event]
+pub struct ExitFeeBurned {
+    pub event_nonce: u64,
+    pub user_state: Pubkey,
+    pub shares: u64,
+    /// xMUTR burned by exit fees so far
+    pub total_burned: u64,
+}
+
+/// An emergency withdrawal was queued; it can run from `executable_at`.
+#[event]
+pub struct EmergencyWithdrawQueued {
+    pub event_nonce: u64,
+    pub amount: u64,
+    pub recovery_vault: Pubkey,
+    pub executable_at: i64,
+}
+
+/// A queued emergency withdrawal moved MUTR out of the CLR vault.
+#[event]
+pub struct EmergencyWithdrawExecuted {
+    pub event_nonce: u64,
+    /// MUTR moved, capped at the vault balance
+    pub amount: u64,
+    pub recovery_vault: Pubkey,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct NextEventNonce<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// Any registered game, enabled or not, so refunds of a disabled game are numbered
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+}
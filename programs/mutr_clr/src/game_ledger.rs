@@ -0,0 +1,171 @@
+//! Per-game house accounting.
+//!
+//! Each registered game has a `GameLedger` at `[b"game_ledger", game]`, created with the
+//! game by `register_game`; games registered before ledgers existed get theirs from
+//! anyone with `initialize_game_ledger`. Every bet escrowed through `place_bet` adds its
+//! MUTR wager and counts toward `bet_count`, every resolution adds its payout, and a
+//! refunded expired bet drops out again, so `net_pnl` is what the house kept from the
+//! game's bets. A ledger books only bets placed after the slot it was created in, so a
+//! bet already open then cannot pay out or refund against totals it never entered.
+//! Games that escrow wagers themselves book each one through `record_game_wager` and
+//! their payouts through `send_prize` and `send_vested_prize`; a game paying winners
+//! out of its own escrow books that part with `record_game_payout`. These carry no
+//! `Bet`, so they are booked whenever the ledger exists. Jackpot contributions, referral
+//! cuts, `send_prize_many` and `credit_prize` payouts are outside the bet flow and not
+//! booked here.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+pub fn create_ledger(ctx: Context<InitializeGameLedger>) -> Result<()> {
+    let ledger = &mut ctx.accounts.game_ledger.load_init()?;
+    ledger.game = ctx.accounts.game_config.load()?.game;
+    ledger.created_slot = Clock::get()?.slot;
+    ledger.bump = ctx.bumps.game_ledger;
+    Ok(())
+}
+
+/// Book what a game paid winners out of its own escrow.
+pub fn book_game_payout(ctx: Context<RecordGamePayout>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    ctx.accounts.game_ledger.load_mut()?.book_payout(amount)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Running totals of one game's bets, in MUTR. Zero-copy, laid out like `GameConfig`.
+#[account(zero_copy)]
+pub struct GameLedger {
+    /// Wagers less payouts; positive while the house is ahead
+    pub net_pnl: i128,
+    pub game: Pubkey,
+    pub total_wagered: u64,
+    pub total_payouts: u64,
+    pub bet_count: u64,
+    /// Only bets placed after this slot are booked
+    pub created_slot: u64,
+    pub bump: u8,
+    pub _padding: [u8; 15],
+}
+
+impl GameLedger {
+    pub const LEN: usize = std::mem::size_of::<GameLedger>();
+
+    fn books(&self, bet: &Bet) -> bool {
+        bet.placed_slot > self.created_slot
+    }
+
+    pub fn record_wager(&mut self, bet: &Bet) -> Result<()> {
+        if !self.books(bet) {
+            return Ok(());
+        }
+        self.book_wager(bet.amount)
+    }
+
+    /// Add a wager taken outside `place_bet`, counted as one bet.
+    pub fn book_wager(&mut self, amount: u64) -> Result<()> {
+        self.total_wagered = self
+            .total_wagered
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        self.bet_count = self
+            .bet_count
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+        self.net_pnl = self
+            .net_pnl
+            .checked_add(amount as i128)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_payout(&mut self, bet: &Bet) -> Result<()> {
+        if !self.books(bet) {
+            return Ok(());
+        }
+        self.book_payout(bet.payout)
+    }
+
+    /// Add a payout made outside `resolve_bet`.
+    pub fn book_payout(&mut self, payout: u64) -> Result<()> {
+        self.total_payouts = self
+            .total_payouts
+            .checked_add(payout)
+            .ok_or(MutrError::MathOverflow)?;
+        self.net_pnl = self
+            .net_pnl
+            .checked_sub(payout as i128)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Take a refunded bet back out of the totals.
+    pub fn record_refund(&mut self, bet: &Bet) -> Result<()> {
+        if !self.books(bet) {
+            return Ok(());
+        }
+        let amount = bet.amount;
+        self.total_wagered = self
+            .total_wagered
+            .checked_sub(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        self.bet_count = self
+            .bet_count
+            .checked_sub(1)
+            .ok_or(MutrError::MathOverflow)?;
+        self.net_pnl = self
+            .net_pnl
+            .checked_sub(amount as i128)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct RecordGamePayout<'info> {
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", game.key().as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    /// Registered game that paid out
+    pub game: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGameLedger<'info> {
+    #[account(
+        seeds = [b"game", game_config.load()?.game.as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + GameLedger::LEN,
+        seeds = [b"game_ledger", game_config.load()?.game.as_ref()],
+        bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
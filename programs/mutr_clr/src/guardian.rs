@@ -0,0 +1,112 @@
+//! Emergency pause.
+//!
+//! `GlobalState::pause_flags` halts staking, withdrawals (unstaking and reward claims)
+//! or new bets; setting all of them at once with `PAUSE_ALL` is the circuit breaker. The
+//! authority sets and clears the flags. The guardian, a second key the authority names
+//! (ideally a security council multisig), can only raise them: it changes no parameter
+//! and moves no funds, so it can react fast without being trusted with either. Pending
+//! bets still resolve and refund while betting is paused.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Halts `stake`, `stake_on_behalf` and `stake_with_referral`
+pub const PAUSE_STAKING: u8 = 1 << 0;
+/// Halts `unstake`, the reward claims and `claim_vested_prize`
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+/// Halts new bets
+pub const PAUSE_BETTING: u8 = 1 << 2;
+/// Circuit breaker
+pub const PAUSE_ALL: u8 = PAUSE_STAKING | PAUSE_WITHDRAWALS | PAUSE_BETTING;
+
+pub fn appoint(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    ctx.accounts.state.load_mut()?.guardian = guardian;
+    Ok(())
+}
+
+pub fn set_flags(ctx: Context<SetPauseFlags>, flags: u8) -> Result<()> {
+    require!(flags & !PAUSE_ALL == 0, MutrError::InvalidConfig);
+    ctx.accounts.state.load_mut()?.pause_flags = flags;
+    Ok(())
+}
+
+/// Raise `flags` on top of the ones already set.
+pub fn pause(ctx: Context<GuardianPause>, flags: u8) -> Result<()> {
+    require!(
+        flags != 0 && flags & !PAUSE_ALL == 0,
+        MutrError::InvalidConfig
+    );
+    ctx.accounts.state.load_mut()?.pause_flags |= flags;
+    Ok(())
+}
+
+/// Reject the instruction while any of `flags` is raised.
+pub(crate) fn check(state: &GlobalState, flags: u8) -> Result<()> {
+    require!(state.pause_flags & flags == 0, MutrError::Paused);
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = guardian @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub guardian: Signer<'info>,
+}
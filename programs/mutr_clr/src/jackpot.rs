@@ -0,0 +1,321 @@
+//! Progressive jackpot shared by the registered games.
+//!
+//! `contribution_bps` of every wager is moved from the CLR vault into the jackpot vault,
+//! so the pot is funded by the house side of the bet flow. Bets escrowed through
+//! `place_bet` contribute through the jackpot accounts; games that take wagers into the
+//! CLR vault themselves report them with `contribute_jackpot`. Once the jackpot exists,
+//! `GlobalState::jackpot_enabled` is set and those accounts are required, so no wager
+//! skips its share. A game pays the whole pot to a winner with `hit_jackpot` when its
+//! own draw lands the (rare) jackpot outcome.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Upper bound on the jackpot contribution (5% of each wager)
+pub const MAX_JACKPOT_CONTRIBUTION_BPS: u16 = 500;
+
+pub fn create(ctx: Context<InitializeJackpot>, contribution_bps: u16) -> Result<()> {
+    require!(
+        contribution_bps <= MAX_JACKPOT_CONTRIBUTION_BPS,
+        MutrError::InvalidConfig
+    );
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.jackpot_vault = ctx.accounts.jackpot_vault.key();
+    jackpot.contribution_bps = contribution_bps;
+    jackpot.total_contributed = 0;
+    jackpot.hits = 0;
+    jackpot.last_winner = Pubkey::default();
+    jackpot.last_payout = 0;
+    jackpot.bump = ctx.bumps.jackpot;
+    ctx.accounts.state.load_mut()?.jackpot_enabled = 1;
+    Ok(())
+}
+
+pub fn set_contribution(ctx: Context<SetJackpotContribution>, contribution_bps: u16) -> Result<()> {
+    require!(
+        contribution_bps <= MAX_JACKPOT_CONTRIBUTION_BPS,
+        MutrError::InvalidConfig
+    );
+    ctx.accounts.jackpot.contribution_bps = contribution_bps;
+    ctx.accounts.state.load_mut()?.jackpot_enabled = 1;
+    Ok(())
+}
+
+pub fn contribute_for(ctx: Context<ContributeJackpot>, wager: u64) -> Result<()> {
+    require!(wager > 0, MutrError::InvalidAmount);
+    contribute(
+        &mut ctx.accounts.jackpot,
+        &ctx.accounts.jackpot_vault,
+        &ctx.accounts.state,
+        &ctx.accounts.clr_vault,
+        &ctx.accounts.mutr_mint,
+        &ctx.accounts.token_program,
+        wager,
+    )
+}
+
+/// Pay the whole pot to the winner.
+pub fn hit(ctx: Context<HitJackpot>) -> Result<()> {
+    let payout = ctx.accounts.jackpot_vault.amount;
+    require!(payout > 0, MutrError::JackpotEmpty);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.jackpot_vault.to_account_info(),
+        ctx.accounts.winner_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        payout,
+    )?;
+
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.hits = jackpot.hits.checked_add(1).ok_or(MutrError::MathOverflow)?;
+    jackpot.last_winner = ctx.accounts.winner_mutr_account.owner;
+    jackpot.last_payout = payout;
+    achievements::reach(&mut ctx.accounts.winner_stats, Milestone::JackpotHit);
+
+    // Paid from the jackpot vault, so the CLR vault balance is unchanged
+    ctx.accounts.stats.load_mut()?.add_prizes(payout)
+}
+
+/// Move the jackpot's share of `wager` from the CLR vault into the jackpot vault.
+pub(crate) fn contribute<'info>(
+    jackpot: &mut Account<'info, Jackpot>,
+    jackpot_vault: &InterfaceAccount<'info, TokenAccount>,
+    state: &AccountLoader<'info, GlobalState>,
+    clr_vault: &InterfaceAccount<'info, TokenAccount>,
+    mutr_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    wager: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        jackpot_vault.key(),
+        jackpot.jackpot_vault,
+        MutrError::InvalidVault
+    );
+    let amount =
+        (wager as u128 * jackpot.contribution_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+    vault_transfer(
+        token_program.to_account_info(),
+        clr_vault.to_account_info(),
+        jackpot_vault.to_account_info(),
+        mutr_mint,
+        state.to_account_info(),
+        state.load()?.bump,
+        amount,
+    )?;
+    jackpot.total_contributed = jackpot
+        .total_contributed
+        .checked_add(amount)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Jackpot {
+    /// MUTR vault holding the pot, owned by the state PDA
+    pub jackpot_vault: Pubkey,
+    /// Share of every wager moved from the CLR vault into the pot
+    pub contribution_bps: u16,
+    pub total_contributed: u64,
+    pub hits: u64,
+    pub last_winner: Pubkey,
+    pub last_payout: u64,
+    pub bump: u8,
+}
+
+impl Jackpot {
+    pub const LEN: usize = 32 // jackpot_vault
+        + 2  // contribution_bps
+        + 8  // total_contributed
+        + 8  // hits
+        + 32 // last_winner
+        + 8  // last_payout
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeJackpot<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Jackpot::LEN,
+        seeds = [b"jackpot"],
+        bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    /// Holds the pot; a plain token transfer seeds it
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"jackpot_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub jackpot_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetJackpotContribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot"],
+        bump = jackpot.bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeJackpot<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot"],
+        bump = jackpot.bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub jackpot_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority reporting the wager
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct HitJackpot<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot"],
+        bump = jackpot.bump,
+        has_one = jackpot_vault @ MutrError::InvalidVault
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(mut)]
+    pub jackpot_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = winner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub winner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Winner's stats; passing them records the jackpot milestone (see `achievements`)
+    #[account(
+        mut,
+        seeds = [b"user_stats", winner_mutr_account.owner.as_ref()],
+        bump = winner_stats.bump
+    )]
+    pub winner_stats: Option<Account<'info, UserStats>>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority whose draw hit the jackpot
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,239 @@
+//! Bounded on-chain leaderboards.
+//!
+//! A `Leaderboard` holds the top `LEADERBOARD_SIZE` entries of one `LeaderboardKind`,
+//! sorted by score, highest first. The stakers board ranks positions by their xMUTR
+//! shares and is offered the position on `stake` / `stake_on_behalf`; the winners board
+//! ranks single bet payouts and is offered a winning bet when it is resolved. Both are
+//! updated opportunistically, only when the board is passed to the instruction. Each
+//! season also gets its own board of players by season winnings (see `seasons`).
+//!
+//! Anyone can bring a missed or stale entry up to date with `challenge_leaderboard`,
+//! proving the score with the `UserState` or settled `Bet` it comes from. An entry
+//! already on the board takes its proven score, higher or lower (a position proven empty
+//! leaves the board); a new entry must beat the lowest score of a full board.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Entries kept per board
+pub const LEADERBOARD_SIZE: usize = 32;
+
+pub fn create_board(ctx: Context<InitializeLeaderboard>, kind: LeaderboardKind) -> Result<()> {
+    let leaderboard = &mut ctx.accounts.leaderboard.load_init()?;
+    leaderboard.kind = kind as u8;
+    leaderboard.bump = ctx.bumps.leaderboard;
+    Ok(())
+}
+
+pub fn challenge(ctx: Context<ChallengeLeaderboard>) -> Result<()> {
+    let kind = ctx.accounts.leaderboard.load()?.kind()?;
+    match kind {
+        LeaderboardKind::Stakers => {
+            let user_state = ctx
+                .accounts
+                .user_state
+                .as_ref()
+                .ok_or(MutrError::InvalidLeaderboardProof)?;
+            offer_position(&ctx.accounts.leaderboard, user_state)
+        }
+        LeaderboardKind::Winners => {
+            let bet = ctx
+                .accounts
+                .bet
+                .as_ref()
+                .ok_or(MutrError::InvalidLeaderboardProof)?;
+            require!(
+                bet.load()?.status == BetStatus::Won as u8,
+                MutrError::InvalidLeaderboardProof
+            );
+            offer_win(&ctx.accounts.leaderboard, bet)
+        }
+        // Season boards live at per-season addresses and only move with season bets
+        LeaderboardKind::Season => err!(MutrError::InvalidLeaderboardProof),
+    }
+}
+
+/// Offer a staking position to the stakers board, scored by its total shares.
+pub(crate) fn offer_position<'info>(
+    leaderboard: &AccountLoader<'info, Leaderboard>,
+    user_state: &Account<'info, UserState>,
+) -> Result<()> {
+    let leaderboard = &mut leaderboard.load_mut()?;
+    require!(
+        leaderboard.kind()? == LeaderboardKind::Stakers,
+        MutrError::InvalidLeaderboardProof
+    );
+    let shares = user_state
+        .staked_shares
+        .checked_add(user_state.dividend_shares)
+        .ok_or(MutrError::MathOverflow)?;
+    leaderboard.offer(user_state.key(), user_state.owner, shares);
+    Ok(())
+}
+
+/// Offer a settled bet to the winners board, scored by its payout.
+pub(crate) fn offer_win<'info>(
+    leaderboard: &AccountLoader<'info, Leaderboard>,
+    bet: &AccountLoader<'info, Bet>,
+) -> Result<()> {
+    let leaderboard = &mut leaderboard.load_mut()?;
+    require!(
+        leaderboard.kind()? == LeaderboardKind::Winners,
+        MutrError::InvalidLeaderboardProof
+    );
+    let bet_data = bet.load()?;
+    leaderboard.offer(bet.key(), bet_data.player, bet_data.payout);
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardKind {
+    /// Staking positions by xMUTR shares
+    Stakers,
+    /// Single bets by payout
+    Winners,
+    /// Players by season winnings, one board per season (see `seasons`)
+    Season,
+}
+
+impl LeaderboardKind {
+    /// Decode a kind stored by discriminant in a zero-copy account.
+    pub fn from_stored(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Stakers),
+            1 => Ok(Self::Winners),
+            2 => Ok(Self::Season),
+            _ => err!(MutrError::InvalidLeaderboardProof),
+        }
+    }
+}
+
+#[zero_copy]
+#[derive(Debug)]
+pub struct LeaderboardEntry {
+    /// The `UserState` or `Bet` the score was proven with
+    pub key: Pubkey,
+    /// Position owner or bet player
+    pub owner: Pubkey,
+    pub score: u64,
+}
+
+/// Top entries of one kind, highest score first. Zero-copy so the fixed array is not
+/// deserialized on every stake or win.
+#[account(zero_copy)]
+pub struct Leaderboard {
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+    /// A `LeaderboardKind`
+    pub kind: u8,
+    /// Number of filled entries
+    pub len: u8,
+    pub bump: u8,
+    pub _padding: [u8; 5],
+}
+
+impl Leaderboard {
+    pub const LEN: usize = std::mem::size_of::<Leaderboard>();
+
+    pub fn kind(&self) -> Result<LeaderboardKind> {
+        LeaderboardKind::from_stored(self.kind)
+    }
+
+    /// Insert or update the entry for `key` and keep the entries sorted. A zero score
+    /// removes the entry.
+    pub fn offer(&mut self, key: Pubkey, owner: Pubkey, score: u64) {
+        let len = self.len as usize;
+        let existing = self.entries[..len].iter().position(|entry| entry.key == key);
+
+        if score == 0 {
+            if let Some(index) = existing {
+                self.entries.copy_within(index + 1..len, index);
+                self.entries[len - 1] = LeaderboardEntry {
+                    key: Pubkey::default(),
+                    owner: Pubkey::default(),
+                    score: 0,
+                };
+                self.len -= 1;
+            }
+            return;
+        }
+
+        let mut index = match existing {
+            Some(index) => index,
+            None if len < LEADERBOARD_SIZE => {
+                self.len += 1;
+                len
+            }
+            // A full board replaces its lowest entry
+            None if score > self.entries[len - 1].score => len - 1,
+            None => return,
+        };
+        self.entries[index] = LeaderboardEntry { key, owner, score };
+
+        let entries = &mut self.entries[..self.len as usize];
+        while index > 0 && entries[index - 1].score < entries[index].score {
+            entries.swap(index - 1, index);
+            index -= 1;
+        }
+        while index + 1 < entries.len() && entries[index + 1].score > entries[index].score {
+            entries.swap(index, index + 1);
+            index += 1;
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(kind: LeaderboardKind)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Leaderboard::LEN,
+        seeds = [b"leaderboard".as_ref(), &[kind as u8]],
+        bump
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeLeaderboard<'info> {
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[leaderboard.load()?.kind]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+
+    /// Proof for the stakers board
+    pub user_state: Option<Account<'info, UserState>>,
+
+    /// Proof for the winners board; must be a won bet
+    pub bet: Option<AccountLoader<'info, Bet>>,
+}
@@ -1,70 +1,440 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer, MintTo};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{
+    self, mpl_token_metadata::types::DataV2, CreateMasterEditionV3, CreateMetadataAccountsV3,
+    Metadata, UpdateMetadataAccountsV2,
+};
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
 
 declare_id!("CLRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
 
+pub mod achievements;
+pub mod affiliates;
+pub mod attestation;
+pub mod audit;
+pub mod bets;
+pub mod claim_lockup;
+pub mod delegates;
+pub mod deposit_caps;
+pub mod dividend_escrow;
+pub mod dust;
+pub mod emergency_withdraw;
+pub mod emissions;
+pub mod events;
+pub mod game_ledger;
+pub mod guardian;
+pub mod jackpot;
+pub mod leaderboard;
+pub mod migrations;
+pub mod loyalty;
+pub mod math;
+pub mod multisig;
+pub mod nft_boosts;
+pub mod operators;
+pub mod pools;
+pub mod prize_vesting;
+pub mod prizes;
+pub mod pyth;
+pub mod rakeback;
+pub mod randomness;
+pub mod rate_limit;
+pub mod recovery;
+pub mod referrals;
+pub mod responsible_gaming;
+pub mod risk;
+pub mod seasons;
+pub mod sessions;
+pub mod smoothing;
+pub mod snapshots;
+pub mod stats;
+pub mod twap;
+pub mod user_state_transfer;
+pub mod views;
+pub mod vip;
+
+pub use randomness::RandomnessProvider;
+
+pub use achievements::*;
+pub use affiliates::*;
+pub use audit::*;
+pub use bets::*;
+pub use delegates::*;
+pub use deposit_caps::*;
+pub use dust::*;
+pub use emergency_withdraw::*;
+pub use emissions::*;
+pub use events::*;
+pub use game_ledger::*;
+pub use guardian::*;
+pub use jackpot::*;
+pub use leaderboard::*;
+pub use migrations::*;
+pub use loyalty::*;
+pub use math::*;
+pub use multisig::*;
+pub use nft_boosts::*;
+pub use operators::*;
+pub use pools::*;
+pub use prize_vesting::*;
+pub use prizes::*;
+pub use pyth::*;
+pub use rakeback::*;
+pub use recovery::*;
+pub use referrals::*;
+pub use responsible_gaming::*;
+pub use seasons::*;
+pub use sessions::*;
+pub use smoothing::*;
+pub use snapshots::*;
+pub use stats::*;
+pub use twap::*;
+pub use user_state_transfer::*;
+pub use views::*;
+pub use vip::*;
+
 /// Precision for reward accounting (like 1e12)
 const REWARD_PRECISION: u128 = 1_000_000_000_000;
 
+/// Symbol used for position receipt NFT metadata
+const RECEIPT_SYMBOL: &str = "xMUTRP";
+
+/// Maximum metadata URI length accepted by Metaplex
+const MAX_RECEIPT_URI_LEN: usize = 200;
+
+/// Maximum metadata name and symbol lengths accepted by Metaplex
+const MAX_METADATA_NAME_LEN: usize = 32;
+const MAX_METADATA_SYMBOL_LEN: usize = 10;
+
+/// Basis-point denominator; also the 1x dividend boost
+const BPS_DENOMINATOR: u64 = 10_000;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Upper bound on the crank keeper fee (1%)
+const MAX_KEEPER_FEE_BPS: u16 = 100;
+
+/// Upper bound on the stake and unstake fees (10%)
+const MAX_FEE_BPS: u16 = 1_000;
+
+/// Upper bound on the dividend-pool exit fee (10%)
+const MAX_EXIT_PENALTY_BPS: u16 = 1_000;
+
+/// Clockwork thread program (automation threads for the permissionless cranks)
+pub mod thread_program {
+    use super::*;
+    declare_id!("CLoCKyJ6DXBJqqu2VWx9RLbgnwwR6BMHHuyasVmfMjBh");
+}
+
 #[program]
 pub mod mutr_clr {
     use super::*;
 
     /// One-time initializer. Creates global state and wires up mints/accounts.
-    pub fn initialize_clr(
-        ctx: Context<InitializeClr>,
-        stake_fee_bps: u16,
-        unstake_fee_bps: u16,
-        lower_threshold: u64,
-        upper_threshold: u64,
-    ) -> Result<()> {
-        let state = &mut ctx.accounts.state;
+    pub fn initialize_clr(ctx: Context<InitializeClr>, config: InitConfig) -> Result<()> {
+        config.validate()?;
+
+        // `state` is `init_if_needed` so that a second call ends here, with a clear error,
+        // rather than failing in the system program
+        let mut state = ctx
+            .accounts
+            .state
+            .load_init()
+            .map_err(|_| error!(MutrError::AlreadyInitialized))?;
         state.authority = ctx.accounts.authority.key();
         state.mutr_mint = ctx.accounts.mutr_mint.key();
         state.xmutr_mint = ctx.accounts.xmutr_mint.key();
         state.clr_vault = ctx.accounts.clr_vault.key();
-        state.stake_fee_bps = stake_fee_bps; // e.g. 300 = 3%
-        state.unstake_fee_bps = unstake_fee_bps; // e.g. 300 = 3%
-        state.lower_threshold = lower_threshold;
-        state.upper_threshold = upper_threshold;
+        state.stake_fee_bps = config.stake_fee_bps; // e.g. 300 = 3%
+        state.unstake_fee_bps = config.unstake_fee_bps; // e.g. 300 = 3%
+        state.lower_threshold = config.lower_threshold;
+        state.upper_threshold = config.upper_threshold;
+        state.exit_penalty_max_bps = config.exit_penalty_max_bps; // e.g. 400 = 4% on joining
+        state.exit_penalty_decay_secs = config.exit_penalty_decay_secs;
+        state.fee_split = FeeSplit::default();
         state.acc_reward_per_share = 0;
         state.total_dividend_shares = 0;
-        state.bump = *ctx.bumps.get("state").unwrap();
+        state.bump = ctx.bumps.state;
+        state.version = GLOBAL_STATE_VERSION;
+        Ok(())
+    }
+
+    /// Admin: update fee and threshold parameters. `None` fields are left unchanged.
+    pub fn update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::UpdateConfig {
+                update: update.clone(),
+            },
+        )?;
+        let mut state = ctx.accounts.state.load_mut()?;
+        if let Some(stake_fee_bps) = update.stake_fee_bps {
+            require!(stake_fee_bps <= MAX_FEE_BPS, MutrError::InvalidConfig);
+            state.stake_fee_bps = stake_fee_bps;
+        }
+        if let Some(unstake_fee_bps) = update.unstake_fee_bps {
+            require!(unstake_fee_bps <= MAX_FEE_BPS, MutrError::InvalidConfig);
+            state.unstake_fee_bps = unstake_fee_bps;
+        }
+        if let Some(lower_threshold) = update.lower_threshold {
+            state.lower_threshold = lower_threshold;
+        }
+        if let Some(upper_threshold) = update.upper_threshold {
+            state.upper_threshold = upper_threshold;
+        }
+        if let Some(exit_penalty_max_bps) = update.exit_penalty_max_bps {
+            require!(exit_penalty_max_bps <= MAX_EXIT_PENALTY_BPS, MutrError::InvalidConfig);
+            state.exit_penalty_max_bps = exit_penalty_max_bps;
+        }
+        if let Some(exit_penalty_decay_secs) = update.exit_penalty_decay_secs {
+            require!(exit_penalty_decay_secs >= 0, MutrError::InvalidConfig);
+            state.exit_penalty_decay_secs = exit_penalty_decay_secs;
+        }
+        if let Some(fee_split) = update.fee_split {
+            fee_split.validate()?;
+            state.fee_split = fee_split;
+        }
+        if let Some(profit_epoch_secs) = update.profit_epoch_secs {
+            require!(profit_epoch_secs >= 0, MutrError::InvalidConfig);
+            // Switching modes must not strand buffered profit
+            require!(
+                state.profit_buffer == 0 && state.smoothing_reserve == 0,
+                MutrError::InvalidConfig
+            );
+            state.profit_epoch_secs = profit_epoch_secs;
+            state.profit_epoch_start = Clock::get()?.unix_timestamp;
+        }
+        if let Some(drip_slots) = update.drip_slots {
+            // Applies from the next release; an in-flight drip keeps its window
+            state.drip_slots = drip_slots;
+        }
+        if let Some(keeper_fee_bps) = update.keeper_fee_bps {
+            require!(keeper_fee_bps <= MAX_KEEPER_FEE_BPS, MutrError::InvalidConfig);
+            state.keeper_fee_bps = keeper_fee_bps;
+        }
+        if let Some(max_payout_bps) = update.max_payout_bps {
+            require!(max_payout_bps as u64 <= BPS_DENOMINATOR, MutrError::InvalidConfig);
+            state.max_payout_bps = max_payout_bps;
+        }
+        if let Some(threshold_bps) = update.prize_vesting_threshold_bps {
+            require!(threshold_bps as u64 <= BPS_DENOMINATOR, MutrError::InvalidConfig);
+            state.prize_vesting_threshold_bps = threshold_bps;
+        }
+        if let Some(upfront_bps) = update.prize_upfront_bps {
+            require!(upfront_bps as u64 <= BPS_DENOMINATOR, MutrError::InvalidConfig);
+            state.prize_upfront_bps = upfront_bps;
+        }
+        if let Some(vesting_slots) = update.prize_vesting_slots {
+            // Applies to prizes streamed from now on
+            state.prize_vesting_slots = vesting_slots;
+        }
+        if let Some(max_daily_wager) = update.max_daily_wager {
+            state.max_daily_wager = max_daily_wager;
+        }
+        if let Some(min_stake) = update.min_stake {
+            state.min_stake = min_stake;
+        }
+        if let Some(min_unstake) = update.min_unstake {
+            state.min_unstake = min_unstake;
+        }
+        if let Some(claim_lockup_slots) = update.claim_lockup_slots {
+            state.claim_lockup_slots = claim_lockup_slots;
+        }
+        if let Some(smoothing_epochs) = update.smoothing_epochs {
+            require!(
+                smoothing_epochs as usize <= MAX_SMOOTHING_EPOCHS,
+                MutrError::InvalidConfig
+            );
+            state.smoothing_epochs = smoothing_epochs;
+        }
+        Ok(())
+    }
+
+    /// Admin: hand the authority role to a new key (e.g. the governance PDA).
+    pub fn set_authority(ctx: Context<SetAuthority>, new_authority: Pubkey) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetAuthority { new_authority },
+        )?;
+        require_keys_neq!(new_authority, Pubkey::default(), MutrError::InvalidConfig);
+        ctx.accounts.state.load_mut()?.authority = new_authority;
+        Ok(())
+    }
+
+    /// Admin: create the protocol treasury vault. Must run before staking opens, since
+    /// the fee paths route the treasury's `fee_split` share into this vault.
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        treasury_spend_delay_secs: i64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeTreasury {
+                treasury_spend_delay_secs,
+            },
+        )?;
+        require!(treasury_spend_delay_secs >= 0, MutrError::InvalidConfig);
+        let mut state = ctx.accounts.state.load_mut()?;
+        state.treasury_vault = ctx.accounts.treasury_vault.key();
+        state.treasury_spend_delay_secs = treasury_spend_delay_secs;
+        Ok(())
+    }
+
+    /// Admin: queue a treasury spend. It becomes executable after the spend delay.
+    pub fn propose_spend(ctx: Context<ProposeSpend>, amount: u64, recipient: Pubkey) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::ProposeSpend { amount, recipient },
+        )?;
+        require!(amount > 0, MutrError::InvalidAmount);
+
+        let mut state = ctx.accounts.state.load_mut()?;
+        let proposal = &mut ctx.accounts.spend_proposal;
+        proposal.id = state.spend_proposal_count;
+        proposal.amount = amount;
+        proposal.recipient = recipient;
+        proposal.executable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(state.treasury_spend_delay_secs)
+            .ok_or(MutrError::MathOverflow)?;
+        proposal.bump = ctx.bumps.spend_proposal;
+
+        state.spend_proposal_count = state
+            .spend_proposal_count
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Admin: drop a queued treasury spend.
+    pub fn cancel_spend(ctx: Context<CancelSpend>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::CancelSpend {},
+        )?;
+        Ok(())
+    }
+
+    /// Permissionless: pay out a queued treasury spend once its delay has passed.
+    pub fn execute_spend(ctx: Context<ExecuteSpend>) -> Result<()> {
+        let proposal = &ctx.accounts.spend_proposal;
+        require!(
+            Clock::get()?.unix_timestamp >= proposal.executable_at,
+            MutrError::SpendNotReady
+        );
+
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            ctx.accounts.recipient_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            ctx.accounts.state.load()?.bump,
+            proposal.amount,
+        )?;
         Ok(())
     }
 
-    /// Stake MUTR into the CLR and mint xMUTR to the user.
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    /// Stake MUTR into the CLR and mint xMUTR to the user. Fails once past `deadline_slot`,
+    /// when given. With `join_dividends` the new xMUTR goes straight into the dividend
+    /// pool, as `join_dividend_pool` would move it; the dividend vault must then be passed.
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+        position_index: u32,
+        lock_tier: u8,
+        deadline_slot: Option<u64>,
+        join_dividends: bool,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
         require!(amount > 0, MutrError::InvalidAmount);
 
-        let state = &ctx.accounts.state;
+        let state = *ctx.accounts.state.load()?;
+        guardian::check(&state, PAUSE_STAKING)?;
+        require!(amount >= state.min_stake, MutrError::StakeTooSmall);
         let clr_vault_before = ctx.accounts.clr_vault.amount;
 
         // 1) Transfer MUTR from user to CLR vault
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
             to: ctx.accounts.clr_vault.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // A transfer-fee mint delivers less than `amount`; price the shares on what arrived
+        let received = received_amount(&mut ctx.accounts.clr_vault, clr_vault_before)?;
+
+        // 2) Apply stake fee (we only issue shares for net amount) at the user's VIP rate,
+        // less any loyalty fee credit
+        let stake_fee_bps = vip::discounted_bps(
+            ctx.accounts.vip_config.as_deref(),
+            ctx.accounts.user_stats.as_deref(),
+            state.stake_fee_bps,
+        )
+        .saturating_sub(ctx.accounts.user_state.nft_fee_discount_bps);
+        let mut net_amount = apply_fee(received, stake_fee_bps)?;
+        net_amount += loyalty::waive_fee(&mut ctx.accounts.user_stats, received - net_amount);
 
-        // 2) Apply stake fee (fee stays inside CLR, so we only issue shares for net amount)
-        let net_amount = apply_fee(amount, state.stake_fee_bps)?;
+        // 2b) Pay the referrer's cut, then split the rest of the fee: the CLR share stays,
+        // the rest is burned or sent to treasury
+        let referral_cut = referrals::credit_referrer(
+            &ctx.accounts.referral_config,
+            &mut ctx.accounts.referrer,
+            &mut ctx.accounts.user_stats,
+            ctx.accounts.user.key(),
+            ReferralSource::StakeFee,
+            received - net_amount,
+        )?;
+        referrals::move_cut(
+            &ctx.accounts.token_program,
+            &ctx.accounts.clr_vault,
+            &ctx.accounts.referral_vault,
+            &ctx.accounts.mutr_mint,
+            &ctx.accounts.state,
+            referral_cut,
+        )?;
+        let (burn_cut, treasury_cut) = state
+            .fee_split
+            .portions(received - net_amount - referral_cut)?;
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            treasury_cut,
+        )?;
+        vault_burn(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mutr_mint.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            burn_cut,
+        )?;
 
         // 3) Determine how many xMUTR to mint
-        let xmutr_supply = ctx.accounts.xmutr_mint.supply;
-        let shares_to_mint = if xmutr_supply == 0 || clr_vault_before == 0 {
-            // First staker or empty vault: 1:1 (minus fee)
-            net_amount
-        } else {
-            // shares = net_amount * total_shares / clr_balance_before
-            (net_amount as u128)
-                .checked_mul(xmutr_supply as u128)
-                .unwrap()
-                .checked_div(clr_vault_before as u128)
-                .unwrap() as u64
-        };
+        let shares_to_mint =
+            shares_for_deposit(
+                net_amount,
+                ctx.accounts.xmutr_mint.supply,
+                state.backing(clr_vault_before),
+            )?;
 
         require!(shares_to_mint > 0, MutrError::ZeroShares);
 
@@ -85,32 +455,257 @@ pub mod mutr_clr {
             cpi_accounts,
             signer_seeds,
         );
-        token::mint_to(cpi_ctx, shares_to_mint)?;
+        token_interface::mint_to(cpi_ctx, shares_to_mint)?;
 
-        // 5) Update user state
+        // 5) Update user state (registering the position on first use)
+        let new_staker = ctx.accounts.user_positions.owner == Pubkey::default();
         let user_state = &mut ctx.accounts.user_state;
-        if user_state.owner == Pubkey::default() {
-            user_state.owner = ctx.accounts.user.key();
-        }
+        open_position_if_new(
+            &mut ctx.accounts.user_positions,
+            user_state,
+            ctx.accounts.user.key(),
+            position_index,
+        )?;
         require_keys_eq!(user_state.owner, ctx.accounts.user.key(), MutrError::Unauthorized);
+        user_state.check_movable()?;
+        apply_lock_tier(user_state, lock_tier, true)?;
+        loyalty::touch_position(
+            &ctx.accounts.loyalty_config,
+            &mut ctx.accounts.user_stats,
+            user_state,
+        )?;
         user_state.staked_shares = user_state
             .staked_shares
             .checked_add(shares_to_mint)
             .ok_or(MutrError::MathOverflow)?;
+        if join_dividends {
+            let mut state = ctx.accounts.state.load_mut()?;
+            enter_dividend_pool(&mut state, user_state, shares_to_mint)?;
+        }
 
-        Ok(())
+        if let Some(leaderboard) = &ctx.accounts.leaderboard {
+            leaderboard::offer_position(leaderboard, &ctx.accounts.user_state)?;
+        }
+
+        // 6) Update the protocol stats
+        if new_staker {
+            ctx.accounts.stats.load_mut()?.add_staker()?;
+        }
+        stats::record_share_change(
+            &ctx.accounts.stats,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+            received - net_amount,
+        )?;
+        twap::observe(
+            &ctx.accounts.share_price_oracle,
+            ctx.accounts.clr_vault.amount,
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        deposit_caps::check(
+            &state,
+            &mut ctx.accounts.user_xmutr_account,
+            ctx.accounts.clr_vault.amount,
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        achievements::reach(&mut ctx.accounts.user_stats, Milestone::FirstStake);
+        if join_dividends {
+            let dividend_vault = ctx
+                .accounts
+                .dividend_vault
+                .as_ref()
+                .ok_or(MutrError::MissingDividendVault)?;
+            dividend_escrow::escrow(
+                &ctx.accounts.token_program,
+                &ctx.accounts.user_xmutr_account,
+                &ctx.accounts.xmutr_mint,
+                dividend_vault,
+                &ctx.accounts.user,
+                &mut ctx.accounts.user_state,
+                shares_to_mint,
+            )?;
+        }
+        vip::record_staked(
+            &mut ctx.accounts.user_stats,
+            &mut ctx.accounts.user_xmutr_account,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+        )
+    }
+
+    /// Stake MUTR paid by `payer`, crediting the xMUTR and `UserState` to `beneficiary`.
+    /// Used for gifting, custodial onboarding and fiat ramps.
+    pub fn stake_on_behalf(
+        ctx: Context<StakeOnBehalf>,
+        amount: u64,
+        beneficiary: Pubkey,
+        position_index: u32,
+        lock_tier: u8,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
+        require!(amount > 0, MutrError::InvalidAmount);
+        require_keys_neq!(beneficiary, Pubkey::default(), MutrError::InvalidBeneficiary);
+
+        let state = *ctx.accounts.state.load()?;
+        guardian::check(&state, PAUSE_STAKING)?;
+        require!(amount >= state.min_stake, MutrError::StakeTooSmall);
+        let clr_vault_before = ctx.accounts.clr_vault.amount;
+
+        // 1) Transfer MUTR from payer to CLR vault
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.payer_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.clr_vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // A transfer-fee mint delivers less than `amount`; price the shares on what arrived
+        let received = received_amount(&mut ctx.accounts.clr_vault, clr_vault_before)?;
+
+        // 2) Apply and split the stake fee exactly as `stake` does
+        let stake_fee_bps = vip::discounted_bps(
+            ctx.accounts.vip_config.as_deref(),
+            ctx.accounts.user_stats.as_deref(),
+            state.stake_fee_bps,
+        )
+        .saturating_sub(ctx.accounts.user_state.nft_fee_discount_bps);
+        let mut net_amount = apply_fee(received, stake_fee_bps)?;
+        net_amount += loyalty::waive_fee(&mut ctx.accounts.user_stats, received - net_amount);
+        let (burn_cut, treasury_cut) = state.fee_split.portions(received - net_amount)?;
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            treasury_cut,
+        )?;
+        vault_burn(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mutr_mint.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            burn_cut,
+        )?;
+
+        // 3) Determine how many xMUTR to mint
+        let shares_to_mint =
+            shares_for_deposit(
+                net_amount,
+                ctx.accounts.xmutr_mint.supply,
+                state.backing(clr_vault_before),
+            )?;
+
+        require!(shares_to_mint > 0, MutrError::ZeroShares);
+
+        // 4) Mint xMUTR to the beneficiary (program as mint authority via PDA)
+        let state_seeds: &[&[u8]] = &[
+            b"state",
+            &[state.bump],
+        ];
+        let signer_seeds = &[state_seeds];
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.xmutr_mint.to_account_info(),
+            to: ctx.accounts.beneficiary_xmutr_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::mint_to(cpi_ctx, shares_to_mint)?;
+
+        // 5) Update the beneficiary's user state (payer gains nothing)
+        let new_staker = ctx.accounts.user_positions.owner == Pubkey::default();
+        let user_state = &mut ctx.accounts.user_state;
+        open_position_if_new(
+            &mut ctx.accounts.user_positions,
+            user_state,
+            beneficiary,
+            position_index,
+        )?;
+        require_keys_eq!(user_state.owner, beneficiary, MutrError::Unauthorized);
+        user_state.check_movable()?;
+        apply_lock_tier(user_state, lock_tier, false)?;
+        loyalty::touch_position(
+            &ctx.accounts.loyalty_config,
+            &mut ctx.accounts.user_stats,
+            user_state,
+        )?;
+        user_state.staked_shares = user_state
+            .staked_shares
+            .checked_add(shares_to_mint)
+            .ok_or(MutrError::MathOverflow)?;
+
+        if let Some(leaderboard) = &ctx.accounts.leaderboard {
+            leaderboard::offer_position(leaderboard, &ctx.accounts.user_state)?;
+        }
+
+        // 6) Update the protocol stats
+        if new_staker {
+            ctx.accounts.stats.load_mut()?.add_staker()?;
+        }
+        stats::record_share_change(
+            &ctx.accounts.stats,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+            received - net_amount,
+        )?;
+        twap::observe(
+            &ctx.accounts.share_price_oracle,
+            ctx.accounts.clr_vault.amount,
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        deposit_caps::check(
+            &state,
+            &mut ctx.accounts.beneficiary_xmutr_account,
+            ctx.accounts.clr_vault.amount,
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        achievements::reach(&mut ctx.accounts.user_stats, Milestone::FirstStake);
+        vip::record_staked(
+            &mut ctx.accounts.user_stats,
+            &mut ctx.accounts.beneficiary_xmutr_account,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+        )
     }
 
-    /// Unstake xMUTR and withdraw MUTR from the CLR (fee stays in CLR).
-    pub fn unstake(ctx: Context<Unstake>, shares: u64) -> Result<()> {
+    /// Unstake xMUTR and withdraw MUTR from the CLR to any MUTR account (fee split per `fee_split`).
+    /// Locked positions cannot be unstaked before `lock_until`. Fails once past
+    /// `deadline_slot`, when given.
+    pub fn unstake(
+        ctx: Context<Unstake>,
+        shares: u64,
+        _position_index: u32,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
         require!(shares > 0, MutrError::InvalidAmount);
 
-        let state = &ctx.accounts.state;
+        let state = *ctx.accounts.state.load()?;
+        guardian::check(&state, PAUSE_WITHDRAWALS)?;
         let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
+        require!(
+            Clock::get()?.unix_timestamp >= user_state.lock_until,
+            MutrError::PositionLocked
+        );
         require!(
             user_state.staked_shares >= shares + user_state.dividend_shares,
             MutrError::InsufficientShares
         );
+        loyalty::touch_position(
+            &ctx.accounts.loyalty_config,
+            &mut ctx.accounts.user_stats,
+            user_state,
+        )?;
 
         // 1) Burn xMUTR from user
         let cpi_accounts = Burn {
@@ -119,7 +714,7 @@ pub mod mutr_clr {
             authority: ctx.accounts.user.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::burn(cpi_ctx, shares)?;
+        token_interface::burn(cpi_ctx, shares)?;
 
         user_state.staked_shares = user_state
             .staked_shares
@@ -127,18 +722,45 @@ pub mod mutr_clr {
             .ok_or(MutrError::MathOverflow)?;
 
         // 2) Calculate how much MUTR this share amount is worth
-        let clr_balance = ctx.accounts.clr_vault.amount;
-        let xmutr_supply = ctx.accounts.xmutr_mint.supply;
-        require!(xmutr_supply > 0, MutrError::ZeroShares);
+        let mutt_before_fee = amount_for_shares(
+            shares,
+            state.backing(ctx.accounts.clr_vault.amount),
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        require!(
+            mutt_before_fee >= state.min_unstake || user_state.staked_shares == 0,
+            MutrError::UnstakeTooSmall
+        );
 
-        let mutt_before_fee = (clr_balance as u128)
-            .checked_mul(shares as u128)
-            .unwrap()
-            .checked_div(xmutr_supply as u128)
-            .unwrap() as u64;
+        // 3) Apply unstake fee at the user's VIP rate, less any loyalty fee credit
+        let unstake_fee_bps = vip::discounted_bps(
+            ctx.accounts.vip_config.as_deref(),
+            ctx.accounts.user_stats.as_deref(),
+            state.unstake_fee_bps,
+        );
+        let mut net_amount = apply_fee(mutt_before_fee, unstake_fee_bps)?;
+        net_amount +=
+            loyalty::waive_fee(&mut ctx.accounts.user_stats, mutt_before_fee - net_amount);
 
-        // 3) Apply unstake fee
-        let net_amount = apply_fee(mutt_before_fee, state.unstake_fee_bps)?;
+        // 3b) Split the fee: the CLR share stays, the rest is burned or sent to treasury
+        let (burn_cut, treasury_cut) = state.fee_split.portions(mutt_before_fee - net_amount)?;
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            treasury_cut,
+        )?;
+        vault_burn(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mutr_mint.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            burn_cut,
+        )?;
 
         // 4) Transfer MUTR from CLR vault to user
         let state_seeds: &[&[u8]] = &[
@@ -147,9 +769,10 @@ pub mod mutr_clr {
         ];
         let signer_seeds = &[state_seeds];
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.clr_vault.to_account_info(),
-            to: ctx.accounts.user_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.destination_mutr_account.to_account_info(),
             authority: ctx.accounts.state.to_account_info(),
         };
         let cpi_ctx = CpiContext::new_with_signer(
@@ -157,57 +780,77 @@ pub mod mutr_clr {
             cpi_accounts,
             signer_seeds,
         );
-        token::transfer(cpi_ctx, net_amount)?;
+        token_interface::transfer_checked(cpi_ctx, net_amount, ctx.accounts.mutr_mint.decimals)?;
 
-        Ok(())
+        // 5) Update the protocol stats
+        stats::record_share_change(
+            &ctx.accounts.stats,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+            mutt_before_fee - net_amount,
+        )?;
+        twap::observe(
+            &ctx.accounts.share_price_oracle,
+            ctx.accounts.clr_vault.amount,
+            ctx.accounts.xmutr_mint.supply,
+        )?;
+        vip::record_staked(
+            &mut ctx.accounts.user_stats,
+            &mut ctx.accounts.user_xmutr_account,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+        )
     }
 
-    /// Move xMUTR into the dividend pool (0% fee, but must settle rewards first).
-    pub fn join_dividend_pool(ctx: Context<JoinDividendPool>, shares: u64) -> Result<()> {
+    /// Move xMUTR into the dividend pool (0% fee, but must settle rewards first). The xMUTR
+    /// is escrowed in the dividend vault until the position leaves.
+    pub fn join_dividend_pool(
+        ctx: Context<JoinDividendPool>,
+        shares: u64,
+        _position_index: u32,
+    ) -> Result<()> {
         require!(shares > 0, MutrError::InvalidAmount);
 
-        let state = &mut ctx.accounts.state;
+        let mut state = ctx.accounts.state.load_mut()?;
         let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
         require!(user_state.staked_shares >= shares, MutrError::InsufficientShares);
 
-        // settle current rewards
-        settle_user_rewards(state, user_state)?;
-
-        user_state.staked_shares = user_state
-            .staked_shares
-            .checked_sub(shares)
-            .ok_or(MutrError::MathOverflow)?;
-        user_state.dividend_shares = user_state
-            .dividend_shares
-            .checked_add(shares)
-            .ok_or(MutrError::MathOverflow)?;
-
-        state.total_dividend_shares = state
-            .total_dividend_shares
-            .checked_add(shares as u128)
-            .ok_or(MutrError::MathOverflow)?;
-
-        // update reward debt
-        user_state.reward_debt = (user_state.dividend_shares as u128)
-            .checked_mul(state.acc_reward_per_share)
-            .unwrap();
-
-        Ok(())
+        enter_dividend_pool(&mut state, user_state, shares)?;
+        dividend_escrow::escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.user_xmutr_account,
+            &ctx.accounts.xmutr_mint,
+            &ctx.accounts.dividend_vault,
+            &ctx.accounts.user,
+            user_state,
+            shares,
+        )
     }
 
-    /// Leave the dividend pool (4% fee on shares, fee is burned).
-    pub fn leave_dividend_pool(ctx: Context<LeaveDividendPool>, shares: u64) -> Result<()> {
+    /// Leave the dividend pool. The exit fee on shares decays linearly from
+    /// `exit_penalty_max_bps` (at most `MAX_EXIT_PENALTY_BPS`) to zero over
+    /// `exit_penalty_decay_secs` since joining; the forfeited xMUTR is burned and its MUTR
+    /// value split per `fee_split`, reported in `DividendPoolLeft` and `ExitFeeBurned`.
+    pub fn leave_dividend_pool(
+        ctx: Context<LeaveDividendPool>,
+        shares: u64,
+        _position_index: u32,
+    ) -> Result<()> {
         require!(shares > 0, MutrError::InvalidAmount);
 
-        let state = &mut ctx.accounts.state;
+        let mut state = ctx.accounts.state.load_mut()?;
         let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
         require!(user_state.dividend_shares >= shares, MutrError::InsufficientShares);
+        claim_lockup::check(&state, user_state)?;
 
         // settle rewards first
-        settle_user_rewards(state, user_state)?;
+        settle_user_rewards(&state, user_state)?;
+        let weight_before = dividend_weight(user_state)?;
 
-        // apply 4% exit fee on shares (burned)
-        let fee_bps: u16 = 400;
+        // apply the time-decayed exit fee on shares
+        let fee_bps = exit_penalty_bps(&state, user_state, Clock::get()?.unix_timestamp)?;
         let net_shares = apply_fee(shares, fee_bps)?;
 
         // move net shares back to staked_shares
@@ -222,56 +865,209 @@ pub mod mutr_clr {
             .ok_or(MutrError::MathOverflow)?;
 
         // update global dividend supply (we remove the full shares, including fee)
+        let weight_after = dividend_weight(user_state)?;
         state.total_dividend_shares = state
             .total_dividend_shares
-            .checked_sub(shares as u128)
+            .checked_sub(weight_before - weight_after)
             .ok_or(MutrError::MathOverflow)?;
 
         // update reward debt
-        user_state.reward_debt = (user_state.dividend_shares as u128)
-            .checked_mul(state.acc_reward_per_share)
-            .unwrap();
+        checkpoint_reward_debt(&state, user_state)?;
 
-        Ok(())
+        // escrowed xMUTR goes back less the fee, which is burned (see `dividend_escrow`)
+        let fee_shares = shares - net_shares;
+        let (released, fee_from_escrow) =
+            dividend_escrow::release(user_state, shares, fee_shares);
+        state.exit_fee_shares_burned = state
+            .exit_fee_shares_burned
+            .checked_add(fee_shares)
+            .ok_or(MutrError::MathOverflow)?;
+
+        // the forfeited shares' MUTR value is split like any other fee
+        let xmutr_supply = ctx.accounts.xmutr_mint.supply;
+        let fee_value = if xmutr_supply == 0 {
+            0
+        } else {
+            amount_for_shares(
+                fee_shares,
+                state.backing(ctx.accounts.clr_vault.amount),
+                xmutr_supply,
+            )?
+        };
+        emit!(DividendPoolLeft {
+            event_nonce: events::next_nonce(&mut state)?,
+            user: ctx.accounts.user.key(),
+            user_state: ctx.accounts.user_state.key(),
+            shares,
+            fee_bps,
+            fee_shares,
+            fee_amount: fee_value,
+        });
+        if fee_shares > 0 {
+            emit!(ExitFeeBurned {
+                event_nonce: events::next_nonce(&mut state)?,
+                user_state: ctx.accounts.user_state.key(),
+                shares: fee_shares,
+                total_burned: state.exit_fee_shares_burned,
+            });
+        }
+        // The transfers below borrow the state account as the vault authority
+        drop(state);
+        let state = *ctx.accounts.state.load()?;
+        if released > 0 || fee_from_escrow > 0 {
+            let dividend_vault = ctx
+                .accounts
+                .dividend_vault
+                .as_ref()
+                .ok_or(MutrError::MissingDividendVault)?;
+            vault_transfer(
+                ctx.accounts.token_program.to_account_info(),
+                dividend_vault.to_account_info(),
+                ctx.accounts.user_xmutr_account.to_account_info(),
+                &ctx.accounts.xmutr_mint,
+                ctx.accounts.state.to_account_info(),
+                state.bump,
+                released,
+            )?;
+            vault_burn(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.xmutr_mint.to_account_info(),
+                dividend_vault.to_account_info(),
+                ctx.accounts.state.to_account_info(),
+                state.bump,
+                fee_from_escrow,
+            )?;
+        }
+        // the fee on shares that never went into escrow is burned from the wallet
+        let fee_from_wallet = fee_shares - fee_from_escrow;
+        if fee_from_wallet > 0 {
+            let cpi_accounts = Burn {
+                mint: ctx.accounts.xmutr_mint.to_account_info(),
+                from: ctx.accounts.user_xmutr_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::burn(cpi_ctx, fee_from_wallet)?;
+        }
+        let (burn_cut, treasury_cut) = state.fee_split.portions(fee_value)?;
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            treasury_cut,
+        )?;
+        vault_burn(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mutr_mint.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+            state.bump,
+            burn_cut,
+        )?;
+
+        stats::record_share_change(
+            &ctx.accounts.stats,
+            &mut ctx.accounts.clr_vault,
+            &mut ctx.accounts.xmutr_mint,
+            fee_value,
+        )
     }
 
-    /// Record new profit in the CLR and update reward per share.
-    /// Simplified MasterChef-style accounting.
+    /// Record new profit in the CLR. With profit epochs enabled the profit is buffered
+    /// until the epoch rolls over; otherwise it is released right away (through the
+    /// drip if one is configured).
+    /// Simplified MasterChef-style accounting. Operators book profit with
+    /// `operator_record_profit` instead (see `operators`).
     pub fn record_profit(ctx: Context<RecordProfit>, profit_amount: u64) -> Result<()> {
-        let state = &mut ctx.accounts.state;
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::RecordProfit { profit_amount },
+        )?;
+        let mut state = ctx.accounts.state.load_mut()?;
+        book_profit(&mut state, profit_amount)?;
+        ctx.accounts
+            .stats
+            .load_mut()?
+            .add_profit(profit_amount, state.total_dividend_shares)
+    }
 
-        require!(state.total_dividend_shares > 0, MutrError::NoDividendShares);
+    /// Permissionless: close the current profit epoch once it has ended and fold its
+    /// buffered profit into reward per share. The caller earns `keeper_fee_bps` of it.
+    pub fn epoch_rollover(ctx: Context<EpochRollover>) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        require!(state.profit_epoch_secs > 0, MutrError::InvalidConfig);
 
-        let profit_u128 = profit_amount as u128;
-        let increment = profit_u128
-            .checked_mul(REWARD_PRECISION)
-            .unwrap()
-            .checked_div(state.total_dividend_shares)
-            .unwrap();
+        let now = Clock::get()?.unix_timestamp;
+        let keeper_fee_bps = state.keeper_fee_bps;
+        let keeper_fee = roll_profit_epoch_if_due(&mut state, now, keeper_fee_bps)?
+            .ok_or(MutrError::EpochNotEnded)?;
 
-        state.acc_reward_per_share = state
-            .acc_reward_per_share
-            .checked_add(increment)
-            .ok_or(MutrError::MathOverflow)?;
+        let state_bump = state.bump;
+        drop(state);
 
-        Ok(())
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.keeper_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state_bump,
+            keeper_fee,
+        )
+    }
+
+    /// Permissionless: release the share of the drip buffer that has vested since the
+    /// last drip into reward per share. The caller earns `keeper_fee_bps` of it.
+    pub fn drip(ctx: Context<Drip>) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        require!(state.drip_remaining > 0, MutrError::NothingToDrip);
+        let keeper_fee_bps = state.keeper_fee_bps;
+        let keeper_fee = drip_due(&mut state, Clock::get()?.slot, keeper_fee_bps)?;
+
+        let state_bump = state.bump;
+        drop(state);
+
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.keeper_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            state_bump,
+            keeper_fee,
+        )
     }
 
-    /// Claim accumulated MUTR rewards from the dividend pool.
-    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-        let state = &mut ctx.accounts.state;
+    /// Claim accumulated MUTR rewards from the dividend pool to any MUTR account. The
+    /// position's delegate may claim too, into an account of the owner's only.
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, _position_index: u32) -> Result<()> {
+        let state = *ctx.accounts.state.load()?;
+        guardian::check(&state, PAUSE_WITHDRAWALS)?;
         let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
+        delegates::check_caller(user_state, &ctx.accounts.user, &ctx.accounts.caller)?;
+        if ctx.accounts.caller.key() != ctx.accounts.user.key() {
+            require_keys_eq!(
+                ctx.accounts.destination_mutr_account.owner,
+                ctx.accounts.user.key(),
+                MutrError::Unauthorized
+            );
+        }
+        claim_lockup::check(&state, user_state)?;
 
-        let pending = pending_rewards(state, user_state)?;
+        let pending = pending_rewards(&state, user_state)?;
         if pending == 0 {
             return Ok(());
         }
 
         // update accounting before transfer
         user_state.pending_rewards = 0;
-        user_state.reward_debt = (user_state.dividend_shares as u128)
-            .checked_mul(state.acc_reward_per_share)
-            .unwrap();
+        user_state.reward_debt = reward_debt_for(&state, user_state)?;
 
         // transfer from CLR vault to user
         let state_seeds: &[&[u8]] = &[
@@ -280,9 +1076,10 @@ pub mod mutr_clr {
         ];
         let signer_seeds = &[state_seeds];
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.clr_vault.to_account_info(),
-            to: ctx.accounts.user_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.destination_mutr_account.to_account_info(),
             authority: ctx.accounts.state.to_account_info(),
         };
         let cpi_ctx = CpiContext::new_with_signer(
@@ -290,370 +1087,3979 @@ pub mod mutr_clr {
             cpi_accounts,
             signer_seeds,
         );
-        token::transfer(cpi_ctx, pending)?;
+        token_interface::transfer_checked(cpi_ctx, pending, ctx.accounts.mutr_mint.decimals)?;
 
         Ok(())
     }
 
-    /// Pay prize to a winner from the CLR vault (for approved games later).
-    pub fn send_prize(ctx: Context<SendPrize>, amount: u64) -> Result<()> {
-        require!(amount > 0, MutrError::InvalidAmount);
+    /// Admin: set up the secondary reward mint (e.g. a partner token) for incentive
+    /// campaigns. Creates the bonus reward vault owned by the state PDA.
+    pub fn initialize_bonus_rewards(ctx: Context<InitializeBonusRewards>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeBonusRewards {},
+        )?;
+        let mut state = ctx.accounts.state.load_mut()?;
+        state.bonus_mint = ctx.accounts.bonus_mint.key();
+        state.bonus_vault = ctx.accounts.bonus_vault.key();
+        Ok(())
+    }
 
-        let state = &ctx.accounts.state;
-        let state_seeds: &[&[u8]] = &[
-            b"state",
-            &[state.bump],
-        ];
-        let signer_seeds = &[state_seeds];
+    /// Admin: deposit bonus tokens and distribute them over the current dividend shares.
+    pub fn fund_bonus_rewards(ctx: Context<FundBonusRewards>, amount: u64) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::FundBonusRewards { amount },
+        )?;
+        require!(amount > 0, MutrError::InvalidAmount);
+        let mut state = ctx.accounts.state.load_mut()?;
+        dust::credit_bonus(&mut state, amount)?;
+        drop(state);
 
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.clr_vault.to_account_info(),
-            to: ctx.accounts.winner_mutr_account.to_account_info(),
-            authority: ctx.accounts.state.to_account_info(),
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.funder_bonus_account.to_account_info(),
+            mint: ctx.accounts.bonus_mint.to_account_info(),
+            to: ctx.accounts.bonus_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, amount)?;
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.bonus_mint.decimals)?;
 
         Ok(())
     }
-}
 
-// -----------------------------------------------------------------------------
-// Helper functions
-// -----------------------------------------------------------------------------
+    /// Claim accumulated bonus-token rewards to any account of the bonus mint.
+    pub fn claim_bonus_rewards(ctx: Context<ClaimBonusRewards>, _position_index: u32) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        guardian::check(&state, PAUSE_WITHDRAWALS)?;
+        let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
 
-/// Apply fee in basis points; fee is kept in CLR (we just return net).
-fn apply_fee(amount: u64, fee_bps: u16) -> Result<u64> {
-    let fee = (amount as u128)
-        .checked_mul(fee_bps as u128)
-        .unwrap()
-        .checked_div(10_000)
-        .unwrap() as u64;
-    Ok(amount
-        .checked_sub(fee)
-        .ok_or(MutrError::MathOverflow)?)
-}
+        let pending = pending_bonus_rewards(&state, user_state)?;
+        if pending == 0 {
+            return Ok(());
+        }
 
-/// Settle user rewards into pending_rewards.
-fn settle_user_rewards(state: &GlobalState, user: &mut UserState) -> Result<()> {
-    let pending = pending_rewards(state, user)?;
-    user.pending_rewards = user
-        .pending_rewards
-        .checked_add(pending)
-        .ok_or(MutrError::MathOverflow)?;
-    Ok(())
-}
+        user_state.pending_rewards_2 = 0;
+        user_state.reward_debt_2 = bonus_reward_debt_for(&state, user_state)?;
+        // Bonus funded before `bonus_owed` existed is not tracked by it
+        state.bonus_owed = state.bonus_owed.saturating_sub(pending);
+        let state_bump = state.bump;
+        drop(state);
 
-/// Calculate pending rewards (current).
-fn pending_rewards(state: &GlobalState, user: &UserState) -> Result<u64> {
-    if user.dividend_shares == 0 {
-        return Ok(user.pending_rewards as u64);
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.bonus_vault.to_account_info(),
+            ctx.accounts.destination_bonus_account.to_account_info(),
+            &ctx.accounts.bonus_mint,
+            ctx.accounts.state.to_account_info(),
+            state_bump,
+            pending,
+        )
     }
-    let acc_per_share = state.acc_reward_per_share;
-    let accumulated = (user.dividend_shares as u128)
-        .checked_mul(acc_per_share)
-        .unwrap();
-    let pending_u128 = accumulated
-        .checked_sub(user.reward_debt)
-        .unwrap()
-        .checked_div(REWARD_PRECISION)
-        .unwrap()
-        .checked_add(user.pending_rewards)
-        .unwrap();
-    Ok(pending_u128 as u64)
-}
 
-// -----------------------------------------------------------------------------
-// Data structures & error types
-// -----------------------------------------------------------------------------
+    /// Post a Merkle root of per-user rewards for the next dividend epoch. Epoch rewards
+    /// are paid from the CLR vault and coexist with the live reward-per-share path; until
+    /// claimed they are booked in `epoch_rewards_owed` and no longer back xMUTR.
+    pub fn post_dividend_epoch(
+        ctx: Context<PostDividendEpoch>,
+        merkle_root: [u8; 32],
+        total_rewards: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::PostDividendEpoch {
+                merkle_root,
+                total_rewards,
+            },
+        )?;
+        require!(total_rewards > 0, MutrError::InvalidAmount);
 
-#[account]
-pub struct GlobalState {
-    pub authority: Pubkey,
-    pub mutr_mint: Pubkey,
-    pub xmutr_mint: Pubkey,
-    pub clr_vault: Pubkey,
+        let mut state = ctx.accounts.state.load_mut()?;
+        let epoch = &mut ctx.accounts.dividend_epoch;
+        epoch.epoch = state.dividend_epoch_count;
+        epoch.merkle_root = merkle_root;
+        epoch.total_rewards = total_rewards;
+        epoch.claimed_rewards = 0;
+        epoch.bump = ctx.bumps.dividend_epoch;
 
-    pub stake_fee_bps: u16,
-    pub unstake_fee_bps: u16,
-    pub lower_threshold: u64,
-    pub upper_threshold: u64,
+        state.dividend_epoch_count = state
+            .dividend_epoch_count
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+        state.epoch_rewards_owed = state
+            .epoch_rewards_owed
+            .checked_add(total_rewards)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
 
-    pub acc_reward_per_share: u128,
-    pub total_dividend_shares: u128,
+    /// Claim `amount` from a posted dividend epoch. The leaf is
+    /// `keccak(epoch_le || user || amount_le)`; the claim record blocks a second claim.
+    pub fn claim_epoch_rewards(
+        ctx: Context<ClaimEpochRewards>,
+        _epoch: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(amount > 0, MutrError::InvalidAmount);
+        let bump = {
+            let mut state = ctx.accounts.state.load_mut()?;
+            guardian::check(&state, PAUSE_WITHDRAWALS)?;
+            // Epochs posted before `epoch_rewards_owed` existed are not tracked by it
+            state.epoch_rewards_owed = state.epoch_rewards_owed.saturating_sub(amount);
+            state.bump
+        };
+        let epoch = &mut ctx.accounts.dividend_epoch;
 
-    pub bump: u8,
-}
+        let leaf = keccak::hashv(&[
+            &epoch.epoch.to_le_bytes(),
+            ctx.accounts.user.key().as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .0;
+        require!(
+            verify_merkle_proof(&proof, epoch.merkle_root, leaf),
+            MutrError::InvalidProof
+        );
 
-impl GlobalState {
-    pub const LEN: usize = 32  // authority
-        + 32 // mutr_mint
-        + 32 // xmutr_mint
-        + 32 // clr_vault
-        + 2  // stake_fee_bps
-        + 2  // unstake_fee_bps
-        + 8  // lower_threshold
-        + 8  // upper_threshold
-        + 16 // acc_reward_per_share
-        + 16 // total_dividend_shares
-        + 1; // bump
-}
+        epoch.claimed_rewards = epoch
+            .claimed_rewards
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        require!(
+            epoch.claimed_rewards <= epoch.total_rewards,
+            MutrError::EpochRewardsExhausted
+        );
 
-#[account]
-pub struct UserState {
-    pub owner: Pubkey,
-    pub staked_shares: u64,
+        let claim = &mut ctx.accounts.epoch_claim;
+        claim.epoch = epoch.epoch;
+        claim.user = ctx.accounts.user.key();
+        claim.amount = amount;
+
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.destination_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            bump,
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Admin: open the MUTR emissions vault and set the per-slot emission rate.
+    pub fn initialize_emissions(
+        ctx: Context<InitializeEmissions>,
+        emission_rate_per_slot: u64,
+        emission_end_slot: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeEmissions {
+                emission_rate_per_slot,
+                emission_end_slot,
+            },
+        )?;
+        emissions::initialize(ctx, emission_rate_per_slot, emission_end_slot)
+    }
+
+    /// Admin: change the emission rate and end slot (accrues at the old rate first).
+    pub fn update_emissions(
+        ctx: Context<UpdateEmissions>,
+        emission_rate_per_slot: u64,
+        emission_end_slot: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::UpdateEmissions {
+                emission_rate_per_slot,
+                emission_end_slot,
+            },
+        )?;
+        emissions::update(ctx, emission_rate_per_slot, emission_end_slot)
+    }
+
+    /// Admin: set the slot milestones at which the emission rate halves.
+    pub fn set_emission_halvings(
+        ctx: Context<SetEmissionHalvings>,
+        halving_slots: Vec<u64>,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetEmissionHalvings {
+                halving_slots: halving_slots.clone(),
+            },
+        )?;
+        emissions::set_halvings(ctx, halving_slots)
+    }
+
+    /// Permissionless: release accrued emissions to dividend-pool members, applying
+    /// any halving milestones passed since the last harvest.
+    pub fn harvest_emissions(ctx: Context<HarvestEmissions>) -> Result<()> {
+        emissions::harvest(ctx)
+    }
+
+    /// Admin: create an automation thread that calls `crank` on a cron `schedule`,
+    /// funded with `fund_lamports` for transaction fees. The thread is owned by the
+    /// program's `automation` PDA and collects the crank's keeper fee into
+    /// `keeper_mutr_account`.
+    pub fn create_automation_thread(
+        ctx: Context<CreateAutomationThread>,
+        crank: AutomatedCrank,
+        schedule: String,
+        fund_lamports: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::CreateAutomationThread {
+                crank,
+                schedule: schedule.clone(),
+                fund_lamports,
+            },
+        )?;
+        let thread = ctx.accounts.thread.key();
+        // The crank runs under whichever token program owns the MUTR mint
+        let token_program = *ctx.accounts.keeper_mutr_account.to_account_info().owner;
+        let (crank_accounts, crank_data) = match crank {
+            AutomatedCrank::Drip => (
+                accounts::Drip {
+                    state: ctx.accounts.state.key(),
+                    mutr_mint: ctx.accounts.state.load()?.mutr_mint,
+                    clr_vault: ctx.accounts.state.load()?.clr_vault,
+                    keeper_mutr_account: ctx.accounts.keeper_mutr_account.key(),
+                    keeper: thread,
+                    token_program,
+                }
+                .to_account_metas(Some(true)),
+                instruction::Drip {}.data(),
+            ),
+            AutomatedCrank::EpochRollover => (
+                accounts::EpochRollover {
+                    state: ctx.accounts.state.key(),
+                    mutr_mint: ctx.accounts.state.load()?.mutr_mint,
+                    clr_vault: ctx.accounts.state.load()?.clr_vault,
+                    keeper_mutr_account: ctx.accounts.keeper_mutr_account.key(),
+                    keeper: thread,
+                    token_program,
+                }
+                .to_account_metas(Some(true)),
+                instruction::EpochRollover {}.data(),
+            ),
+            AutomatedCrank::HarvestEmissions => (
+                accounts::HarvestEmissions {
+                    state: ctx.accounts.state.key(),
+                    mutr_mint: ctx.accounts.state.load()?.mutr_mint,
+                    xmutr_mint: ctx.accounts.state.load()?.xmutr_mint,
+                    emission_schedule: Pubkey::find_program_address(&[b"emission_schedule"], &crate::ID).0,
+                    emission_vault: ctx.accounts.state.load()?.emission_vault,
+                    clr_vault: ctx.accounts.state.load()?.clr_vault,
+                    keeper_mutr_account: ctx.accounts.keeper_mutr_account.key(),
+                    share_price_oracle: None,
+                    keeper: thread,
+                    token_program,
+                }
+                .to_account_metas(Some(true)),
+                instruction::HarvestEmissions {}.data(),
+            ),
+        };
+        let crank_ix = ThreadInstruction {
+            program_id: crate::ID,
+            accounts: crank_accounts
+                .into_iter()
+                .map(|meta| ThreadAccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: crank_data,
+        };
+
+        let mut data = thread_discriminator("thread_create").to_vec();
+        (
+            fund_lamports,
+            crank.thread_id().to_vec(),
+            vec![crank_ix],
+            ThreadTrigger::Cron {
+                schedule,
+                skippable: true,
+            },
+        )
+            .serialize(&mut data)?;
+        let ix = Instruction {
+            program_id: thread_program::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.automation_authority.key(), true),
+                AccountMeta::new(ctx.accounts.authority.key(), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(thread, false),
+            ],
+            data,
+        };
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.automation_authority.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.thread.to_account_info(),
+            ],
+            &[&[b"automation", &[ctx.bumps.automation_authority]]],
+        )?;
+        Ok(())
+    }
+
+    /// Top up an automation thread's fee balance. Anyone may fund it.
+    pub fn fund_automation_thread(
+        ctx: Context<FundAutomationThread>,
+        _crank: AutomatedCrank,
+        lamports: u64,
+    ) -> Result<()> {
+        require!(lamports > 0, MutrError::InvalidAmount);
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.thread.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, lamports)
+    }
+
+    /// Admin: delete an automation thread, returning its lamports to the authority.
+    pub fn delete_automation_thread(ctx: Context<DeleteAutomationThread>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::DeleteAutomationThread {},
+        )?;
+        let ix = Instruction {
+            program_id: thread_program::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.automation_authority.key(), true),
+                AccountMeta::new(ctx.accounts.authority.key(), false),
+                AccountMeta::new(ctx.accounts.thread.key(), false),
+            ],
+            data: thread_discriminator("thread_delete").to_vec(),
+        };
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.automation_authority.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.thread.to_account_info(),
+            ],
+            &[&[b"automation", &[ctx.bumps.automation_authority]]],
+        )?;
+        Ok(())
+    }
+
+    /// Admin: create or update the Metaplex metadata of the xMUTR mint so wallets show
+    /// the share token by name. The state PDA, as mint authority, signs the creation and
+    /// stays the metadata's update authority.
+    pub fn set_xmutr_metadata(
+        ctx: Context<SetXmutrMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetXmutrMetadata {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+            },
+        )?;
+        require!(
+            name.len() <= MAX_METADATA_NAME_LEN
+                && symbol.len() <= MAX_METADATA_SYMBOL_LEN
+                && uri.len() <= MAX_RECEIPT_URI_LEN,
+            MutrError::InvalidTokenMetadata
+        );
+
+        let state_seeds: &[&[u8]] = &[
+            b"state",
+            &[ctx.accounts.state.load()?.bump],
+        ];
+        let signer_seeds = &[state_seeds];
+        let data = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        if ctx.accounts.metadata.data_is_empty() {
+            let cpi_accounts = CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.xmutr_mint.to_account_info(),
+                mint_authority: ctx.accounts.state.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                update_authority: ctx.accounts.state.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            metadata::create_metadata_accounts_v3(cpi_ctx, data, true, true, None)
+        } else {
+            let cpi_accounts = UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.state.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            metadata::update_metadata_accounts_v2(cpi_ctx, None, Some(data), None, None)
+        }
+    }
+
+    /// Tokenize a position as a transferable receipt NFT. The position's xMUTR is escrowed
+    /// by the program and the position is frozen until the receipt is redeemed. A burned
+    /// receipt's mint outlives it, so `receipt_nonce` gives each tokenization of the same
+    /// position a fresh mint address.
+    pub fn mint_position_receipt(
+        ctx: Context<MintPositionReceipt>,
+        position_index: u32,
+        uri: String,
+        _receipt_nonce: u64,
+    ) -> Result<()> {
+        require!(uri.len() <= MAX_RECEIPT_URI_LEN, MutrError::InvalidReceiptUri);
+
+        let state = *ctx.accounts.state.load()?;
+        let user_state = &mut ctx.accounts.user_state;
+        user_state.check_movable()?;
+
+        let total_shares = user_state
+            .staked_shares
+            .checked_add(user_state.dividend_shares)
+            .ok_or(MutrError::MathOverflow)?;
+        require!(total_shares > 0, MutrError::ZeroShares);
+
+        // 1) Escrow the position's xMUTR so the receipt is fully backed; what the dividend
+        // vault already holds stays there
+        let total_shares = total_shares - user_state.escrowed_shares;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_xmutr_account.to_account_info(),
+            mint: ctx.accounts.xmutr_mint.to_account_info(),
+            to: ctx.accounts.receipt_escrow.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, total_shares, ctx.accounts.xmutr_mint.decimals)?;
+
+        user_state.receipt_mint = ctx.accounts.receipt_mint.key();
+
+        // 2) Mint the receipt NFT to the user
+        let state_seeds: &[&[u8]] = &[
+            b"state",
+            &[state.bump],
+        ];
+        let signer_seeds = &[state_seeds];
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            to: ctx.accounts.user_receipt_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::mint_to(cpi_ctx, 1)?;
+
+        // 3) Metaplex metadata + master edition (max supply 0 makes it a 1/1)
+        let cpi_accounts = CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.metadata.to_account_info(),
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            mint_authority: ctx.accounts.state.to_account_info(),
+            payer: ctx.accounts.user.to_account_info(),
+            update_authority: ctx.accounts.state.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        let data = DataV2 {
+            name: format!("xMUTR Position #{}", position_index),
+            symbol: RECEIPT_SYMBOL.to_string(),
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+        metadata::create_metadata_accounts_v3(cpi_ctx, data, false, true, None)?;
+
+        let cpi_accounts = CreateMasterEditionV3 {
+            edition: ctx.accounts.master_edition.to_account_info(),
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            update_authority: ctx.accounts.state.to_account_info(),
+            mint_authority: ctx.accounts.state.to_account_info(),
+            payer: ctx.accounts.user.to_account_info(),
+            metadata: ctx.accounts.metadata.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        metadata::create_master_edition_v3(cpi_ctx, Some(0))?;
+
+        Ok(())
+    }
+
+    /// Burn a position receipt NFT and release the underlying shares (and any accrued
+    /// rewards) into one of the holder's own positions.
+    pub fn redeem_position_receipt(
+        ctx: Context<RedeemPositionReceipt>,
+        position_index: u32,
+    ) -> Result<()> {
+        let source = &mut ctx.accounts.source_user_state;
+
+        // 1) Burn the receipt held by the redeemer
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            from: ctx.accounts.holder_receipt_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, 1)?;
+
+        // 2) Release the escrowed xMUTR to the redeemer, bar what the dividend vault holds
+        let total_shares = source
+            .staked_shares
+            .checked_add(source.dividend_shares)
+            .and_then(|shares| shares.checked_sub(source.escrowed_shares))
+            .ok_or(MutrError::MathOverflow)?;
+
+        let state_seeds: &[&[u8]] = &[
+            b"state",
+            &[ctx.accounts.state.load()?.bump],
+        ];
+        let signer_seeds = &[state_seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.receipt_escrow.to_account_info(),
+            mint: ctx.accounts.xmutr_mint.to_account_info(),
+            to: ctx.accounts.holder_xmutr_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, total_shares, ctx.accounts.xmutr_mint.decimals)?;
+
+        // 3) Move shares and accrued rewards into the holder's position
+        let mut state = ctx.accounts.state.load_mut()?;
+        let holder_state = &mut ctx.accounts.holder_user_state;
+        open_position_if_new(
+            &mut ctx.accounts.holder_positions,
+            holder_state,
+            ctx.accounts.holder.key(),
+            position_index,
+        )?;
+        holder_state.check_movable()?;
+
+        merge_position(&mut state, source, holder_state)?;
+        source.receipt_mint = Pubkey::default();
+
+        Ok(())
+    }
+
+    /// Permissionless: drop an expired lock back to the unlocked tier so the position
+    /// stops earning boosted dividend weight.
+    pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        let user_state = &mut ctx.accounts.user_state;
+        require!(user_state.lock_tier != 0, MutrError::PositionNotLocked);
+        require!(
+            Clock::get()?.unix_timestamp >= user_state.lock_until,
+            MutrError::PositionLocked
+        );
+
+        settle_user_rewards(&state, user_state)?;
+        let weight_before = dividend_weight(user_state)?;
+
+        let (_, boost_bps) = lock_tier_terms(0)?;
+        user_state.lock_tier = 0;
+        user_state.boost_bps = boost_bps;
+
+        let weight_after = dividend_weight(user_state)?;
+        state.total_dividend_shares = state
+            .total_dividend_shares
+            .checked_sub(weight_before - weight_after)
+            .ok_or(MutrError::MathOverflow)?;
+        checkpoint_reward_debt(&state, user_state)?;
+
+        Ok(())
+    }
+
+    /// Close an empty `UserState` and refund its rent lamports to the owner.
+    pub fn close_user_state(ctx: Context<CloseUserState>, _position_index: u32) -> Result<()> {
+        let user_state = &ctx.accounts.user_state;
+        user_state.check_movable()?;
+        require!(
+            user_state.staked_shares == 0
+                && user_state.dividend_shares == 0
+                && user_state.pending_rewards == 0
+                && user_state.pending_rewards_2 == 0,
+            MutrError::UserStateNotEmpty
+        );
+        require!(
+            !responsible_gaming::limits_active(user_state, Clock::get()?.unix_timestamp),
+            MutrError::GamingLimitsActive
+        );
+        Ok(())
+    }
+
+    /// Admin: register a game (or oracle) allowed to resolve bets and pay prizes.
+    pub fn register_game(
+        ctx: Context<RegisterGame>,
+        game: Pubkey,
+        max_multiplier_bps: u32,
+        bet_ttl_slots: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::RegisterGame {
+                game,
+                max_multiplier_bps,
+                bet_ttl_slots,
+            },
+        )?;
+        bets::register(ctx, game, max_multiplier_bps, bet_ttl_slots)
+    }
+
+    /// Admin: enable or disable a registered game.
+    pub fn set_game_enabled(ctx: Context<SetGameEnabled>, enabled: bool) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetGameEnabled { enabled },
+        )?;
+        bets::set_enabled(ctx, enabled)
+    }
+
+    /// Admin: choose a game's randomness provider and the house edge of its bets.
+    pub fn set_game_randomness(
+        ctx: Context<SetGameEnabled>,
+        provider: RandomnessProvider,
+        house_edge_bps: u16,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetGameRandomness {
+                provider,
+                house_edge_bps,
+            },
+        )?;
+        bets::set_randomness(ctx, provider, house_edge_bps)
+    }
+
+    /// Admin: set the Ed25519 oracle key whose signed results settle a game's bets.
+    pub fn set_game_oracle(ctx: Context<SetGameEnabled>, oracle: Pubkey) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetGameOracle { oracle },
+        )?;
+        bets::set_oracle(ctx, oracle)
+    }
+
+    /// Admin: set a game's bet rate limit and the per-player limit while betting on it.
+    pub fn set_game_rate_limit(
+        ctx: Context<SetGameEnabled>,
+        burst: u16,
+        refill_per_slot: u16,
+        player_burst: u16,
+        player_refill_per_slot: u16,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetGameRateLimit {
+                burst,
+                refill_per_slot,
+                player_burst,
+                player_refill_per_slot,
+            },
+        )?;
+        rate_limit::set_limits(ctx, burst, refill_per_slot, player_burst, player_refill_per_slot)
+    }
+
+    /// Escrow a wager in the CLR vault and open a `Bet` for a registered game.
+    /// Randomness-backed games need a fresh request from their provider. Fails once past
+    /// `deadline_slot`, when given.
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        nonce: u64,
+        amount: u64,
+        multiplier_bps: u32,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
+        bets::place(ctx, nonce, amount, multiplier_bps)
+    }
+
+    /// Place a bet as `place_bet` does, requiring the referral accounts and the player's
+    /// stats so the referrer earns its cut of the house edge (see `referrals`).
+    pub fn place_bet_with_referral(
+        ctx: Context<PlaceBet>,
+        nonce: u64,
+        amount: u64,
+        multiplier_bps: u32,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
+        require!(
+            ctx.accounts.referral_config.is_some()
+                && ctx.accounts.referrer.is_some()
+                && ctx.accounts.referral_vault.is_some()
+                && ctx.accounts.user_stats.is_some(),
+            MutrError::MissingReferral
+        );
+        bets::place(ctx, nonce, amount, multiplier_bps)
+    }
+
+    /// Like `place_bet`, with the wager given in USD cents. Needs the USD pricing and a
+    /// Pyth price update, here and when a win is resolved.
+    pub fn place_bet_usd(
+        ctx: Context<PlaceBet>,
+        nonce: u64,
+        usd_cents: u64,
+        multiplier_bps: u32,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        check_deadline(deadline_slot)?;
+        bets::place_usd(ctx, nonce, usd_cents, multiplier_bps)
+    }
+
+    /// Game/oracle: settle a pending bet, paying `amount * multiplier` on a win.
+    pub fn resolve_bet(ctx: Context<ResolveBet>, won: bool) -> Result<()> {
+        bets::resolve(ctx, won)
+    }
+
+    /// Permissionless: settle a randomness-backed bet from the revealed value.
+    pub fn resolve_bet_with_randomness(ctx: Context<ResolveBetWithRandomness>) -> Result<()> {
+        bets::resolve_with_randomness(ctx)
+    }
+
+    /// Permissionless: settle a bet from the game oracle's signed result, verified by
+    /// an Ed25519 program instruction placed right before this one.
+    pub fn resolve_bet_with_attestation(
+        ctx: Context<ResolveBetWithAttestation>,
+        won: bool,
+    ) -> Result<()> {
+        bets::resolve_with_attestation(ctx, won)
+    }
+
+    /// Recover the wager of a bet the game never resolved before its expiry slot, less
+    /// any transfer fee taken when it was placed. Randomness-backed bets are forfeited
+    /// rather than refunded.
+    pub fn refund_expired_bet(ctx: Context<RefundExpiredBet>) -> Result<()> {
+        bets::refund_expired(ctx)
+    }
+
+    /// Close a settled bet and refund its rent to the player.
+    pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
+        bets::close(ctx)
+    }
+
+    /// Game: check a wager the game escrowed itself against the player's
+    /// responsible-gaming limits, the protocol's daily wager limit, the game's rate limits
+    /// and the betting pause, and book it in the game's ledger.
+    pub fn record_game_wager(ctx: Context<RecordGameWager>, amount: u64) -> Result<()> {
+        bets::check_game_wager(ctx, amount)
+    }
+
+    /// Game: book in the game's ledger what it paid winners out of its own escrow.
+    pub fn record_game_payout(ctx: Context<RecordGamePayout>, amount: u64) -> Result<()> {
+        game_ledger::book_game_payout(ctx, amount)
+    }
+
+    /// Game: move revenue that carries no bankroll risk (a rake, a ticket cut) from a
+    /// token account the game controls into the CLR vault and book it as profit. While
+    /// nobody holds dividend shares it is left in the bankroll instead.
+    pub fn deposit_game_profit(ctx: Context<DepositGameProfit>, amount: u64) -> Result<()> {
+        bets::deposit_profit(ctx, amount)
+    }
+
+    /// Pay prize to a winner from the CLR vault (registered games only).
+    pub fn send_prize(ctx: Context<SendPrize>, amount: u64) -> Result<()> {
+        require!(amount > 0, MutrError::InvalidAmount);
+
+        let state = *ctx.accounts.state.load()?;
+        risk::check_payout(&state, ctx.accounts.clr_vault.amount, amount)?;
+        prize_vesting::check_unvested(&state, ctx.accounts.clr_vault.amount, amount)?;
+        let state_seeds: &[&[u8]] = &[
+            b"state",
+            &[state.bump],
+        ];
+        let signer_seeds = &[state_seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.clr_vault.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.winner_mutr_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+
+        ctx.accounts.game_ledger.load_mut()?.book_payout(amount)?;
+        stats::record_prize(&ctx.accounts.stats, &mut ctx.accounts.clr_vault, amount)
+    }
+
+    /// Game: pay several winners from the CLR vault in one transaction. Winner token
+    /// accounts are passed as remaining accounts, one per amount and each only once.
+    pub fn send_prize_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SendPrizeMany<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        prizes::send_many(ctx, amounts)
+    }
+
+    /// Game: credit a prize to the winner's claim account instead of pushing tokens.
+    pub fn credit_prize(ctx: Context<CreditPrize>, winner: Pubkey, amount: u64) -> Result<()> {
+        prizes::credit(ctx, winner, amount)
+    }
+
+    /// Winner: collect every prize credited so far; closes the claim account.
+    pub fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+        prizes::claim(ctx)
+    }
+
+    /// Admin: set the MUTR/USD price feed and the USD-denominated limits.
+    pub fn set_usd_pricing(ctx: Context<SetUsdPricing>, params: UsdPricingParams) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetUsdPricing {
+                params: params.clone(),
+            },
+        )?;
+        pyth::set_pricing(ctx, params)
+    }
+
+    /// Permissionless: reprice the CLR thresholds from their USD values.
+    pub fn sync_usd_thresholds(ctx: Context<SyncUsdThresholds>) -> Result<()> {
+        pyth::sync_thresholds(ctx)
+    }
+
+    /// Admin: create the registry of active pools; pool creation starts admin-only.
+    pub fn initialize_pool_registry(
+        ctx: Context<InitializePoolRegistry>,
+        creation_fee: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializePoolRegistry { creation_fee },
+        )?;
+        pools::initialize_registry(ctx, creation_fee)
+    }
+
+    /// Admin: open pool creation to everyone (for `creation_fee` lamports) or close it.
+    pub fn set_pool_creation(
+        ctx: Context<SetPoolCreation>,
+        permissionless: bool,
+        creation_fee: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetPoolCreation {
+                permissionless,
+                creation_fee,
+            },
+        )?;
+        pools::set_creation(ctx, permissionless, creation_fee)
+    }
+
+    /// Open a bankroll pool for `mint` with its own vault and share mint, and list it
+    /// in the registry. Admin-only unless creation is permissionless.
+    pub fn create_pool(ctx: Context<CreatePool>, params: PoolConfigParams) -> Result<()> {
+        pools::open(ctx, params)
+    }
+
+    /// Admin: activate or deactivate a pool.
+    pub fn set_pool_active(ctx: Context<SetPoolActive>, active: bool) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetPoolActive { active },
+        )?;
+        pools::set_active(ctx, active)
+    }
+
+    /// Admin: update a pool's fees and thresholds. `None` fields are left unchanged.
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        update: PoolConfigUpdate,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::UpdatePoolConfig {
+                update: update.clone(),
+            },
+        )?;
+        pools::configure(ctx, update)
+    }
+
+    /// Deposit into a pool's bankroll for pool shares.
+    pub fn pool_deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
+        pools::deposit(ctx, amount)
+    }
+
+    /// Deposit native SOL into the wSOL pool, wrapping it in the vault.
+    pub fn pool_deposit_sol(ctx: Context<PoolDepositSol>, lamports: u64) -> Result<()> {
+        pools::deposit_sol(ctx, lamports)
+    }
+
+    /// Burn pool shares for their part of the bankroll.
+    pub fn pool_withdraw(ctx: Context<PoolWithdraw>, shares: u64) -> Result<()> {
+        pools::withdraw(ctx, shares)
+    }
+
+    /// Burn wSOL pool shares and receive their value as native SOL.
+    pub fn pool_withdraw_sol(ctx: Context<PoolWithdrawSol>, shares: u64) -> Result<()> {
+        pools::withdraw_sol(ctx, shares)
+    }
+
+    /// Lock pool shares to earn the pool's dividends.
+    pub fn join_pool_dividends(ctx: Context<JoinPoolDividends>, shares: u64) -> Result<()> {
+        pools::join_dividends(ctx, shares)
+    }
+
+    /// Unlock pool shares from the dividends.
+    pub fn leave_pool_dividends(ctx: Context<LeavePoolDividends>, shares: u64) -> Result<()> {
+        pools::leave_dividends(ctx, shares)
+    }
+
+    /// Claim the dividends earned in a pool.
+    pub fn claim_pool_dividends(ctx: Context<ClaimPoolDividends>) -> Result<()> {
+        pools::claim_dividends(ctx)
+    }
+
+    /// Admin: book part of a pool's bankroll as profit for its dividend holders.
+    pub fn record_pool_profit(ctx: Context<RecordPoolProfit>, amount: u64) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::RecordPoolProfit { amount },
+        )?;
+        pools::book_dividends(ctx, amount)
+    }
+
+    /// Game: pay a prize from a pool's bankroll.
+    pub fn send_pool_prize(ctx: Context<SendPoolPrize>, amount: u64) -> Result<()> {
+        pools::pay_prize(ctx, amount)
+    }
+
+    /// Game: deposit risk-free revenue into a pool, booked for its dividend holders.
+    pub fn deposit_pool_profit(ctx: Context<DepositPoolProfit>, amount: u64) -> Result<()> {
+        pools::deposit_game_revenue(ctx, amount)
+    }
+
+    /// Admin: create the progressive jackpot and its vault.
+    pub fn initialize_jackpot(ctx: Context<InitializeJackpot>, contribution_bps: u16) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeJackpot { contribution_bps },
+        )?;
+        jackpot::create(ctx, contribution_bps)
+    }
+
+    /// Admin: set the share of every wager that feeds the jackpot.
+    pub fn set_jackpot_contribution(
+        ctx: Context<SetJackpotContribution>,
+        contribution_bps: u16,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetJackpotContribution { contribution_bps },
+        )?;
+        jackpot::set_contribution(ctx, contribution_bps)
+    }
+
+    /// Game: feed the jackpot its share of a wager the game took into the CLR vault.
+    pub fn contribute_jackpot(ctx: Context<ContributeJackpot>, wager: u64) -> Result<()> {
+        jackpot::contribute_for(ctx, wager)
+    }
+
+    /// Game: pay the whole jackpot to the winner of a jackpot outcome.
+    pub fn hit_jackpot(ctx: Context<HitJackpot>) -> Result<()> {
+        jackpot::hit(ctx)
+    }
+
+    /// Game: pay a prize too large for `send_prize`, part now and the rest streamed to
+    /// the winner over the vesting period (see `prize_vesting`).
+    pub fn send_vested_prize(
+        ctx: Context<SendVestedPrize>,
+        winner: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        prize_vesting::send_vested(ctx, winner, amount)
+    }
+
+    /// Winner: collect the part of a streamed prize released so far.
+    pub fn claim_vested_prize(ctx: Context<ClaimVestedPrize>) -> Result<()> {
+        prize_vesting::claim_vested(ctx)
+    }
+
+    /// Admin: create the protocol stats account. `unique_stakers` seeds the staker count
+    /// with wallets that staked before it existed.
+    pub fn initialize_stats(ctx: Context<InitializeStats>, unique_stakers: u64) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeStats { unique_stakers },
+        )?;
+        stats::open_stats(ctx, unique_stakers)
+    }
+
+    /// Admin: create the leaderboard of one kind.
+    pub fn initialize_leaderboard(
+        ctx: Context<InitializeLeaderboard>,
+        kind: LeaderboardKind,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeLeaderboard { kind },
+        )?;
+        leaderboard::create_board(ctx, kind)
+    }
+
+    /// Permissionless: insert or refresh a leaderboard entry by proving its score with
+    /// the position's `UserState` (stakers) or a won `Bet` (winners).
+    pub fn challenge_leaderboard(ctx: Context<ChallengeLeaderboard>) -> Result<()> {
+        leaderboard::challenge(ctx)
+    }
+
+    /// Admin: open a season, funding its prize vault from the treasury.
+    pub fn open_season(ctx: Context<OpenSeason>, id: u64, params: SeasonParams) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::OpenSeason {
+                id,
+                params: params.clone(),
+            },
+        )?;
+        seasons::start(ctx, id, params)
+    }
+
+    /// Create the caller's stats account for a season.
+    pub fn join_season(ctx: Context<JoinSeason>) -> Result<()> {
+        seasons::join(ctx)
+    }
+
+    /// Permissionless: pay an ended season's prizes to its leaderboard. The entries' MUTR
+    /// token accounts are passed as remaining accounts, in board order.
+    pub fn distribute_season_prizes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeSeasonPrizes<'info>>,
+    ) -> Result<()> {
+        seasons::distribute(ctx)
+    }
+
+    /// Admin: set the loyalty point accrual and redemption rates.
+    pub fn set_loyalty_config(ctx: Context<SetLoyaltyConfig>, params: LoyaltyParams) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetLoyaltyConfig {
+                params: params.clone(),
+            },
+        )?;
+        loyalty::set_rates(ctx, params)
+    }
+
+    /// Open the caller's loyalty `UserStats`.
+    pub fn open_user_stats(ctx: Context<OpenUserStats>) -> Result<()> {
+        loyalty::open_user(ctx)
+    }
+
+    /// Permissionless: settle a position's staking points into its owner's stats.
+    pub fn accrue_staking_points(ctx: Context<AccrueStakingPoints>) -> Result<()> {
+        loyalty::accrue_position(ctx)
+    }
+
+    /// Spend loyalty points for a fee credit or a bonus prize credit.
+    pub fn redeem_points(
+        ctx: Context<RedeemPoints>,
+        points: u64,
+        redemption: Redemption,
+    ) -> Result<()> {
+        loyalty::redeem(ctx, points, redemption)
+    }
+
+    /// Admin: set the share of each bet's house edge paid back as rakeback.
+    pub fn set_rakeback_config(ctx: Context<SetRakebackConfig>, rakeback_bps: u16) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetRakebackConfig { rakeback_bps },
+        )?;
+        rakeback::set_rate(ctx, rakeback_bps)
+    }
+
+    /// Admin: top up the rakeback vault from the treasury.
+    pub fn fund_rakeback(ctx: Context<FundRakeback>, amount: u64) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::FundRakeback { amount },
+        )?;
+        rakeback::fund(ctx, amount)
+    }
+
+    /// Collect accrued rakeback from the rakeback vault.
+    pub fn claim_rakeback(ctx: Context<ClaimRakeback>) -> Result<()> {
+        rakeback::claim_accrued(ctx)
+    }
+
+    /// Admin: set the VIP tiers, lowest first.
+    pub fn set_vip_config(ctx: Context<SetVipConfig>, tiers: Vec<VipTier>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetVipConfig {
+                tiers: tiers.clone(),
+            },
+        )?;
+        vip::set_tiers(ctx, tiers)
+    }
+
+    /// Admin: set the referrers' shares of stake fees and house edge.
+    pub fn set_referral_config(
+        ctx: Context<SetReferralConfig>,
+        fee_share_bps: u16,
+        edge_share_bps: u16,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetReferralConfig {
+                fee_share_bps,
+                edge_share_bps,
+            },
+        )?;
+        referrals::set_shares(ctx, fee_share_bps, edge_share_bps)
+    }
+
+    /// Claim a referral code for the caller.
+    pub fn create_referral_code(ctx: Context<CreateReferralCode>, code: String) -> Result<()> {
+        referrals::create_code(ctx, code)
+    }
+
+    /// Stake as `stake` does, requiring the referral accounts and the user's stats so the
+    /// referrer earns its cut of the stake fee (see `referrals`).
+    pub fn stake_with_referral(
+        ctx: Context<Stake>,
+        amount: u64,
+        position_index: u32,
+        lock_tier: u8,
+        deadline_slot: Option<u64>,
+        join_dividends: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.referral_config.is_some()
+                && ctx.accounts.referrer.is_some()
+                && ctx.accounts.referral_vault.is_some()
+                && ctx.accounts.user_stats.is_some(),
+            MutrError::MissingReferral
+        );
+        stake(
+            ctx,
+            amount,
+            position_index,
+            lock_tier,
+            deadline_slot,
+            join_dividends,
+        )
+    }
+
+    /// Collect a referrer's earnings from the referral vault.
+    pub fn claim_referral_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+        referrals::claim_earnings(ctx)
+    }
+
+    /// Admin: sign or update an affiliate's revenue-share agreement.
+    pub fn set_affiliate(ctx: Context<SetAffiliate>, terms: AffiliateTerms) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetAffiliate {
+                terms: terms.clone(),
+            },
+        )?;
+        affiliates::set_terms(ctx, terms)
+    }
+
+    /// Permissionless: pay a due affiliate its revenue share from the treasury.
+    pub fn settle_affiliate(ctx: Context<SettleAffiliate>) -> Result<()> {
+        affiliates::settle(ctx)
+    }
+
+    /// Admin: set the NFT collection that boosts positions and the boost it grants.
+    pub fn set_nft_boost_config(
+        ctx: Context<SetNftBoostConfig>,
+        collection: Pubkey,
+        stake_fee_discount_bps: u16,
+        dividend_boost_bps: u16,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetNftBoostConfig {
+                collection,
+                stake_fee_discount_bps,
+                dividend_boost_bps,
+            },
+        )?;
+        nft_boosts::set_collection(ctx, collection, stake_fee_discount_bps, dividend_boost_bps)
+    }
+
+    /// Boost a position with an NFT of the configured collection held by its owner.
+    pub fn verify_nft_boost(ctx: Context<VerifyNftBoost>, _position_index: u32) -> Result<()> {
+        nft_boosts::verify(ctx)
+    }
+
+    /// Permissionless: clear the boost of a position whose owner sold or moved the NFT.
+    pub fn revoke_nft_boost(ctx: Context<RevokeNftBoost>) -> Result<()> {
+        nft_boosts::revoke(ctx)
+    }
+
+    /// Set the wager milestone and metadata base URI of achievement NFTs.
+    pub fn set_achievement_config(
+        ctx: Context<SetAchievementConfig>,
+        wager_milestone: u64,
+        base_uri: String,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetAchievementConfig {
+                wager_milestone,
+                base_uri: base_uri.clone(),
+            },
+        )?;
+        achievements::set_milestones(ctx, wager_milestone, base_uri)
+    }
+
+    /// Mint the soulbound achievement NFT of a milestone the caller has reached.
+    pub fn mint_achievement(ctx: Context<MintAchievement>, milestone: Milestone) -> Result<()> {
+        achievements::mint(ctx, milestone)
+    }
+
+    /// Bar the caller from betting until `excluded_until`; can only be extended.
+    pub fn set_self_exclusion(ctx: Context<SetGamingLimits>, excluded_until: i64) -> Result<()> {
+        responsible_gaming::set_exclusion(ctx, excluded_until)
+    }
+
+    /// Cap the MUTR the caller can wager per day (0 removes the cap). A looser cap only
+    /// applies after a cooldown.
+    pub fn set_daily_wager_cap(ctx: Context<SetGamingLimits>, daily_wager_cap: u64) -> Result<()> {
+        responsible_gaming::set_wager_cap(ctx, daily_wager_cap)
+    }
+
+    /// Authorize `session_key` to place bets for the caller until `expires_at`, wagering
+    /// at most `spend_cap`, and send it `lamports` for fees.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        expires_at: i64,
+        spend_cap: u64,
+        lamports: u64,
+    ) -> Result<()> {
+        sessions::authorize(ctx, session_key, expires_at, spend_cap, lamports)
+    }
+
+    /// End a session early and revoke its delegation.
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        sessions::end(ctx)
+    }
+
+    /// View: MUTR per xMUTR, with the vault balance and xMUTR supply behind it.
+    pub fn get_exchange_rate(ctx: Context<GetExchangeRate>) -> Result<ExchangeRate> {
+        views::exchange_rate(ctx)
+    }
+
+    /// View: MUTR and bonus rewards a position could claim now.
+    pub fn get_pending_rewards(
+        ctx: Context<GetPendingRewards>,
+        _owner: Pubkey,
+        _position_index: u32,
+    ) -> Result<PendingRewards> {
+        views::pending(ctx)
+    }
+
+    /// View: dividend pool accounting and protocol totals.
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+        views::pool_stats(ctx)
+    }
+
+    /// Quote the transfer fee, stake fee and xMUTR `stake` would mint for `amount`.
+    pub fn preview_stake(
+        ctx: Context<PreviewStake>,
+        amount: u64,
+        _owner: Pubkey,
+        _position_index: u32,
+    ) -> Result<StakeQuote> {
+        views::quote_stake(ctx, amount)
+    }
+
+    /// Quote the MUTR value, unstake fee and payout `unstake` would give for `shares`.
+    pub fn preview_unstake(
+        ctx: Context<PreviewUnstake>,
+        shares: u64,
+        _owner: Pubkey,
+    ) -> Result<UnstakeQuote> {
+        views::quote_unstake(ctx, shares)
+    }
+
+    /// Admin: create the share price oracle, seeded with the current price.
+    pub fn initialize_share_price_oracle(ctx: Context<InitializeSharePriceOracle>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::InitializeSharePriceOracle {},
+        )?;
+        twap::create_oracle(ctx)
+    }
+
+    /// Share price averaged over the last `window_slots` slots, or as far back as the
+    /// oracle's observations reach.
+    pub fn get_twap(ctx: Context<GetTwap>, window_slots: u64) -> Result<Twap> {
+        views::time_weighted_price(ctx, window_slots)
+    }
+
+    /// Game: take the next event sequence number, to stamp on an event about to be
+    /// emitted.
+    pub fn next_event_nonce(ctx: Context<NextEventNonce>) -> Result<u64> {
+        events::take_nonce(ctx)
+    }
+
+    /// Admin: create the admin action log. Every authority-gated instruction requires it
+    /// from here on.
+    pub fn initialize_admin_log(ctx: Context<InitializeAdminLog>) -> Result<()> {
+        audit::create_log(ctx)
+    }
+
+    /// Permissionless: bring `GlobalState`, and the `UserState` if passed, up to the
+    /// current layout version, growing the account where needed.
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        migrations::migrate(ctx)
+    }
+
+    /// Owner: move a position opened before positions were indexed to the free
+    /// `position_index` and close the unindexed account.
+    pub fn migrate_legacy_position(
+        ctx: Context<MigrateLegacyPosition>,
+        position_index: u32,
+    ) -> Result<()> {
+        migrations::migrate_legacy(ctx, position_index)
+    }
+
+    /// Admin: grow `GlobalState`, a `GameConfig`, `Pool` or `PoolConfig` to the current
+    /// size of its layout. The authority pays the extra rent.
+    pub fn expand_account(ctx: Context<ExpandAccount>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::ExpandAccount {},
+        )?;
+        migrations::expand(ctx)
+    }
+
+    /// Admin: set the guarded launch deposit caps and the schedule raising them.
+    pub fn set_deposit_caps(
+        ctx: Context<SetDepositCaps>,
+        schedule: DepositCapSchedule,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetDepositCaps {
+                schedule: schedule.clone(),
+            },
+        )?;
+        deposit_caps::set_caps(ctx, schedule)
+    }
+
+    /// Permissionless: fold rounding dust left in the bonus vault into dividends.
+    pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+        dust::sweep(ctx)
+    }
+
+    /// Admin: name the guardian allowed to raise the pause flags; default for none.
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetGuardian { guardian },
+        )?;
+        guardian::appoint(ctx, guardian)
+    }
+
+    /// Admin: set or clear the pause flags.
+    pub fn set_pause_flags(ctx: Context<SetPauseFlags>, flags: u8) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetPauseFlags { flags },
+        )?;
+        guardian::set_flags(ctx, flags)
+    }
+
+    /// Guardian: raise pause flags; `PAUSE_ALL` trips the circuit breaker. Only the
+    /// authority clears them.
+    pub fn guardian_pause(ctx: Context<GuardianPause>, flags: u8) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.guardian,
+            &instruction::GuardianPause { flags },
+        )?;
+        guardian::pause(ctx, flags)
+    }
+
+    /// Create an M-of-N multisig whose signer PDA can hold the authority role (see
+    /// `multisig`). `id` tells apart the multisigs of one creator.
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        id: u64,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        multisig::create_wallet(ctx, id, owners, threshold)
+    }
+
+    /// Owner: propose a CLR instruction for the multisig to sign, approving it.
+    pub fn propose_multisig_transaction(
+        ctx: Context<ProposeMultisigTransaction>,
+        account_metas: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        multisig::propose(ctx, account_metas, data)
+    }
+
+    /// Owner: approve a proposed multisig transaction.
+    pub fn approve_multisig_transaction(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+        multisig::approve(ctx)
+    }
+
+    /// Permissionless: run a multisig transaction once enough owners approved it.
+    pub fn execute_multisig_transaction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteMultisigTransaction<'info>>,
+    ) -> Result<()> {
+        multisig::execute(ctx)
+    }
+
+    /// Admin: replace the operator keys allowed to book profit.
+    pub fn set_operators(ctx: Context<SetOperators>, operators: Vec<Pubkey>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetOperators {
+                operators: operators.clone(),
+            },
+        )?;
+        operators::set_keys(ctx, operators)
+    }
+
+    /// Operator: record new profit in the CLR as `record_profit` does.
+    pub fn operator_record_profit(
+        ctx: Context<OperatorRecordProfit>,
+        profit_amount: u64,
+    ) -> Result<()> {
+        operators::book(ctx, profit_amount)
+    }
+
+    /// Owner: let `delegate` claim and compound the position's rewards, or revoke it
+    /// with the default key (see `delegates`).
+    pub fn set_position_delegate(
+        ctx: Context<SetPositionDelegate>,
+        _position_index: u32,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        delegates::set_delegate(ctx, delegate)
+    }
+
+    /// Restake the position's pending MUTR rewards as xMUTR, fee-free. The owner or the
+    /// position's delegate may call it.
+    pub fn compound_rewards(ctx: Context<CompoundRewards>, _position_index: u32) -> Result<()> {
+        delegates::compound(ctx)
+    }
+
+    /// Permissionless: record the xMUTR supply, dividend shares and share price at the
+    /// current slot in a `Snapshot` for off-chain votes and airdrops (see `snapshots`).
+    pub fn take_snapshot(ctx: Context<TakeSnapshot>, id: u64) -> Result<()> {
+        snapshots::take(ctx, id)
+    }
+
+    /// Owner: offer the position to `new_owner`, moving its wallet xMUTR into the transfer
+    /// escrow and freezing it until accepted or cancelled (see `user_state_transfer`).
+    pub fn propose_user_state_transfer(
+        ctx: Context<ProposeUserStateTransfer>,
+        _position_index: u32,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        user_state_transfer::propose_transfer(ctx, new_owner)
+    }
+
+    /// Owner: withdraw a pending transfer offer and take the escrowed xMUTR back.
+    pub fn cancel_user_state_transfer(
+        ctx: Context<CancelUserStateTransfer>,
+        _position_index: u32,
+    ) -> Result<()> {
+        user_state_transfer::cancel_transfer(ctx)
+    }
+
+    /// New owner: take over a position offered to them, merging it with its pending
+    /// rewards into their position `position_index`.
+    pub fn accept_user_state_transfer(
+        ctx: Context<AcceptUserStateTransfer>,
+        position_index: u32,
+        _source_position_index: u32,
+    ) -> Result<()> {
+        user_state_transfer::accept_transfer(ctx, position_index)
+    }
+
+    /// Owner: name the address that may claim the position after a delay if the owner
+    /// loses their key, or remove it with the default key (see `recovery`).
+    pub fn set_recovery_address(
+        ctx: Context<SetRecoveryAddress>,
+        _position_index: u32,
+        recovery: Pubkey,
+    ) -> Result<()> {
+        recovery::set_address(ctx, recovery)
+    }
+
+    /// Recovery address: start a claim of the position, executable after
+    /// `RECOVERY_DELAY_SECS` unless the owner cancels it.
+    pub fn initiate_recovery(ctx: Context<InitiateRecovery>, _position_index: u32) -> Result<()> {
+        recovery::initiate(ctx)
+    }
+
+    /// Owner: cancel a pending recovery claim of the position.
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>, _position_index: u32) -> Result<()> {
+        recovery::cancel_claim(ctx)
+    }
+
+    /// Recovery address: once the delay has passed, move the position's escrowed dividend
+    /// shares and pending rewards into its own position `position_index`.
+    pub fn execute_recovery(
+        ctx: Context<ExecuteRecovery>,
+        position_index: u32,
+        _source_position_index: u32,
+    ) -> Result<()> {
+        recovery::execute_claim(ctx, position_index)
+    }
+
+    /// Admin: register the MUTR account emergency withdrawals pay into, dropping any
+    /// queued withdrawal (see `emergency_withdraw`).
+    pub fn set_recovery_vault(ctx: Context<SetRecoveryVault>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::SetRecoveryVault {},
+        )?;
+        emergency_withdraw::set_vault(ctx)
+    }
+
+    /// Admin: queue moving `amount` MUTR from the CLR vault to the recovery vault. It
+    /// becomes executable after `EMERGENCY_WITHDRAW_DELAY_SECS`.
+    pub fn queue_emergency_withdraw(
+        ctx: Context<QueueEmergencyWithdraw>,
+        amount: u64,
+    ) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::QueueEmergencyWithdraw { amount },
+        )?;
+        emergency_withdraw::queue(ctx, amount)
+    }
+
+    /// Admin: drop the queued emergency withdrawal.
+    pub fn cancel_emergency_withdraw(ctx: Context<CancelEmergencyWithdraw>) -> Result<()> {
+        audit::record(
+            &ctx.accounts.admin_log,
+            &ctx.accounts.authority,
+            &instruction::CancelEmergencyWithdraw {},
+        )?;
+        emergency_withdraw::cancel_withdraw(ctx)
+    }
+
+    /// Permissionless: carry out the queued emergency withdrawal once its delay has passed.
+    pub fn execute_emergency_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+        emergency_withdraw::execute_withdraw(ctx)
+    }
+
+    /// Permissionless: create the `GameLedger` of a game registered before ledgers
+    /// existed (see `game_ledger`).
+    pub fn initialize_game_ledger(ctx: Context<InitializeGameLedger>) -> Result<()> {
+        game_ledger::create_ledger(ctx)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Burn MUTR out of a vault owned by the `state` PDA. Zero amounts are a no-op.
+fn vault_burn<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    state: AccountInfo<'info>,
+    state_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let state_seeds: &[&[u8]] = &[
+        b"state",
+        &[state_bump],
+    ];
+    let signer_seeds = &[state_seeds];
+
+    let cpi_accounts = Burn {
+        mint,
+        from,
+        authority: state,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token_interface::burn(cpi_ctx, amount)
+}
+
+/// Amount a vault gained since `balance_before`, i.e. what a transfer into it actually
+/// delivered once a Token-2022 transfer fee is withheld.
+fn received_amount(vault: &mut InterfaceAccount<TokenAccount>, balance_before: u64) -> Result<u64> {
+    vault.reload()?;
+    Ok(vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(MutrError::MathOverflow)?)
+}
+
+/// Transfer out of a vault owned by the `state` PDA. Zero amounts are a no-op.
+fn vault_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    state: AccountInfo<'info>,
+    state_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let state_seeds: &[&[u8]] = &[
+        b"state",
+        &[state_bump],
+    ];
+    let signer_seeds = &[state_seeds];
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to,
+        authority: state,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Reject a transaction landing after the slot its sender gave as deadline, so a quote
+/// that went stale while the network was congested is not acted on.
+fn check_deadline(deadline_slot: Option<u64>) -> Result<()> {
+    if let Some(deadline_slot) = deadline_slot {
+        require!(
+            Clock::get()?.slot <= deadline_slot,
+            MutrError::DeadlineExpired
+        );
+    }
+    Ok(())
+}
+
+/// Register a freshly created `UserState` as position `position_index` of `owner`.
+/// Positions are opened sequentially; indices of closed positions may be reused.
+fn open_position_if_new(
+    positions: &mut UserPositions,
+    user_state: &mut UserState,
+    owner: Pubkey,
+    position_index: u32,
+) -> Result<()> {
+    if user_state.owner != Pubkey::default() {
+        return Ok(());
+    }
+    if positions.owner == Pubkey::default() {
+        positions.owner = owner;
+    }
+    require_keys_eq!(positions.owner, owner, MutrError::Unauthorized);
+    require!(
+        position_index <= positions.position_count,
+        MutrError::InvalidPositionIndex
+    );
+    if position_index == positions.position_count {
+        positions.position_count = positions
+            .position_count
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+    }
+    user_state.owner = owner;
+    user_state.position_index = position_index;
+    user_state.boost_bps = BPS_DENOMINATOR as u16;
+    user_state.version = USER_STATE_VERSION;
+    Ok(())
+}
+
+/// Lock duration (seconds) and dividend boost (bps) for a lock tier.
+fn lock_tier_terms(lock_tier: u8) -> Result<(i64, u16)> {
+    match lock_tier {
+        0 => Ok((0, 10_000)),                     // unlocked, 1x
+        1 => Ok((30 * SECONDS_PER_DAY, 10_000)),  // 30 days, 1x
+        2 => Ok((90 * SECONDS_PER_DAY, 15_000)),  // 90 days, 1.5x
+        3 => Ok((180 * SECONDS_PER_DAY, 20_000)), // 180 days, 2x
+        _ => err!(MutrError::InvalidLockTier),
+    }
+}
+
+/// Apply the lock tier chosen at stake time. A position keeps a single tier. The owner
+/// staking more into a locked position extends its lock to a full term from now, never
+/// shortening it; a stake on someone else's behalf only locks a position that holds
+/// nothing yet, so a third party cannot push an existing lock out.
+fn apply_lock_tier(user: &mut UserState, lock_tier: u8, by_owner: bool) -> Result<()> {
+    let (duration, boost_bps) = lock_tier_terms(lock_tier)?;
+    let is_empty = user.staked_shares == 0 && user.dividend_shares == 0;
+    require!(
+        is_empty || user.lock_tier == lock_tier,
+        MutrError::LockTierMismatch
+    );
+    user.lock_tier = lock_tier;
+    user.boost_bps = boost_bps;
+    if duration > 0 && (by_owner || is_empty) {
+        let lock_until = Clock::get()?
+            .unix_timestamp
+            .checked_add(duration)
+            .ok_or(MutrError::MathOverflow)?;
+        user.lock_until = user.lock_until.max(lock_until);
+    }
+    Ok(())
+}
+
+/// Move a position's shares and accrued rewards into `holder`, leaving `source` empty so
+/// its owner can close it.
+fn merge_position(
+    state: &mut GlobalState,
+    source: &mut UserState,
+    holder: &mut UserState,
+) -> Result<()> {
+    settle_user_rewards(state, source)?;
+    settle_user_rewards(state, holder)?;
+
+    // locked shares stay locked: a fresh position inherits the lock, an existing one
+    // must be on the same tier and keeps the later expiry
+    if holder.staked_shares == 0 && holder.dividend_shares == 0 {
+        holder.lock_tier = source.lock_tier;
+        holder.lock_until = source.lock_until;
+        holder.boost_bps = source.boost_bps;
+    } else {
+        require!(
+            holder.lock_tier == source.lock_tier,
+            MutrError::LockTierMismatch
+        );
+        holder.lock_until = holder.lock_until.max(source.lock_until);
+    }
+    holder.joined_at = blend_joined_at(
+        holder.joined_at,
+        holder.dividend_shares,
+        source.joined_at,
+        source.dividend_shares,
+    )?;
+    holder.joined_slot = holder.joined_slot.max(source.joined_slot);
+    // the later loyalty clock wins, so no shares earn points for time before it
+    holder.points_accrued_at = holder.points_accrued_at.max(source.points_accrued_at);
+    let weight_before = dividend_weight(source)?
+        .checked_add(dividend_weight(holder)?)
+        .ok_or(MutrError::MathOverflow)?;
+
+    holder.staked_shares = holder
+        .staked_shares
+        .checked_add(source.staked_shares)
+        .ok_or(MutrError::MathOverflow)?;
+    holder.dividend_shares = holder
+        .dividend_shares
+        .checked_add(source.dividend_shares)
+        .ok_or(MutrError::MathOverflow)?;
+    holder.escrowed_shares = holder
+        .escrowed_shares
+        .checked_add(source.escrowed_shares)
+        .ok_or(MutrError::MathOverflow)?;
+    holder.pending_rewards = holder
+        .pending_rewards
+        .checked_add(source.pending_rewards)
+        .ok_or(MutrError::MathOverflow)?;
+    holder.pending_rewards_2 = holder
+        .pending_rewards_2
+        .checked_add(source.pending_rewards_2)
+        .ok_or(MutrError::MathOverflow)?;
+    checkpoint_reward_debt(state, holder)?;
+
+    // the source position is left empty so its owner can close it
+    source.staked_shares = 0;
+    source.dividend_shares = 0;
+    source.escrowed_shares = 0;
+    source.pending_rewards = 0;
+    source.reward_debt = 0;
+    source.pending_rewards_2 = 0;
+    source.reward_debt_2 = 0;
+
+    // merging can shift the weight by rounding only
+    let weight_after = dividend_weight(holder)?;
+    state.total_dividend_shares = state
+        .total_dividend_shares
+        .checked_sub(weight_before)
+        .ok_or(MutrError::MathOverflow)?
+        .checked_add(weight_after)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+/// Move `shares` of a position's staked xMUTR into the dividend pool, settling its
+/// rewards first.
+fn enter_dividend_pool(
+    state: &mut GlobalState,
+    user_state: &mut UserState,
+    shares: u64,
+) -> Result<()> {
+    // settle current rewards
+    settle_user_rewards(state, user_state)?;
+    let weight_before = dividend_weight(user_state)?;
+
+    // the exit penalty clock restarts for new shares only
+    user_state.joined_at = blend_joined_at(
+        user_state.joined_at,
+        user_state.dividend_shares,
+        Clock::get()?.unix_timestamp,
+        shares,
+    )?;
+    user_state.joined_slot = Clock::get()?.slot;
+
+    user_state.staked_shares = user_state
+        .staked_shares
+        .checked_sub(shares)
+        .ok_or(MutrError::MathOverflow)?;
+    user_state.dividend_shares = user_state
+        .dividend_shares
+        .checked_add(shares)
+        .ok_or(MutrError::MathOverflow)?;
+
+    // total_dividend_shares tracks boost-weighted shares
+    let weight_after = dividend_weight(user_state)?;
+    state.total_dividend_shares = state
+        .total_dividend_shares
+        .checked_add(weight_after - weight_before)
+        .ok_or(MutrError::MathOverflow)?;
+
+    // update reward debt
+    checkpoint_reward_debt(state, user_state)?;
+
+    Ok(())
+}
+
+/// Dividend-share-weighted average of two join timestamps.
+pub fn blend_joined_at(
+    joined_at: i64,
+    shares: u64,
+    other_joined_at: i64,
+    other_shares: u64,
+) -> Result<i64> {
+    let total = (shares as i128)
+        .checked_add(other_shares as i128)
+        .ok_or(MutrError::MathOverflow)?;
+    if total == 0 {
+        return Ok(other_joined_at);
+    }
+    let weighted = (joined_at as i128)
+        .checked_mul(shares as i128)
+        .ok_or(MutrError::MathOverflow)?
+        .checked_add(
+            (other_joined_at as i128)
+                .checked_mul(other_shares as i128)
+                .ok_or(MutrError::MathOverflow)?,
+        )
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(i64::try_from(weighted / total).map_err(|_| MutrError::MathOverflow)?)
+}
+
+/// Dividend-pool exit fee in bps: `exit_penalty_max_bps` at `joined_at`, decaying
+/// linearly to zero after `exit_penalty_decay_secs`.
+pub fn exit_penalty_bps(state: &GlobalState, user: &UserState, now: i64) -> Result<u16> {
+    let elapsed = now.saturating_sub(user.joined_at).max(0);
+    if elapsed >= state.exit_penalty_decay_secs {
+        return Ok(0);
+    }
+    let remaining = (state.exit_penalty_decay_secs - elapsed) as u128;
+    let bps = (state.exit_penalty_max_bps as u128)
+        .checked_mul(remaining)
+        .ok_or(MutrError::MathOverflow)?
+        / state.exit_penalty_decay_secs as u128;
+    Ok(bps as u16)
+}
+
+/// Boost-weighted dividend shares of a position.
+pub fn dividend_weight(user: &UserState) -> Result<u128> {
+    Ok((user.dividend_shares as u128)
+        .checked_mul(user.boost_bps as u128 + user.nft_boost_bps as u128)
+        .ok_or(MutrError::MathOverflow)?
+        / BPS_DENOMINATOR as u128)
+}
+
+/// Reward debt matching the position's current weight.
+pub fn reward_debt_for(state: &GlobalState, user: &UserState) -> Result<u128> {
+    Ok(dividend_weight(user)?
+        .checked_mul(state.acc_reward_per_share)
+        .ok_or(MutrError::MathOverflow)?)
+}
+
+/// Bonus-token reward debt matching the position's current weight.
+fn bonus_reward_debt_for(state: &GlobalState, user: &UserState) -> Result<u128> {
+    Ok(dividend_weight(user)?
+        .checked_mul(state.acc_reward_per_share_2)
+        .ok_or(MutrError::MathOverflow)?)
+}
+
+/// Checkpoint both reward debts after the position's weight changed.
+pub fn checkpoint_reward_debt(state: &GlobalState, user: &mut UserState) -> Result<()> {
+    user.reward_debt = reward_debt_for(state, user)?;
+    user.reward_debt_2 = bonus_reward_debt_for(state, user)?;
+    Ok(())
+}
+
+/// Settle MUTR and bonus rewards into pending and checkpoint the reward debts.
+pub fn settle_user_rewards(state: &GlobalState, user: &mut UserState) -> Result<()> {
+    user.pending_rewards = pending_rewards(state, user)? as u128;
+    user.pending_rewards_2 = pending_bonus_rewards(state, user)? as u128;
+    checkpoint_reward_debt(state, user)
+}
+
+/// Anchor instruction discriminator for a Clockwork thread program instruction.
+fn thread_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Clockwork thread address for `id` under the program's automation authority.
+fn thread_address(automation_authority: &Pubkey, id: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"thread", automation_authority.as_ref(), id],
+        &thread_program::ID,
+    )
+    .0
+}
+
+/// Fold profit into `acc_reward_per_share` for the current dividend shares.
+pub fn distribute_profit(state: &mut GlobalState, profit_amount: u64) -> Result<()> {
+    require!(state.total_dividend_shares > 0, MutrError::NoDividendShares);
+
+    let increment = (profit_amount as u128)
+        .checked_mul(REWARD_PRECISION)
+        .ok_or(MutrError::MathOverflow)?
+        .checked_div(state.total_dividend_shares)
+        .ok_or(MutrError::MathOverflow)?;
+
+    state.acc_reward_per_share = state
+        .acc_reward_per_share
+        .checked_add(increment)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+/// Book new profit: buffered until the epoch rolls over when profit epochs are enabled,
+/// released right away otherwise.
+fn book_profit(state: &mut GlobalState, profit_amount: u64) -> Result<()> {
+    if state.profit_epoch_secs == 0 {
+        if state.drip_slots == 0 {
+            claim_lockup::mark_distribution(state)?;
+        }
+        return release_profit(state, profit_amount, Clock::get()?.slot);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    roll_profit_epoch_if_due(state, now, 0)?;
+    state.profit_buffer = state
+        .profit_buffer
+        .checked_add(profit_amount)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+/// Hand profit to the dividend pool: straight into reward per share, or into the drip
+/// buffer when `drip_slots` is set. New profit restarts the drip window for everything
+/// still undripped.
+pub fn release_profit(state: &mut GlobalState, profit_amount: u64, slot: u64) -> Result<()> {
+    if state.drip_slots == 0 {
+        return distribute_profit(state, profit_amount);
+    }
+
+    drip_due(state, slot, 0)?;
+    state.drip_remaining = state
+        .drip_remaining
+        .checked_add(profit_amount)
+        .ok_or(MutrError::MathOverflow)?;
+    state.drip_last_slot = slot;
+    state.drip_end_slot = slot
+        .checked_add(state.drip_slots)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+/// Release the linearly vested part of the drip buffer up to `slot`, holding back
+/// `keeper_fee_bps` of it for the caller. Nothing is released (and the clock is not
+/// advanced) while the dividend pool is empty. Returns the keeper fee.
+pub fn drip_due(state: &mut GlobalState, slot: u64, keeper_fee_bps: u16) -> Result<u64> {
+    if state.drip_remaining == 0 || state.total_dividend_shares == 0 {
+        return Ok(0);
+    }
+    let until = slot.min(state.drip_end_slot);
+    if until <= state.drip_last_slot {
+        return Ok(0);
+    }
+
+    let elapsed = (until - state.drip_last_slot) as u128;
+    let window = (state.drip_end_slot - state.drip_last_slot) as u128;
+    let release = ((state.drip_remaining as u128)
+        .checked_mul(elapsed)
+        .ok_or(MutrError::MathOverflow)?
+        / window) as u64;
+
+    let distributed = after_cut(release, keeper_fee_bps)?;
+    distribute_profit(state, distributed)?;
+    state.drip_remaining -= release;
+    state.drip_last_slot = until;
+    Ok(release - distributed)
+}
+
+/// Roll the profit epoch over if it has ended, folding the buffer into reward per
+/// share. The buffer is carried forward while nobody is in the dividend pool.
+/// `keeper_fee_bps` of the buffer is held back for the caller.
+/// Returns the keeper fee, or `None` if the epoch has not ended yet.
+fn roll_profit_epoch_if_due(
+    state: &mut GlobalState,
+    now: i64,
+    keeper_fee_bps: u16,
+) -> Result<Option<u64>> {
+    let epoch_end = state
+        .profit_epoch_start
+        .checked_add(state.profit_epoch_secs)
+        .ok_or(MutrError::MathOverflow)?;
+    if now < epoch_end {
+        return Ok(None);
+    }
+
+    let mut keeper_fee = 0;
+    if (state.profit_buffer > 0 || state.smoothing_reserve > 0) && state.total_dividend_shares > 0
+    {
+        let buffered = smoothing::smooth(state)?;
+        let released = after_cut(buffered, keeper_fee_bps)?;
+        if state.drip_slots == 0 {
+            claim_lockup::mark_distribution(state)?;
+        }
+        release_profit(state, released, Clock::get()?.slot)?;
+        keeper_fee = buffered - released;
+    }
+    state.profit_epoch_start = now;
+    Ok(Some(keeper_fee))
+}
+
+/// Calculate pending bonus-token rewards (current).
+fn pending_bonus_rewards(state: &GlobalState, user: &UserState) -> Result<u64> {
+    if user.dividend_shares == 0 {
+        return Ok(user.pending_rewards_2 as u64);
+    }
+    let pending = bonus_reward_debt_for(state, user)?
+        .checked_sub(user.reward_debt_2)
+        .ok_or(MutrError::MathOverflow)?
+        / REWARD_PRECISION;
+    Ok(pending
+        .checked_add(user.pending_rewards_2)
+        .ok_or(MutrError::MathOverflow)? as u64)
+}
+
+/// Calculate pending rewards (current).
+pub fn pending_rewards(state: &GlobalState, user: &UserState) -> Result<u64> {
+    if user.dividend_shares == 0 {
+        return Ok(user.pending_rewards as u64);
+    }
+    let acc_per_share = state.acc_reward_per_share;
+    let accumulated = dividend_weight(user)?
+        .checked_mul(acc_per_share)
+        .unwrap();
+    let pending_u128 = accumulated
+        .checked_sub(user.reward_debt)
+        .unwrap()
+        .checked_div(REWARD_PRECISION)
+        .unwrap()
+        .checked_add(user.pending_rewards)
+        .unwrap();
+    Ok(pending_u128 as u64)
+}
+
+/// Verify a Merkle proof using sorted-pair keccak hashing (same scheme as `mutr_distributor`).
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).0
+        } else {
+            keccak::hashv(&[node, &computed]).0
+        };
+    }
+    computed == root
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Zero-copy so the hot path reads it in place; fields are grouped by alignment
+/// (u128, then 32-, 8-, 2- and 1-byte fields) so the layout has no implicit padding.
+#[account(zero_copy)]
+pub struct GlobalState {
+    pub acc_reward_per_share: u128,
+    pub acc_reward_per_share_2: u128,
+    /// Boost-weighted dividend shares (see `lock_tier_terms`)
+    pub total_dividend_shares: u128,
+
+    pub authority: Pubkey,
+    pub mutr_mint: Pubkey,
+    pub xmutr_mint: Pubkey,
+    pub clr_vault: Pubkey,
+    /// Secondary reward token for incentive campaigns (default until initialized)
+    pub bonus_mint: Pubkey,
+    pub bonus_vault: Pubkey,
+    /// Pre-funded MUTR emissions (see `emissions`)
+    pub emission_vault: Pubkey,
+    /// Protocol treasury (kept apart from the game bankroll)
+    pub treasury_vault: Pubkey,
+
+    pub lower_threshold: u64,
+    pub upper_threshold: u64,
+    /// Dividend-pool exit fee decays to zero over this many seconds after joining
+    pub exit_penalty_decay_secs: i64,
+    /// Profit epoch length in seconds; 0 distributes profit immediately
+    pub profit_epoch_secs: i64,
+    pub profit_epoch_start: i64,
+    /// Profit recorded during the current epoch, not yet in `acc_reward_per_share`
+    pub profit_buffer: u64,
+    /// Slots over which released profit drips into reward per share; 0 disables the drip
+    pub drip_slots: u64,
+    pub drip_remaining: u64,
+    pub drip_last_slot: u64,
+    pub drip_end_slot: u64,
+    pub emission_rate_per_slot: u64,
+    pub emission_end_slot: u64,
+    pub emission_last_slot: u64,
+    pub treasury_spend_delay_secs: i64,
+    pub spend_proposal_count: u64,
+    /// Number of posted Merkle dividend epochs
+    pub dividend_epoch_count: u64,
+    /// Most MUTR a wallet can wager per rolling day; 0 disables the limit (see
+    /// `responsible_gaming`)
+    pub max_daily_wager: u64,
+    /// Sequence number of the last event (see `events`)
+    pub event_nonce: u64,
+
+    pub stake_fee_bps: u16,
+    pub unstake_fee_bps: u16,
+    /// Dividend-pool exit fee right after joining
+    pub exit_penalty_max_bps: u16,
+    /// Cut of the processed amount paid to whoever calls a permissionless crank
+    pub keeper_fee_bps: u16,
+    /// Largest single payout as a share of the CLR vault; 0 disables the cap
+    pub max_payout_bps: u16,
+    /// Prizes above this share of the CLR vault are streamed, `prize_upfront_bps` of them
+    /// paid at once and the rest over `prize_vesting_slots` (see `prize_vesting`); 0 for
+    /// no streaming
+    pub prize_vesting_threshold_bps: u16,
+    pub prize_upfront_bps: u16,
+    /// Routing of stake/unstake/dividend-exit fees
+    pub fee_split: FeeSplit,
+    pub prize_vesting_slots: u32,
+
+    /// Non-zero when `place_bet` enforces the USD limits of `UsdPricing` (see `pyth`)
+    pub usd_limits_enabled: u8,
+    pub bump: u8,
+    /// Layout version (see `migrations`)
+    pub version: u8,
+    pub _padding: [u8; 5],
+    /// Smallest MUTR amount `stake` takes and smallest MUTR value (before fees) `unstake`
+    /// pays out short of closing the position; 0 for no minimum
+    pub min_stake: u64,
+    pub min_unstake: u64,
+    /// Guarded launch caps on the vault and on one staker, their targets and the window
+    /// they rise over (see `deposit_caps`); 0 for no cap
+    pub tvl_cap: u64,
+    pub user_stake_cap: u64,
+    pub tvl_cap_target: u64,
+    pub user_stake_cap_target: u64,
+    pub cap_ramp_start: i64,
+    pub cap_ramp_end: i64,
+    /// Bonus tokens credited to dividend shares and not claimed yet (see `dust`)
+    pub bonus_owed: u64,
+    /// Key allowed to raise `pause_flags` and nothing else; default for none
+    pub guardian: Pubkey,
+    /// `PAUSE_*` bits of the halted operations (see `guardian`)
+    pub pause_flags: u8,
+    pub _padding_2: [u8; 7],
+    /// Slot of the last lump profit distribution and how long positions that joined
+    /// just before it wait to claim or leave (see `claim_lockup`)
+    pub last_profit_slot: u64,
+    pub claim_lockup_slots: u64,
+    /// xMUTR burned by dividend-pool exit fees so far
+    pub exit_fee_shares_burned: u64,
+    /// Profit of the latest epochs, newest first, and the part of it not distributed yet
+    /// (see `smoothing`)
+    pub smoothing_ring: [u64; MAX_SMOOTHING_EPOCHS],
+    pub smoothing_reserve: u64,
+    /// Epochs each rollover averages the profit over; 0 or 1 for no smoothing
+    pub smoothing_epochs: u8,
+    /// Non-zero once the jackpot exists; every wager must then feed it (see `jackpot`)
+    pub jackpot_enabled: u8,
+    pub _padding_3: [u8; 6],
+    /// Streamed prize remainders not claimed yet; they sit in the CLR vault but do not
+    /// back xMUTR (see `prize_vesting`)
+    pub prize_vesting_owed: u64,
+    /// Posted dividend epoch rewards not claimed yet; like `prize_vesting_owed`, held out
+    /// of what backs xMUTR
+    pub epoch_rewards_owed: u64,
+    /// Room for fields added later; they take bytes from the front so the account keeps
+    /// its size (see `migrations`)
+    pub _reserved: [u8; 56],
+}
+
+impl GlobalState {
+    pub const LEN: usize = std::mem::size_of::<GlobalState>();
+
+    /// Part of a CLR vault balance that backs xMUTR: the balance less what the vault
+    /// still owes streamed prizes and posted dividend epochs.
+    pub fn backing(&self, vault_balance: u64) -> u64 {
+        vault_balance
+            .saturating_sub(self.prize_vesting_owed)
+            .saturating_sub(self.epoch_rewards_owed)
+    }
+}
+
+#[account]
+pub struct UserState {
+    pub owner: Pubkey,
+    pub position_index: u32,
+    pub staked_shares: u64,
     pub dividend_shares: u64,
     pub reward_debt: u128,
     pub pending_rewards: u128,
+    /// Receipt NFT mint while the position is tokenized, default otherwise
+    pub receipt_mint: Pubkey,
+    pub lock_tier: u8,
+    /// Unix timestamp before which the position cannot be unstaked
+    pub lock_until: i64,
+    /// Dividend weight multiplier in bps (10_000 = 1x)
+    pub boost_bps: u16,
+    /// Share-weighted time the dividend shares joined the pool (exit penalty clock)
+    pub joined_at: i64,
+    /// Bonus-token counterparts of `reward_debt` / `pending_rewards`
+    pub reward_debt_2: u128,
+    pub pending_rewards_2: u128,
+    /// Unix timestamp the position's loyalty points were last settled at (see `loyalty`)
+    pub points_accrued_at: i64,
+    /// NFT boosting the position, default otherwise (see `nft_boosts`)
+    pub nft_boost_mint: Pubkey,
+    /// Added to `boost_bps` while the NFT boost holds
+    pub nft_boost_bps: u16,
+    /// Taken off the stake fee while the NFT boost holds
+    pub nft_fee_discount_bps: u16,
+    /// Unix timestamp before which the owner cannot bet; position 0 only, like the
+    /// fields below (see `responsible_gaming`)
+    pub excluded_until: i64,
+    /// Most MUTR the owner can wager per day, 0 for no cap
+    pub daily_wager_cap: u64,
+    /// Looser cap waiting out its cooldown, applied at `pending_wager_cap_at`
+    pub pending_wager_cap: u64,
+    pub pending_wager_cap_at: i64,
+    /// Start of the current daily cap window and the MUTR wagered in it
+    pub wager_window_start: i64,
+    pub wagered_in_window: u64,
+    /// Layout version (see `migrations`)
+    pub version: u8,
+    /// Slot the position last joined the dividend pool at (see `claim_lockup`)
+    pub joined_slot: u64,
+    /// Part of `dividend_shares` whose xMUTR is held in the dividend vault (see
+    /// `dividend_escrow`)
+    pub escrowed_shares: u64,
+    /// Key that may claim and compound for the owner, default for none (see `delegates`)
+    pub delegate: Pubkey,
+    /// Owner the position is being handed to, default for none (see
+    /// `user_state_transfer`)
+    pub pending_owner: Pubkey,
+    /// Key that may claim the position if the owner loses theirs, default for none (see
+    /// `recovery`)
+    pub recovery: Pubkey,
+    /// Unix timestamp a pending recovery claim can be executed from, 0 for none
+    pub recovery_claimable_at: i64,
+}
+
+impl UserState {
+    pub const LEN: usize = 32 // owner
+        + 4  // position_index
+        + 8  // staked_shares
+        + 8  // dividend_shares
+        + 16 // reward_debt
+        + 16 // pending_rewards
+        + 32 // receipt_mint
+        + 1  // lock_tier
+        + 8  // lock_until
+        + 2  // boost_bps
+        + 8  // joined_at
+        + 16 // reward_debt_2
+        + 16 // pending_rewards_2
+        + 8  // points_accrued_at
+        + 32 // nft_boost_mint
+        + 2  // nft_boost_bps
+        + 2  // nft_fee_discount_bps
+        + 8  // excluded_until
+        + 8  // daily_wager_cap
+        + 8  // pending_wager_cap
+        + 8  // pending_wager_cap_at
+        + 8  // wager_window_start
+        + 8  // wagered_in_window
+        + 1  // version
+        + 8  // joined_slot
+        + 8  // escrowed_shares
+        + 32 // delegate
+        + 32 // pending_owner
+        + 32 // recovery
+        + 8; // recovery_claimable_at
+
+    pub fn is_tokenized(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+
+    /// Reject changes to a position held as a receipt NFT or on its way to a new owner.
+    pub fn check_movable(&self) -> Result<()> {
+        require!(!self.is_tokenized(), MutrError::PositionTokenized);
+        require_keys_eq!(
+            self.pending_owner,
+            Pubkey::default(),
+            MutrError::PositionTransferPending
+        );
+        Ok(())
+    }
+}
+
+/// How collected fees are routed; the three parts must add up to 100%.
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct FeeSplit {
+    /// Stays in the CLR vault (accrues to xMUTR holders)
+    pub clr_bps: u16,
+    /// Burned from the CLR vault
+    pub burn_bps: u16,
+    /// Sent to the treasury vault
+    pub treasury_bps: u16,
+}
+
+impl FeeSplit {
+    pub fn validate(&self) -> Result<()> {
+        let total = self.clr_bps as u64 + self.burn_bps as u64 + self.treasury_bps as u64;
+        require!(total == BPS_DENOMINATOR, MutrError::InvalidFeeSplit);
+        Ok(())
+    }
+
+    /// `(burn, treasury)` portions of a fee; the CLR keeps the remainder.
+    pub fn portions(&self, fee: u64) -> Result<(u64, u64)> {
+        let part = |bps: u16| -> Result<u64> {
+            Ok(((fee as u128)
+                .checked_mul(bps as u128)
+                .ok_or(MutrError::MathOverflow)?
+                / BPS_DENOMINATOR as u128) as u64)
+        };
+        Ok((part(self.burn_bps)?, part(self.treasury_bps)?))
+    }
+}
+
+impl Default for FeeSplit {
+    /// Everything stays in the CLR.
+    fn default() -> Self {
+        Self {
+            clr_bps: BPS_DENOMINATOR as u16,
+            burn_bps: 0,
+            treasury_bps: 0,
+        }
+    }
+}
+
+/// Partial config update for `update_config`; `None` keeps the current value.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ConfigUpdate {
+    pub stake_fee_bps: Option<u16>,
+    pub unstake_fee_bps: Option<u16>,
+    pub lower_threshold: Option<u64>,
+    pub upper_threshold: Option<u64>,
+    pub exit_penalty_max_bps: Option<u16>,
+    pub exit_penalty_decay_secs: Option<i64>,
+    pub fee_split: Option<FeeSplit>,
+    pub profit_epoch_secs: Option<i64>,
+    pub drip_slots: Option<u64>,
+    pub keeper_fee_bps: Option<u16>,
+    pub max_payout_bps: Option<u16>,
+    pub prize_vesting_threshold_bps: Option<u16>,
+    pub prize_upfront_bps: Option<u16>,
+    pub prize_vesting_slots: Option<u32>,
+    pub max_daily_wager: Option<u64>,
+    pub min_stake: Option<u64>,
+    pub min_unstake: Option<u64>,
+    pub claim_lockup_slots: Option<u64>,
+    pub smoothing_epochs: Option<u8>,
+}
+
+/// Parameters of `initialize_clr`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct InitConfig {
+    /// Must match the `mutr_mint` and `xmutr_mint` accounts passed
+    pub mutr_mint: Pubkey,
+    pub xmutr_mint: Pubkey,
+    pub stake_fee_bps: u16,
+    pub unstake_fee_bps: u16,
+    pub lower_threshold: u64,
+    pub upper_threshold: u64,
+    pub exit_penalty_max_bps: u16,
+    pub exit_penalty_decay_secs: i64,
+}
+
+impl InitConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.stake_fee_bps <= MAX_FEE_BPS && self.unstake_fee_bps <= MAX_FEE_BPS,
+            MutrError::InvalidConfig
+        );
+        require!(
+            self.lower_threshold <= self.upper_threshold,
+            MutrError::InvalidConfig
+        );
+        require!(
+            self.exit_penalty_max_bps <= MAX_EXIT_PENALTY_BPS
+                && self.exit_penalty_decay_secs >= 0,
+            MutrError::InvalidConfig
+        );
+        require_keys_neq!(self.mutr_mint, Pubkey::default(), MutrError::InvalidMint);
+        require_keys_neq!(self.xmutr_mint, Pubkey::default(), MutrError::InvalidMint);
+        require_keys_neq!(self.mutr_mint, self.xmutr_mint, MutrError::InvalidMint);
+        Ok(())
+    }
+}
+
+/// Queued treasury payout, executable after `executable_at`.
+#[account]
+pub struct SpendProposal {
+    pub id: u64,
+    pub amount: u64,
+    /// MUTR token account receiving the spend
+    pub recipient: Pubkey,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+impl SpendProposal {
+    pub const LEN: usize = 8 // id
+        + 8  // amount
+        + 32 // recipient
+        + 8  // executable_at
+        + 1; // bump
+}
+
+/// Snapshot of per-user dividend rewards for one epoch, committed as a Merkle root.
+#[account]
+pub struct DividendEpoch {
+    pub epoch: u64,
+    pub merkle_root: [u8; 32],
+    pub total_rewards: u64,
+    pub claimed_rewards: u64,
+    pub bump: u8,
+}
+
+impl DividendEpoch {
+    pub const LEN: usize = 8 // epoch
+        + 32 // merkle_root
+        + 8  // total_rewards
+        + 8  // claimed_rewards
+        + 1; // bump
+}
+
+/// Marks a user's epoch reward as claimed.
+#[account]
+pub struct EpochClaim {
+    pub epoch: u64,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+impl EpochClaim {
+    pub const LEN: usize = 8 // epoch
+        + 32 // user
+        + 8; // amount
+}
+
+/// Permissionless cranks that can be driven by an automation thread.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AutomatedCrank {
+    Drip,
+    EpochRollover,
+    HarvestEmissions,
+}
+
+impl AutomatedCrank {
+    pub fn thread_id(&self) -> &'static [u8] {
+        match self {
+            AutomatedCrank::Drip => b"mutr_drip",
+            AutomatedCrank::EpochRollover => b"mutr_epoch_rollover",
+            AutomatedCrank::HarvestEmissions => b"mutr_harvest_emissions",
+        }
+    }
+}
+
+/// Wire layout of Clockwork's `SerializableInstruction`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ThreadInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ThreadAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// Wire layout of Clockwork's `SerializableAccount`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ThreadAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Wire layout of the leading variants of Clockwork's `Trigger`; only `Cron` is used.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum ThreadTrigger {
+    Account {
+        address: Pubkey,
+        offset: u64,
+        size: u64,
+    },
+    Cron {
+        schedule: String,
+        skippable: bool,
+    },
+}
+
+/// Per-wallet counter of opened staking positions.
+#[account]
+pub struct UserPositions {
+    pub owner: Pubkey,
+    pub position_count: u32,
+}
+
+impl UserPositions {
+    pub const LEN: usize = 32 // owner
+        + 4; // position_count
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(config: InitConfig)]
+pub struct InitializeClr<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"state"],
+        bump,
+        space = 8 + GlobalState::LEN
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// MUTR mint (existing SPL token mint)
+    #[account(address = config.mutr_mint @ MutrError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// xMUTR liquidity share mint (must have mint authority set to `state` PDA)
+    #[account(mut, address = config.xmutr_mint @ MutrError::InvalidMint)]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// CLR vault that holds MUTR, owned by `state` PDA
+    #[account(
+        mut,
+        constraint = clr_vault.mint == mutr_mint.key() @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"treasury_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBonusRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub bonus_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"bonus_vault"],
+        bump,
+        token::mint = bonus_mint,
+        token::authority = state
+    )]
+    pub bonus_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundBonusRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = bonus_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub bonus_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = bonus_mint.key() == state.load()?.bonus_mint @ MutrError::InvalidMint
+    )]
+    pub bonus_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = funder_bonus_account.mint == state.load()?.bonus_mint @ MutrError::InvalidMint
+    )]
+    pub funder_bonus_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct ClaimBonusRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = bonus_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub bonus_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = bonus_mint.key() == state.load()?.bonus_mint @ MutrError::InvalidMint
+    )]
+    pub bonus_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = destination_bonus_account.mint == state.load()?.bonus_mint @ MutrError::InvalidMint
+    )]
+    pub destination_bonus_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeSpend<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SpendProposal::LEN,
+        seeds = [b"spend", state.load()?.spend_proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub spend_proposal: Account<'info, SpendProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+
+    /// Rent payer for the proposal (separate so a PDA authority can propose)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSpend<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"spend", spend_proposal.id.to_le_bytes().as_ref()],
+        bump = spend_proposal.bump,
+        close = authority
+    )]
+    pub spend_proposal: Account<'info, SpendProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSpend<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = treasury_vault @ MutrError::InvalidVault,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"spend", spend_proposal.id.to_le_bytes().as_ref()],
+        bump = spend_proposal.bump,
+        close = authority
+    )]
+    pub spend_proposal: Account<'info, SpendProposal>,
+
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_mutr_account.key() == spend_proposal.recipient @ MutrError::InvalidRecipient
+    )]
+    pub recipient_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the CLR authority that queued the spend; gets the proposal's rent back
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, position_index: u32, lock_tier: u8)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_vault.key() == state.load()?.treasury_vault @ MutrError::InvalidVault
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = user_mutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", user.key().as_ref()],
+        bump
+    )]
+    pub user_positions: Account<'info, UserPositions>,
+
+    /// Stakers leaderboard; the position is offered to it when passed
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[LeaderboardKind::Stakers as u8]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Share price oracle; the new price is recorded when passed
+    #[account(
+        mut,
+        seeds = [b"share_price_oracle"],
+        bump = share_price_oracle.load()?.bump
+    )]
+    pub share_price_oracle: Option<AccountLoader<'info, SharePriceOracle>>,
+
+    /// Loyalty config and the user's stats; staking points accrue when both are passed,
+    /// and the fee credit is spent whenever the stats are
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// VIP config; the user's tier discounts the fee when passed with the stats
+    #[account(
+        seeds = [b"vip_config"],
+        bump = vip_config.bump
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Referral config, the referrer and the referral vault; the referrer earns its cut
+    /// when they are passed with the user's stats (see `referrals`)
+    #[account(
+        seeds = [b"referral_config"],
+        bump = referral_config.bump
+    )]
+    pub referral_config: Option<Account<'info, ReferralConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.code.as_bytes()],
+        bump = referrer.bump
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_vault"],
+        bump
+    )]
+    pub referral_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Dividend vault; required with `join_dividends`
+    #[account(
+        mut,
+        seeds = [b"dividend_vault"],
+        bump
+    )]
+    pub dividend_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, beneficiary: Pubkey, position_index: u32, lock_tier: u8)]
+pub struct StakeOnBehalf<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_vault.key() == state.load()?.treasury_vault @ MutrError::InvalidVault
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// MUTR source, owned by the payer
+    #[account(
+        mut,
+        constraint = payer_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = payer_mutr_account.owner == payer.key() @ MutrError::Unauthorized
+    )]
+    pub payer_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// xMUTR destination, owned by the beneficiary
+    #[account(
+        mut,
+        constraint = beneficiary_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = beneficiary_xmutr_account.owner == beneficiary @ MutrError::InvalidBeneficiary
+    )]
+    pub beneficiary_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", beneficiary.as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", beneficiary.as_ref()],
+        bump
+    )]
+    pub user_positions: Account<'info, UserPositions>,
+
+    /// Stakers leaderboard; the position is offered to it when passed
+    #[account(
+        mut,
+        seeds = [b"leaderboard".as_ref(), &[LeaderboardKind::Stakers as u8]],
+        bump = leaderboard.load()?.bump
+    )]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// Share price oracle; the new price is recorded when passed
+    #[account(
+        mut,
+        seeds = [b"share_price_oracle"],
+        bump = share_price_oracle.load()?.bump
+    )]
+    pub share_price_oracle: Option<AccountLoader<'info, SharePriceOracle>>,
+
+    /// Loyalty config and the beneficiary's stats; staking points accrue when both are passed,
+    /// and the fee credit is spent whenever the stats are
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", beneficiary.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// VIP config; the user's tier discounts the fee when passed with the stats
+    #[account(
+        seeds = [b"vip_config"],
+        bump = vip_config.bump
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(shares: u64, position_index: u32)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_vault.key() == state.load()?.treasury_vault @ MutrError::InvalidVault
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// MUTR destination; any account of the MUTR mint (e.g. a cold wallet or exchange deposit)
+    #[account(
+        mut,
+        constraint = destination_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub destination_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    /// Loyalty config and the user's stats; staking points accrue when both are passed,
+    /// and the fee credit is spent whenever the stats are
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Option<Account<'info, LoyaltyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// VIP config; the user's tier discounts the fee when passed with the stats
+    #[account(
+        seeds = [b"vip_config"],
+        bump = vip_config.bump
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    /// Share price oracle; the new price is recorded when passed
+    #[account(
+        mut,
+        seeds = [b"share_price_oracle"],
+        bump = share_price_oracle.load()?.bump
+    )]
+    pub share_price_oracle: Option<AccountLoader<'info, SharePriceOracle>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(shares: u64, position_index: u32)]
+pub struct JoinDividendPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrows the dividend pool's xMUTR (see `dividend_escrow`)
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"dividend_vault"],
+        bump,
+        token::mint = xmutr_mint,
+        token::authority = state
+    )]
+    pub dividend_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-impl UserState {
-    pub const LEN: usize = 32 // owner
-        + 8  // staked_shares
-        + 8  // dividend_shares
-        + 16 // reward_debt
-        + 16; // pending_rewards
+#[derive(Accounts)]
+#[instruction(shares: u64, position_index: u32)]
+pub struct LeaveDividendPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        mut,
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_vault.key() == state.load()?.treasury_vault @ MutrError::InvalidVault
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Dividend vault; required while the position has escrowed shares
+    #[account(
+        mut,
+        seeds = [b"dividend_vault"],
+        bump
+    )]
+    pub dividend_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-// -----------------------------------------------------------------------------
-// Accounts
-// -----------------------------------------------------------------------------
-
 #[derive(Accounts)]
-pub struct InitializeClr<'info> {
+pub struct RecordProfit<'info> {
     #[account(
-        init,
-        payer = authority,
+        mut,
         seeds = [b"state"],
-        bump,
-        space = 8 + GlobalState::LEN
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
     )]
-    pub state: Account<'info, GlobalState>,
-
-    /// MUTR mint (existing SPL token mint)
-    pub mutr_mint: Account<'info, Mint>,
+    pub state: AccountLoader<'info, GlobalState>,
 
-    /// xMUTR liquidity share mint (must have mint authority set to `state` PDA)
-    #[account(mut)]
-    pub xmutr_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
 
-    /// CLR vault that holds MUTR, owned by `state` PDA
     #[account(
         mut,
-        constraint = clr_vault.mint == mutr_mint.key() @ MutrError::InvalidMint,
-        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
     )]
-    pub clr_vault: Account<'info, TokenAccount>,
+    pub admin_log: AccountLoader<'info, AdminLog>,
 
-    #[account(mut)]
     pub authority: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Stake<'info> {
+pub struct EpochRollover<'info> {
     #[account(
         mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.load()?.bump
     )]
-    pub state: Account<'info, GlobalState>,
+    pub state: AccountLoader<'info, GlobalState>,
 
     #[account(
-        constraint = mutr_mint.key() == state.mutr_mint @ MutrError::InvalidMint
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub mutr_mint: Account<'info, Mint>,
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        constraint = xmutr_mint.key() == state.xmutr_mint @ MutrError::InvalidMint
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the keeper fee
+    #[account(
+        mut,
+        constraint = keeper_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub keeper_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Drip<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub xmutr_mint: Account<'info, Mint>,
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        constraint = clr_vault.key() == state.clr_vault @ MutrError::InvalidVault,
-        constraint = clr_vault.mint == state.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
         constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
     )]
-    pub clr_vault: Account<'info, TokenAccount>,
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// Receives the keeper fee
     #[account(
         mut,
-        constraint = user_mutr_account.mint == state.mutr_mint @ MutrError::InvalidMint,
-        constraint = user_mutr_account.owner == user.key() @ MutrError::Unauthorized
+        constraint = keeper_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub keeper_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(crank: AutomatedCrank)]
+pub struct CreateAutomationThread<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
     )]
-    pub user_mutr_account: Account<'info, TokenAccount>,
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: PDA that owns the program's automation threads
+    #[account(seeds = [b"automation"], bump)]
+    pub automation_authority: UncheckedAccount<'info>,
 
+    /// CHECK: created by the thread program
     #[account(
         mut,
-        constraint = user_xmutr_account.mint == state.xmutr_mint @ MutrError::InvalidMint,
-        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+        address = thread_address(&automation_authority.key(), crank.thread_id()) @ MutrError::InvalidThread
     )]
-    pub user_xmutr_account: Account<'info, TokenAccount>,
+    pub thread: UncheckedAccount<'info>,
 
+    /// Receives the keeper fee earned by the thread
     #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + UserState::LEN,
-        seeds = [b"user_state", user.key().as_ref()],
-        bump
+        constraint = keeper_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub user_state: Account<'info, UserState>,
+    pub keeper_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
 
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Clockwork thread program
+    #[account(address = thread_program::ID)]
+    pub thread_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Unstake<'info> {
+#[instruction(crank: AutomatedCrank)]
+pub struct FundAutomationThread<'info> {
+    /// CHECK: PDA that owns the program's automation threads
+    #[account(seeds = [b"automation"], bump)]
+    pub automation_authority: UncheckedAccount<'info>,
+
+    /// CHECK: must be one of the program's threads
     #[account(
         mut,
-        seeds = [b"state"],
-        bump = state.bump
+        owner = thread_program::ID @ MutrError::InvalidThread,
+        address = thread_address(&automation_authority.key(), crank.thread_id()) @ MutrError::InvalidThread
     )]
-    pub state: Account<'info, GlobalState>,
+    pub thread: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
 
+#[derive(Accounts)]
+pub struct DeleteAutomationThread<'info> {
     #[account(
-        constraint = mutr_mint.key() == state.mutr_mint @ MutrError::InvalidMint
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
     )]
-    pub mutr_mint: Account<'info, Mint>,
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: PDA that owns the program's automation threads
+    #[account(seeds = [b"automation"], bump)]
+    pub automation_authority: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the thread program against `automation_authority`
+    #[account(mut, owner = thread_program::ID @ MutrError::InvalidThread)]
+    pub thread: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        constraint = xmutr_mint.key() == state.xmutr_mint @ MutrError::InvalidMint
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
     )]
-    pub xmutr_mint: Account<'info, Mint>,
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Clockwork thread program
+    #[account(address = thread_program::ID)]
+    pub thread_program: UncheckedAccount<'info>,
+}
 
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct ClaimRewards<'info> {
     #[account(
         mut,
-        constraint = clr_vault.key() == state.clr_vault @ MutrError::InvalidVault,
-        constraint = clr_vault.mint == state.mutr_mint @ MutrError::InvalidMint,
-        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+        seeds = [b"state"],
+        bump = state.load()?.bump
     )]
-    pub clr_vault: Account<'info, TokenAccount>,
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        constraint = user_mutr_account.mint == state.mutr_mint @ MutrError::InvalidMint,
-        constraint = user_mutr_account.owner == user.key() @ MutrError::Unauthorized
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
     )]
-    pub user_mutr_account: Account<'info, TokenAccount>,
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// MUTR destination; any account of the MUTR mint (e.g. a cold wallet or exchange deposit)
     #[account(
         mut,
-        constraint = user_xmutr_account.mint == state.xmutr_mint @ MutrError::InvalidMint,
-        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+        constraint = destination_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub user_xmutr_account: Account<'info, TokenAccount>,
+    pub destination_mutr_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [b"user_state", user.key().as_ref()],
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
         bump
     )]
     pub user_state: Account<'info, UserState>,
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+    /// CHECK: position owner, fixing the `user_state` address
+    pub user: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// The owner or the position's delegate (see `delegates`)
+    pub caller: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct JoinDividendPool<'info> {
+pub struct PostDividendEpoch<'info> {
     #[account(
         mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
     )]
-    pub state: Account<'info, GlobalState>,
+    pub state: AccountLoader<'info, GlobalState>,
 
     #[account(
-        mut,
-        seeds = [b"user_state", user.key().as_ref()],
+        init,
+        payer = authority,
+        space = 8 + DividendEpoch::LEN,
+        seeds = [b"dividend_epoch", state.load()?.dividend_epoch_count.to_le_bytes().as_ref()],
         bump
     )]
-    pub user_state: Account<'info, UserState>,
+    pub dividend_epoch: Account<'info, DividendEpoch>,
 
-    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct LeaveDividendPool<'info> {
+#[instruction(epoch: u64)]
+pub struct ClaimEpochRewards<'info> {
     #[account(
         mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub state: Account<'info, GlobalState>,
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"user_state", user.key().as_ref()],
+        seeds = [b"dividend_epoch", epoch.to_le_bytes().as_ref()],
+        bump = dividend_epoch.bump
+    )]
+    pub dividend_epoch: Account<'info, DividendEpoch>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EpochClaim::LEN,
+        seeds = [b"epoch_claim", epoch.to_le_bytes().as_ref(), user.key().as_ref()],
         bump
     )]
-    pub user_state: Account<'info, UserState>,
+    pub epoch_claim: Account<'info, EpochClaim>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub destination_mutr_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut)]
     pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct RecordProfit<'info> {
+pub struct SetXmutrMetadata<'info> {
     #[account(
-        mut,
         seeds = [b"state"],
-        bump = state.bump,
-        has_one = authority @ MutrError::Unauthorized
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Metaplex metadata PDA of the xMUTR mint, validated by the token metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
     )]
-    pub state: Account<'info, GlobalState>,
+    pub admin_log: AccountLoader<'info, AdminLog>,
 
+    #[account(mut)]
     pub authority: Signer<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
+#[instruction(position_index: u32, uri: String, receipt_nonce: u64)]
+pub struct MintPositionReceipt<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared xMUTR escrow backing all outstanding receipts
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"receipt_escrow"],
+        bump,
+        token::mint = xmutr_mint,
+        token::authority = state
+    )]
+    pub receipt_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [
+            b"receipt_mint",
+            user_state.key().as_ref(),
+            &receipt_nonce.to_le_bytes()
+        ],
+        bump,
+        mint::decimals = 0,
+        mint::authority = state,
+        mint::freeze_authority = state
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub user_receipt_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata PDA, validated by the token metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition PDA, validated by the token metadata program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct RedeemPositionReceipt<'info> {
     #[account(
         mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.load()?.bump
     )]
-    pub state: Account<'info, GlobalState>,
+    pub state: AccountLoader<'info, GlobalState>,
 
+    /// Tokenized position backing the receipt
     #[account(
         mut,
-        constraint = clr_vault.key() == state.clr_vault @ MutrError::InvalidVault,
-        constraint = clr_vault.mint == state.mutr_mint @ MutrError::InvalidMint,
-        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+        constraint = source_user_state.receipt_mint == receipt_mint.key() @ MutrError::InvalidReceipt
+    )]
+    pub source_user_state: Box<Account<'info, UserState>>,
+
+    #[account(mut)]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = holder_receipt_account.mint == receipt_mint.key() @ MutrError::InvalidReceipt,
+        constraint = holder_receipt_account.owner == holder.key() @ MutrError::Unauthorized,
+        constraint = holder_receipt_account.amount == 1 @ MutrError::InvalidReceipt
     )]
-    pub clr_vault: Account<'info, TokenAccount>,
+    pub holder_receipt_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        constraint = user_mutr_account.mint == state.mutr_mint @ MutrError::InvalidMint,
-        constraint = user_mutr_account.owner == user.key() @ MutrError::Unauthorized
+        seeds = [b"receipt_escrow"],
+        bump
+    )]
+    pub receipt_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
     )]
-    pub user_mutr_account: Account<'info, TokenAccount>,
+    pub xmutr_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        seeds = [b"user_state", user.key().as_ref()],
+        constraint = holder_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = holder_xmutr_account.owner == holder.key() @ MutrError::Unauthorized
+    )]
+    pub holder_xmutr_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", holder.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub holder_user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", holder.key().as_ref()],
         bump
     )]
+    pub holder_positions: Box<Account<'info, UserPositions>>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
     pub user_state: Account<'info, UserState>,
+}
 
-    pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct CloseUserState<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_state", owner.key().as_ref(), &position_index.to_le_bytes()],
+        bump,
+        has_one = owner @ MutrError::Unauthorized,
+        close = owner
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -661,28 +5067,54 @@ pub struct SendPrize<'info> {
     #[account(
         mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
     )]
-    pub state: Account<'info, GlobalState>,
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        constraint = clr_vault.key() == state.clr_vault @ MutrError::InvalidVault,
-        constraint = clr_vault.mint == state.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
         constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
     )]
-    pub clr_vault: Account<'info, TokenAccount>,
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = winner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub winner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
 
     #[account(
         mut,
-        constraint = winner_mutr_account.mint == state.mutr_mint @ MutrError::InvalidMint
+        seeds = [b"game_ledger", game.key().as_ref()],
+        bump = game_ledger.load()?.bump
     )]
-    pub winner_mutr_account: Account<'info, TokenAccount>,
+    pub game_ledger: AccountLoader<'info, GameLedger>,
 
-    /// Game authority; later restricted to approved games
+    /// Registered game authority
     pub game: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[error_code]
@@ -703,6 +5135,192 @@ pub enum MutrError {
     InvalidMint,
     #[msg("Invalid CLR vault")]
     InvalidVault,
+    #[msg("Invalid beneficiary")]
+    InvalidBeneficiary,
+    #[msg("User state still holds shares or rewards")]
+    UserStateNotEmpty,
+    #[msg("Invalid position index")]
+    InvalidPositionIndex,
+    #[msg("Position is held as a receipt NFT")]
+    PositionTokenized,
+    #[msg("Invalid position receipt")]
+    InvalidReceipt,
+    #[msg("Receipt metadata URI too long")]
+    InvalidReceiptUri,
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+    #[msg("Position lock tier does not match")]
+    LockTierMismatch,
+    #[msg("Position is still locked")]
+    PositionLocked,
+    #[msg("Position is not locked")]
+    PositionNotLocked,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Treasury spend is still timelocked")]
+    SpendNotReady,
+    #[msg("Invalid recipient")]
+    InvalidRecipient,
+    #[msg("Fee split must add up to 10000 bps")]
+    InvalidFeeSplit,
+    #[msg("Invalid Merkle proof")]
+    InvalidProof,
+    #[msg("Epoch rewards exhausted")]
+    EpochRewardsExhausted,
+    #[msg("Profit epoch has not ended")]
+    EpochNotEnded,
+    #[msg("Nothing left to drip")]
+    NothingToDrip,
+    #[msg("Invalid automation thread")]
+    InvalidThread,
+    #[msg("No emissions to harvest")]
+    NothingToHarvest,
+    #[msg("Game is disabled")]
+    GameDisabled,
+    #[msg("Invalid payout multiplier")]
+    InvalidMultiplier,
+    #[msg("Bet is not pending")]
+    BetNotPending,
+    #[msg("Bet is not settled")]
+    BetNotSettled,
+    #[msg("Bet has expired")]
+    BetExpired,
+    #[msg("Bet has not expired")]
+    BetNotExpired,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Randomness already revealed")]
+    RandomnessAlreadyRevealed,
+    #[msg("Randomness not revealed in this slot")]
+    RandomnessNotRevealed,
+    #[msg("Missing or invalid oracle attestation")]
+    InvalidAttestation,
+    #[msg("Payout exceeds the bankroll risk limit")]
+    PayoutTooLarge,
+    #[msg("Jackpot is empty")]
+    JackpotEmpty,
+    #[msg("Prize is above the vesting threshold; pay it with send_vested_prize")]
+    PrizeNeedsVesting,
+    #[msg("Prize amounts must match distinct winner accounts, up to the batch limit")]
+    InvalidPrizeBatch,
+    #[msg("Missing or invalid Pyth price update")]
+    InvalidPriceFeed,
+    #[msg("Price is older than the staleness limit")]
+    StalePrice,
+    #[msg("Price confidence interval is too wide")]
+    PriceTooUncertain,
+    #[msg("Bet exceeds the USD bet limit")]
+    BetTooLarge,
+    #[msg("Pool registry is full")]
+    PoolRegistryFull,
+    #[msg("Pool is inactive")]
+    PoolInactive,
+    #[msg("Token metadata name, symbol or URI too long")]
+    InvalidTokenMetadata,
+    #[msg("Leaderboard entry not proven by a matching position or won bet")]
+    InvalidLeaderboardProof,
+    #[msg("Season accounts do not match")]
+    InvalidSeason,
+    #[msg("Season has ended")]
+    SeasonEnded,
+    #[msg("Season has not ended yet")]
+    SeasonNotEnded,
+    #[msg("Season prizes already distributed")]
+    SeasonDistributed,
+    #[msg("Not enough loyalty points")]
+    InsufficientPoints,
+    #[msg("Redemption is disabled")]
+    RedemptionDisabled,
+    #[msg("Referral codes are 1-16 ASCII letters and digits")]
+    InvalidReferralCode,
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+    #[msg("User is bound to a different referrer")]
+    ReferrerMismatch,
+    #[msg("Referral accounts are missing")]
+    MissingReferral,
+    #[msg("Affiliate is inactive")]
+    AffiliateInactive,
+    #[msg("Affiliate payout is not due yet")]
+    PayoutNotDue,
+    #[msg("NFT is not held or not in the boost collection")]
+    InvalidNft,
+    #[msg("Milestone has not been reached")]
+    MilestoneNotReached,
+    #[msg("Achievement already minted")]
+    AchievementMinted,
+    #[msg("Self-exclusion must end in the future and later than the current one")]
+    InvalidExclusion,
+    #[msg("Player is self-excluded from betting")]
+    SelfExcluded,
+    #[msg("Bet exceeds the player's daily wager cap")]
+    WagerCapExceeded,
+    #[msg("Position holds active responsible-gaming limits")]
+    GamingLimitsActive,
+    #[msg("Bet exceeds the daily wager limit")]
+    DailyWagerLimitExceeded,
+    #[msg("Player stats are required by the daily wager limit or the player rate limit")]
+    MissingUserStats,
+    #[msg("Bet rate limit reached; retry in a later slot")]
+    RateLimited,
+    #[msg("Session must expire in the future and have a spend cap")]
+    InvalidSession,
+    #[msg("Session has expired")]
+    SessionExpired,
+    #[msg("Bet exceeds the session's spend cap")]
+    SessionCapExceeded,
+    #[msg("CLR state is already initialized")]
+    AlreadyInitialized,
+    #[msg("Stake is below the minimum")]
+    StakeTooSmall,
+    #[msg("Unstake is below the minimum")]
+    UnstakeTooSmall,
+    #[msg("Stake would take the vault over its deposit cap")]
+    TvlCapExceeded,
+    #[msg("Stake would take the staker over their deposit cap")]
+    UserStakeCapExceeded,
+    #[msg("Transaction landed after its deadline slot")]
+    DeadlineExpired,
+    #[msg("Bonus vault holds nothing beyond what is owed")]
+    NothingToSweep,
+    #[msg("Bonus vault surplus is too large to sweep as dust")]
+    DustTooLarge,
+    #[msg("Operation is paused")]
+    Paused,
+    #[msg("Multisig owners and threshold are invalid")]
+    InvalidMultisig,
+    #[msg("Multisig transaction lacks approvals")]
+    ThresholdNotMet,
+    #[msg("Multisig transaction already executed")]
+    TransactionExecuted,
+    #[msg("Accounts do not match the multisig transaction")]
+    InvalidTransactionAccounts,
+    #[msg("Position joined just before a profit distribution; claims and exits are locked")]
+    ClaimLocked,
+    #[msg("Dividend vault is required to move escrowed xMUTR")]
+    MissingDividendVault,
+    #[msg("Position is being transferred to a new owner")]
+    PositionTransferPending,
+    #[msg("No transfer of this position is pending")]
+    NoPendingTransfer,
+    #[msg("Position cannot be transferred to this owner")]
+    InvalidNewOwner,
+    #[msg("A recovery claim is already pending")]
+    RecoveryPending,
+    #[msg("No recovery claim is pending")]
+    NoPendingRecovery,
+    #[msg("Recovery delay has not elapsed")]
+    RecoveryNotReady,
+    #[msg("An emergency withdrawal is already queued")]
+    EmergencyWithdrawPending,
+    #[msg("No emergency withdrawal is queued")]
+    NoEmergencyWithdraw,
+    #[msg("Emergency withdrawal delay has not elapsed")]
+    EmergencyWithdrawNotReady,
+    #[msg("Vault is empty while shares are outstanding")]
+    EmptyVault,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpot,
 }
 
 
@@ -0,0 +1,411 @@
+//! Loyalty points.
+//!
+//! Players open a `UserStats` PDA with `open_user_stats`; it holds their points, which
+//! cannot be transferred, next to their lifetime wagers. Points accrue at the rates in
+//! the authority's `LoyaltyConfig`:
+//!
+//! - wagering: `wager_points_bps` of the wager, when a bet is resolved with the player's
+//!   stats and the loyalty config passed;
+//! - staking: `stake_points_bps_per_day` of the position's shares per day staked. Each
+//!   position keeps its own clock; `stake` and `unstake` settle it before the shares
+//!   change, and `accrue_staking_points` settles it on demand. Staking or unstaking
+//!   without the stats passed restarts the clock, so the time since the last
+//!   settlement is forfeited rather than priced at the new share count.
+//!
+//! `redeem_points` spends points for either a fee credit, MUTR of stake and unstake fees
+//! waived on later stakes and unstakes made with the stats passed, or a bonus credit,
+//! MUTR moved from the treasury into the player's `PrizeClaim` (see `prizes`). A zero
+//! rate disables the matching accrual or redemption.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn set_rates(ctx: Context<SetLoyaltyConfig>, params: LoyaltyParams) -> Result<()> {
+    let config = &mut ctx.accounts.loyalty_config;
+    config.wager_points_bps = params.wager_points_bps;
+    config.stake_points_bps_per_day = params.stake_points_bps_per_day;
+    config.fee_credit_bps = params.fee_credit_bps;
+    config.bonus_credit_bps = params.bonus_credit_bps;
+    config.bump = ctx.bumps.loyalty_config;
+    Ok(())
+}
+
+pub fn open_user(ctx: Context<OpenUserStats>) -> Result<()> {
+    let user_stats = &mut ctx.accounts.user_stats;
+    user_stats.owner = ctx.accounts.user.key();
+    user_stats.bump = ctx.bumps.user_stats;
+    Ok(())
+}
+
+/// Permissionless: settle a position's staking points into its owner's stats.
+pub fn accrue_position(ctx: Context<AccrueStakingPoints>) -> Result<()> {
+    accrue_stake(
+        &ctx.accounts.loyalty_config,
+        &mut ctx.accounts.user_stats,
+        &mut ctx.accounts.user_state,
+    )
+}
+
+pub fn redeem(ctx: Context<RedeemPoints>, points: u64, redemption: Redemption) -> Result<()> {
+    require!(points > 0, MutrError::InvalidAmount);
+    let config = &ctx.accounts.loyalty_config;
+    let user_stats = &mut ctx.accounts.user_stats;
+    user_stats.points = user_stats
+        .points
+        .checked_sub(points)
+        .ok_or(MutrError::InsufficientPoints)?;
+
+    match redemption {
+        Redemption::FeeCredit => {
+            let credit = points_value(points, config.fee_credit_bps)?;
+            require!(credit > 0, MutrError::RedemptionDisabled);
+            user_stats.fee_credit = user_stats
+                .fee_credit
+                .checked_add(credit)
+                .ok_or(MutrError::MathOverflow)?;
+        }
+        Redemption::BonusCredit => {
+            let credit = points_value(points, config.bonus_credit_bps)?;
+            require!(credit > 0, MutrError::RedemptionDisabled);
+            vault_transfer(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.treasury_vault.to_account_info(),
+                ctx.accounts.prize_claim_vault.to_account_info(),
+                &ctx.accounts.mutr_mint,
+                ctx.accounts.state.to_account_info(),
+                ctx.accounts.state.load()?.bump,
+                credit,
+            )?;
+
+            let claim = &mut ctx.accounts.prize_claim;
+            claim.winner = ctx.accounts.user.key();
+            claim.amount = claim
+                .amount
+                .checked_add(credit)
+                .ok_or(MutrError::MathOverflow)?;
+            claim.bump = ctx.bumps.prize_claim;
+        }
+    }
+    Ok(())
+}
+
+/// Settle a position's staking points, or restart its clock when the player's stats
+/// or the loyalty config are not passed. Call before the position's shares change.
+pub(crate) fn touch_position<'info>(
+    config: &Option<Account<'info, LoyaltyConfig>>,
+    user_stats: &mut Option<Account<'info, UserStats>>,
+    user_state: &mut UserState,
+) -> Result<()> {
+    match (config, user_stats) {
+        (Some(config), Some(user_stats)) => accrue_stake(config, user_stats, user_state),
+        _ => {
+            user_state.points_accrued_at = Clock::get()?.unix_timestamp;
+            Ok(())
+        }
+    }
+}
+
+/// Credit a resolved bet's wager to the player's stats, when they are passed.
+pub(crate) fn record_wager<'info>(
+    config: &Option<Account<'info, LoyaltyConfig>>,
+    user_stats: &mut Option<Account<'info, UserStats>>,
+    bet: &Bet,
+) -> Result<()> {
+    let Some(user_stats) = user_stats else {
+        return Ok(());
+    };
+    require_keys_eq!(user_stats.owner, bet.player, MutrError::Unauthorized);
+    user_stats.wagered = user_stats
+        .wagered
+        .checked_add(bet.amount)
+        .ok_or(MutrError::MathOverflow)?;
+    if let Some(config) = config {
+        user_stats.add_points(points_value(bet.amount, config.wager_points_bps)?)?;
+    }
+    Ok(())
+}
+
+/// Waive up to `fee` from the player's fee credit; returns the MUTR waived.
+pub(crate) fn waive_fee(user_stats: &mut Option<Account<'_, UserStats>>, fee: u64) -> u64 {
+    let Some(user_stats) = user_stats else {
+        return 0;
+    };
+    let waived = fee.min(user_stats.fee_credit);
+    user_stats.fee_credit -= waived;
+    waived
+}
+
+fn accrue_stake(
+    config: &LoyaltyConfig,
+    user_stats: &mut UserStats,
+    user_state: &mut UserState,
+) -> Result<()> {
+    require_keys_eq!(user_stats.owner, user_state.owner, MutrError::Unauthorized);
+    let now = Clock::get()?.unix_timestamp;
+    // A position starts its clock at its first settlement
+    if user_state.points_accrued_at > 0 {
+        let elapsed = now.saturating_sub(user_state.points_accrued_at).max(0) as u128;
+        let shares = user_state
+            .staked_shares
+            .checked_add(user_state.dividend_shares)
+            .ok_or(MutrError::MathOverflow)? as u128;
+        let points = shares
+            .checked_mul(elapsed)
+            .and_then(|v| v.checked_mul(config.stake_points_bps_per_day as u128))
+            .ok_or(MutrError::MathOverflow)?
+            / (BPS_DENOMINATOR as u128 * SECONDS_PER_DAY as u128);
+        user_stats.add_points(u64::try_from(points).map_err(|_| MutrError::MathOverflow)?)?;
+    }
+    user_state.points_accrued_at = now;
+    Ok(())
+}
+
+fn points_value(amount: u64, rate_bps: u16) -> Result<u64> {
+    u64::try_from(amount as u128 * rate_bps as u128 / BPS_DENOMINATOR as u128)
+        .map_err(|_| error!(MutrError::MathOverflow))
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Accrual and redemption rates, all in bps.
+#[account]
+pub struct LoyaltyConfig {
+    /// Points per MUTR wagered
+    pub wager_points_bps: u16,
+    /// Points per xMUTR share per day staked
+    pub stake_points_bps_per_day: u16,
+    /// MUTR of fee credit per point redeemed
+    pub fee_credit_bps: u16,
+    /// MUTR of bonus credit per point redeemed
+    pub bonus_credit_bps: u16,
+    pub bump: u8,
+}
+
+impl LoyaltyConfig {
+    pub const LEN: usize = 2 // wager_points_bps
+        + 2  // stake_points_bps_per_day
+        + 2  // fee_credit_bps
+        + 2  // bonus_credit_bps
+        + 1; // bump
+}
+
+/// A player's loyalty points and lifetime activity.
+#[account]
+pub struct UserStats {
+    pub owner: Pubkey,
+    /// Points available to redeem
+    pub points: u64,
+    /// Points ever earned
+    pub lifetime_points: u64,
+    /// MUTR wagered in bets resolved with these stats passed
+    pub wagered: u64,
+    /// MUTR value of the owner's xMUTR at their last stake or unstake with these stats
+    /// passed (see `vip`)
+    pub staked: u64,
+    /// MUTR of stake and unstake fees still to be waived
+    pub fee_credit: u64,
+    /// MUTR of rakeback accrued and not yet claimed (see `rakeback`)
+    pub rakeback: u64,
+    /// `Referrer` the owner is bound to, default until their first referred stake or
+    /// bet (see `referrals`)
+    pub referrer: Pubkey,
+    /// `Milestone` bits of the event milestones reached (see `achievements`)
+    pub milestones: u8,
+    /// `Milestone` bits of the achievements minted
+    pub achievements: u8,
+    /// MUTR wagered over the rolling day ending at `daily_wagered_at`, checked against
+    /// the daily wager limit (see `responsible_gaming`)
+    pub daily_wagered: u64,
+    pub daily_wagered_at: i64,
+    /// Player bet rate bucket: tokens left and the slot it was last refilled at (see
+    /// `rate_limit`)
+    pub bet_tokens: u16,
+    pub bet_tokens_slot: u64,
+    pub bump: u8,
+}
+
+impl UserStats {
+    pub const LEN: usize = 32 // owner
+        + 8  // points
+        + 8  // lifetime_points
+        + 8  // wagered
+        + 8  // staked
+        + 8  // fee_credit
+        + 8  // rakeback
+        + 32 // referrer
+        + 1  // milestones
+        + 1  // achievements
+        + 8  // daily_wagered
+        + 8  // daily_wagered_at
+        + 2  // bet_tokens
+        + 8  // bet_tokens_slot
+        + 1; // bump
+
+    pub fn add_points(&mut self, points: u64) -> Result<()> {
+        self.points = self
+            .points
+            .checked_add(points)
+            .ok_or(MutrError::MathOverflow)?;
+        self.lifetime_points = self
+            .lifetime_points
+            .checked_add(points)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LoyaltyParams {
+    pub wager_points_bps: u16,
+    pub stake_points_bps_per_day: u16,
+    pub fee_credit_bps: u16,
+    pub bonus_credit_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Redemption {
+    /// Waive stake and unstake fees
+    FeeCredit,
+    /// Credit MUTR to the player's prize claim, paid by the treasury
+    BonusCredit,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetLoyaltyConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + LoyaltyConfig::LEN,
+        seeds = [b"loyalty_config"],
+        bump
+    )]
+    pub loyalty_config: Account<'info, LoyaltyConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenUserStats<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStats::LEN,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueStakingPoints<'info> {
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Account<'info, LoyaltyConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user_state.owner.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user_state",
+            user_state.owner.as_ref(),
+            &user_state.position_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPoints<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = treasury_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"loyalty_config"],
+        bump = loyalty_config.bump
+    )]
+    pub loyalty_config: Account<'info, LoyaltyConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    /// Source of bonus credits
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"prize_claim_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub prize_claim_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PrizeClaim::LEN,
+        seeds = [b"prize_claim", user.key().as_ref()],
+        bump
+    )]
+    pub prize_claim: Account<'info, PrizeClaim>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
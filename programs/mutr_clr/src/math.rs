@@ -0,0 +1,151 @@
+//! Share and fee arithmetic.
+//!
+//! When a result does not come out even, the remainder stays with the vault: shares
+//! minted for a deposit and tokens paid out for burned shares round down, fees charged to
+//! the user round up. A cut the protocol pays out of its own take, like the keeper fee,
+//! rounds down too. Share and fee amounts in the CLR and the pools are computed here so
+//! the policy holds everywhere; `mutr-fuzz` checks that the vault per xMUTR never falls
+//! through it.
+//!
+//! Shares are priced with `VIRTUAL_SHARES` and `VIRTUAL_ASSETS` added to the supply and
+//! the vault. A donation to a vault with only dust staked then mostly accrues to the
+//! virtual shares, so inflating the share price against the next depositor costs the
+//! donor far more than that depositor loses to rounding.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Shares counted on top of the supply when pricing a deposit or a redemption
+pub const VIRTUAL_SHARES: u64 = 1_000_000;
+
+/// Tokens counted on top of the vault balance, at the same 1:1 starting price
+pub const VIRTUAL_ASSETS: u64 = 1_000_000;
+
+/// `a * b / denominator`, rounded down.
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, MutrError::MathOverflow);
+    let value = a as u128 * b as u128 / denominator as u128;
+    u64::try_from(value).map_err(|_| error!(MutrError::MathOverflow))
+}
+
+/// `a * b / denominator`, rounded up.
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, MutrError::MathOverflow);
+    let value = (a as u128 * b as u128).div_ceil(denominator as u128);
+    u64::try_from(value).map_err(|_| error!(MutrError::MathOverflow))
+}
+
+/// What the user keeps of `amount` after a fee of `fee_bps`, the fee rounded up. The fee
+/// stays with the protocol.
+pub fn apply_fee(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = mul_div_ceil(amount, fee_bps as u64, BPS_DENOMINATOR)?;
+    amount
+        .checked_sub(fee)
+        .ok_or(MutrError::MathOverflow.into())
+}
+
+/// What the protocol keeps of `amount` after paying a cut of `cut_bps` out of it, the
+/// cut rounded down.
+pub fn after_cut(amount: u64, cut_bps: u16) -> Result<u64> {
+    let cut = mul_div_floor(amount, cut_bps as u64, BPS_DENOMINATOR)?;
+    amount
+        .checked_sub(cut)
+        .ok_or(MutrError::MathOverflow.into())
+}
+
+/// Shares to mint for a net deposit, priced against the vault balance before the
+/// deposit; rounded down. The first deposit into an empty vault mints 1:1. Fails while
+/// shares are out against an empty vault, where minting would dilute them for nothing.
+pub fn shares_for_deposit(net_amount: u64, share_supply: u64, vault_before: u64) -> Result<u64> {
+    require!(share_supply == 0 || vault_before > 0, MutrError::EmptyVault);
+    mul_div_floor(
+        net_amount,
+        virtual_supply(share_supply)?,
+        virtual_vault(vault_before)?,
+    )
+}
+
+/// Part of `vault_balance` that `shares` of `share_supply` are worth; rounded down.
+pub fn amount_for_shares(shares: u64, vault_balance: u64, share_supply: u64) -> Result<u64> {
+    require!(share_supply > 0, MutrError::ZeroShares);
+    mul_div_floor(
+        shares,
+        virtual_vault(vault_balance)?,
+        virtual_supply(share_supply)?,
+    )
+}
+
+fn virtual_supply(share_supply: u64) -> Result<u64> {
+    share_supply
+        .checked_add(VIRTUAL_SHARES)
+        .ok_or(MutrError::MathOverflow.into())
+}
+
+fn virtual_vault(vault_balance: u64) -> Result<u64> {
+    vault_balance
+        .checked_add(VIRTUAL_ASSETS)
+        .ok_or(MutrError::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_rounds_each_way() {
+        assert_eq!(mul_div_floor(10, 10, 3).unwrap(), 33);
+        assert_eq!(mul_div_ceil(10, 10, 3).unwrap(), 34);
+        assert_eq!(mul_div_floor(9, 10, 3).unwrap(), 30);
+        assert_eq!(mul_div_ceil(9, 10, 3).unwrap(), 30);
+        assert!(mul_div_floor(1, 1, 0).is_err());
+        assert!(mul_div_ceil(u64::MAX, 2, 1).is_err());
+    }
+
+    #[test]
+    fn fees_round_up() {
+        // 1% of 150 is 1.5: the user keeps 148
+        assert_eq!(apply_fee(150, 100).unwrap(), 148);
+        // any fee on a non-zero amount costs at least one unit
+        assert_eq!(apply_fee(1, 1).unwrap(), 0);
+        assert_eq!(apply_fee(1_000, 0).unwrap(), 1_000);
+        // a cut paid by the protocol rounds down instead
+        assert_eq!(after_cut(150, 100).unwrap(), 149);
+    }
+
+    #[test]
+    fn first_deposit_mints_one_to_one() {
+        assert_eq!(shares_for_deposit(1_000, 0, 0).unwrap(), 1_000);
+        // tokens left in a vault without shares are priced in like any balance
+        assert_eq!(shares_for_deposit(1_000, 0, 500).unwrap(), 999);
+    }
+
+    #[test]
+    fn empty_vault_with_shares_out_rejects_deposits() {
+        assert!(shares_for_deposit(1_000, 1_000, 0).is_err());
+    }
+
+    #[test]
+    fn amount_for_shares_needs_supply() {
+        assert!(amount_for_shares(1, 1_000, 0).is_err());
+        // 3 * 3_000_000 / 2_000_000, rounded down
+        assert_eq!(amount_for_shares(3, 2_000_000, 1_000_000).unwrap(), 4);
+    }
+
+    #[test]
+    fn donation_after_dust_first_deposit_does_not_inflate_the_price() {
+        // one unit staked, then a large donation to the vault
+        let (supply, vault) = (shares_for_deposit(1, 0, 0).unwrap(), 1 + 1_000_000);
+        assert_eq!(supply, 1);
+        // a deposit smaller than the donation still mints
+        assert!(shares_for_deposit(999_999, supply, vault).unwrap() > 0);
+        // a larger one keeps all but a rounding sliver of what it put in
+        let shares = shares_for_deposit(1_500_000, supply, vault).unwrap();
+        let (supply, vault) = (supply + shares, vault + 1_500_000);
+        let redeemed = amount_for_shares(shares, vault, supply).unwrap();
+        assert!(redeemed <= 1_500_000);
+        assert!(redeemed >= 1_500_000 - 1_500_000 / 1_000);
+        // while the dust share is worth a few units of the donation
+        assert!(amount_for_shares(1, vault, supply).unwrap() < 10);
+    }
+}
@@ -0,0 +1,300 @@
+//! Account layout versions.
+//!
+//! `GlobalState` and `UserState` carry the version of the layout they were written with;
+//! accounts created before versioning read as version 0. When a layout changes, its
+//! version constant goes up and `migrate_state` learns the step from the previous one.
+//! Migrating grows an account to the current size where needed, with the caller paying
+//! the extra rent, and zero-fills the new bytes, so fields appended by a layout change
+//! start at zero unless their step says otherwise. Anyone can migrate any account, and
+//! migrating an account that is already current does nothing.
+//!
+//! The zero-copy `GlobalState` and `GameConfig` keep `_reserved` bytes for new fields,
+//! so most layout changes leave their size alone. When a change does outgrow them, the
+//! authority grows the deployed accounts with `expand_account` before anything loads
+//! them; `Pool` and `PoolConfig` are grown the same way when fields are appended.
+//!
+//! Positions opened before a wallet could hold several sit at the unindexed
+//! `[b"user_state", owner]` address, in the layout without `position_index`. The owner
+//! moves one to an indexed address with `migrate_legacy_position`, which closes the old
+//! account.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::*;
+
+/// Current `GlobalState` layout
+pub const GLOBAL_STATE_VERSION: u8 = 12;
+/// Current `UserState` layout
+pub const USER_STATE_VERSION: u8 = 6;
+
+pub fn migrate(ctx: Context<MigrateState>) -> Result<()> {
+    {
+        let mut state = ctx.accounts.state.load_mut()?;
+        // 0 -> 1: `version` took a byte of padding, nothing to move
+        // 1 -> 2: `_reserved` appended, grown by `expand_account`
+        // 2 -> 3: `min_stake` and `min_unstake` took reserved bytes; zero is no minimum
+        // 3 -> 4: the deposit caps took reserved bytes; zero is no cap
+        // 4 -> 5: `bonus_owed` took reserved bytes; bonus funded before it is untracked,
+        //         so `sweep_dust` only reaches it once it is down to dust (see `dust`)
+        // 5 -> 6: `guardian` and `pause_flags` took reserved bytes; no guardian, unpaused
+        // 6 -> 7: the claim lockup fields took the rest of the reserved bytes and a new
+        //         `_reserved` was appended, grown by `expand_account`; zero is no lockup
+        // 7 -> 8: `exit_fee_shares_burned` took reserved bytes; counts from zero
+        // 8 -> 9: the smoothing fields took reserved bytes; zero is no smoothing
+        // 9 -> 10: `prize_vesting_owed` took the rest of the reserved bytes; prizes
+        //          streamed before it are untracked, so they count toward the share price
+        //          until claimed
+        // 10 -> 11: `epoch_rewards_owed` and a new `_reserved` were appended, grown by
+        //           `expand_account`; epochs posted before it are untracked the same way
+        // 11 -> 12: `jackpot_enabled` took a byte of padding; a jackpot created before it
+        //           is switched on by the next `set_jackpot_contribution`
+        if state.version < GLOBAL_STATE_VERSION {
+            state.version = GLOBAL_STATE_VERSION;
+        }
+    }
+    if let Some(user_state) = &ctx.accounts.user_state {
+        migrate_user_state(
+            user_state,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn expand(ctx: Context<ExpandAccount>) -> Result<()> {
+    let account = &ctx.accounts.account;
+    let space = {
+        let data = account.try_borrow_data()?;
+        require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+        8 + current_len(&data[..8])?
+    };
+    grow(
+        account,
+        space,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+    // Only now can a short `GlobalState` be loaded; a wrong signer reverts the growth
+    require_keys_eq!(
+        ctx.accounts.state.load()?.authority,
+        ctx.accounts.authority.key(),
+        MutrError::Unauthorized
+    );
+    Ok(())
+}
+
+/// Size of the current layout of the account type with `discriminator`.
+fn current_len(discriminator: &[u8]) -> Result<usize> {
+    if discriminator == GlobalState::DISCRIMINATOR {
+        Ok(GlobalState::LEN)
+    } else if discriminator == GameConfig::DISCRIMINATOR {
+        Ok(GameConfig::LEN)
+    } else if discriminator == Pool::DISCRIMINATOR {
+        Ok(Pool::LEN)
+    } else if discriminator == PoolConfig::DISCRIMINATOR {
+        Ok(PoolConfig::LEN)
+    } else {
+        err!(ErrorCode::AccountDiscriminatorMismatch)
+    }
+}
+
+/// Realloc `account` up to `space` bytes, `payer` topping up its rent. Never shrinks.
+fn grow<'info>(
+    account: &UncheckedAccount<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let rent = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.to_account_info(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
+
+fn migrate_user_state<'info>(
+    account: &UncheckedAccount<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == UserState::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+
+    grow(account, 8 + UserState::LEN, payer, system_program)?;
+
+    let mut user_state = UserState::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    // 0 -> 1: `version` appended, covered by the realloc
+    // 1 -> 2: `joined_slot` appended; zero reads as no recent join
+    // 2 -> 3: `escrowed_shares` appended; dividend shares joined before the dividend
+    //         vault stay in the owner's wallet (see `dividend_escrow`)
+    // 3 -> 4: `delegate` appended; the default key is no delegate
+    // 4 -> 5: `pending_owner` appended; the default key is no pending transfer
+    // 5 -> 6: the recovery fields appended; no recovery address, no pending claim
+    if user_state.version < USER_STATE_VERSION {
+        user_state.version = USER_STATE_VERSION;
+        user_state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    }
+    Ok(())
+}
+
+/// Layout of a `UserState` opened before positions were indexed. Later fields were only
+/// ever appended, so these bytes hold even if the account was grown since.
+#[derive(AnchorDeserialize)]
+struct LegacyUserState {
+    owner: Pubkey,
+    staked_shares: u64,
+    dividend_shares: u64,
+    reward_debt: u128,
+    pending_rewards: u128,
+}
+
+pub fn migrate_legacy(
+    ctx: Context<MigrateLegacyPosition>,
+    position_index: u32,
+) -> Result<()> {
+    let legacy_account = &ctx.accounts.legacy_user_state;
+    let legacy = {
+        let data = legacy_account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == UserState::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        LegacyUserState::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        legacy.owner,
+        ctx.accounts.owner.key(),
+        MutrError::Unauthorized
+    );
+
+    let user_state = &mut ctx.accounts.user_state;
+    open_position_if_new(
+        &mut ctx.accounts.user_positions,
+        user_state,
+        legacy.owner,
+        position_index,
+    )?;
+    user_state.staked_shares = legacy.staked_shares;
+    user_state.dividend_shares = legacy.dividend_shares;
+    user_state.reward_debt = legacy.reward_debt;
+    user_state.pending_rewards = legacy.pending_rewards;
+
+    // Close the legacy account back to its owner
+    let owner = ctx.accounts.owner.to_account_info();
+    **owner.try_borrow_mut_lamports()? = owner
+        .lamports()
+        .checked_add(legacy_account.lamports())
+        .ok_or(MutrError::MathOverflow)?;
+    **legacy_account.try_borrow_mut_lamports()? = 0;
+    legacy_account.assign(&system_program::ID);
+    legacy_account.realloc(0, false)?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: `UserState` position to migrate; may be too short to deserialize before the
+    /// migration, so the owner is checked here and the discriminator by the handler
+    #[account(mut, owner = crate::ID)]
+    pub user_state: Option<UncheckedAccount<'info>>,
+
+    /// Pays the rent of grown accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpandAccount<'info> {
+    /// Not loaded until the target is grown, as it may be the target itself; the
+    /// authority is checked by the handler
+    #[account(seeds = [b"state"], bump)]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: `GlobalState`, `GameConfig`, `Pool` or `PoolConfig` to grow, told apart by
+    /// the handler from its discriminator
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct MigrateLegacyPosition<'info> {
+    /// CHECK: position at the unindexed address; read in its old layout by the handler
+    #[account(
+        mut,
+        seeds = [b"user_state", owner.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub legacy_user_state: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", owner.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", owner.key().as_ref()],
+        bump
+    )]
+    pub user_positions: Account<'info, UserPositions>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,280 @@
+//! Built-in M-of-N multisig.
+//!
+//! A `Multisig` lists up to `MAX_MULTISIG_OWNERS` owners and how many of them must
+//! approve a transaction. Its signer PDA, `[b"multisig_signer", multisig]`, holds no data
+//! and takes the CLR `authority` role through `set_authority` (or the guardian's through
+//! `set_guardian`), so admin powers need `threshold` owner keys rather than one hot key,
+//! without an external multisig program. Instructions where the authority pays rent need
+//! the signer PDA funded with SOL.
+//!
+//! An owner proposes a CLR instruction with `propose_multisig_transaction`, approving it
+//! on the way, and other owners approve it with `approve_multisig_transaction`. Once the
+//! threshold is met anyone runs it with `execute_multisig_transaction`, passing the
+//! instruction's accounts as `remaining_accounts` in the proposed order; the CLR calls
+//! itself with the signer PDA signing. Transactions only target the CLR and run once.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::*;
+
+/// Most owners a multisig may have
+pub const MAX_MULTISIG_OWNERS: usize = 16;
+
+pub fn create_wallet(
+    ctx: Context<CreateMultisig>,
+    _id: u64,
+    owners: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        !owners.is_empty()
+            && owners.len() <= MAX_MULTISIG_OWNERS
+            && threshold > 0
+            && threshold as usize <= owners.len(),
+        MutrError::InvalidMultisig
+    );
+    for (i, owner) in owners.iter().enumerate() {
+        require!(!owners[..i].contains(owner), MutrError::InvalidMultisig);
+    }
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.owners = owners;
+    multisig.threshold = threshold;
+    multisig.transaction_count = 0;
+    multisig.bump = ctx.bumps.multisig;
+    multisig.signer_bump = ctx.bumps.multisig_signer;
+    Ok(())
+}
+
+pub fn propose(
+    ctx: Context<ProposeMultisigTransaction>,
+    account_metas: Vec<TransactionAccount>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let owner_index = multisig.owner_index(&ctx.accounts.proposer.key())?;
+
+    let transaction = &mut ctx.accounts.transaction;
+    transaction.multisig = multisig.key();
+    transaction.id = multisig.transaction_count;
+    transaction.proposer = ctx.accounts.proposer.key();
+    transaction.accounts = account_metas;
+    transaction.data = data;
+    transaction.approvals = 1 << owner_index;
+    transaction.executed = false;
+    transaction.bump = ctx.bumps.transaction;
+
+    multisig.transaction_count = multisig
+        .transaction_count
+        .checked_add(1)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+pub fn approve(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+    let owner_index = ctx
+        .accounts
+        .multisig
+        .owner_index(&ctx.accounts.owner.key())?;
+    let transaction = &mut ctx.accounts.transaction;
+    require!(!transaction.executed, MutrError::TransactionExecuted);
+    transaction.approvals |= 1 << owner_index;
+    Ok(())
+}
+
+pub fn execute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteMultisigTransaction<'info>>,
+) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let transaction = &mut ctx.accounts.transaction;
+    require!(!transaction.executed, MutrError::TransactionExecuted);
+    require!(
+        transaction.approvals.count_ones() >= multisig.threshold as u32,
+        MutrError::ThresholdNotMet
+    );
+    require!(
+        ctx.remaining_accounts.len() == transaction.accounts.len()
+            && ctx
+                .remaining_accounts
+                .iter()
+                .zip(&transaction.accounts)
+                .all(|(info, account)| info.key() == account.pubkey),
+        MutrError::InvalidTransactionAccounts
+    );
+    transaction.executed = true;
+
+    let ix = Instruction {
+        program_id: crate::ID,
+        accounts: transaction
+            .accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: transaction.data.clone(),
+    };
+    let multisig_key = multisig.key();
+    invoke_signed(
+        &ix,
+        ctx.remaining_accounts,
+        &[&[
+            b"multisig_signer",
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ]],
+    )?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Multisig {
+    pub owners: Vec<Pubkey>,
+    /// Approvals a transaction needs to execute
+    pub threshold: u8,
+    pub transaction_count: u64,
+    pub bump: u8,
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub const LEN: usize = 4 + 32 * MAX_MULTISIG_OWNERS // owners
+        + 1  // threshold
+        + 8  // transaction_count
+        + 1  // bump
+        + 1; // signer_bump
+
+    fn owner_index(&self, key: &Pubkey) -> Result<usize> {
+        self.owners
+            .iter()
+            .position(|owner| owner == key)
+            .ok_or(MutrError::Unauthorized.into())
+    }
+}
+
+/// CLR instruction awaiting the owners' approval.
+#[account]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    /// Instruction data, discriminator included
+    pub data: Vec<u8>,
+    /// Bit `i` is set once `owners[i]` approved
+    pub approvals: u16,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl MultisigTransaction {
+    pub fn space(accounts: &[TransactionAccount], data: &[u8]) -> usize {
+        8 + 32 // multisig
+            + 8  // id
+            + 32 // proposer
+            + 4 + accounts.len() * TransactionAccount::LEN // accounts
+            + 4 + data.len() // data
+            + 2  // approvals
+            + 1  // executed
+            + 1 // bump
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TransactionAccount {
+    pub const LEN: usize = 32 + 1 + 1;
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Multisig::LEN,
+        seeds = [b"multisig", creator.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: data-less PDA that signs the multisig's transactions
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(account_metas: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct ProposeMultisigTransaction<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigTransaction::space(&account_metas, &data),
+        seeds = [
+            b"multisig_tx",
+            multisig.key().as_ref(),
+            &multisig.transaction_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMultisigTransaction<'info> {
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig @ MutrError::InvalidMultisig
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigTransaction<'info> {
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig @ MutrError::InvalidMultisig
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+
+    pub clr_program: Program<'info, crate::program::MutrClr>,
+}
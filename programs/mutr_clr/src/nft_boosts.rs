@@ -0,0 +1,216 @@
+//! NFT-gated position boosts.
+//!
+//! The authority names a Metaplex collection in the `NftBoostConfig` with
+//! `set_nft_boost_config`. A position owner holding an NFT verified as part of that
+//! collection calls `verify_nft_boost` to write the boost into the position's
+//! `UserState`: `nft_boost_mint` records the NFT, `nft_boost_bps` is added to the
+//! position's dividend weight multiplier and `nft_fee_discount_bps` is taken off its
+//! stake fee. The boost is fixed at verification; verifying again picks up new config
+//! values.
+//!
+//! Once the owner no longer holds the NFT, anyone can call `revoke_nft_boost` with the
+//! token account now holding it to clear the boost.
+
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::MetadataAccount;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::*;
+
+pub fn set_collection(
+    ctx: Context<SetNftBoostConfig>,
+    collection: Pubkey,
+    stake_fee_discount_bps: u16,
+    dividend_boost_bps: u16,
+) -> Result<()> {
+    require!(
+        stake_fee_discount_bps as u64 <= BPS_DENOMINATOR
+            && dividend_boost_bps as u64 <= BPS_DENOMINATOR,
+        MutrError::InvalidConfig
+    );
+    let config = &mut ctx.accounts.nft_boost_config;
+    config.collection = collection;
+    config.stake_fee_discount_bps = stake_fee_discount_bps;
+    config.dividend_boost_bps = dividend_boost_bps;
+    config.bump = ctx.bumps.nft_boost_config;
+    Ok(())
+}
+
+pub fn verify(ctx: Context<VerifyNftBoost>) -> Result<()> {
+    let config = &ctx.accounts.nft_boost_config;
+    let in_collection = ctx
+        .accounts
+        .nft_metadata
+        .collection
+        .as_ref()
+        .is_some_and(|collection| collection.verified && collection.key == config.collection);
+    require!(in_collection, MutrError::InvalidNft);
+
+    set_boost(
+        &mut *ctx.accounts.state.load_mut()?,
+        &mut ctx.accounts.user_state,
+        ctx.accounts.nft_mint.key(),
+        config.dividend_boost_bps,
+        config.stake_fee_discount_bps,
+    )
+}
+
+/// Permissionless: clear the boost of a position whose owner no longer holds the NFT.
+pub fn revoke(ctx: Context<RevokeNftBoost>) -> Result<()> {
+    set_boost(
+        &mut *ctx.accounts.state.load_mut()?,
+        &mut ctx.accounts.user_state,
+        Pubkey::default(),
+        0,
+        0,
+    )
+}
+
+/// Rewrite a position's NFT boost, settling its rewards at the old dividend weight.
+fn set_boost(
+    state: &mut GlobalState,
+    user_state: &mut UserState,
+    mint: Pubkey,
+    boost_bps: u16,
+    fee_discount_bps: u16,
+) -> Result<()> {
+    settle_user_rewards(state, user_state)?;
+    let weight_before = dividend_weight(user_state)?;
+
+    user_state.nft_boost_mint = mint;
+    user_state.nft_boost_bps = boost_bps;
+    user_state.nft_fee_discount_bps = fee_discount_bps;
+
+    // total_dividend_shares tracks boost-weighted shares
+    let weight_after = dividend_weight(user_state)?;
+    state.total_dividend_shares = state
+        .total_dividend_shares
+        .checked_sub(weight_before)
+        .and_then(|total| total.checked_add(weight_after))
+        .ok_or(MutrError::MathOverflow)?;
+    checkpoint_reward_debt(state, user_state)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct NftBoostConfig {
+    /// Verified Metaplex collection whose NFTs qualify
+    pub collection: Pubkey,
+    /// Taken off a boosted position's stake fee
+    pub stake_fee_discount_bps: u16,
+    /// Added to a boosted position's dividend weight multiplier
+    pub dividend_boost_bps: u16,
+    pub bump: u8,
+}
+
+impl NftBoostConfig {
+    pub const LEN: usize = 32 // collection
+        + 2  // stake_fee_discount_bps
+        + 2  // dividend_boost_bps
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetNftBoostConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + NftBoostConfig::LEN,
+        seeds = [b"nft_boost_config"],
+        bump
+    )]
+    pub nft_boost_config: Account<'info, NftBoostConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct VerifyNftBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"nft_boost_config"],
+        bump = nft_boost_config.bump
+    )]
+    pub nft_boost_config: Account<'info, NftBoostConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump,
+        constraint = user_state.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MutrError::InvalidNft
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Metaplex metadata of the NFT; owned by the token metadata program
+    #[account(constraint = nft_metadata.mint == nft_mint.key() @ MutrError::InvalidNft)]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        constraint = nft_account.mint == nft_mint.key() @ MutrError::InvalidNft,
+        constraint = nft_account.owner == user.key() @ MutrError::InvalidNft,
+        constraint = nft_account.amount == 1 @ MutrError::InvalidNft
+    )]
+    pub nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeNftBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        constraint = user_state.nft_boost_mint != Pubkey::default() @ MutrError::InvalidNft
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    /// Token account now holding the boost NFT, owned by someone else
+    #[account(
+        constraint = nft_account.mint == user_state.nft_boost_mint @ MutrError::InvalidNft,
+        constraint = nft_account.owner != user_state.owner @ MutrError::InvalidNft,
+        constraint = nft_account.amount == 1 @ MutrError::InvalidNft
+    )]
+    pub nft_account: InterfaceAccount<'info, TokenAccount>,
+}
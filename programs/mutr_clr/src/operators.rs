@@ -0,0 +1,119 @@
+//! Profit operators.
+//!
+//! Daily accounting runs from a bot whose key should not hold the authority. The
+//! authority lists up to `MAX_OPERATORS` operator keys in the `Operators` PDA with
+//! `set_operators`, replacing the whole list to rotate or revoke them. An operator can
+//! call `operator_record_profit`, which books profit exactly as `record_profit` does, and
+//! nothing else: no fee, parameter or fund movement accepts an operator signature.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Most operator keys the authority may list
+pub const MAX_OPERATORS: usize = 8;
+
+pub fn set_keys(ctx: Context<SetOperators>, operators: Vec<Pubkey>) -> Result<()> {
+    require!(operators.len() <= MAX_OPERATORS, MutrError::InvalidConfig);
+    for (i, operator) in operators.iter().enumerate() {
+        require!(
+            *operator != Pubkey::default() && !operators[..i].contains(operator),
+            MutrError::InvalidConfig
+        );
+    }
+    let config = &mut ctx.accounts.operators;
+    config.operators = operators;
+    config.bump = ctx.bumps.operators;
+    Ok(())
+}
+
+pub fn book(ctx: Context<OperatorRecordProfit>, profit_amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts
+            .operators
+            .operators
+            .contains(&ctx.accounts.operator.key()),
+        MutrError::Unauthorized
+    );
+    let mut state = ctx.accounts.state.load_mut()?;
+    book_profit(&mut state, profit_amount)?;
+    ctx.accounts
+        .stats
+        .load_mut()?
+        .add_profit(profit_amount, state.total_dividend_shares)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Operators {
+    pub operators: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Operators {
+    pub const LEN: usize = 4 + 32 * MAX_OPERATORS // operators
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetOperators<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Operators::LEN,
+        seeds = [b"operators"],
+        bump
+    )]
+    pub operators: Account<'info, Operators>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OperatorRecordProfit<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        seeds = [b"operators"],
+        bump = operators.bump
+    )]
+    pub operators: Account<'info, Operators>,
+
+    pub operator: Signer<'info>,
+}
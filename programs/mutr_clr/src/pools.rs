@@ -0,0 +1,1227 @@
+//! Per-mint bankroll pools.
+//!
+//! Besides the MUTR bankroll kept in `GlobalState`, the protocol can run a separate
+//! `Pool` per asset (wSOL, USDC, ...), seeded by its mint. Each pool has its own vault,
+//! share mint and `PoolConfig` (stake/unstake fees and thresholds, updated with
+//! `update_pool_config`), and its own dividend accounting: holders lock
+//! shares into the pool's share escrow to earn the profit booked to the pool. Games use
+//! the same registry (`GameConfig`) as the MUTR bankroll to pay prizes from a pool and
+//! deposit revenue into it.
+//!
+//! Rewards booked to dividend holders stay in the vault until claimed but are excluded
+//! from the pool's bankroll, so they neither back prizes nor price new shares.
+//!
+//! A wSOL pool also takes native SOL: `pool_deposit_sol` wraps the lamports straight
+//! into the vault and `pool_withdraw_sol` pays out unwrapped lamports, so SOL holders
+//! never handle a wSOL account.
+//!
+//! Active pools are listed in the `PoolRegistry`. `create_pool` is admin-only until the
+//! authority opens it to everyone, in which case the creator pays the registry's
+//! creation fee (in lamports) to the authority. A deactivated pool leaves the registry
+//! and stops taking deposits and paying prizes; holders can still withdraw and claim.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, MintTo, SyncNative, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::*;
+
+/// Upper bound on the pool stake and unstake fees (10%)
+const MAX_POOL_FEE_BPS: u16 = 1_000;
+
+/// Most pools the registry can list
+pub const MAX_POOLS: usize = 16;
+
+pub fn initialize_registry(ctx: Context<InitializePoolRegistry>, creation_fee: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.permissionless = false;
+    registry.creation_fee = creation_fee;
+    registry.pools = Vec::new();
+    registry.bump = ctx.bumps.registry;
+    Ok(())
+}
+
+pub fn set_creation(
+    ctx: Context<SetPoolCreation>,
+    permissionless: bool,
+    creation_fee: u64,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.permissionless = permissionless;
+    registry.creation_fee = creation_fee;
+    Ok(())
+}
+
+pub fn open(ctx: Context<CreatePool>, params: PoolConfigParams) -> Result<()> {
+    validate_config(
+        params.stake_fee_bps,
+        params.unstake_fee_bps,
+        params.lower_threshold,
+        params.upper_threshold,
+    )?;
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        registry.pools.len() < MAX_POOLS,
+        MutrError::PoolRegistryFull
+    );
+
+    let creator = ctx.accounts.creator.key();
+    if creator != ctx.accounts.state.load()?.authority {
+        require!(registry.permissionless, MutrError::Unauthorized);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.fee_recipient.to_account_info(),
+                },
+            ),
+            registry.creation_fee,
+        )?;
+    }
+    registry.pools.push(ctx.accounts.pool.key());
+
+    let pool = &mut ctx.accounts.pool;
+    pool.mint = ctx.accounts.mint.key();
+    pool.vault = ctx.accounts.pool_vault.key();
+    pool.share_mint = ctx.accounts.share_mint.key();
+    pool.share_escrow = ctx.accounts.share_escrow.key();
+    pool.acc_reward_per_share = 0;
+    pool.total_dividend_shares = 0;
+    pool.unclaimed_rewards = 0;
+    pool.active = true;
+    pool.bump = ctx.bumps.pool;
+
+    let pool_config = &mut ctx.accounts.pool_config;
+    pool_config.pool = pool.key();
+    pool_config.stake_fee_bps = params.stake_fee_bps;
+    pool_config.unstake_fee_bps = params.unstake_fee_bps;
+    pool_config.lower_threshold = params.lower_threshold;
+    pool_config.upper_threshold = params.upper_threshold;
+    pool_config.bump = ctx.bumps.pool_config;
+    Ok(())
+}
+
+/// Admin: (de)activate a pool, listing it in or dropping it from the registry.
+pub fn set_active(ctx: Context<SetPoolActive>, active: bool) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let registry = &mut ctx.accounts.registry;
+    let listed = registry.pools.iter().position(|pool| *pool == pool_key);
+    match (active, listed) {
+        (true, None) => {
+            require!(
+                registry.pools.len() < MAX_POOLS,
+                MutrError::PoolRegistryFull
+            );
+            registry.pools.push(pool_key);
+        }
+        (false, Some(index)) => {
+            registry.pools.swap_remove(index);
+        }
+        _ => {}
+    }
+    ctx.accounts.pool.active = active;
+    Ok(())
+}
+
+/// Admin: update a pool's fees and thresholds. `None` fields are left unchanged.
+pub fn configure(ctx: Context<UpdatePoolConfig>, update: PoolConfigUpdate) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config;
+    if let Some(stake_fee_bps) = update.stake_fee_bps {
+        pool_config.stake_fee_bps = stake_fee_bps;
+    }
+    if let Some(unstake_fee_bps) = update.unstake_fee_bps {
+        pool_config.unstake_fee_bps = unstake_fee_bps;
+    }
+    if let Some(lower_threshold) = update.lower_threshold {
+        pool_config.lower_threshold = lower_threshold;
+    }
+    if let Some(upper_threshold) = update.upper_threshold {
+        pool_config.upper_threshold = upper_threshold;
+    }
+    validate_config(
+        pool_config.stake_fee_bps,
+        pool_config.unstake_fee_bps,
+        pool_config.lower_threshold,
+        pool_config.upper_threshold,
+    )
+}
+
+/// Deposit into the pool's bankroll and mint shares for the net amount.
+pub fn deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let pool = &ctx.accounts.pool;
+    let bankroll_before = pool.bankroll(&ctx.accounts.pool_vault)?;
+    let vault_before = ctx.accounts.pool_vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.pool_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    let received = received_amount(&mut ctx.accounts.pool_vault, vault_before)?;
+
+    issue_shares(
+        &ctx.accounts.token_program,
+        &ctx.accounts.pool,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.user_share_account,
+        apply_fee(received, ctx.accounts.pool_config.stake_fee_bps)?,
+        bankroll_before,
+    )
+}
+
+/// Deposit native SOL into a wSOL pool: the lamports go straight into the vault, which
+/// is synced, so the depositor needs no wSOL account of their own.
+pub fn deposit_sol(ctx: Context<PoolDepositSol>, lamports: u64) -> Result<()> {
+    require!(lamports > 0, MutrError::InvalidAmount);
+    let pool = &ctx.accounts.pool;
+    let bankroll_before = pool.bankroll(&ctx.accounts.pool_vault)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.pool_vault.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        SyncNative {
+            account: ctx.accounts.pool_vault.to_account_info(),
+        },
+    ))?;
+
+    issue_shares(
+        &ctx.accounts.token_program,
+        pool,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.user_share_account,
+        apply_fee(lamports, ctx.accounts.pool_config.stake_fee_bps)?,
+        bankroll_before,
+    )
+}
+
+/// Burn shares for their part of the bankroll, less the unstake fee.
+pub fn withdraw(ctx: Context<PoolWithdraw>, shares: u64) -> Result<()> {
+    require!(shares > 0, MutrError::InvalidAmount);
+    let pool = &ctx.accounts.pool;
+    let net_amount = apply_fee(
+        share_value(
+            pool,
+            &ctx.accounts.pool_vault,
+            &ctx.accounts.share_mint,
+            shares,
+        )?,
+        ctx.accounts.pool_config.unstake_fee_bps,
+    )?;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_share_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::burn(cpi_ctx, shares)?;
+
+    pool_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.pool_vault.to_account_info(),
+        ctx.accounts.destination_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        pool,
+        net_amount,
+    )
+}
+
+/// Withdraw from a wSOL pool as native SOL: the wSOL is moved into a temporary account
+/// that is closed to the user, unwrapping it to lamports.
+pub fn withdraw_sol(ctx: Context<PoolWithdrawSol>, shares: u64) -> Result<()> {
+    require!(shares > 0, MutrError::InvalidAmount);
+    let pool = &ctx.accounts.pool;
+    let net_amount = apply_fee(
+        share_value(
+            pool,
+            &ctx.accounts.pool_vault,
+            &ctx.accounts.share_mint,
+            shares,
+        )?,
+        ctx.accounts.pool_config.unstake_fee_bps,
+    )?;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_share_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::burn(cpi_ctx, shares)?;
+
+    pool_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.pool_vault.to_account_info(),
+        ctx.accounts.unwrap_account.to_account_info(),
+        &ctx.accounts.native_mint,
+        pool,
+        net_amount,
+    )?;
+
+    let pool_seeds: &[&[u8]] = &[b"pool", pool.mint.as_ref(), &[pool.bump]];
+    let signer_seeds = &[pool_seeds];
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.unwrap_account.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+/// Lock shares in the share escrow to earn the pool's dividends.
+pub fn join_dividends(ctx: Context<JoinPoolDividends>, shares: u64) -> Result<()> {
+    require!(shares > 0, MutrError::InvalidAmount);
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    if position.owner == Pubkey::default() {
+        position.owner = ctx.accounts.user.key();
+        position.pool = pool.key();
+        position.bump = ctx.bumps.position;
+    }
+    position.settle(pool)?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_share_account.to_account_info(),
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.share_escrow.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, shares, ctx.accounts.share_mint.decimals)?;
+
+    position.dividend_shares = position
+        .dividend_shares
+        .checked_add(shares)
+        .ok_or(MutrError::MathOverflow)?;
+    pool.total_dividend_shares = pool
+        .total_dividend_shares
+        .checked_add(shares)
+        .ok_or(MutrError::MathOverflow)?;
+    position.checkpoint(pool)
+}
+
+/// Unlock dividend shares back to the holder; earned rewards stay claimable.
+pub fn leave_dividends(ctx: Context<LeavePoolDividends>, shares: u64) -> Result<()> {
+    require!(shares > 0, MutrError::InvalidAmount);
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    require!(
+        position.dividend_shares >= shares,
+        MutrError::InsufficientShares
+    );
+    position.settle(pool)?;
+
+    position.dividend_shares -= shares;
+    pool.total_dividend_shares = pool
+        .total_dividend_shares
+        .checked_sub(shares)
+        .ok_or(MutrError::MathOverflow)?;
+    position.checkpoint(pool)?;
+
+    pool_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.share_escrow.to_account_info(),
+        ctx.accounts.user_share_account.to_account_info(),
+        &ctx.accounts.share_mint,
+        &ctx.accounts.pool,
+        shares,
+    )
+}
+
+pub fn claim_dividends(ctx: Context<ClaimPoolDividends>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    position.settle(pool)?;
+    position.checkpoint(pool)?;
+
+    let amount = position.pending_rewards;
+    if amount == 0 {
+        return Ok(());
+    }
+    position.pending_rewards = 0;
+    pool.unclaimed_rewards = pool
+        .unclaimed_rewards
+        .checked_sub(amount)
+        .ok_or(MutrError::MathOverflow)?;
+
+    pool_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.pool_vault.to_account_info(),
+        ctx.accounts.destination_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.pool,
+        amount,
+    )
+}
+
+/// Admin: book part of the bankroll as profit for the pool's dividend holders.
+pub fn book_dividends(ctx: Context<RecordPoolProfit>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let bankroll = ctx.accounts.pool.bankroll(&ctx.accounts.pool_vault)?;
+    require!(amount <= bankroll, MutrError::InvalidAmount);
+    ctx.accounts.pool.distribute(amount)
+}
+
+/// Game: pay a prize out of the pool's bankroll.
+pub fn pay_prize(ctx: Context<SendPoolPrize>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    require!(
+        amount <= ctx.accounts.pool.bankroll(&ctx.accounts.pool_vault)?,
+        MutrError::PayoutTooLarge
+    );
+    pool_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.pool_vault.to_account_info(),
+        ctx.accounts.winner_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.pool,
+        amount,
+    )
+}
+
+/// Game: deposit risk-free revenue (a rake) and book it for the dividend holders.
+pub fn deposit_game_revenue(ctx: Context<DepositPoolProfit>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let vault_before = ctx.accounts.pool_vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.source.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.pool_vault.to_account_info(),
+        authority: ctx.accounts.game.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    let received = received_amount(&mut ctx.accounts.pool_vault, vault_before)?;
+    ctx.accounts.pool.distribute(received)
+}
+
+fn validate_config(
+    stake_fee_bps: u16,
+    unstake_fee_bps: u16,
+    lower_threshold: u64,
+    upper_threshold: u64,
+) -> Result<()> {
+    require!(
+        stake_fee_bps <= MAX_POOL_FEE_BPS
+            && unstake_fee_bps <= MAX_POOL_FEE_BPS
+            && lower_threshold <= upper_threshold,
+        MutrError::InvalidConfig
+    );
+    Ok(())
+}
+
+/// Mint shares for a net deposit, priced against the bankroll before the deposit. The
+/// stake fee is left in the vault, accruing to the existing shares.
+fn issue_shares<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    pool: &Account<'info, Pool>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    net_amount: u64,
+    bankroll_before: u64,
+) -> Result<()> {
+    let shares = shares_for_deposit(net_amount, share_mint.supply, bankroll_before)?;
+    require!(shares > 0, MutrError::ZeroShares);
+
+    let pool_seeds: &[&[u8]] = &[b"pool", pool.mint.as_ref(), &[pool.bump]];
+    let signer_seeds = &[pool_seeds];
+    let cpi_accounts = MintTo {
+        mint: share_mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::mint_to(cpi_ctx, shares)
+}
+
+/// Part of the bankroll `shares` are worth, before the unstake fee.
+fn share_value(
+    pool: &Pool,
+    pool_vault: &TokenAccount,
+    share_mint: &Mint,
+    shares: u64,
+) -> Result<u64> {
+    amount_for_shares(shares, pool.bankroll(pool_vault)?, share_mint.supply)
+}
+
+/// Transfer out of a token account owned by the pool PDA. Zero amounts are a no-op.
+fn pool_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    pool: &Account<'info, Pool>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let pool_seeds: &[&[u8]] = &[b"pool", pool.mint.as_ref(), &[pool.bump]];
+    let signer_seeds = &[pool_seeds];
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to,
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Bankroll for one asset, seeded by its mint.
+#[account]
+pub struct Pool {
+    pub mint: Pubkey,
+    /// Token account holding the bankroll, owned by the pool PDA
+    pub vault: Pubkey,
+    /// Mint of the pool's shares (the pool's xMUTR)
+    pub share_mint: Pubkey,
+    /// Holds the shares locked for dividends
+    pub share_escrow: Pubkey,
+    pub acc_reward_per_share: u128,
+    pub total_dividend_shares: u64,
+    /// Rewards booked to dividend holders, still in the vault until claimed
+    pub unclaimed_rewards: u64,
+    /// Takes deposits and pays prizes; listed in the registry
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const LEN: usize = 32 // mint
+        + 32 // vault
+        + 32 // share_mint
+        + 32 // share_escrow
+        + 16 // acc_reward_per_share
+        + 8  // total_dividend_shares
+        + 8  // unclaimed_rewards
+        + 1  // active
+        + 1; // bump
+
+    /// Vault balance backing shares and prizes, net of unclaimed rewards.
+    pub fn bankroll(&self, vault: &TokenAccount) -> Result<u64> {
+        vault
+            .amount
+            .checked_sub(self.unclaimed_rewards)
+            .ok_or(error!(MutrError::MathOverflow))
+    }
+
+    /// Fold `amount` of the vault into reward per share for the dividend holders.
+    fn distribute(&mut self, amount: u64) -> Result<()> {
+        require!(self.total_dividend_shares > 0, MutrError::NoDividendShares);
+        let increment = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(MutrError::MathOverflow)?
+            / self.total_dividend_shares as u128;
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(MutrError::MathOverflow)?;
+        self.unclaimed_rewards = self
+            .unclaimed_rewards
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Fees and thresholds of one pool, in the pool's token.
+#[account]
+pub struct PoolConfig {
+    pub pool: Pubkey,
+    pub stake_fee_bps: u16,
+    pub unstake_fee_bps: u16,
+    pub lower_threshold: u64,
+    pub upper_threshold: u64,
+    pub bump: u8,
+}
+
+impl PoolConfig {
+    pub const LEN: usize = 32 // pool
+        + 2  // stake_fee_bps
+        + 2  // unstake_fee_bps
+        + 8  // lower_threshold
+        + 8  // upper_threshold
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolConfigParams {
+    pub stake_fee_bps: u16,
+    pub unstake_fee_bps: u16,
+    pub lower_threshold: u64,
+    pub upper_threshold: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct PoolConfigUpdate {
+    pub stake_fee_bps: Option<u16>,
+    pub unstake_fee_bps: Option<u16>,
+    pub lower_threshold: Option<u64>,
+    pub upper_threshold: Option<u64>,
+}
+
+/// Directory of the active pools.
+#[account]
+pub struct PoolRegistry {
+    /// Anyone may create a pool, paying `creation_fee`
+    pub permissionless: bool,
+    /// Lamports a non-admin creator pays to the authority
+    pub creation_fee: u64,
+    pub pools: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    pub const LEN: usize = 1 // permissionless
+        + 8  // creation_fee
+        + 4 + 32 * MAX_POOLS // pools
+        + 1; // bump
+}
+
+/// A holder's dividend shares in one pool.
+#[account]
+pub struct PoolPosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub dividend_shares: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl PoolPosition {
+    pub const LEN: usize = 32 // owner
+        + 32 // pool
+        + 8  // dividend_shares
+        + 16 // reward_debt
+        + 8  // pending_rewards
+        + 1; // bump
+
+    fn accrued(&self, pool: &Pool) -> Result<u128> {
+        Ok((self.dividend_shares as u128)
+            .checked_mul(pool.acc_reward_per_share)
+            .ok_or(MutrError::MathOverflow)?
+            / REWARD_PRECISION)
+    }
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`.
+    fn settle(&mut self, pool: &Pool) -> Result<()> {
+        let earned = self.accrued(pool)?.saturating_sub(self.reward_debt);
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(u64::try_from(earned).map_err(|_| MutrError::MathOverflow)?)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    fn checkpoint(&mut self, pool: &Pool) -> Result<()> {
+        self.reward_debt = self.accrued(pool)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializePoolRegistry<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PoolRegistry::LEN,
+        seeds = [b"pool_registry"],
+        bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolCreation<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Box<Account<'info, PoolRegistry>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Pool::LEN,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + PoolConfig::LEN,
+        seeds = [b"pool_config", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool
+    )]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_shares", mint.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = pool
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_share_escrow", mint.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = pool
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: the protocol authority, receiving the creation fee
+    #[account(mut, address = state.load()?.authority @ MutrError::Unauthorized)]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_config", pool_config.pool.as_ref()],
+        bump = pool_config.bump
+    )]
+    pub pool_config: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolActive<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PoolDeposit<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = mint @ MutrError::InvalidMint,
+        constraint = pool.active @ MutrError::PoolInactive,
+        has_one = share_mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"pool_config", pool.key().as_ref()],
+        bump = pool_config.bump
+    )]
+    pub pool_config: Account<'info, PoolConfig>,
+
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.mint @ MutrError::InvalidMint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == share_mint.key() @ MutrError::InvalidMint
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PoolWithdraw<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = mint @ MutrError::InvalidMint,
+        has_one = share_mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"pool_config", pool.key().as_ref()],
+        bump = pool_config.bump
+    )]
+    pub pool_config: Account<'info, PoolConfig>,
+
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == share_mint.key() @ MutrError::InvalidMint
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == pool.mint @ MutrError::InvalidMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PoolDepositSol<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.active @ MutrError::PoolInactive,
+        constraint = pool.mint == native_mint::ID @ MutrError::InvalidMint,
+        has_one = share_mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"pool_config", pool.key().as_ref()],
+        bump = pool_config.bump
+    )]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut)]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = share_mint,
+        associated_token::authority = user
+    )]
+    pub user_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PoolWithdrawSol<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.mint == native_mint::ID @ MutrError::InvalidMint,
+        has_one = share_mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"pool_config", pool.key().as_ref()],
+        bump = pool_config.bump
+    )]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut)]
+    pub pool_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == share_mint.key() @ MutrError::InvalidMint
+    )]
+    pub user_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = native_mint::ID @ MutrError::InvalidMint)]
+    pub native_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Temporary wSOL account, closed to the user within the instruction
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"unwrap", user.key().as_ref()],
+        bump,
+        token::mint = native_mint,
+        token::authority = pool
+    )]
+    pub unwrap_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinPoolDividends<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = share_mint @ MutrError::InvalidMint,
+        has_one = share_escrow @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PoolPosition::LEN,
+        seeds = [b"pool_position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, PoolPosition>,
+
+    #[account(mut)]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == pool.share_mint @ MutrError::InvalidMint
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeavePoolDividends<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = share_mint @ MutrError::InvalidMint,
+        has_one = share_escrow @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_position", pool.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, PoolPosition>,
+
+    #[account(mut)]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == pool.share_mint @ MutrError::InvalidMint
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPoolDividends<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_position", pool.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, PoolPosition>,
+
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == pool.mint @ MutrError::InvalidMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RecordPoolProfit<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendPoolPrize<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = mint @ MutrError::InvalidMint,
+        constraint = pool.active @ MutrError::PoolInactive,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == pool.mint @ MutrError::InvalidMint
+    )]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DepositPoolProfit<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = mint @ MutrError::InvalidMint,
+        constraint = pool.vault == pool_vault.key() @ MutrError::InvalidVault
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = source.mint == pool.mint @ MutrError::InvalidMint
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Registration only; disabled games may still deposit so they can wind down
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
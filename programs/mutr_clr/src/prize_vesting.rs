@@ -0,0 +1,289 @@
+//! Streamed payout of oversized prizes.
+//!
+//! A prize above `prize_vesting_threshold_bps` of the CLR vault cannot leave in one
+//! piece: `send_prize`, `send_prize_many` and `credit_prize` reject it, and the game
+//! pays it with `send_vested_prize` instead. That pays `prize_upfront_bps` of the prize
+//! at once and records the rest in the winner's `PrizeVesting` PDA, which releases it
+//! linearly over `prize_vesting_slots`; the winner collects what has released so far
+//! with `claim_vested_prize`. A new streamed prize for a winner who is still vesting
+//! keeps what has already released claimable and restarts the stream, from now, over
+//! the unreleased rest plus the new remainder. The streamed part stays in the CLR vault
+//! until claimed and is booked as a prize only when it leaves; meanwhile
+//! `GlobalState::prize_vesting_owed` holds it out of the share price and the payout
+//! limits. A threshold of zero turns vesting off.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn send_vested(ctx: Context<SendVestedPrize>, winner: Pubkey, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let mut state = ctx.accounts.state.load_mut()?;
+    risk::check_payout(&state, ctx.accounts.clr_vault.amount, amount)?;
+
+    let streamed = if needs_vesting(&state, ctx.accounts.clr_vault.amount, amount) {
+        amount - upfront_part(&state, amount)
+    } else {
+        0
+    };
+    let upfront = amount - streamed;
+
+    let slot = Clock::get()?.slot;
+    let vesting = &mut ctx.accounts.prize_vesting;
+    if streamed > 0 {
+        vesting.add(slot, state.prize_vesting_slots as u64, streamed)?;
+        state.prize_vesting_owed = state
+            .prize_vesting_owed
+            .checked_add(streamed)
+            .ok_or(MutrError::MathOverflow)?;
+    }
+    vesting.winner = winner;
+    vesting.bump = ctx.bumps.prize_vesting;
+    let bump = state.bump;
+    drop(state);
+
+    if upfront > 0 {
+        vault_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            ctx.accounts.winner_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.state.to_account_info(),
+            bump,
+            upfront,
+        )?;
+    }
+    // The streamed part is the winner's from now on, so the game books the whole prize
+    ctx.accounts.game_ledger.load_mut()?.book_payout(amount)?;
+    stats::record_prize(&ctx.accounts.stats, &mut ctx.accounts.clr_vault, upfront)
+}
+
+pub fn claim_vested(ctx: Context<ClaimVestedPrize>) -> Result<()> {
+    let mut state = ctx.accounts.state.load_mut()?;
+    guardian::check(&state, PAUSE_WITHDRAWALS)?;
+    let vesting = &mut ctx.accounts.prize_vesting;
+    let amount = vesting.claimable(Clock::get()?.slot)?;
+    require!(amount > 0, MutrError::InvalidAmount);
+    vesting.claimed = vesting
+        .claimed
+        .checked_add(amount)
+        .ok_or(MutrError::MathOverflow)?;
+    // Prizes streamed before `prize_vesting_owed` existed are not tracked by it
+    state.prize_vesting_owed = state.prize_vesting_owed.saturating_sub(amount);
+    let bump = state.bump;
+    drop(state);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        ctx.accounts.winner_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        bump,
+        amount,
+    )?;
+    stats::record_prize(&ctx.accounts.stats, &mut ctx.accounts.clr_vault, amount)
+}
+
+/// Require that a prize paid in one piece stays within the vesting threshold.
+pub(crate) fn check_unvested(state: &GlobalState, vault_balance: u64, amount: u64) -> Result<()> {
+    require!(
+        !needs_vesting(state, vault_balance, amount),
+        MutrError::PrizeNeedsVesting
+    );
+    Ok(())
+}
+
+/// Whether a prize of `amount` is above the vesting threshold for the current vault
+/// balance, so it has to go through `send_vested_prize`.
+pub fn needs_vesting(state: &GlobalState, vault_balance: u64, amount: u64) -> bool {
+    state.prize_vesting_threshold_bps != 0
+        && amount as u128 * BPS_DENOMINATOR as u128
+            > state.backing(vault_balance) as u128 * state.prize_vesting_threshold_bps as u128
+}
+
+fn upfront_part(state: &GlobalState, amount: u64) -> u64 {
+    (amount as u128 * state.prize_upfront_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// A winner's streamed prize.
+#[account]
+pub struct PrizeVesting {
+    pub winner: Pubkey,
+    /// Released before the current stream began
+    pub carried: u64,
+    /// Released linearly from `start_slot` to `end_slot`
+    pub amount: u64,
+    /// Part of `carried + amount` paid out so far
+    pub claimed: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub bump: u8,
+}
+
+impl PrizeVesting {
+    pub const LEN: usize = 32 // winner
+        + 8  // carried
+        + 8  // amount
+        + 8  // claimed
+        + 8  // start_slot
+        + 8  // end_slot
+        + 1; // bump
+
+    /// Part of the stream released by `slot`.
+    fn streamed(&self, slot: u64) -> u64 {
+        if slot >= self.end_slot {
+            return self.amount;
+        }
+        let elapsed = slot.saturating_sub(self.start_slot);
+        let duration = self.end_slot - self.start_slot;
+        (self.amount as u128 * elapsed as u128 / duration as u128) as u64
+    }
+
+    /// Released by `slot` and not claimed yet.
+    pub fn claimable(&self, slot: u64) -> Result<u64> {
+        Ok(self
+            .carried
+            .checked_add(self.streamed(slot))
+            .ok_or(MutrError::MathOverflow)?
+            - self.claimed)
+    }
+
+    /// Restart the stream at `slot` over what has not released yet plus `amount`.
+    fn add(&mut self, slot: u64, duration: u64, amount: u64) -> Result<()> {
+        let unreleased = self.amount - self.streamed(slot);
+        self.carried = self.claimable(slot)?;
+        self.amount = unreleased
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        self.claimed = 0;
+        self.start_slot = slot;
+        self.end_slot = slot.checked_add(duration).ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(winner: Pubkey)]
+pub struct SendVestedPrize<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the part paid at once
+    #[account(
+        mut,
+        constraint = winner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = winner_mutr_account.owner == winner @ MutrError::InvalidRecipient
+    )]
+    pub winner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PrizeVesting::LEN,
+        seeds = [b"prize_vesting", winner.as_ref()],
+        bump
+    )]
+    pub prize_vesting: Account<'info, PrizeVesting>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_ledger", game.key().as_ref()],
+        bump = game_ledger.load()?.bump
+    )]
+    pub game_ledger: AccountLoader<'info, GameLedger>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+
+    /// Funds the vesting account's rent when the winner has none yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVestedPrize<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_vesting", winner.key().as_ref()],
+        bump = prize_vesting.bump
+    )]
+    pub prize_vesting: Account<'info, PrizeVesting>,
+
+    #[account(
+        mut,
+        constraint = winner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = winner_mutr_account.owner == winner.key() @ MutrError::InvalidRecipient
+    )]
+    pub winner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub winner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
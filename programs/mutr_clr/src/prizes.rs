@@ -0,0 +1,315 @@
+//! Batch and pull-based prize distribution.
+//!
+//! `send_prize_many` pays several winners from the CLR vault in one transaction, so a
+//! tournament or jackpot split does not take one `send_prize` per winner. The winners'
+//! MUTR token accounts are passed as `remaining_accounts`, in the same order as the
+//! amounts, each winner at most once. The batch as a whole is held to the bankroll risk
+//! limit (see `risk`) and the vesting threshold (see `prize_vesting`) against the vault
+//! balance before it, and each payout against what the payouts ahead of it left.
+//!
+//! Games that would rather not push tokens credit the winner with `credit_prize`
+//! instead: the prize moves from the CLR vault into the shared claim vault and is
+//! recorded in the winner's `PrizeClaim` PDA, and the winner collects it with
+//! `claim_prize`, creating their token account on the way if it does not exist yet.
+//! Credits to the same winner accumulate until claimed.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Most winners one `send_prize_many` call may pay
+pub const MAX_PRIZE_BATCH: usize = 20;
+
+pub fn send_many<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SendPrizeMany<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(
+        !amounts.is_empty()
+            && amounts.len() <= MAX_PRIZE_BATCH
+            && amounts.len() == ctx.remaining_accounts.len(),
+        MutrError::InvalidPrizeBatch
+    );
+
+    let state = ctx.accounts.state.load()?;
+    let vault_balance = ctx.accounts.clr_vault.amount;
+    let winners = ctx.remaining_accounts;
+    let mut total: u64 = 0;
+    for (index, amount) in amounts.iter().enumerate() {
+        require!(*amount > 0, MutrError::InvalidAmount);
+        require!(
+            !winners[..index]
+                .iter()
+                .any(|winner| winner.key == winners[index].key),
+            MutrError::InvalidPrizeBatch
+        );
+        let balance = vault_balance
+            .checked_sub(total)
+            .ok_or(MutrError::MathOverflow)?;
+        risk::check_payout(&state, balance, *amount)?;
+        prize_vesting::check_unvested(&state, balance, *amount)?;
+        total = total.checked_add(*amount).ok_or(MutrError::MathOverflow)?;
+    }
+    // Splitting a prize across entries must not get it past the limits
+    risk::check_payout(&state, vault_balance, total)?;
+    prize_vesting::check_unvested(&state, vault_balance, total)?;
+
+    pay_batch(
+        &ctx.accounts.token_program,
+        &ctx.accounts.clr_vault,
+        &ctx.accounts.mutr_mint,
+        &ctx.accounts.state,
+        ctx.remaining_accounts,
+        &amounts,
+        None,
+    )?;
+    stats::record_prize(&ctx.accounts.stats, &mut ctx.accounts.clr_vault, total)
+}
+
+/// Pay each amount from `vault` to the token account at the same position in `winners`.
+/// With `owners`, each token account must also belong to the matching owner.
+pub(crate) fn pay_batch<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    state: &AccountLoader<'info, GlobalState>,
+    winners: &'info [AccountInfo<'info>],
+    amounts: &[u64],
+    owners: Option<&[Pubkey]>,
+) -> Result<()> {
+    let state_bump = state.load()?.bump;
+    for (index, (winner_info, amount)) in winners.iter().zip(amounts).enumerate() {
+        let winner = InterfaceAccount::<TokenAccount>::try_from(winner_info)?;
+        require_keys_eq!(winner.mint, mint.key(), MutrError::InvalidMint);
+        if let Some(owners) = owners {
+            require_keys_eq!(winner.owner, owners[index], MutrError::InvalidRecipient);
+        }
+
+        vault_transfer(
+            token_program.to_account_info(),
+            vault.to_account_info(),
+            winner_info.clone(),
+            mint,
+            state.to_account_info(),
+            state_bump,
+            *amount,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn credit(ctx: Context<CreditPrize>, winner: Pubkey, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let state = ctx.accounts.state.load()?;
+    risk::check_payout(&state, ctx.accounts.clr_vault.amount, amount)?;
+    prize_vesting::check_unvested(&state, ctx.accounts.clr_vault.amount, amount)?;
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.clr_vault.to_account_info(),
+        ctx.accounts.prize_claim_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        state.bump,
+        amount,
+    )?;
+
+    let claim = &mut ctx.accounts.prize_claim;
+    claim.winner = winner;
+    claim.amount = claim
+        .amount
+        .checked_add(amount)
+        .ok_or(MutrError::MathOverflow)?;
+    claim.bump = ctx.bumps.prize_claim;
+
+    stats::record_prize(&ctx.accounts.stats, &mut ctx.accounts.clr_vault, amount)
+}
+
+pub fn claim(ctx: Context<ClaimPrize>) -> Result<()> {
+    let amount = ctx.accounts.prize_claim.amount;
+    require!(amount > 0, MutrError::InvalidAmount);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.prize_claim_vault.to_account_info(),
+        ctx.accounts.winner_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Prizes credited to a winner and not yet claimed.
+#[account]
+pub struct PrizeClaim {
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl PrizeClaim {
+    pub const LEN: usize = 32 // winner
+        + 8  // amount
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SendPrizeMany<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault,
+        constraint = clr_vault.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = clr_vault.owner == state.key() @ MutrError::Unauthorized
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(winner: Pubkey)]
+pub struct CreditPrize<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Shared MUTR vault holding every credited, unclaimed prize
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"prize_claim_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub prize_claim_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PrizeClaim::LEN,
+        seeds = [b"prize_claim", winner.as_ref()],
+        bump
+    )]
+    pub prize_claim: Account<'info, PrizeClaim>,
+
+    #[account(
+        seeds = [b"game", game.key().as_ref()],
+        bump = game_config.load()?.bump,
+        constraint = game_config.load()?.is_enabled() @ MutrError::GameDisabled
+    )]
+    pub game_config: AccountLoader<'info, GameConfig>,
+
+    /// Registered game authority
+    pub game: Signer<'info>,
+
+    /// Funds the claim account's rent when the winner has none yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_claim_vault"],
+        bump
+    )]
+    pub prize_claim_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_claim", winner.key().as_ref()],
+        bump = prize_claim.bump,
+        close = winner
+    )]
+    pub prize_claim: Account<'info, PrizeClaim>,
+
+    #[account(
+        init_if_needed,
+        payer = winner,
+        associated_token::mint = mutr_mint,
+        associated_token::authority = winner
+    )]
+    pub winner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub winner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
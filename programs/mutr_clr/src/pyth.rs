@@ -0,0 +1,305 @@
+//! Pyth price adapter for USD-denominated limits.
+//!
+//! The authority configures the MUTR/USD feed and limits in USD cents with
+//! `set_usd_pricing`. While the limits are enabled, `place_bet` reads a Pyth
+//! `PriceUpdateV2` account (posted by the Pyth receiver program) and converts the USD
+//! max bet and max payout to MUTR at that price. `sync_usd_thresholds` converts the USD
+//! thresholds into `lower_threshold` / `upper_threshold` the same way. A price is only
+//! used when it is fully verified, no older than `max_staleness_secs` and its
+//! confidence interval is within `max_conf_bps` of the price.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::*;
+
+/// Pyth Solana receiver program, owner of `PriceUpdateV2` accounts
+pub mod pyth_receiver {
+    use super::*;
+    declare_id!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+}
+
+/// Widest confidence interval an admin may accept (10% of the price)
+const MAX_CONF_BPS: u16 = 1_000;
+
+const CENTS_PER_USD: u128 = 100;
+
+/// Wire layout of the receiver's `VerificationLevel`.
+#[derive(AnchorDeserialize, PartialEq, Eq)]
+enum VerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+/// Wire layout of the receiver's `PriceFeedMessage`.
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+/// Wire layout of the receiver's `PriceUpdateV2` account.
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+impl PriceUpdateV2 {
+    fn load(price_update: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *price_update.owner,
+            pyth_receiver::ID,
+            MutrError::InvalidPriceFeed
+        );
+        let data = price_update.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == price_update_discriminator(),
+            MutrError::InvalidPriceFeed
+        );
+        Ok(Self::deserialize(&mut &data[8..])?)
+    }
+}
+
+/// Anchor account discriminator of the receiver's `PriceUpdateV2`.
+fn price_update_discriminator() -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(
+        &anchor_lang::solana_program::hash::hash(b"account:PriceUpdateV2").to_bytes()[..8],
+    );
+    discriminator
+}
+
+/// MUTR/USD price checked against the pricing config: USD for one whole MUTR is
+/// `price * 10^exponent`.
+pub struct UsdPrice {
+    pub price: u64,
+    pub exponent: i32,
+    /// Decimals of the MUTR mint
+    pub decimals: u8,
+}
+
+impl UsdPrice {
+    /// MUTR base units worth `usd_cents` at this price.
+    pub fn cents_to_mutr(&self, usd_cents: u64) -> Result<u64> {
+        // mutr = cents / 100 / (price * 10^exponent) * 10^decimals
+        let mut numerator = usd_cents as u128 * 10u128.pow(self.decimals as u32);
+        let mut denominator = self.price as u128 * CENTS_PER_USD;
+        if self.exponent < 0 {
+            numerator = numerator
+                .checked_mul(10u128.pow(self.exponent.unsigned_abs()))
+                .ok_or(MutrError::MathOverflow)?;
+        } else {
+            denominator = denominator
+                .checked_mul(10u128.pow(self.exponent as u32))
+                .ok_or(MutrError::MathOverflow)?;
+        }
+        u64::try_from(numerator / denominator).map_err(|_| error!(MutrError::MathOverflow))
+    }
+}
+
+/// Read and validate the price in `price_update` for the configured feed.
+pub fn load_price(pricing: &UsdPricing, price_update: &AccountInfo) -> Result<UsdPrice> {
+    let update = PriceUpdateV2::load(price_update)?;
+    require!(
+        update.verification_level == VerificationLevel::Full,
+        MutrError::InvalidPriceFeed
+    );
+    let message = update.price_message;
+    require!(
+        message.feed_id == pricing.feed_id,
+        MutrError::InvalidPriceFeed
+    );
+    require!(message.price > 0, MutrError::InvalidPriceFeed);
+    require!(
+        Clock::get()?
+            .unix_timestamp
+            .saturating_sub(message.publish_time)
+            <= pricing.max_staleness_secs,
+        MutrError::StalePrice
+    );
+    let price = message.price as u64;
+    require!(
+        message.conf as u128 * BPS_DENOMINATOR as u128
+            <= price as u128 * pricing.max_conf_bps as u128,
+        MutrError::PriceTooUncertain
+    );
+    Ok(UsdPrice {
+        price,
+        exponent: message.exponent,
+        decimals: pricing.mutr_decimals,
+    })
+}
+
+/// Hold a bet of `amount` with potential `payout` to the USD limits at the current price.
+pub fn check_usd_limits(
+    pricing: &UsdPricing,
+    price_update: &AccountInfo,
+    amount: u64,
+    payout: u64,
+) -> Result<()> {
+    let price = load_price(pricing, price_update)?;
+    if pricing.max_bet_usd_cents > 0 {
+        require!(
+            amount <= price.cents_to_mutr(pricing.max_bet_usd_cents)?,
+            MutrError::BetTooLarge
+        );
+    }
+    if pricing.max_payout_usd_cents > 0 {
+        require!(
+            payout <= price.cents_to_mutr(pricing.max_payout_usd_cents)?,
+            MutrError::PayoutTooLarge
+        );
+    }
+    Ok(())
+}
+
+pub fn set_pricing(ctx: Context<SetUsdPricing>, params: UsdPricingParams) -> Result<()> {
+    require!(
+        params.max_staleness_secs > 0
+            && params.max_conf_bps > 0
+            && params.max_conf_bps <= MAX_CONF_BPS
+            && params.lower_threshold_usd_cents <= params.upper_threshold_usd_cents,
+        MutrError::InvalidConfig
+    );
+    let pricing = &mut ctx.accounts.usd_pricing;
+    pricing.feed_id = params.feed_id;
+    pricing.max_staleness_secs = params.max_staleness_secs;
+    pricing.max_conf_bps = params.max_conf_bps;
+    pricing.max_bet_usd_cents = params.max_bet_usd_cents;
+    pricing.max_payout_usd_cents = params.max_payout_usd_cents;
+    pricing.lower_threshold_usd_cents = params.lower_threshold_usd_cents;
+    pricing.upper_threshold_usd_cents = params.upper_threshold_usd_cents;
+    pricing.mutr_decimals = ctx.accounts.mutr_mint.decimals;
+    pricing.bump = ctx.bumps.usd_pricing;
+
+    ctx.accounts.state.load_mut()?.usd_limits_enabled = params.enabled as u8;
+    Ok(())
+}
+
+pub fn sync_thresholds(ctx: Context<SyncUsdThresholds>) -> Result<()> {
+    let pricing = &ctx.accounts.usd_pricing;
+    require!(
+        pricing.upper_threshold_usd_cents > 0,
+        MutrError::InvalidConfig
+    );
+    let price = load_price(pricing, &ctx.accounts.price_update)?;
+
+    let state = &mut ctx.accounts.state.load_mut()?;
+    state.lower_threshold = price.cents_to_mutr(pricing.lower_threshold_usd_cents)?;
+    state.upper_threshold = price.cents_to_mutr(pricing.upper_threshold_usd_cents)?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// MUTR/USD feed and the limits expressed in USD cents; a zero limit is disabled.
+#[account]
+pub struct UsdPricing {
+    /// Pyth price feed id of MUTR/USD
+    pub feed_id: [u8; 32],
+    pub max_staleness_secs: i64,
+    /// Widest accepted confidence interval, as a share of the price
+    pub max_conf_bps: u16,
+    pub max_bet_usd_cents: u64,
+    pub max_payout_usd_cents: u64,
+    pub lower_threshold_usd_cents: u64,
+    pub upper_threshold_usd_cents: u64,
+    pub mutr_decimals: u8,
+    pub bump: u8,
+}
+
+impl UsdPricing {
+    pub const LEN: usize = 32 // feed_id
+        + 8  // max_staleness_secs
+        + 2  // max_conf_bps
+        + 8  // max_bet_usd_cents
+        + 8  // max_payout_usd_cents
+        + 8  // lower_threshold_usd_cents
+        + 8  // upper_threshold_usd_cents
+        + 1  // mutr_decimals
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UsdPricingParams {
+    pub feed_id: [u8; 32],
+    pub max_staleness_secs: i64,
+    pub max_conf_bps: u16,
+    pub max_bet_usd_cents: u64,
+    pub max_payout_usd_cents: u64,
+    pub lower_threshold_usd_cents: u64,
+    pub upper_threshold_usd_cents: u64,
+    /// Enforce the USD limits in `place_bet`
+    pub enabled: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetUsdPricing<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UsdPricing::LEN,
+        seeds = [b"usd_pricing"],
+        bump
+    )]
+    pub usd_pricing: Account<'info, UsdPricing>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncUsdThresholds<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"usd_pricing"],
+        bump = usd_pricing.bump
+    )]
+    pub usd_pricing: Account<'info, UsdPricing>,
+
+    /// CHECK: Pyth `PriceUpdateV2`, validated by `load_price`
+    pub price_update: UncheckedAccount<'info>,
+}
@@ -0,0 +1,223 @@
+//! Rakeback for active players.
+//!
+//! Every resolved bet carries the game's house edge, `house_edge_bps` of its wager (see
+//! `set_game_randomness`). When a bet is resolved with the player's `UserStats` and the
+//! `RakebackConfig` passed, `rakeback_bps` of that edge is credited to the player's
+//! accrued rakeback, win or lose. The player collects it with `claim_rakeback` from the
+//! rakeback vault, a budget the authority tops up from the treasury with
+//! `fund_rakeback` (anyone may also transfer MUTR into it). A claim pays what the vault
+//! holds, up to the accrued amount; the rest stays accrued for a later claim.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn set_rate(ctx: Context<SetRakebackConfig>, rakeback_bps: u16) -> Result<()> {
+    require!(
+        rakeback_bps as u64 <= BPS_DENOMINATOR,
+        MutrError::InvalidConfig
+    );
+    let config = &mut ctx.accounts.rakeback_config;
+    config.rakeback_bps = rakeback_bps;
+    config.bump = ctx.bumps.rakeback_config;
+    Ok(())
+}
+
+pub fn fund(ctx: Context<FundRakeback>, amount: u64) -> Result<()> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.treasury_vault.to_account_info(),
+        ctx.accounts.rakeback_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )
+}
+
+pub fn claim_accrued(ctx: Context<ClaimRakeback>) -> Result<()> {
+    let amount = ctx
+        .accounts
+        .user_stats
+        .rakeback
+        .min(ctx.accounts.rakeback_vault.amount);
+    require!(amount > 0, MutrError::InvalidAmount);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.rakeback_vault.to_account_info(),
+        ctx.accounts.user_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )?;
+    ctx.accounts.user_stats.rakeback -= amount;
+    Ok(())
+}
+
+/// Credit a resolved bet's rakeback to the player's stats, when both the stats and the
+/// rakeback config are passed.
+pub(crate) fn accrue<'info>(
+    config: &Option<Account<'info, RakebackConfig>>,
+    user_stats: &mut Option<Account<'info, UserStats>>,
+    house_edge_bps: u16,
+    bet: &Bet,
+) -> Result<()> {
+    let (Some(config), Some(user_stats)) = (config, user_stats) else {
+        return Ok(());
+    };
+    require_keys_eq!(user_stats.owner, bet.player, MutrError::Unauthorized);
+    let rakeback = bet.amount as u128 * house_edge_bps as u128 * config.rakeback_bps as u128
+        / (BPS_DENOMINATOR as u128 * BPS_DENOMINATOR as u128);
+    user_stats.rakeback = user_stats
+        .rakeback
+        .checked_add(rakeback as u64)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct RakebackConfig {
+    /// Share of each bet's house edge credited back to the player
+    pub rakeback_bps: u16,
+    pub bump: u8,
+}
+
+impl RakebackConfig {
+    pub const LEN: usize = 2 // rakeback_bps
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetRakebackConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RakebackConfig::LEN,
+        seeds = [b"rakeback_config"],
+        bump
+    )]
+    pub rakeback_config: Account<'info, RakebackConfig>,
+
+    /// Rakeback budget, paying every claim
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"rakeback_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub rakeback_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundRakeback<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = treasury_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rakeback_vault"],
+        bump
+    )]
+    pub rakeback_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRakeback<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        seeds = [b"rakeback_vault"],
+        bump
+    )]
+    pub rakeback_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub user_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
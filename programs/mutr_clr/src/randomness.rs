@@ -0,0 +1,184 @@
+//! Verifiable randomness for bet resolution.
+//!
+//! Each game picks a `RandomnessProvider`. The client opens the provider's request in
+//! the same transaction as `place_bet` (a Switchboard on-demand commitment, or an ORAO
+//! VRF request); the bet stores the account and `place_bet` checks that its value is
+//! still unknown. `resolve_bet_with_randomness` then reads the revealed value and
+//! derives the outcome on-chain; anyone may call it, so a bet left to expire after the
+//! reveal is forfeited rather than refunded.
+
+use anchor_lang::prelude::*;
+use switchboard_on_demand::{RandomnessAccountData, ON_DEMAND_MAINNET_PID};
+
+use crate::*;
+
+/// ORAO VRF program
+pub mod orao_vrf {
+    use super::*;
+    declare_id!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+}
+
+/// Resolution of the win roll: outcomes are drawn from `0..ROLL_RANGE`.
+const ROLL_RANGE: u64 = 1_000_000;
+
+/// Where a game's bet outcomes come from.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RandomnessProvider {
+    /// The registered game resolves bets itself
+    None,
+    /// Switchboard on-demand randomness (commit / reveal)
+    Switchboard,
+    /// ORAO VRF (request / fulfill)
+    Orao,
+}
+
+impl RandomnessProvider {
+    /// Decode a provider stored by discriminant in a zero-copy account.
+    pub fn from_stored(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Switchboard),
+            2 => Ok(Self::Orao),
+            _ => err!(MutrError::InvalidRandomness),
+        }
+    }
+}
+
+/// Wire layout of ORAO's `Randomness` request account.
+#[derive(AnchorDeserialize)]
+struct OraoRandomness {
+    _seed: [u8; 32],
+    /// All zeroes until the request is fulfilled
+    randomness: [u8; 64],
+}
+
+impl OraoRandomness {
+    fn load(randomness_account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *randomness_account.owner,
+            orao_vrf::ID,
+            MutrError::InvalidRandomness
+        );
+        let data = randomness_account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 + 32 + 64 && data[..8] == orao_discriminator(),
+            MutrError::InvalidRandomness
+        );
+        Ok(Self::deserialize(&mut &data[8..8 + 32 + 64])?)
+    }
+
+    fn is_fulfilled(&self) -> bool {
+        self.randomness != [0u8; 64]
+    }
+}
+
+/// Anchor account discriminator of ORAO's `Randomness`.
+fn orao_discriminator() -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(
+        &anchor_lang::solana_program::hash::hash(b"account:Randomness").to_bytes()[..8],
+    );
+    discriminator
+}
+
+/// Check that `randomness_account` is an open request whose value nobody can know yet:
+/// a Switchboard commitment from the previous slot, or an unfulfilled ORAO request.
+pub fn check_fresh_request(
+    provider: RandomnessProvider,
+    randomness_account: &AccountInfo,
+    slot: u64,
+) -> Result<()> {
+    match provider {
+        RandomnessProvider::None => err!(MutrError::InvalidRandomness),
+        RandomnessProvider::Switchboard => {
+            require_keys_eq!(
+                *randomness_account.owner,
+                Pubkey::new_from_array(ON_DEMAND_MAINNET_PID.to_bytes()),
+                MutrError::InvalidRandomness
+            );
+            let data = RandomnessAccountData::parse(randomness_account.data.borrow())
+                .map_err(|_| error!(MutrError::InvalidRandomness))?;
+            require!(
+                data.seed_slot == slot.saturating_sub(1) && data.reveal_slot == 0,
+                MutrError::RandomnessAlreadyRevealed
+            );
+            Ok(())
+        }
+        RandomnessProvider::Orao => {
+            let request = OraoRandomness::load(randomness_account)?;
+            require!(
+                !request.is_fulfilled(),
+                MutrError::RandomnessAlreadyRevealed
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Check that the value of an already committed request has not been revealed yet, for
+/// games that keep accepting player actions between the commit and the reveal.
+pub fn check_unrevealed(
+    provider: RandomnessProvider,
+    randomness_account: &AccountInfo,
+) -> Result<()> {
+    match provider {
+        RandomnessProvider::None => err!(MutrError::InvalidRandomness),
+        RandomnessProvider::Switchboard => {
+            let data = RandomnessAccountData::parse(randomness_account.data.borrow())
+                .map_err(|_| error!(MutrError::InvalidRandomness))?;
+            require!(data.reveal_slot == 0, MutrError::RandomnessAlreadyRevealed);
+            Ok(())
+        }
+        RandomnessProvider::Orao => {
+            let request = OraoRandomness::load(randomness_account)?;
+            require!(
+                !request.is_fulfilled(),
+                MutrError::RandomnessAlreadyRevealed
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Revealed value of a request opened at `placed_slot`. Once revealed, the value is
+/// final, so it can be read in any later slot; the seed slot ties it to the bet.
+pub fn revealed_value(
+    provider: RandomnessProvider,
+    randomness_account: &AccountInfo,
+    placed_slot: u64,
+) -> Result<[u8; 32]> {
+    match provider {
+        RandomnessProvider::None => err!(MutrError::InvalidRandomness),
+        RandomnessProvider::Switchboard => {
+            let data = RandomnessAccountData::parse(randomness_account.data.borrow())
+                .map_err(|_| error!(MutrError::InvalidRandomness))?;
+            require!(
+                data.seed_slot == placed_slot.saturating_sub(1),
+                MutrError::InvalidRandomness
+            );
+            require!(data.reveal_slot != 0, MutrError::RandomnessNotRevealed);
+            Ok(data.value)
+        }
+        RandomnessProvider::Orao => {
+            let request = OraoRandomness::load(randomness_account)?;
+            require!(request.is_fulfilled(), MutrError::RandomnessNotRevealed);
+            let mut value = [0u8; 32];
+            value.copy_from_slice(&request.randomness[..32]);
+            Ok(value)
+        }
+    }
+}
+
+/// Whether the roll drawn from `value` wins a bet paying `multiplier_bps`, with the
+/// house edge taken out of the win probability: `(1 - edge) / multiplier`.
+pub fn roll_wins(value: &[u8; 32], multiplier_bps: u32, house_edge_bps: u16) -> Result<bool> {
+    let mut roll_bytes = [0u8; 8];
+    roll_bytes.copy_from_slice(&value[..8]);
+    let roll = u64::from_le_bytes(roll_bytes) % ROLL_RANGE;
+
+    let win_chance = (BPS_DENOMINATOR - house_edge_bps as u64)
+        .checked_mul(ROLL_RANGE)
+        .ok_or(MutrError::MathOverflow)?
+        / multiplier_bps as u64;
+    Ok(roll < win_chance)
+}
@@ -0,0 +1,82 @@
+//! Per-slot bet rate limits.
+//!
+//! `place_bet` and `record_game_wager` take a token from two token buckets, one per game
+//! and one per player, so bots cannot flood a single slot with bets and outrun the
+//! bankroll's exposure checks. Both are configured per game with `set_game_rate_limit`: a bucket holds at
+//! most its burst of tokens and regains its refill rate every slot. The game's bucket
+//! lives in its `GameConfig`; the player's lives in their `UserStats` (see `loyalty`),
+//! shared by every game and refilled at the rate of the game being bet on, and is
+//! required while the game limits players. A burst of 0 disables its bucket.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+pub fn set_limits(
+    ctx: Context<SetGameEnabled>,
+    burst: u16,
+    refill_per_slot: u16,
+    player_burst: u16,
+    player_refill_per_slot: u16,
+) -> Result<()> {
+    require!(
+        (burst == 0 || refill_per_slot > 0) && (player_burst == 0 || player_refill_per_slot > 0),
+        MutrError::InvalidConfig
+    );
+    let slot = Clock::get()?.slot;
+    let game_config = &mut ctx.accounts.game_config.load_mut()?;
+    game_config.rate_burst = burst;
+    game_config.rate_refill_per_slot = refill_per_slot;
+    game_config.rate_tokens = burst;
+    game_config.rate_updated_slot = slot;
+    game_config.player_rate_burst = player_burst;
+    game_config.player_rate_refill_per_slot = player_refill_per_slot;
+    Ok(())
+}
+
+/// Take a bet's token from the game's bucket and from the player's.
+pub(crate) fn take_bet_token(
+    game_config: &mut GameConfig,
+    user_stats: &mut Option<Account<'_, UserStats>>,
+    slot: u64,
+) -> Result<()> {
+    if game_config.rate_burst > 0 {
+        game_config.rate_tokens = take_token(
+            game_config.rate_tokens,
+            game_config.rate_updated_slot,
+            game_config.rate_burst,
+            game_config.rate_refill_per_slot,
+            slot,
+        )?;
+        game_config.rate_updated_slot = slot;
+    }
+    if game_config.player_rate_burst > 0 {
+        let user_stats = user_stats.as_mut().ok_or(MutrError::MissingUserStats)?;
+        user_stats.bet_tokens = take_token(
+            user_stats.bet_tokens,
+            user_stats.bet_tokens_slot,
+            game_config.player_rate_burst,
+            game_config.player_rate_refill_per_slot,
+            slot,
+        )?;
+        user_stats.bet_tokens_slot = slot;
+    }
+    Ok(())
+}
+
+/// Refill a bucket up to `burst` for the slots since `updated_slot` and take one token;
+/// returns the tokens left.
+fn take_token(
+    tokens: u16,
+    updated_slot: u64,
+    burst: u16,
+    refill_per_slot: u16,
+    slot: u64,
+) -> Result<u16> {
+    let refill = slot
+        .saturating_sub(updated_slot)
+        .saturating_mul(refill_per_slot as u64);
+    let tokens = (tokens as u64).saturating_add(refill).min(burst as u64) as u16;
+    require!(tokens > 0, MutrError::RateLimited);
+    Ok(tokens - 1)
+}
@@ -0,0 +1,197 @@
+//! Recovery of positions whose owner lost their key.
+//!
+//! An owner can name a recovery address per position with `set_recovery_address`. That
+//! address may start a claim with `initiate_recovery`, which `execute_recovery` carries
+//! out no sooner than `RECOVERY_DELAY_SECS` later; until then the owner, if they still
+//! hold the key, stops it with `cancel_recovery`, and naming another recovery address
+//! drops it too. Only what the program holds can change hands: the escrowed dividend
+//! shares, whose xMUTR sits in the dividend vault, and the pending rewards are merged
+//! into one of the recovery address's positions. xMUTR in the lost wallet is out of the
+//! program's reach, so the shares it backs stay on the old position.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Time the owner has to cancel a recovery claim
+pub const RECOVERY_DELAY_SECS: i64 = 30 * SECONDS_PER_DAY;
+
+pub fn set_address(ctx: Context<SetRecoveryAddress>, recovery: Pubkey) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    require_keys_neq!(recovery, ctx.accounts.user.key(), MutrError::Unauthorized);
+    user_state.recovery = recovery;
+    user_state.recovery_claimable_at = 0;
+    Ok(())
+}
+
+pub fn initiate(ctx: Context<InitiateRecovery>) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.check_movable()?;
+    require!(
+        user_state.recovery_claimable_at == 0,
+        MutrError::RecoveryPending
+    );
+    user_state.recovery_claimable_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(RECOVERY_DELAY_SECS)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(())
+}
+
+pub fn cancel_claim(ctx: Context<CancelRecovery>) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    require!(
+        user_state.recovery_claimable_at != 0,
+        MutrError::NoPendingRecovery
+    );
+    user_state.recovery_claimable_at = 0;
+    Ok(())
+}
+
+pub fn execute_claim(ctx: Context<ExecuteRecovery>, position_index: u32) -> Result<()> {
+    let source = &mut ctx.accounts.source_user_state;
+    source.check_movable()?;
+    require!(
+        source.recovery_claimable_at != 0,
+        MutrError::NoPendingRecovery
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= source.recovery_claimable_at,
+        MutrError::RecoveryNotReady
+    );
+    source.recovery_claimable_at = 0;
+
+    // split the escrowed shares and the rewards off into a position of their own, then
+    // merge that into the recovery address's position
+    let mut state = ctx.accounts.state.load_mut()?;
+    settle_user_rewards(&state, source)?;
+    let weight_before = dividend_weight(source)?;
+    // the NFT stays in the lost wallet, so its boost does not travel with the shares
+    let mut recovered = UserState {
+        staked_shares: 0,
+        dividend_shares: source.escrowed_shares,
+        nft_boost_mint: Pubkey::default(),
+        nft_boost_bps: 0,
+        nft_fee_discount_bps: 0,
+        ..(**source).clone()
+    };
+    source.dividend_shares -= source.escrowed_shares;
+    source.escrowed_shares = 0;
+    source.pending_rewards = 0;
+    source.pending_rewards_2 = 0;
+    checkpoint_reward_debt(&state, source)?;
+    checkpoint_reward_debt(&state, &mut recovered)?;
+
+    // splitting can shift the weight by rounding only
+    let weight_after = dividend_weight(source)?
+        .checked_add(dividend_weight(&recovered)?)
+        .ok_or(MutrError::MathOverflow)?;
+    state.total_dividend_shares = state
+        .total_dividend_shares
+        .checked_sub(weight_before)
+        .ok_or(MutrError::MathOverflow)?
+        .checked_add(weight_after)
+        .ok_or(MutrError::MathOverflow)?;
+
+    let holder_state = &mut ctx.accounts.recovery_user_state;
+    open_position_if_new(
+        &mut ctx.accounts.recovery_positions,
+        holder_state,
+        ctx.accounts.recovery.key(),
+        position_index,
+    )?;
+    holder_state.check_movable()?;
+    merge_position(&mut state, &mut recovered, holder_state)
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct SetRecoveryAddress<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct InitiateRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_state", owner.key().as_ref(), &position_index.to_le_bytes()],
+        bump,
+        constraint = user_state.recovery == recovery.key() @ MutrError::Unauthorized
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    /// CHECK: position owner, fixing the `user_state` address
+    pub owner: UncheckedAccount<'info>,
+
+    pub recovery: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct CancelRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32, source_position_index: u32)]
+pub struct ExecuteRecovery<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", owner.key().as_ref(), &source_position_index.to_le_bytes()],
+        bump,
+        constraint = source_user_state.recovery == recovery.key() @ MutrError::Unauthorized
+    )]
+    pub source_user_state: Account<'info, UserState>,
+
+    /// CHECK: owner of the position being recovered, fixing its address
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = recovery,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", recovery.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub recovery_user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = recovery,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", recovery.key().as_ref()],
+        bump
+    )]
+    pub recovery_positions: Account<'info, UserPositions>,
+
+    #[account(mut)]
+    pub recovery: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
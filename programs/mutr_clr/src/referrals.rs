@@ -0,0 +1,306 @@
+//! Referral codes.
+//!
+//! Anyone can claim a referral code with `create_referral_code`, opening a `Referrer`
+//! PDA at the code's address. A user is bound to a referrer the first time they stake
+//! or bet with the referrer passed along with their `UserStats` (see `loyalty`); the
+//! binding is permanent, and referring yourself is rejected. `stake_with_referral` and
+//! `place_bet_with_referral` require the referral accounts, while `stake` and
+//! `place_bet` credit the referrer whenever they are passed.
+//!
+//! The referrer earns `fee_share_bps` of the stake fees and `edge_share_bps` of the
+//! house edge (`house_edge_bps` of each wager) its users generate. Its cut of a stake
+//! fee is taken before the `fee_split`; its cut of the house edge is taken from the
+//! escrowed wager. Either way the cut moves from the CLR vault into the referral vault,
+//! where the referrer collects it with `claim_referral_earnings`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Longest referral code, in bytes
+pub const MAX_REFERRAL_CODE_LEN: usize = 16;
+
+pub fn set_shares(
+    ctx: Context<SetReferralConfig>,
+    fee_share_bps: u16,
+    edge_share_bps: u16,
+) -> Result<()> {
+    require!(
+        fee_share_bps as u64 <= BPS_DENOMINATOR && edge_share_bps as u64 <= BPS_DENOMINATOR,
+        MutrError::InvalidConfig
+    );
+    let config = &mut ctx.accounts.referral_config;
+    config.fee_share_bps = fee_share_bps;
+    config.edge_share_bps = edge_share_bps;
+    config.bump = ctx.bumps.referral_config;
+    Ok(())
+}
+
+pub fn create_code(ctx: Context<CreateReferralCode>, code: String) -> Result<()> {
+    require!(
+        !code.is_empty()
+            && code.len() <= MAX_REFERRAL_CODE_LEN
+            && code.bytes().all(|b| b.is_ascii_alphanumeric()),
+        MutrError::InvalidReferralCode
+    );
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.owner = ctx.accounts.owner.key();
+    referrer.code = code;
+    referrer.earned = 0;
+    referrer.lifetime_earned = 0;
+    referrer.referred_users = 0;
+    referrer.revenue = 0;
+    referrer.bump = ctx.bumps.referrer;
+    Ok(())
+}
+
+pub fn claim_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+    let amount = ctx.accounts.referrer.earned;
+    require!(amount > 0, MutrError::InvalidAmount);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.referral_vault.to_account_info(),
+        ctx.accounts.owner_mutr_account.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        amount,
+    )?;
+    ctx.accounts.referrer.earned = 0;
+    Ok(())
+}
+
+/// Bind `user` to the passed referrer on first use and credit the referrer its share of
+/// `base`. Returns the MUTR the caller must move from the CLR vault into the referral
+/// vault; 0 when any of the referral accounts is missing.
+pub(crate) fn credit_referrer<'info>(
+    config: &Option<Account<'info, ReferralConfig>>,
+    referrer: &mut Option<Account<'info, Referrer>>,
+    user_stats: &mut Option<Account<'info, UserStats>>,
+    user: Pubkey,
+    source: ReferralSource,
+    base: u64,
+) -> Result<u64> {
+    let (Some(config), Some(referrer), Some(user_stats)) = (config, referrer, user_stats) else {
+        return Ok(0);
+    };
+    require_keys_eq!(user_stats.owner, user, MutrError::Unauthorized);
+    require_keys_neq!(referrer.owner, user, MutrError::SelfReferral);
+    if user_stats.referrer == Pubkey::default() {
+        user_stats.referrer = referrer.key();
+        referrer.referred_users = referrer
+            .referred_users
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+    }
+    require_keys_eq!(
+        user_stats.referrer,
+        referrer.key(),
+        MutrError::ReferrerMismatch
+    );
+
+    let cut = (base as u128 * config.share_bps(source) as u128 / BPS_DENOMINATOR as u128) as u64;
+    referrer.earned = referrer
+        .earned
+        .checked_add(cut)
+        .ok_or(MutrError::MathOverflow)?;
+    referrer.lifetime_earned = referrer
+        .lifetime_earned
+        .checked_add(cut)
+        .ok_or(MutrError::MathOverflow)?;
+    referrer.revenue = referrer
+        .revenue
+        .checked_add(base)
+        .ok_or(MutrError::MathOverflow)?;
+    Ok(cut)
+}
+
+/// Move a referrer's cut from the CLR vault into the referral vault.
+pub(crate) fn move_cut<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    clr_vault: &InterfaceAccount<'info, TokenAccount>,
+    referral_vault: &Option<InterfaceAccount<'info, TokenAccount>>,
+    mutr_mint: &InterfaceAccount<'info, Mint>,
+    state: &AccountLoader<'info, GlobalState>,
+    cut: u64,
+) -> Result<()> {
+    if cut == 0 {
+        return Ok(());
+    }
+    let referral_vault = referral_vault.as_ref().ok_or(MutrError::MissingReferral)?;
+    vault_transfer(
+        token_program.to_account_info(),
+        clr_vault.to_account_info(),
+        referral_vault.to_account_info(),
+        mutr_mint,
+        state.to_account_info(),
+        state.load()?.bump,
+        cut,
+    )
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct ReferralConfig {
+    /// Referrer's share of its users' stake fees
+    pub fee_share_bps: u16,
+    /// Referrer's share of the house edge on its users' bets
+    pub edge_share_bps: u16,
+    pub bump: u8,
+}
+
+impl ReferralConfig {
+    pub const LEN: usize = 2 // fee_share_bps
+        + 2  // edge_share_bps
+        + 1; // bump
+
+    pub fn share_bps(&self, source: ReferralSource) -> u16 {
+        match source {
+            ReferralSource::StakeFee => self.fee_share_bps,
+            ReferralSource::HouseEdge => self.edge_share_bps,
+        }
+    }
+}
+
+#[account]
+pub struct Referrer {
+    pub owner: Pubkey,
+    pub code: String,
+    /// MUTR earned and not yet claimed
+    pub earned: u64,
+    pub lifetime_earned: u64,
+    pub referred_users: u64,
+    /// Stake fees and house edge generated by the referrer's users (see `affiliates`)
+    pub revenue: u64,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const LEN: usize = 32 // owner
+        + 4 + MAX_REFERRAL_CODE_LEN // code
+        + 8  // earned
+        + 8  // lifetime_earned
+        + 8  // referred_users
+        + 8  // revenue
+        + 1; // bump
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferralSource {
+    StakeFee,
+    HouseEdge,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetReferralConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ReferralConfig::LEN,
+        seeds = [b"referral_config"],
+        bump
+    )]
+    pub referral_config: Account<'info, ReferralConfig>,
+
+    /// Holds every referrer's unclaimed earnings
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"referral_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct CreateReferralCode<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Referrer::LEN,
+        seeds = [b"referrer", code.as_bytes()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralEarnings<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.code.as_bytes()],
+        bump = referrer.bump,
+        has_one = owner @ MutrError::Unauthorized
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_vault"],
+        bump
+    )]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub owner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,179 @@
+//! Responsible-gaming self-exclusion and daily wager caps.
+//!
+//! A player's limits live in their first staking position, the `UserState` at position
+//! index 0, which `set_self_exclusion` and `set_daily_wager_cap` open when needed.
+//! `set_self_exclusion` bars the player from betting until a timestamp; an exclusion can
+//! be extended but never shortened. `set_daily_wager_cap` caps the MUTR they can wager
+//! in a day, counted over a `SECONDS_PER_DAY` window opened by their first bet after
+//! the previous window ran out. A tighter cap applies at once, while a looser one
+//! (raising or removing the cap) only applies `WAGER_CAP_COOLDOWN_SECS` later.
+//!
+//! `place_bet` and `record_game_wager` always take that position as `player_state` and
+//! enforce the limits whenever it exists. A position holding active limits cannot be closed.
+//!
+//! On top of these, the authority can cap what any wallet wagers per rolling day with
+//! `max_daily_wager` (see `update_config`), bounding the losses of a stolen wallet.
+//! While it is set, `place_bet` and `record_game_wager` require the player's `UserStats`
+//! (see `loyalty`), whose `daily_wagered` approximates the rolling day by decaying
+//! linearly over `SECONDS_PER_DAY`.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Delay before a looser daily wager cap applies
+pub const WAGER_CAP_COOLDOWN_SECS: i64 = SECONDS_PER_DAY;
+
+pub fn set_exclusion(ctx: Context<SetGamingLimits>, excluded_until: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let user_state = &mut ctx.accounts.user_state;
+    open_position_if_new(
+        &mut ctx.accounts.user_positions,
+        user_state,
+        ctx.accounts.user.key(),
+        0,
+    )?;
+    require!(
+        excluded_until > now && excluded_until > user_state.excluded_until,
+        MutrError::InvalidExclusion
+    );
+    user_state.excluded_until = excluded_until;
+    Ok(())
+}
+
+/// Set the daily wager cap; 0 removes it.
+pub fn set_wager_cap(ctx: Context<SetGamingLimits>, daily_wager_cap: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let user_state = &mut ctx.accounts.user_state;
+    open_position_if_new(
+        &mut ctx.accounts.user_positions,
+        user_state,
+        ctx.accounts.user.key(),
+        0,
+    )?;
+    apply_pending_cap(user_state, now);
+
+    let tighter = daily_wager_cap != 0
+        && (user_state.daily_wager_cap == 0 || daily_wager_cap <= user_state.daily_wager_cap);
+    if tighter {
+        user_state.daily_wager_cap = daily_wager_cap;
+        user_state.pending_wager_cap = 0;
+        user_state.pending_wager_cap_at = 0;
+    } else {
+        user_state.pending_wager_cap = daily_wager_cap;
+        user_state.pending_wager_cap_at = now
+            .checked_add(WAGER_CAP_COOLDOWN_SECS)
+            .ok_or(MutrError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Enforce the player's limits on a new wager of `amount` and count it toward their
+/// daily cap. Players who never opened position 0 have no limits.
+pub(crate) fn check_wager(player_state: &AccountInfo, amount: u64) -> Result<()> {
+    if player_state.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*player_state.owner, crate::ID, MutrError::Unauthorized);
+    let mut data = player_state.try_borrow_mut_data()?;
+    let mut user_state = UserState::try_deserialize(&mut &data[..])?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= user_state.excluded_until, MutrError::SelfExcluded);
+    apply_pending_cap(&mut user_state, now);
+    if user_state.daily_wager_cap != 0 {
+        let window_end = user_state
+            .wager_window_start
+            .saturating_add(SECONDS_PER_DAY);
+        if now >= window_end {
+            user_state.wager_window_start = now;
+            user_state.wagered_in_window = 0;
+        }
+        user_state.wagered_in_window = user_state
+            .wagered_in_window
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        require!(
+            user_state.wagered_in_window <= user_state.daily_wager_cap,
+            MutrError::WagerCapExceeded
+        );
+    }
+
+    let mut writer: &mut [u8] = &mut data;
+    user_state.try_serialize(&mut writer)
+}
+
+/// Enforce the protocol's daily wager limit on a new wager of `amount` and count it in
+/// the player's rolling day.
+pub(crate) fn check_daily_wager(
+    user_stats: &mut Option<Account<'_, UserStats>>,
+    max_daily_wager: u64,
+    amount: u64,
+) -> Result<()> {
+    if max_daily_wager == 0 {
+        return Ok(());
+    }
+    let user_stats = user_stats.as_mut().ok_or(MutrError::MissingUserStats)?;
+    let now = Clock::get()?.unix_timestamp;
+    let elapsed = now.saturating_sub(user_stats.daily_wagered_at);
+    let carried = if elapsed >= SECONDS_PER_DAY {
+        0
+    } else {
+        (user_stats.daily_wagered as u128 * (SECONDS_PER_DAY - elapsed) as u128
+            / SECONDS_PER_DAY as u128) as u64
+    };
+    let daily_wagered = carried.checked_add(amount).ok_or(MutrError::MathOverflow)?;
+    require!(
+        daily_wagered <= max_daily_wager,
+        MutrError::DailyWagerLimitExceeded
+    );
+    user_stats.daily_wagered = daily_wagered;
+    user_stats.daily_wagered_at = now;
+    Ok(())
+}
+
+/// Whether the position holds limits that closing it would drop.
+pub(crate) fn limits_active(user_state: &UserState, now: i64) -> bool {
+    user_state.excluded_until > now
+        || user_state.daily_wager_cap != 0
+        || user_state.pending_wager_cap_at != 0
+}
+
+/// Apply a looser cap whose cooldown has run out.
+fn apply_pending_cap(user_state: &mut UserState, now: i64) {
+    if user_state.pending_wager_cap_at != 0 && now >= user_state.pending_wager_cap_at {
+        user_state.daily_wager_cap = user_state.pending_wager_cap;
+        user_state.pending_wager_cap = 0;
+        user_state.pending_wager_cap_at = 0;
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetGamingLimits<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref(), &0u32.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", user.key().as_ref()],
+        bump
+    )]
+    pub user_positions: Account<'info, UserPositions>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
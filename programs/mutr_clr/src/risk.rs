@@ -0,0 +1,38 @@
+//! Bankroll risk limits.
+//!
+//! `max_payout_bps` caps any single payout at a share of the CLR vault balance, less
+//! what it owes streamed prizes, so one lucky bet cannot drain the bankroll. `place_bet`
+//! enforces it on the bet's potential payout and every prize path on the prize itself;
+//! game programs that pick their own multipliers use `max_multiplier_bps` to bound what
+//! a player may target.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Largest payout the vault currently allows; `u64::MAX` when the cap is disabled.
+pub fn max_payout(state: &GlobalState, vault_balance: u64) -> u64 {
+    if state.max_payout_bps == 0 {
+        return u64::MAX;
+    }
+    let backing = state.backing(vault_balance);
+    (backing as u128 * state.max_payout_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Require that `payout` fits under the cap for the current vault balance.
+pub fn check_payout(state: &GlobalState, vault_balance: u64, payout: u64) -> Result<()> {
+    require!(
+        payout <= max_payout(state, vault_balance),
+        MutrError::PayoutTooLarge
+    );
+    Ok(())
+}
+
+/// Highest multiplier a wager of `amount` may target under the cap.
+pub fn max_multiplier_bps(state: &GlobalState, vault_balance: u64, amount: u64) -> u32 {
+    if amount == 0 {
+        return 0;
+    }
+    let max = max_payout(state, vault_balance) as u128 * BPS_DENOMINATOR as u128 / amount as u128;
+    max.min(u32::MAX as u128) as u32
+}
@@ -0,0 +1,391 @@
+//! Seasons with their own leaderboard and prize pool.
+//!
+//! The authority opens a season with `open_season`, moving its prize budget from the
+//! treasury into a season prize vault and creating a fresh season leaderboard, so every
+//! season starts from an empty board. Players join with `join_season`, which creates
+//! their `SeasonPlayer` stats account. While the season runs, resolving a bet with the
+//! season accounts passed credits its wager and payout to the player's season stats and
+//! offers the player's season winnings to the season board.
+//!
+//! Once the season has ended, anyone can call `distribute_season_prizes` with the board
+//! entries' MUTR token accounts, in board order, as remaining accounts. Rank `i` receives
+//! `rank_bps[i]` of the prize vault through the batch payout path; the share of empty
+//! ranks returns to the treasury.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::*;
+
+/// Ranks of a season leaderboard that can win a prize
+pub const SEASON_PRIZE_RANKS: usize = 10;
+
+pub fn start(ctx: Context<OpenSeason>, id: u64, params: SeasonParams) -> Result<()> {
+    require!(
+        params.start_ts < params.end_ts && params.prize_budget > 0,
+        MutrError::InvalidConfig
+    );
+    let total_bps = params
+        .rank_bps
+        .iter()
+        .map(|bps| *bps as u64)
+        .sum::<u64>();
+    require!(total_bps <= BPS_DENOMINATOR, MutrError::InvalidConfig);
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.treasury_vault.to_account_info(),
+        ctx.accounts.season_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        params.prize_budget,
+    )?;
+
+    let leaderboard = &mut ctx.accounts.season_leaderboard.load_init()?;
+    leaderboard.kind = LeaderboardKind::Season as u8;
+    leaderboard.bump = ctx.bumps.season_leaderboard;
+
+    let season = &mut ctx.accounts.season;
+    season.id = id;
+    season.start_ts = params.start_ts;
+    season.end_ts = params.end_ts;
+    season.prize_budget = params.prize_budget;
+    season.rank_bps = params.rank_bps;
+    season.leaderboard = ctx.accounts.season_leaderboard.key();
+    season.total_wagered = 0;
+    season.total_won = 0;
+    season.players = 0;
+    season.distributed = false;
+    season.bump = ctx.bumps.season;
+    Ok(())
+}
+
+pub fn join(ctx: Context<JoinSeason>) -> Result<()> {
+    let season = &mut ctx.accounts.season;
+    require!(
+        Clock::get()?.unix_timestamp < season.end_ts,
+        MutrError::SeasonEnded
+    );
+    season.players = season
+        .players
+        .checked_add(1)
+        .ok_or(MutrError::MathOverflow)?;
+
+    let season_player = &mut ctx.accounts.season_player;
+    season_player.season = season.key();
+    season_player.player = ctx.accounts.player.key();
+    season_player.wagered = 0;
+    season_player.won = 0;
+    season_player.bets = 0;
+    season_player.bump = ctx.bumps.season_player;
+    Ok(())
+}
+
+pub fn distribute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeSeasonPrizes<'info>>,
+) -> Result<()> {
+    let season = &ctx.accounts.season;
+    require!(
+        Clock::get()?.unix_timestamp >= season.end_ts,
+        MutrError::SeasonNotEnded
+    );
+    require!(!season.distributed, MutrError::SeasonDistributed);
+
+    let budget = ctx.accounts.season_vault.amount;
+    let leaderboard = ctx.accounts.season_leaderboard.load()?;
+    let mut owners = Vec::with_capacity(SEASON_PRIZE_RANKS);
+    let mut amounts = Vec::with_capacity(SEASON_PRIZE_RANKS);
+    for (entry, bps) in leaderboard.entries[..leaderboard.len as usize]
+        .iter()
+        .zip(season.rank_bps)
+    {
+        owners.push(entry.owner);
+        amounts.push((budget as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64);
+    }
+    drop(leaderboard);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len(),
+        MutrError::InvalidPrizeBatch
+    );
+
+    prizes::pay_batch(
+        &ctx.accounts.token_program,
+        &ctx.accounts.season_vault,
+        &ctx.accounts.mutr_mint,
+        &ctx.accounts.state,
+        ctx.remaining_accounts,
+        &amounts,
+        Some(&owners),
+    )?;
+
+    // Prizes of ranks nobody reached go back to the treasury
+    ctx.accounts.season_vault.reload()?;
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.season_vault.to_account_info(),
+        ctx.accounts.treasury_vault.to_account_info(),
+        &ctx.accounts.mutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        ctx.accounts.season_vault.amount,
+    )?;
+
+    ctx.accounts.season.distributed = true;
+    ctx.accounts
+        .stats
+        .load_mut()?
+        .add_prizes(amounts.iter().sum())
+}
+
+/// Credit a resolved bet to the player's season stats, when the season accounts are
+/// passed and the season is running.
+pub(crate) fn record_bet<'info>(
+    season: &mut Option<Account<'info, Season>>,
+    season_player: &mut Option<Account<'info, SeasonPlayer>>,
+    season_leaderboard: &Option<AccountLoader<'info, Leaderboard>>,
+    bet: &Bet,
+) -> Result<()> {
+    let (Some(season), Some(season_player), Some(season_leaderboard)) =
+        (season, season_player, season_leaderboard)
+    else {
+        return Ok(());
+    };
+    require_keys_eq!(season_player.season, season.key(), MutrError::InvalidSeason);
+    require_keys_eq!(season_player.player, bet.player, MutrError::InvalidSeason);
+    require_keys_eq!(
+        season_leaderboard.key(),
+        season.leaderboard,
+        MutrError::InvalidSeason
+    );
+    let now = Clock::get()?.unix_timestamp;
+    if now < season.start_ts || now >= season.end_ts {
+        return Ok(());
+    }
+
+    season_player.wagered = season_player
+        .wagered
+        .checked_add(bet.amount)
+        .ok_or(MutrError::MathOverflow)?;
+    season_player.won = season_player
+        .won
+        .checked_add(bet.payout)
+        .ok_or(MutrError::MathOverflow)?;
+    season_player.bets = season_player
+        .bets
+        .checked_add(1)
+        .ok_or(MutrError::MathOverflow)?;
+    season.total_wagered = season
+        .total_wagered
+        .checked_add(bet.amount)
+        .ok_or(MutrError::MathOverflow)?;
+    season.total_won = season
+        .total_won
+        .checked_add(bet.payout)
+        .ok_or(MutrError::MathOverflow)?;
+
+    season_leaderboard.load_mut()?.offer(
+        season_player.key(),
+        season_player.player,
+        season_player.won,
+    );
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Season {
+    pub id: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// MUTR moved from the treasury into the season prize vault
+    pub prize_budget: u64,
+    /// Share of the prize vault for each leaderboard rank, highest rank first
+    pub rank_bps: [u16; SEASON_PRIZE_RANKS],
+    /// Season board, ranking players by season winnings
+    pub leaderboard: Pubkey,
+    pub total_wagered: u64,
+    pub total_won: u64,
+    pub players: u64,
+    pub distributed: bool,
+    pub bump: u8,
+}
+
+impl Season {
+    pub const LEN: usize = 8 // id
+        + 8  // start_ts
+        + 8  // end_ts
+        + 8  // prize_budget
+        + 2 * SEASON_PRIZE_RANKS // rank_bps
+        + 32 // leaderboard
+        + 8  // total_wagered
+        + 8  // total_won
+        + 8  // players
+        + 1  // distributed
+        + 1; // bump
+}
+
+/// A player's bets during one season.
+#[account]
+pub struct SeasonPlayer {
+    pub season: Pubkey,
+    pub player: Pubkey,
+    pub wagered: u64,
+    /// Payouts won; the season leaderboard score
+    pub won: u64,
+    pub bets: u32,
+    pub bump: u8,
+}
+
+impl SeasonPlayer {
+    pub const LEN: usize = 32 // season
+        + 32 // player
+        + 8  // wagered
+        + 8  // won
+        + 4  // bets
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SeasonParams {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub prize_budget: u64,
+    pub rank_bps: [u16; SEASON_PRIZE_RANKS],
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct OpenSeason<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = treasury_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Season::LEN,
+        seeds = [b"season", id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Leaderboard::LEN,
+        seeds = [b"season_leaderboard", season.key().as_ref()],
+        bump
+    )]
+    pub season_leaderboard: AccountLoader<'info, Leaderboard>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = state
+    )]
+    pub season_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSeason<'info> {
+    #[account(
+        mut,
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + SeasonPlayer::LEN,
+        seeds = [b"season_player", season.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub season_player: Account<'info, SeasonPlayer>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeSeasonPrizes<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = treasury_vault @ MutrError::InvalidVault
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    #[account(
+        constraint = mutr_mint.key() == state.load()?.mutr_mint @ MutrError::InvalidMint
+    )]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(address = season.leaderboard @ MutrError::InvalidSeason)]
+    pub season_leaderboard: AccountLoader<'info, Leaderboard>,
+
+    #[account(
+        mut,
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump
+    )]
+    pub season_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
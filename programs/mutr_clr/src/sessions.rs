@@ -0,0 +1,188 @@
+//! Session keys for betting without a wallet prompt per bet.
+//!
+//! A player authorizes an ephemeral keypair with `create_session`, which opens a
+//! `Session` PDA holding the key, an expiry and a MUTR spend cap, approves the session
+//! PDA as delegate of the player's MUTR account for the cap, and can send the key some
+//! lamports to pay for transactions and bet accounts. A token account has a single
+//! delegate, so opening a session replaces the allowance of any earlier one.
+//!
+//! Until it expires, the session key can sign `place_bet` (and the games built on it)
+//! in the player's place: it pays for the bet account, the wager moves under the
+//! session's delegation, and every wager counts against the cap. The player ends a
+//! session early with `revoke_session`, which closes it and revokes the delegation.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Approve, Revoke, TokenAccount, TokenInterface};
+
+use crate::*;
+
+pub fn authorize(
+    ctx: Context<CreateSession>,
+    session_key: Pubkey,
+    expires_at: i64,
+    spend_cap: u64,
+    lamports: u64,
+) -> Result<()> {
+    require!(
+        expires_at > Clock::get()?.unix_timestamp && spend_cap > 0,
+        MutrError::InvalidSession
+    );
+    let session = &mut ctx.accounts.session;
+    session.owner = ctx.accounts.owner.key();
+    session.session_key = session_key;
+    session.expires_at = expires_at;
+    session.spend_cap = spend_cap;
+    session.spent = 0;
+    session.bump = ctx.bumps.session;
+
+    let cpi_accounts = Approve {
+        to: ctx.accounts.owner_mutr_account.to_account_info(),
+        delegate: ctx.accounts.session.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::approve(cpi_ctx, spend_cap)?;
+
+    if lamports > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.session_signer.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, lamports)?;
+    }
+    Ok(())
+}
+
+pub fn end(ctx: Context<RevokeSession>) -> Result<()> {
+    // Leave the delegation of a newer session in place
+    if ctx.accounts.owner_mutr_account.delegate == Some(ctx.accounts.session.key()).into() {
+        let cpi_accounts = Revoke {
+            source: ctx.accounts.owner_mutr_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::revoke(cpi_ctx)?;
+    }
+    Ok(())
+}
+
+/// Check that a bet of `amount` is signed by the player or, when a session is passed,
+/// by its unexpired key within its cap, and count the wager against the cap.
+pub(crate) fn authorize_bet(
+    player: &AccountInfo,
+    payer: &Signer,
+    session: &mut Option<Account<'_, Session>>,
+    amount: u64,
+) -> Result<()> {
+    let Some(session) = session else {
+        require!(player.is_signer, MutrError::Unauthorized);
+        return Ok(());
+    };
+    require_keys_eq!(session.owner, player.key(), MutrError::Unauthorized);
+    require_keys_eq!(session.session_key, payer.key(), MutrError::Unauthorized);
+    require!(
+        Clock::get()?.unix_timestamp < session.expires_at,
+        MutrError::SessionExpired
+    );
+    session.spent = session
+        .spent
+        .checked_add(amount)
+        .ok_or(MutrError::MathOverflow)?;
+    require!(
+        session.spent <= session.spend_cap,
+        MutrError::SessionCapExceeded
+    );
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Session {
+    pub owner: Pubkey,
+    /// Ephemeral key allowed to bet for the owner
+    pub session_key: Pubkey,
+    /// Unix timestamp the session stops working at
+    pub expires_at: i64,
+    /// Most MUTR the session can wager
+    pub spend_cap: u64,
+    pub spent: u64,
+    pub bump: u8,
+}
+
+impl Session {
+    pub const LEN: usize = 32 // owner
+        + 32 // session_key
+        + 8  // expires_at
+        + 8  // spend_cap
+        + 8  // spent
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Session::LEN,
+        seeds = [b"session", owner.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, Session>,
+
+    /// CHECK: the session key, receiving the lamports sent along
+    #[account(mut, address = session_key @ MutrError::InvalidSession)]
+    pub session_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = owner_mutr_account.mint == state.load()?.mutr_mint @ MutrError::InvalidMint,
+        constraint = owner_mutr_account.owner == owner.key() @ MutrError::Unauthorized
+    )]
+    pub owner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        seeds = [b"session", owner.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        has_one = owner @ MutrError::Unauthorized,
+        close = owner
+    )]
+    pub session: Account<'info, Session>,
+
+    #[account(
+        mut,
+        constraint = owner_mutr_account.owner == owner.key() @ MutrError::Unauthorized
+    )]
+    pub owner_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
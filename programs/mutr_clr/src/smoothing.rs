@@ -0,0 +1,48 @@
+//! Profit smoothing across epochs.
+//!
+//! With profit epochs on, a single whale loss or win can make one epoch's dividend many
+//! times the next. Setting `smoothing_epochs` to K (2 to `MAX_SMOOTHING_EPOCHS`) makes
+//! each rollover distribute the average profit of the last K epochs instead: the epoch's
+//! profit is pushed into `smoothing_ring`, newest first, and parked in
+//! `smoothing_reserve`, and the rollover releases the ring's K-epoch average out of the
+//! reserve. Each epoch's profit thus reaches the dividend pool in K equal parts over K
+//! rollovers. The release never exceeds the reserve, and once the last K epochs booked
+//! nothing the rounding dust left in it goes out whole. 0 or 1 turns smoothing off; the
+//! next rollover then releases the whole reserve.
+
+use anchor_lang::prelude::*;
+
+use crate::*;
+
+/// Most epochs the profit can be averaged over
+pub const MAX_SMOOTHING_EPOCHS: usize = 4;
+
+/// Take the epoch's buffered profit into the ring and return the profit the rollover
+/// distributes.
+pub(crate) fn smooth(state: &mut GlobalState) -> Result<u64> {
+    let profit = std::mem::take(&mut state.profit_buffer);
+    state.smoothing_reserve = state
+        .smoothing_reserve
+        .checked_add(profit)
+        .ok_or(MutrError::MathOverflow)?;
+
+    let window = state.smoothing_epochs as usize;
+    if window <= 1 {
+        state.smoothing_ring = [0; MAX_SMOOTHING_EPOCHS];
+        return Ok(std::mem::take(&mut state.smoothing_reserve));
+    }
+    state.smoothing_ring.rotate_right(1);
+    state.smoothing_ring[0] = profit;
+
+    let recent: u128 = state.smoothing_ring[..window]
+        .iter()
+        .map(|&profit| profit as u128)
+        .sum();
+    let share = if recent == 0 {
+        state.smoothing_reserve
+    } else {
+        ((recent / window as u128) as u64).min(state.smoothing_reserve)
+    };
+    state.smoothing_reserve -= share;
+    Ok(share)
+}
@@ -0,0 +1,90 @@
+//! Holder snapshots.
+//!
+//! Off-chain votes and airdrops need the xMUTR supply and dividend pool at an agreed
+//! point in time. `take_snapshot` writes them, with the slot and share price, into a
+//! `Snapshot` PDA at `[b"snapshot", creator, id]`: the program fills in every field, so
+//! anyone reading the account can trust it without replaying history, and keying it by
+//! creator keeps others from squatting an id a governance tool announced. Snapshots are
+//! never updated; a new one needs a new id.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::*;
+
+pub fn take(ctx: Context<TakeSnapshot>, _id: u64) -> Result<()> {
+    let state = ctx.accounts.state.load()?;
+    let xmutr_supply = ctx.accounts.xmutr_mint.supply;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.creator = ctx.accounts.creator.key();
+    snapshot.slot = Clock::get()?.slot;
+    snapshot.xmutr_supply = xmutr_supply;
+    snapshot.total_dividend_shares = state.total_dividend_shares;
+    snapshot.share_price = stats::share_price(ctx.accounts.clr_vault.amount, xmutr_supply)?;
+    snapshot.bump = ctx.bumps.snapshot;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct Snapshot {
+    pub creator: Pubkey,
+    pub slot: u64,
+    pub xmutr_supply: u64,
+    /// Boost-weighted, like `GlobalState::total_dividend_shares`
+    pub total_dividend_shares: u128,
+    /// MUTR per xMUTR, scaled by `REWARD_PRECISION`
+    pub share_price: u128,
+    pub bump: u8,
+}
+
+impl Snapshot {
+    pub const LEN: usize = 32 // creator
+        + 8  // slot
+        + 8  // xmutr_supply
+        + 16 // total_dividend_shares
+        + 16 // share_price
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct TakeSnapshot<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = clr_vault.key() == state.load()?.clr_vault @ MutrError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Snapshot::LEN,
+        seeds = [b"snapshot", creator.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,266 @@
+//! Protocol-wide statistics.
+//!
+//! The `Stats` PDA keeps running totals so dashboards read one account instead of
+//! replaying history. The authority creates it once with `initialize_stats`; after that
+//! the instructions that move a tracked figure update it: staking and unstaking (fees,
+//! staked MUTR, share price, stakers), dividend-pool exits (fees), profit booking and
+//! every prize path. `total_staked` is the CLR vault balance, the MUTR backing xMUTR,
+//! as of the last update; `share_price` is refreshed whenever xMUTR is minted or burned
+//! or the exit fee moves the vault.
+//!
+//! Booked profit is also bucketed by day for the last `APR_WINDOW_DAYS` days, each day
+//! with its profit and that profit per dividend share at booking. `apr_7d_bps` and
+//! `apr_30d_bps` annualize the per-share profit of the trailing window against the
+//! share price, so they are the yield of one xMUTR in the dividend pool before any
+//! boost. Both are refreshed whenever profit is booked or the share price moves.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::*;
+
+/// Days of profit kept for the APR figures
+pub const APR_WINDOW_DAYS: usize = 30;
+
+/// `unique_stakers` seeds the count with wallets that staked before the account existed.
+pub fn open_stats(ctx: Context<InitializeStats>, unique_stakers: u64) -> Result<()> {
+    let stats = &mut ctx.accounts.stats.load_init()?;
+    stats.unique_stakers = unique_stakers;
+    stats.record_share_price(ctx.accounts.clr_vault.amount, ctx.accounts.xmutr_mint.supply)?;
+    stats.bump = ctx.bumps.stats;
+    Ok(())
+}
+
+/// Book stake, unstake or exit fees and refresh the staked MUTR and share price once the
+/// instruction's transfers, mints and burns are done.
+pub(crate) fn record_share_change<'info>(
+    stats: &AccountLoader<'info, Stats>,
+    clr_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    xmutr_mint: &mut InterfaceAccount<'info, Mint>,
+    fees: u64,
+) -> Result<()> {
+    clr_vault.reload()?;
+    xmutr_mint.reload()?;
+    let stats = &mut stats.load_mut()?;
+    stats.add_fees(fees)?;
+    stats.record_share_price(clr_vault.amount, xmutr_mint.supply)
+}
+
+/// Book a prize paid from the CLR vault and refresh the staked MUTR.
+pub(crate) fn record_prize<'info>(
+    stats: &AccountLoader<'info, Stats>,
+    clr_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+) -> Result<()> {
+    clr_vault.reload()?;
+    let stats = &mut stats.load_mut()?;
+    stats.add_prizes(amount)?;
+    stats.record_vault(clr_vault.amount)
+}
+
+/// MUTR per xMUTR, scaled by `REWARD_PRECISION`; 0 while no xMUTR exists.
+pub(crate) fn share_price(vault_balance: u64, xmutr_supply: u64) -> Result<u128> {
+    if xmutr_supply == 0 {
+        return Ok(0);
+    }
+    Ok((vault_balance as u128)
+        .checked_mul(REWARD_PRECISION)
+        .ok_or(MutrError::MathOverflow)?
+        / xmutr_supply as u128)
+}
+
+fn today() -> Result<u64> {
+    Ok((Clock::get()?.unix_timestamp / SECONDS_PER_DAY).max(0) as u64)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// Running protocol totals. Zero-copy, like `GlobalState`, since most instructions touch it.
+#[account(zero_copy)]
+pub struct Stats {
+    /// MUTR per xMUTR, scaled by `REWARD_PRECISION`; 0 while no xMUTR exists
+    pub share_price: u128,
+    /// MUTR in the CLR vault
+    pub total_staked: u64,
+    /// Stake, unstake and dividend-exit fees, before the `fee_split`
+    pub lifetime_fees: u64,
+    /// Profit booked for stakers
+    pub lifetime_profit_distributed: u64,
+    /// Prizes, bet payouts and jackpots paid to players
+    pub lifetime_prizes_paid: u64,
+    /// Wallets that opened a staking position
+    pub unique_stakers: u64,
+    pub last_update_slot: u64,
+    /// Booked profit by day, indexed by day number modulo `APR_WINDOW_DAYS`
+    pub profit_days: [ProfitDay; APR_WINDOW_DAYS],
+    /// Trailing 7-day yield of dividend-pool xMUTR, annualized, in basis points
+    pub apr_7d_bps: u64,
+    /// Trailing 30-day yield of dividend-pool xMUTR, annualized, in basis points
+    pub apr_30d_bps: u64,
+    pub bump: u8,
+    pub _padding: [u8; 15],
+}
+
+#[zero_copy]
+#[derive(Debug)]
+pub struct ProfitDay {
+    /// `profit` per dividend share at booking, scaled by `REWARD_PRECISION`
+    pub reward_per_share: u128,
+    pub profit: u64,
+    /// Unix day number the bucket holds; an older day is stale
+    pub day: u64,
+}
+
+impl Stats {
+    pub const LEN: usize = std::mem::size_of::<Stats>();
+
+    pub fn record_vault(&mut self, vault_balance: u64) -> Result<()> {
+        self.total_staked = vault_balance;
+        self.last_update_slot = Clock::get()?.slot;
+        Ok(())
+    }
+
+    pub fn record_share_price(&mut self, vault_balance: u64, xmutr_supply: u64) -> Result<()> {
+        self.share_price = share_price(vault_balance, xmutr_supply)?;
+        self.refresh_apr(today()?)?;
+        self.record_vault(vault_balance)
+    }
+
+    pub fn add_fees(&mut self, fees: u64) -> Result<()> {
+        self.lifetime_fees = self
+            .lifetime_fees
+            .checked_add(fees)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Book profit for the current `dividend_shares`. Profit booked while the pool is
+    /// empty counts toward the lifetime total but not the APR.
+    pub fn add_profit(&mut self, profit: u64, dividend_shares: u128) -> Result<()> {
+        self.lifetime_profit_distributed = self
+            .lifetime_profit_distributed
+            .checked_add(profit)
+            .ok_or(MutrError::MathOverflow)?;
+
+        let day = today()?;
+        let bucket = &mut self.profit_days[day as usize % APR_WINDOW_DAYS];
+        if bucket.day != day {
+            *bucket = ProfitDay {
+                reward_per_share: 0,
+                profit: 0,
+                day,
+            };
+        }
+        bucket.profit = bucket
+            .profit
+            .checked_add(profit)
+            .ok_or(MutrError::MathOverflow)?;
+        // Nothing per share while the pool is empty
+        if let Some(reward_per_share) = (profit as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(MutrError::MathOverflow)?
+            .checked_div(dividend_shares)
+        {
+            bucket.reward_per_share = bucket
+                .reward_per_share
+                .checked_add(reward_per_share)
+                .ok_or(MutrError::MathOverflow)?;
+        }
+        self.refresh_apr(day)?;
+        self.last_update_slot = Clock::get()?.slot;
+        Ok(())
+    }
+
+    /// Recompute the APR figures for the windows ending on `day`.
+    pub fn refresh_apr(&mut self, day: u64) -> Result<()> {
+        self.apr_7d_bps = self.apr_bps(day, 7)?;
+        self.apr_30d_bps = self.apr_bps(day, APR_WINDOW_DAYS)?;
+        Ok(())
+    }
+
+    /// Per-share profit of the `days` days up to `day`, annualized against the share
+    /// price; 0 while no xMUTR exists.
+    fn apr_bps(&self, day: u64, days: usize) -> Result<u64> {
+        if self.share_price == 0 {
+            return Ok(0);
+        }
+        let reward_per_share = self
+            .window(day, days)
+            .try_fold(0u128, |sum, bucket| {
+                sum.checked_add(bucket.reward_per_share)
+            })
+            .ok_or(MutrError::MathOverflow)?;
+        let apr = reward_per_share
+            .checked_mul(365 * BPS_DENOMINATOR as u128)
+            .ok_or(MutrError::MathOverflow)?
+            / (days as u128 * self.share_price);
+        Ok(u64::try_from(apr).unwrap_or(u64::MAX))
+    }
+
+    fn window(&self, day: u64, days: usize) -> impl Iterator<Item = &ProfitDay> {
+        let first = day.saturating_sub(days as u64 - 1);
+        self.profit_days
+            .iter()
+            .filter(move |bucket| bucket.day >= first && bucket.day <= day)
+    }
+
+    pub fn add_prizes(&mut self, amount: u64) -> Result<()> {
+        self.lifetime_prizes_paid = self
+            .lifetime_prizes_paid
+            .checked_add(amount)
+            .ok_or(MutrError::MathOverflow)?;
+        self.last_update_slot = Clock::get()?.slot;
+        Ok(())
+    }
+
+    pub fn add_staker(&mut self) -> Result<()> {
+        self.unique_stakers = self
+            .unique_stakers
+            .checked_add(1)
+            .ok_or(MutrError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeStats<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Stats::LEN,
+        seeds = [b"stats"],
+        bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
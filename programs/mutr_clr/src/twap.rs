@@ -0,0 +1,178 @@
+//! Time-weighted share price.
+//!
+//! The `SharePriceOracle` PDA keeps the last `TWAP_OBSERVATIONS` share prices in a ring
+//! buffer, each with the slot it took effect and the running sum of price times slots up
+//! to that slot. `stake`, `stake_on_behalf`, `unstake` and `harvest_emissions` record the
+//! new price when the oracle is passed; `get_twap` averages it over a window of slots.
+//! A price only counts for the slots it held, so moving the vault within one slot and
+//! back again leaves the average untouched.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::*;
+
+/// Observations kept by the oracle
+pub const TWAP_OBSERVATIONS: usize = 64;
+
+pub fn create_oracle(ctx: Context<InitializeSharePriceOracle>) -> Result<()> {
+    let oracle = &mut ctx.accounts.share_price_oracle.load_init()?;
+    let price = share_price(
+        ctx.accounts.clr_vault.amount,
+        ctx.accounts.xmutr_mint.supply,
+    )?;
+    oracle.record(Clock::get()?.slot, price)?;
+    oracle.bump = ctx.bumps.share_price_oracle;
+    Ok(())
+}
+
+/// Record the share price once the instruction's mints, burns and vault moves are done,
+/// when the oracle is passed.
+pub(crate) fn observe<'info>(
+    oracle: &Option<AccountLoader<'info, SharePriceOracle>>,
+    vault_balance: u64,
+    xmutr_supply: u64,
+) -> Result<()> {
+    let Some(oracle) = oracle else {
+        return Ok(());
+    };
+    let price = share_price(vault_balance, xmutr_supply)?;
+    oracle.load_mut()?.record(Clock::get()?.slot, price)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[zero_copy]
+#[derive(Debug)]
+pub struct PriceObservation {
+    /// Sum of price times slots held, from the first observation up to `slot`
+    pub cumulative_price: u128,
+    /// MUTR per xMUTR from `slot` on, scaled by `REWARD_PRECISION`, as in `Stats`
+    pub price_per_share: u128,
+    pub slot: u64,
+    pub _padding: [u8; 8],
+}
+
+impl PriceObservation {
+    /// The running sum carried forward to a later `slot`.
+    pub fn cumulative_at(&self, slot: u64) -> Result<u128> {
+        let held = slot.checked_sub(self.slot).ok_or(MutrError::MathOverflow)?;
+        self.price_per_share
+            .checked_mul(held as u128)
+            .and_then(|v| v.checked_add(self.cumulative_price))
+            .ok_or(MutrError::MathOverflow.into())
+    }
+}
+
+/// Ring buffer of share price observations. Zero-copy so the array is not deserialized
+/// on every stake.
+#[account(zero_copy)]
+pub struct SharePriceOracle {
+    pub observations: [PriceObservation; TWAP_OBSERVATIONS],
+    /// Index of the latest observation
+    pub head: u16,
+    /// Number of filled observations
+    pub len: u16,
+    pub bump: u8,
+    pub _padding: [u8; 11],
+}
+
+impl SharePriceOracle {
+    pub const LEN: usize = std::mem::size_of::<SharePriceOracle>();
+
+    pub fn latest(&self) -> &PriceObservation {
+        &self.observations[self.head as usize]
+    }
+
+    pub fn oldest(&self) -> &PriceObservation {
+        let index =
+            (self.head as usize + TWAP_OBSERVATIONS + 1 - self.len as usize) % TWAP_OBSERVATIONS;
+        &self.observations[index]
+    }
+
+    /// Record the price taking effect at `slot`. A second price in the same slot replaces
+    /// the first, which held for no slot.
+    pub fn record(&mut self, slot: u64, price: u128) -> Result<()> {
+        if self.len == 0 {
+            self.observations[0] = PriceObservation {
+                cumulative_price: 0,
+                price_per_share: price,
+                slot,
+                _padding: [0; 8],
+            };
+            self.len = 1;
+            return Ok(());
+        }
+        let latest = *self.latest();
+        if slot == latest.slot {
+            self.observations[self.head as usize].price_per_share = price;
+            return Ok(());
+        }
+        let cumulative_price = latest.cumulative_at(slot)?;
+        self.head = ((self.head as usize + 1) % TWAP_OBSERVATIONS) as u16;
+        self.observations[self.head as usize] = PriceObservation {
+            cumulative_price,
+            price_per_share: price,
+            slot,
+            _padding: [0; 8],
+        };
+        self.len = (self.len + 1).min(TWAP_OBSERVATIONS as u16);
+        Ok(())
+    }
+
+    /// The running sum at `slot`, from the last observation at or before it; `None` when
+    /// `slot` is older than every observation kept.
+    pub fn cumulative_at(&self, slot: u64) -> Result<Option<u128>> {
+        for age in 0..self.len as usize {
+            let index = (self.head as usize + TWAP_OBSERVATIONS - age) % TWAP_OBSERVATIONS;
+            let observation = &self.observations[index];
+            if observation.slot <= slot {
+                return observation.cumulative_at(slot).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeSharePriceOracle<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SharePriceOracle::LEN,
+        seeds = [b"share_price_oracle"],
+        bump
+    )]
+    pub share_price_oracle: AccountLoader<'info, SharePriceOracle>,
+
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
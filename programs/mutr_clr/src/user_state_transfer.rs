@@ -0,0 +1,253 @@
+//! Position transfers between wallets.
+//!
+//! A `UserState` lives at an address derived from its owner, so a user moving to a new
+//! wallet used to have to unstake, paying the fees, and stake again. Instead the owner
+//! proposes the new owner with `propose_user_state_transfer`: the position's xMUTR held
+//! in the wallet moves into the transfer escrow and the position is frozen. The new owner
+//! then calls `accept_user_state_transfer`, which releases the xMUTR to them and merges
+//! the shares, lock and pending rewards into one of their positions as redeeming a
+//! receipt does; xMUTR in the dividend vault stays there. Until then the old owner can
+//! take the proposal back with `cancel_user_state_transfer`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::*;
+
+pub fn propose_transfer(ctx: Context<ProposeUserStateTransfer>, new_owner: Pubkey) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.check_movable()?;
+    require!(
+        new_owner != Pubkey::default() && new_owner != ctx.accounts.user.key(),
+        MutrError::InvalidNewOwner
+    );
+    user_state.pending_owner = new_owner;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_xmutr_account.to_account_info(),
+        mint: ctx.accounts.xmutr_mint.to_account_info(),
+        to: ctx.accounts.transfer_escrow.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(
+        cpi_ctx,
+        wallet_shares(user_state)?,
+        ctx.accounts.xmutr_mint.decimals,
+    )
+}
+
+pub fn cancel_transfer(ctx: Context<CancelUserStateTransfer>) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    require_keys_neq!(
+        user_state.pending_owner,
+        Pubkey::default(),
+        MutrError::NoPendingTransfer
+    );
+    user_state.pending_owner = Pubkey::default();
+
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.transfer_escrow.to_account_info(),
+        ctx.accounts.user_xmutr_account.to_account_info(),
+        &ctx.accounts.xmutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        wallet_shares(user_state)?,
+    )
+}
+
+pub fn accept_transfer(ctx: Context<AcceptUserStateTransfer>, position_index: u32) -> Result<()> {
+    let source = &mut ctx.accounts.source_user_state;
+    vault_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.transfer_escrow.to_account_info(),
+        ctx.accounts.new_owner_xmutr_account.to_account_info(),
+        &ctx.accounts.xmutr_mint,
+        ctx.accounts.state.to_account_info(),
+        ctx.accounts.state.load()?.bump,
+        wallet_shares(source)?,
+    )?;
+
+    let mut state = ctx.accounts.state.load_mut()?;
+    let holder_state = &mut ctx.accounts.new_owner_user_state;
+    open_position_if_new(
+        &mut ctx.accounts.new_owner_positions,
+        holder_state,
+        ctx.accounts.new_owner.key(),
+        position_index,
+    )?;
+    holder_state.check_movable()?;
+
+    merge_position(&mut state, source, holder_state)?;
+    source.pending_owner = Pubkey::default();
+    Ok(())
+}
+
+/// xMUTR of the position held outside the dividend vault.
+fn wallet_shares(user_state: &UserState) -> Result<u64> {
+    Ok(user_state
+        .staked_shares
+        .checked_add(user_state.dividend_shares)
+        .ok_or(MutrError::MathOverflow)?
+        - user_state.escrowed_shares)
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct ProposeUserStateTransfer<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Holds the xMUTR of every position awaiting its new owner
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"transfer_escrow"],
+        bump,
+        token::mint = xmutr_mint,
+        token::authority = state
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32)]
+pub struct CancelUserStateTransfer<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = user_xmutr_account.owner == user.key() @ MutrError::Unauthorized
+    )]
+    pub user_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_escrow"],
+        bump
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_index: u32, source_position_index: u32)]
+pub struct AcceptUserStateTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        constraint = xmutr_mint.key() == state.load()?.xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_escrow"],
+        bump
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"user_state",
+            old_owner.key().as_ref(),
+            &source_position_index.to_le_bytes()
+        ],
+        bump,
+        constraint = source_user_state.pending_owner == new_owner.key() @ MutrError::NoPendingTransfer
+    )]
+    pub source_user_state: Account<'info, UserState>,
+
+    /// CHECK: owner of the position being handed over, fixing its address
+    pub old_owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = new_owner_xmutr_account.mint == state.load()?.xmutr_mint @ MutrError::InvalidMint,
+        constraint = new_owner_xmutr_account.owner == new_owner.key() @ MutrError::Unauthorized
+    )]
+    pub new_owner_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = new_owner,
+        space = 8 + UserState::LEN,
+        seeds = [b"user_state", new_owner.key().as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub new_owner_user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = new_owner,
+        space = 8 + UserPositions::LEN,
+        seeds = [b"user_positions", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_owner_positions: Account<'info, UserPositions>,
+
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,359 @@
+//! Read-only view instructions.
+//!
+//! Each view computes a figure with the same math the state-changing instructions use
+//! and returns it as the instruction's return data (`set_return_data`, through
+//! anchor's typed return values), so clients `simulateTransaction` a view and decode
+//! the result instead of re-implementing the reward math off-chain. Views touch no
+//! writable account and need no signer beyond the fee payer.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{get_mint_extension_data, Mint, TokenAccount};
+
+use crate::*;
+
+pub fn exchange_rate(ctx: Context<GetExchangeRate>) -> Result<ExchangeRate> {
+    let clr_vault = ctx.accounts.clr_vault.amount;
+    let xmutr_supply = ctx.accounts.xmutr_mint.supply;
+    let mutr_per_xmutr = if xmutr_supply == 0 {
+        REWARD_PRECISION
+    } else {
+        (clr_vault as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(MutrError::MathOverflow)?
+            / xmutr_supply as u128
+    };
+    Ok(ExchangeRate {
+        clr_vault,
+        xmutr_supply,
+        mutr_per_xmutr,
+    })
+}
+
+pub fn pending(ctx: Context<GetPendingRewards>) -> Result<PendingRewards> {
+    let state = ctx.accounts.state.load()?;
+    let user_state = &ctx.accounts.user_state;
+    Ok(PendingRewards {
+        mutr: pending_rewards(&state, user_state)?,
+        bonus: pending_bonus_rewards(&state, user_state)?,
+    })
+}
+
+pub fn pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+    let state = ctx.accounts.state.load()?;
+    let stats = ctx.accounts.stats.load()?;
+    Ok(PoolStats {
+        total_dividend_shares: state.total_dividend_shares,
+        acc_reward_per_share: state.acc_reward_per_share,
+        share_price: stats.share_price,
+        total_staked: stats.total_staked,
+        profit_buffer: state.profit_buffer,
+        smoothing_reserve: state.smoothing_reserve,
+        drip_remaining: state.drip_remaining,
+        drip_end_slot: state.drip_end_slot,
+        lifetime_fees: stats.lifetime_fees,
+        lifetime_profit_distributed: stats.lifetime_profit_distributed,
+        lifetime_prizes_paid: stats.lifetime_prizes_paid,
+        unique_stakers: stats.unique_stakers,
+        apr_7d_bps: stats.apr_7d_bps,
+        apr_30d_bps: stats.apr_30d_bps,
+    })
+}
+
+pub fn quote_stake(ctx: Context<PreviewStake>, amount: u64) -> Result<StakeQuote> {
+    require!(amount > 0, MutrError::InvalidAmount);
+    let state = ctx.accounts.state.load()?;
+
+    // A transfer-fee mint delivers less than `amount` to the vault
+    let mint_info = ctx.accounts.mutr_mint.to_account_info();
+    let transfer_fee = match get_mint_extension_data::<TransferFeeConfig>(&mint_info) {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(MutrError::MathOverflow)?,
+        Err(_) => 0,
+    };
+    let received = amount - transfer_fee;
+
+    let nft_fee_discount_bps = ctx
+        .accounts
+        .user_state
+        .as_ref()
+        .map_or(0, |user_state| user_state.nft_fee_discount_bps);
+    let stake_fee_bps = vip::discounted_bps(
+        ctx.accounts.vip_config.as_deref(),
+        ctx.accounts.user_stats.as_deref(),
+        state.stake_fee_bps,
+    )
+    .saturating_sub(nft_fee_discount_bps);
+    let mut net_amount = apply_fee(received, stake_fee_bps)?;
+    net_amount += waivable(ctx.accounts.user_stats.as_deref(), received - net_amount);
+
+    let shares = shares_for_deposit(
+        net_amount,
+        ctx.accounts.xmutr_mint.supply,
+        state.backing(ctx.accounts.clr_vault.amount),
+    )?;
+    Ok(StakeQuote {
+        transfer_fee,
+        fee: received - net_amount,
+        shares,
+    })
+}
+
+pub fn quote_unstake(ctx: Context<PreviewUnstake>, shares: u64) -> Result<UnstakeQuote> {
+    require!(shares > 0, MutrError::InvalidAmount);
+    let state = ctx.accounts.state.load()?;
+
+    let gross = amount_for_shares(
+        shares,
+        state.backing(ctx.accounts.clr_vault.amount),
+        ctx.accounts.xmutr_mint.supply,
+    )?;
+
+    let unstake_fee_bps = vip::discounted_bps(
+        ctx.accounts.vip_config.as_deref(),
+        ctx.accounts.user_stats.as_deref(),
+        state.unstake_fee_bps,
+    );
+    let mut net_amount = apply_fee(gross, unstake_fee_bps)?;
+    net_amount += waivable(ctx.accounts.user_stats.as_deref(), gross - net_amount);
+
+    Ok(UnstakeQuote {
+        gross,
+        fee: gross - net_amount,
+        mutr: net_amount,
+    })
+}
+
+pub fn time_weighted_price(ctx: Context<GetTwap>, window_slots: u64) -> Result<Twap> {
+    require!(window_slots > 0, MutrError::InvalidAmount);
+    let oracle = ctx.accounts.share_price_oracle.load()?;
+    let slot = Clock::get()?.slot;
+    let spot = oracle.latest().price_per_share;
+
+    // A window reaching past the oldest observation is cut short
+    let start = slot.saturating_sub(window_slots).max(oracle.oldest().slot);
+    if start >= slot {
+        return Ok(Twap {
+            price_per_share: spot,
+            window_slots: 0,
+            spot,
+        });
+    }
+    let cumulative_start = oracle
+        .cumulative_at(start)?
+        .ok_or(MutrError::MathOverflow)?;
+    let cumulative_now = oracle.latest().cumulative_at(slot)?;
+    Ok(Twap {
+        price_per_share: (cumulative_now - cumulative_start) / (slot - start) as u128,
+        window_slots: slot - start,
+        spot,
+    })
+}
+
+/// The part of `fee` the player's loyalty fee credit would cover, as `loyalty::waive_fee`
+/// would waive it, without spending the credit.
+fn waivable(user_stats: Option<&UserStats>, fee: u64) -> u64 {
+    user_stats.map_or(0, |user_stats| fee.min(user_stats.fee_credit))
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExchangeRate {
+    pub clr_vault: u64,
+    pub xmutr_supply: u64,
+    /// MUTR per xMUTR, scaled by `REWARD_PRECISION`; 1:1 while no xMUTR exists
+    pub mutr_per_xmutr: u128,
+}
+
+/// Rewards a position could claim right now.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingRewards {
+    pub mutr: u64,
+    /// Bonus-token rewards (see `initialize_bonus_rewards`)
+    pub bonus: u64,
+}
+
+/// Dividend pool accounting alongside the protocol totals of `Stats`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolStats {
+    pub total_dividend_shares: u128,
+    pub acc_reward_per_share: u128,
+    pub share_price: u128,
+    pub total_staked: u64,
+    /// Profit waiting for the epoch to roll over
+    pub profit_buffer: u64,
+    /// Profit of past epochs held back by smoothing (see `smoothing`)
+    pub smoothing_reserve: u64,
+    /// Profit still dripping into reward per share, until `drip_end_slot`
+    pub drip_remaining: u64,
+    pub drip_end_slot: u64,
+    pub lifetime_fees: u64,
+    pub lifetime_profit_distributed: u64,
+    pub lifetime_prizes_paid: u64,
+    pub unique_stakers: u64,
+    pub apr_7d_bps: u64,
+    pub apr_30d_bps: u64,
+}
+
+/// What `stake` would mint for a deposit right now.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakeQuote {
+    /// Withheld by a Token-2022 transfer fee before the deposit reaches the vault
+    pub transfer_fee: u64,
+    /// Stake fee on the amount received, after VIP, NFT and loyalty discounts
+    pub fee: u64,
+    /// xMUTR minted; zero means `stake` would fail with `ZeroShares`
+    pub shares: u64,
+}
+
+/// What `unstake` would pay out for burning xMUTR right now.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnstakeQuote {
+    /// MUTR the shares are worth before the fee
+    pub gross: u64,
+    /// Unstake fee, after VIP and loyalty discounts
+    pub fee: u64,
+    /// MUTR sent to the player
+    pub mutr: u64,
+}
+
+/// Share price averaged over a window of slots.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Twap {
+    /// MUTR per xMUTR, scaled by `REWARD_PRECISION`, as in `Stats`
+    pub price_per_share: u128,
+    /// Slots actually averaged; shorter than asked when the oracle's history is, and 0
+    /// while its only observations are from this slot
+    pub window_slots: u64,
+    /// Latest recorded price
+    pub spot: u128,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct GetExchangeRate<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey, position_index: u32)]
+pub struct GetPendingRewards<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"user_state", owner.as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"stats"],
+        bump = stats.load()?.bump
+    )]
+    pub stats: AccountLoader<'info, Stats>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, owner: Pubkey, position_index: u32)]
+pub struct PreviewStake<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint,
+        has_one = mutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// Omit for a position that does not exist yet
+    #[account(
+        seeds = [b"user_state", owner.as_ref(), &position_index.to_le_bytes()],
+        bump
+    )]
+    pub user_state: Option<Account<'info, UserState>>,
+
+    #[account(
+        seeds = [b"user_stats", owner.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    #[account(
+        seeds = [b"vip_config"],
+        bump = vip_config.bump
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(shares: u64, owner: Pubkey)]
+pub struct PreviewUnstake<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = clr_vault @ MutrError::InvalidVault,
+        has_one = xmutr_mint @ MutrError::InvalidMint
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"user_stats", owner.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    #[account(
+        seeds = [b"vip_config"],
+        bump = vip_config.bump
+    )]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    #[account(
+        seeds = [b"share_price_oracle"],
+        bump = share_price_oracle.load()?.bump
+    )]
+    pub share_price_oracle: AccountLoader<'info, SharePriceOracle>,
+}
@@ -0,0 +1,167 @@
+//! VIP tiers with discounted fees.
+//!
+//! The authority sets up to `VIP_TIERS` tiers in the `VipConfig` with `set_vip_config`,
+//! lowest first. A player holds the highest tier whose staked or lifetime wagered
+//! threshold their `UserStats` meets (see `loyalty`): `wagered` grows as their bets are
+//! resolved with the stats passed, and `staked`, the MUTR value of their xMUTR, is
+//! refreshed by every `stake`, `stake_on_behalf` and `unstake` made with the stats
+//! passed.
+//!
+//! A tier takes `fee_discount_bps` off the fee rates that apply to its holder: the stake
+//! and unstake fees when the VIP config and the stats are passed to those instructions,
+//! and the house edge of games that accept them (coinflip, dice and limbo), through
+//! `discounted_bps`. The tier in effect is the one held before the instruction runs.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::*;
+
+/// Most tiers a `VipConfig` holds
+pub const VIP_TIERS: usize = 4;
+
+pub fn set_tiers(ctx: Context<SetVipConfig>, tiers: Vec<VipTier>) -> Result<()> {
+    require!(tiers.len() <= VIP_TIERS, MutrError::InvalidConfig);
+    for (i, tier) in tiers.iter().enumerate() {
+        require!(
+            tier.fee_discount_bps as u64 <= BPS_DENOMINATOR,
+            MutrError::InvalidConfig
+        );
+        if i > 0 {
+            let lower = &tiers[i - 1];
+            require!(
+                tier.min_staked >= lower.min_staked
+                    && tier.min_wagered >= lower.min_wagered
+                    && tier.fee_discount_bps >= lower.fee_discount_bps,
+                MutrError::InvalidConfig
+            );
+        }
+    }
+
+    let config = &mut ctx.accounts.vip_config;
+    config.tiers = [VipTier::default(); VIP_TIERS];
+    config.tiers[..tiers.len()].copy_from_slice(&tiers);
+    config.tier_count = tiers.len() as u8;
+    config.bump = ctx.bumps.vip_config;
+    Ok(())
+}
+
+/// `bps` less the discount of the player's tier; `bps` itself when either account is
+/// missing or the player holds no tier.
+pub fn discounted_bps(
+    vip_config: Option<&VipConfig>,
+    user_stats: Option<&UserStats>,
+    bps: u16,
+) -> u16 {
+    let (Some(vip_config), Some(user_stats)) = (vip_config, user_stats) else {
+        return bps;
+    };
+    match vip_config.tier(user_stats) {
+        Some(tier) => {
+            let discount = bps as u64 * tier.fee_discount_bps as u64 / BPS_DENOMINATOR;
+            bps - discount as u16
+        }
+        None => bps,
+    }
+}
+
+/// Refresh the MUTR value of the player's xMUTR in their stats, when they are passed.
+/// Call once the instruction's mints, burns and vault moves are done.
+pub(crate) fn record_staked<'info>(
+    user_stats: &mut Option<Account<'info, UserStats>>,
+    xmutr_account: &mut InterfaceAccount<'info, TokenAccount>,
+    clr_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    xmutr_mint: &mut InterfaceAccount<'info, Mint>,
+) -> Result<()> {
+    let Some(user_stats) = user_stats else {
+        return Ok(());
+    };
+    xmutr_account.reload()?;
+    clr_vault.reload()?;
+    xmutr_mint.reload()?;
+    user_stats.staked = if xmutr_mint.supply == 0 {
+        0
+    } else {
+        (xmutr_account.amount as u128 * clr_vault.amount as u128 / xmutr_mint.supply as u128) as u64
+    };
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct VipTier {
+    /// MUTR value of staked xMUTR that qualifies
+    pub min_staked: u64,
+    /// Lifetime MUTR wagered that qualifies
+    pub min_wagered: u64,
+    /// Share taken off the stake and unstake fees and the house edge
+    pub fee_discount_bps: u16,
+}
+
+impl VipTier {
+    pub const LEN: usize = 8 // min_staked
+        + 8  // min_wagered
+        + 2; // fee_discount_bps
+}
+
+#[account]
+pub struct VipConfig {
+    /// Tiers, lowest first; only the first `tier_count` are in use
+    pub tiers: [VipTier; VIP_TIERS],
+    pub tier_count: u8,
+    pub bump: u8,
+}
+
+impl VipConfig {
+    pub const LEN: usize = VipTier::LEN * VIP_TIERS // tiers
+        + 1  // tier_count
+        + 1; // bump
+
+    /// Highest tier the player qualifies for, by stake or by wagers.
+    pub fn tier(&self, user_stats: &UserStats) -> Option<&VipTier> {
+        self.tiers[..self.tier_count as usize]
+            .iter()
+            .rev()
+            .find(|tier| {
+                user_stats.staked >= tier.min_staked || user_stats.wagered >= tier.min_wagered
+            })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SetVipConfig<'info> {
+    #[account(
+        seeds = [b"state"],
+        bump = state.load()?.bump,
+        has_one = authority @ MutrError::Unauthorized
+    )]
+    pub state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VipConfig::LEN,
+        seeds = [b"vip_config"],
+        bump
+    )]
+    pub vip_config: Account<'info, VipConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log"],
+        bump = admin_log.load()?.bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,644 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{Bet, GameConfig, GlobalState, UserStats, VipConfig};
+
+declare_id!("F1ipRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod mutr_coinflip {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR (provider `None`: coinflip settles bets from its own randomness).
+    pub fn initialize_coinflip(
+        ctx: Context<InitializeCoinflip>,
+        house_edge_bps: u16,
+        min_bet: u64,
+        max_bet: u64,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            (house_edge_bps as u64) < BPS_DENOMINATOR
+                && min_bet > 0
+                && min_bet <= max_bet
+                && randomness_provider != RandomnessProvider::None,
+            CoinflipError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.house_edge_bps = house_edge_bps;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: change the house edge and bet limits.
+    pub fn update_coinflip_config(
+        ctx: Context<UpdateCoinflipConfig>,
+        house_edge_bps: u16,
+        min_bet: u64,
+        max_bet: u64,
+    ) -> Result<()> {
+        require!(
+            (house_edge_bps as u64) < BPS_DENOMINATOR && min_bet > 0 && min_bet <= max_bet,
+            CoinflipError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.house_edge_bps = house_edge_bps;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        Ok(())
+    }
+
+    /// Wager `amount` on heads (or tails). The wager is escrowed as a CLR bet paying
+    /// 2x minus the house edge, discounted by the player's CLR VIP tier when its accounts
+    /// are passed. A fresh randomness request must accompany the flip, which the CLR
+    /// rejects once past `deadline_slot`, when given.
+    pub fn flip(
+        ctx: Context<Flip>,
+        nonce: u64,
+        amount: u64,
+        heads: bool,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            amount >= config.min_bet && amount <= config.max_bet,
+            CoinflipError::BetOutOfRange
+        );
+        let house_edge_bps = mutr_clr::vip::discounted_bps(
+            ctx.accounts.clr_vip_config.as_deref(),
+            ctx.accounts.clr_user_stats.as_deref(),
+            config.house_edge_bps,
+        );
+        let multiplier_bps = 2 * (BPS_DENOMINATOR - house_edge_bps as u64);
+
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let cpi_accounts = mutr_clr::cpi::accounts::PlaceBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            randomness_account: None,
+            jackpot: ctx
+                .accounts
+                .clr_jackpot
+                .as_ref()
+                .map(|jackpot| jackpot.to_account_info()),
+            jackpot_vault: ctx
+                .accounts
+                .clr_jackpot_vault
+                .as_ref()
+                .map(|jackpot_vault| jackpot_vault.to_account_info()),
+            usd_pricing: ctx
+                .accounts
+                .clr_usd_pricing
+                .as_ref()
+                .map(|usd_pricing| usd_pricing.to_account_info()),
+            price_update: ctx
+                .accounts
+                .clr_price_update
+                .as_ref()
+                .map(|price_update| price_update.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            referral_config: None,
+            referrer: None,
+            referral_vault: None,
+            player_state: ctx.accounts.clr_player_state.to_account_info(),
+            session: ctx
+                .accounts
+                .clr_session
+                .as_ref()
+                .map(|session| session.to_account_info()),
+            player: ctx.accounts.player.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.clr_program.to_account_info(), cpi_accounts);
+        mutr_clr::cpi::place_bet(cpi_ctx, nonce, amount, multiplier_bps as u32, deadline_slot)?;
+
+        let stats = &mut ctx.accounts.player_stats;
+        if stats.player == Pubkey::default() {
+            stats.player = ctx.accounts.player.key();
+            stats.bump = ctx.bumps.player_stats;
+        }
+        stats.total_wagered = stats
+            .total_wagered
+            .checked_add(amount)
+            .ok_or(CoinflipError::MathOverflow)?;
+
+        let coin_flip = &mut ctx.accounts.coin_flip;
+        coin_flip.player = ctx.accounts.player.key();
+        coin_flip.bet = ctx.accounts.bet.key();
+        coin_flip.heads = heads;
+        coin_flip.randomness_account = ctx.accounts.randomness_account.key();
+        coin_flip.placed_slot = slot;
+        coin_flip.bump = ctx.bumps.coin_flip;
+        Ok(())
+    }
+
+    /// Permissionless: read the revealed randomness, flip the coin, record the result
+    /// in the player's stats and settle the CLR bet. The flip account is closed back
+    /// to the player.
+    pub fn settle_flip(ctx: Context<SettleFlip>) -> Result<()> {
+        let coin_flip = &ctx.accounts.coin_flip;
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            coin_flip.placed_slot,
+        )?;
+        let landed_heads = value[0] & 1 == 0;
+        let won = landed_heads == coin_flip.heads;
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(FlipSettled {
+            event_nonce,
+            player: coin_flip.player,
+            bet: coin_flip.bet,
+            called_heads: coin_flip.heads,
+            landed_heads,
+            won,
+        });
+
+        let payout = if won {
+            let bet = ctx.accounts.bet.load()?;
+            mutr_clr::bet_payout(bet.amount, bet.multiplier_bps)?
+        } else {
+            0
+        };
+        ctx.accounts.player_stats.record(won, payout)?;
+
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[ctx.accounts.config.authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ResolveBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            stats: ctx.accounts.clr_stats.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            usd_pricing: None,
+            price_update: None,
+            leaderboard: ctx
+                .accounts
+                .clr_leaderboard
+                .as_ref()
+                .map(|leaderboard| leaderboard.to_account_info()),
+            season: ctx
+                .accounts
+                .clr_season
+                .as_ref()
+                .map(|season| season.to_account_info()),
+            season_player: ctx
+                .accounts
+                .clr_season_player
+                .as_ref()
+                .map(|season_player| season_player.to_account_info()),
+            season_leaderboard: ctx
+                .accounts
+                .clr_season_leaderboard
+                .as_ref()
+                .map(|season_leaderboard| season_leaderboard.to_account_info()),
+            loyalty_config: ctx
+                .accounts
+                .clr_loyalty_config
+                .as_ref()
+                .map(|loyalty_config| loyalty_config.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            rakeback_config: ctx
+                .accounts
+                .clr_rakeback_config
+                .as_ref()
+                .map(|rakeback_config| rakeback_config.to_account_info()),
+            game: ctx.accounts.game_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::resolve_bet(cpi_ctx, won)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct CoinflipConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl CoinflipConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 2  // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// An open flip, waiting for its randomness to be revealed.
+#[account]
+pub struct CoinFlip {
+    pub player: Pubkey,
+    /// CLR `Bet` escrowing the wager
+    pub bet: Pubkey,
+    pub heads: bool,
+    pub randomness_account: Pubkey,
+    pub placed_slot: u64,
+    pub bump: u8,
+}
+
+impl CoinFlip {
+    pub const LEN: usize = 32 // player
+        + 32 // bet
+        + 1  // heads
+        + 32 // randomness_account
+        + 8  // placed_slot
+        + 1; // bump
+}
+
+/// Lifetime coinflip record of a player.
+#[account]
+pub struct PlayerStats {
+    pub player: Pubkey,
+    pub flips: u64,
+    pub wins: u64,
+    pub total_wagered: u64,
+    pub total_won: u64,
+    /// Consecutive wins (positive) or losses (negative) up to the latest flip
+    pub current_streak: i32,
+    pub best_win_streak: u32,
+    pub worst_loss_streak: u32,
+    pub bump: u8,
+}
+
+impl PlayerStats {
+    pub const LEN: usize = 32 // player
+        + 8  // flips
+        + 8  // wins
+        + 8  // total_wagered
+        + 8  // total_won
+        + 4  // current_streak
+        + 4  // best_win_streak
+        + 4  // worst_loss_streak
+        + 1; // bump
+
+    fn record(&mut self, won: bool, payout: u64) -> Result<()> {
+        self.flips = self.flips.saturating_add(1);
+        if won {
+            self.wins = self.wins.saturating_add(1);
+            self.total_won = self
+                .total_won
+                .checked_add(payout)
+                .ok_or(CoinflipError::MathOverflow)?;
+            self.current_streak = self.current_streak.max(0).saturating_add(1);
+            self.best_win_streak = self.best_win_streak.max(self.current_streak as u32);
+        } else {
+            self.current_streak = self.current_streak.min(0).saturating_sub(1);
+            self.worst_loss_streak = self
+                .worst_loss_streak
+                .max(self.current_streak.unsigned_abs());
+        }
+        Ok(())
+    }
+}
+
+#[event]
+pub struct FlipSettled {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub bet: Pubkey,
+    pub called_heads: bool,
+    pub landed_heads: bool,
+    pub won: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeCoinflip<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CoinflipConfig::LEN,
+        seeds = [b"coinflip_config"],
+        bump
+    )]
+    pub config: Account<'info, CoinflipConfig>,
+
+    /// CHECK: PDA signer registered as the coinflip game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCoinflipConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"coinflip_config"],
+        bump = config.bump,
+        has_one = authority @ CoinflipError::Unauthorized
+    )]
+    pub config: Account<'info, CoinflipConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Flip<'info> {
+    #[account(
+        seeds = [b"coinflip_config"],
+        bump = config.bump,
+        has_one = clr_state @ CoinflipError::InvalidClrState
+    )]
+    pub config: Account<'info, CoinflipConfig>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Registry entry of the coinflip `game_authority` in the CLR
+    #[account(mut)]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: created by the CLR `place_bet`
+    #[account(mut)]
+    pub bet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CoinFlip::LEN,
+        seeds = [b"flip", bet.key().as_ref()],
+        bump
+    )]
+    pub coin_flip: Account<'info, CoinFlip>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PlayerStats::LEN,
+        seeds = [b"player_stats", player.key().as_ref()],
+        bump
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR USD limits; required with the price update while they are enabled
+    pub clr_usd_pricing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price update; checked by the CLR
+    pub clr_price_update: Option<UncheckedAccount<'info>>,
+
+    /// CLR VIP config and the player's stats; the player's tier discounts the house edge
+    /// when both are passed. The stats are required by the CLR daily wager and rate limits
+    pub clr_vip_config: Option<Account<'info, VipConfig>>,
+
+    #[account(
+        mut,
+        constraint = clr_user_stats.owner == player.key() @ CoinflipError::Unauthorized
+    )]
+    pub clr_user_stats: Option<Account<'info, UserStats>>,
+
+    /// CHECK: the player's first CLR position, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: CLR session whose key signs in the player's place; checked by the CLR
+    #[account(mut)]
+    pub clr_session: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the bettor; the CLR requires their signature unless a session of theirs
+    /// is passed
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// Pays for the bet accounts: the player, or their session key
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFlip<'info> {
+    #[account(
+        seeds = [b"coinflip_config"],
+        bump = config.bump,
+        has_one = clr_state @ CoinflipError::InvalidClrState
+    )]
+    pub config: Account<'info, CoinflipConfig>,
+
+    /// CHECK: PDA signer registered as the coinflip game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    /// CHECK: CLR winners leaderboard; a winning bet is offered to it when passed
+    #[account(mut)]
+    pub clr_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR season accounts; the bet counts toward the season when all are passed
+    #[account(mut)]
+    pub clr_season: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_player: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR loyalty config and the player's stats; the wager earns loyalty points
+    /// when both are passed
+    pub clr_loyalty_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR rakeback config; the bet's rakeback accrues to the player's stats when
+    /// both are passed
+    pub clr_rakeback_config: Option<UncheckedAccount<'info>>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"flip", bet.key().as_ref()],
+        bump = coin_flip.bump,
+        has_one = bet @ CoinflipError::InvalidBet,
+        has_one = player @ CoinflipError::InvalidBet,
+        close = player
+    )]
+    pub coin_flip: Account<'info, CoinFlip>,
+
+    #[account(
+        mut,
+        seeds = [b"player_stats", player.key().as_ref()],
+        bump = player_stats.bump
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
+
+    #[account(mut)]
+    pub bet: AccountLoader<'info, Bet>,
+
+    /// CHECK: must be the request committed at flip time; parsed by `randomness`
+    #[account(address = coin_flip.randomness_account @ CoinflipError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: receives the flip account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum CoinflipError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Flip does not match the bet")]
+    InvalidBet,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
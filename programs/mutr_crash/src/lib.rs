@@ -0,0 +1,1192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+use mutr_clr_cpi::{ClrInstruction, ClrVaults, PayPrize, RecordGamePayout};
+
+declare_id!("CrashRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Resolution of the crash draw: `r` is drawn from `0..ROLL_RANGE`
+const ROLL_RANGE: u64 = 1_000_000;
+
+/// Crash rounds.
+///
+/// A round is opened, takes wagers for `join_slots`, then is locked together with a
+/// fresh randomness request. From the lock slot on, the live multiplier grows by
+/// `growth_bps_per_slot` and players may cash out at the current multiplier for as
+/// long as the request is unrevealed. The reveal fixes the crash point; every cash-out
+/// strictly below it wins. Wagers sit in the game's own vault, and once every entry is
+/// evaluated the round's net result is settled against the CLR: house profit is moved
+/// into the CLR vault, a player net win is paid out of it through `send_prize`. A net
+/// win over the CLR's vesting threshold goes through `send_vested_prize` instead and
+/// `collect_vested_prize` moves it into the game vault as it releases; entries that
+/// find the vault short wait for it.
+///
+/// Rounds draw from ORAO VRF only: a Switchboard value can be read off-chain by whoever
+/// asks the oracle for the reveal, who could then cash out knowing the crash point.
+///
+/// Open -> Locked -> Crashed -> Settled, or Locked -> Voided when the randomness is not
+/// revealed within `reveal_timeout_slots`. A voided round forfeits its wagers to the CLR,
+/// so holding back a reveal never turns a losing round into a refund.
+#[program]
+pub mod mutr_crash {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR so rounds can draw net wins through `send_prize`.
+    pub fn initialize_crash(
+        ctx: Context<InitializeCrash>,
+        params: CrashParams,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        params.validate()?;
+        require!(
+            randomness_provider == RandomnessProvider::Orao,
+            CrashError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.crash_vault = ctx.accounts.crash_vault.key();
+        config.params = params;
+        config.randomness_provider = randomness_provider;
+        config.round_count = 0;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: replace the game parameters. Rounds already open pick them up.
+    pub fn update_crash_config(ctx: Context<UpdateCrashConfig>, params: CrashParams) -> Result<()> {
+        params.validate()?;
+        ctx.accounts.config.params = params;
+        Ok(())
+    }
+
+    /// Permissionless: open the next round for wagers.
+    pub fn open_round(ctx: Context<OpenRound>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let round = &mut ctx.accounts.round;
+        round.round_id = config.round_count;
+        round.status = RoundStatus::Open;
+        round.open_slot = Clock::get()?.slot;
+        round.lock_slot = 0;
+        round.randomness_account = Pubkey::default();
+        round.crash_multiplier_bps = 0;
+        round.entry_count = 0;
+        round.evaluated_entries = 0;
+        round.total_wagered = 0;
+        round.total_payout = 0;
+        round.bump = ctx.bumps.round;
+
+        config.round_count = config
+            .round_count
+            .checked_add(1)
+            .ok_or(CrashError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Join an open round with `amount`; the wager moves into the crash vault.
+    pub fn join_round(ctx: Context<JoinRound>, _round_id: u64, amount: u64) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        require!(
+            amount >= params.min_bet && amount <= params.max_bet,
+            CrashError::BetOutOfRange
+        );
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Open,
+            CrashError::InvalidRoundStatus
+        );
+        require!(
+            Clock::get()?.slot < round.open_slot.saturating_add(params.join_slots),
+            CrashError::JoinWindowClosed
+        );
+
+        let crash_vault_before = ctx.accounts.crash_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.crash_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // Credit what reached the vault, net of any transfer fee
+        ctx.accounts.crash_vault.reload()?;
+        let amount = ctx
+            .accounts
+            .crash_vault
+            .amount
+            .checked_sub(crash_vault_before)
+            .ok_or(CrashError::MathOverflow)?;
+        ctx.accounts
+            .record_wager(ctx.accounts.config.authority_bump, amount)?;
+        let round = &mut ctx.accounts.round;
+
+        round.entry_count = round
+            .entry_count
+            .checked_add(1)
+            .ok_or(CrashError::MathOverflow)?;
+        round.total_wagered = round
+            .total_wagered
+            .checked_add(amount)
+            .ok_or(CrashError::MathOverflow)?;
+
+        let entry = &mut ctx.accounts.entry;
+        entry.round = round.key();
+        entry.player = ctx.accounts.player.key();
+        entry.amount = amount;
+        entry.cashout_multiplier_bps = 0;
+        entry.payout = 0;
+        entry.evaluated = false;
+        entry.bump = ctx.bumps.entry;
+        Ok(())
+    }
+
+    /// Permissionless: close the join window and commit the round to a fresh ORAO VRF
+    /// request. The multiplier starts climbing from this slot.
+    pub fn lock_round(ctx: Context<LockRound>, _round_id: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Open,
+            CrashError::InvalidRoundStatus
+        );
+        require!(
+            config.randomness_provider == RandomnessProvider::Orao,
+            CrashError::InvalidRandomness
+        );
+
+        let slot = Clock::get()?.slot;
+        require!(
+            slot >= round.open_slot.saturating_add(config.params.join_slots),
+            CrashError::JoinWindowOpen
+        );
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        round.status = RoundStatus::Locked;
+        round.lock_slot = slot;
+        round.randomness_account = ctx.accounts.randomness_account.key();
+        Ok(())
+    }
+
+    /// Cash out at the live multiplier. Only possible while the crash point is unknown.
+    pub fn cash_out(ctx: Context<CashOut>, _round_id: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Locked,
+            CrashError::InvalidRoundStatus
+        );
+
+        let slot = Clock::get()?.slot;
+        require!(
+            slot <= round
+                .lock_slot
+                .saturating_add(config.params.reveal_timeout_slots),
+            CrashError::RoundTimedOut
+        );
+        randomness::check_unrevealed(config.randomness_provider, &ctx.accounts.randomness_account)?;
+
+        let entry = &mut ctx.accounts.entry;
+        require!(
+            entry.cashout_multiplier_bps == 0,
+            CrashError::AlreadyCashedOut
+        );
+        let multiplier_bps = live_multiplier_bps(&config.params, round.lock_slot, slot);
+        require!(
+            multiplier_bps as u64 > BPS_DENOMINATOR,
+            CrashError::MultiplierNotStarted
+        );
+        entry.cashout_multiplier_bps = multiplier_bps;
+        Ok(())
+    }
+
+    /// Permissionless: read the revealed randomness and fix the round's crash point.
+    pub fn crash_round(ctx: Context<CrashRound>, _round_id: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Locked,
+            CrashError::InvalidRoundStatus
+        );
+
+        let value = randomness::revealed_value(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            round.lock_slot,
+        )?;
+        round.crash_multiplier_bps = crash_point_bps(&config.params, &value);
+        round.status = RoundStatus::Crashed;
+
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(RoundCrashed {
+            event_nonce,
+            round_id: ctx.accounts.round.round_id,
+            crash_multiplier_bps: ctx.accounts.round.crash_multiplier_bps,
+        });
+        Ok(())
+    }
+
+    /// Permissionless: evaluate one entry of a crashed round. Cash-outs strictly below
+    /// the crash point win `amount * cashout_multiplier_bps / 10_000`.
+    pub fn settle_entry(ctx: Context<SettleEntry>, _round_id: u64) -> Result<()> {
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Crashed,
+            CrashError::InvalidRoundStatus
+        );
+
+        let entry = &mut ctx.accounts.entry;
+        require!(!entry.evaluated, CrashError::EntryAlreadyEvaluated);
+        if entry.cashout_multiplier_bps != 0
+            && entry.cashout_multiplier_bps < round.crash_multiplier_bps
+        {
+            entry.payout = mutr_clr::bet_payout(entry.amount, entry.cashout_multiplier_bps)?;
+        }
+        entry.evaluated = true;
+
+        round.evaluated_entries += 1;
+        round.total_payout = round
+            .total_payout
+            .checked_add(entry.payout)
+            .ok_or(CrashError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Permissionless: once every entry is evaluated, settle the round's net result with
+    /// the CLR. Entries can then be claimed.
+    pub fn settle_round(ctx: Context<SettleRound>, _round_id: u64) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Crashed,
+            CrashError::InvalidRoundStatus
+        );
+        require!(
+            round.evaluated_entries == round.entry_count,
+            CrashError::EntriesNotEvaluated
+        );
+
+        let authority_bump = ctx.accounts.config.authority_bump;
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let accounts = &ctx.accounts;
+        let vaults = ClrVaults {
+            mutr_mint: accounts.mutr_mint.key(),
+            clr_vault: accounts.clr_vault.key(),
+            token_program: accounts.token_program.key(),
+        };
+        // Entries are paid out of the crash vault up to the round's wagers; the CLR books
+        // any net win on top as it pays it
+        let paid_from_vault = round.total_payout.min(round.total_wagered);
+        if paid_from_vault > 0 {
+            RecordGamePayout {
+                game: accounts.game_authority.key(),
+                amount: paid_from_vault,
+            }
+            .invoke_signed(
+                &vaults,
+                &accounts.to_account_infos(),
+                &[authority_seeds],
+            )?;
+        }
+
+        if round.total_wagered >= round.total_payout {
+            let house_profit = round.total_wagered - round.total_payout;
+            game_transfer(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.crash_vault.to_account_info(),
+                ctx.accounts.clr_vault.to_account_info(),
+                &ctx.accounts.mutr_mint,
+                ctx.accounts.game_authority.to_account_info(),
+                authority_bump,
+                house_profit,
+            )?;
+        } else {
+            // A net win over the vesting threshold streams to the game itself; see
+            // `collect_vested_prize`
+            PayPrize {
+                game: accounts.game_authority.key(),
+                winner: accounts.game_authority.key(),
+                winner_mutr_account: accounts.crash_vault.key(),
+                payer: accounts.payer.key(),
+                amount: round.total_payout - round.total_wagered,
+            }
+            .invoke_signed(
+                &vaults,
+                &accounts.clr_state,
+                accounts.clr_vault.amount,
+                &accounts.to_account_infos(),
+                &[authority_seeds],
+            )?;
+        }
+
+        ctx.accounts
+            .contribute_to_jackpot(authority_bump, ctx.accounts.round.total_wagered)?;
+        ctx.accounts.round.status = RoundStatus::Settled;
+        Ok(())
+    }
+
+    /// Permissionless: void a locked round whose randomness was never revealed in time.
+    /// Its wagers are forfeited to the CLR vault; entries close without a payout.
+    pub fn void_round(ctx: Context<VoidRound>, _round_id: u64) -> Result<()> {
+        game_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.crash_vault.to_account_info(),
+            ctx.accounts.clr_vault.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.game_authority.to_account_info(),
+            ctx.accounts.config.authority_bump,
+            ctx.accounts.round.total_wagered,
+        )?;
+
+        let round = &mut ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Locked,
+            CrashError::InvalidRoundStatus
+        );
+        require!(
+            Clock::get()?.slot
+                > round
+                    .lock_slot
+                    .saturating_add(ctx.accounts.config.params.reveal_timeout_slots),
+            CrashError::RoundNotTimedOut
+        );
+        round.status = RoundStatus::Voided;
+        Ok(())
+    }
+
+    /// Permissionless: pay an entry of a settled round and close it back to the player.
+    /// Entries of a voided round close without a payout.
+    pub fn claim_entry(ctx: Context<ClaimEntry>, _round_id: u64) -> Result<()> {
+        let entry = &ctx.accounts.entry;
+        let amount = match ctx.accounts.round.status {
+            RoundStatus::Settled => entry.payout,
+            RoundStatus::Voided => 0,
+            _ => return err!(CrashError::InvalidRoundStatus),
+        };
+        game_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.crash_vault.to_account_info(),
+            ctx.accounts.player_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.game_authority.to_account_info(),
+            ctx.accounts.config.authority_bump,
+            amount,
+        )
+    }
+
+    /// Permissionless: move into the game vault the part of a streamed net win the CLR
+    /// has released so far.
+    pub fn collect_vested_prize(ctx: Context<CollectVestedPrize>) -> Result<()> {
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[ctx.accounts.config.authority_bump]];
+        let signer_seeds = &[authority_seeds];
+        let cpi_accounts = mutr_clr::cpi::accounts::ClaimVestedPrize {
+            state: ctx.accounts.clr_state.to_account_info(),
+            stats: ctx.accounts.clr_stats.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            prize_vesting: ctx.accounts.prize_vesting.to_account_info(),
+            winner_mutr_account: ctx.accounts.crash_vault.to_account_info(),
+            winner: ctx.accounts.game_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::claim_vested_prize(cpi_ctx)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Multiplier reached at `slot` in a round locked at `lock_slot`, capped at the maximum.
+fn live_multiplier_bps(params: &CrashParams, lock_slot: u64, slot: u64) -> u32 {
+    let elapsed = slot.saturating_sub(lock_slot);
+    let multiplier = elapsed
+        .saturating_mul(params.growth_bps_per_slot as u64)
+        .saturating_add(BPS_DENOMINATOR);
+    multiplier.min(params.max_multiplier_bps as u64) as u32
+}
+
+/// Crash point drawn from `value`: `(1 - edge) / (1 - r)` for `r` uniform in `[0, 1)`,
+/// which pays out `1 - edge` in expectation at every cash-out target. Draws below 1x
+/// crash the round before anyone can cash out.
+fn crash_point_bps(params: &CrashParams, value: &[u8; 32]) -> u32 {
+    let mut roll_bytes = [0u8; 8];
+    roll_bytes.copy_from_slice(&value[..8]);
+    let r = u64::from_le_bytes(roll_bytes) % ROLL_RANGE;
+
+    let crash = (BPS_DENOMINATOR - params.house_edge_bps as u64) * ROLL_RANGE / (ROLL_RANGE - r);
+    crash.min(params.max_multiplier_bps as u64) as u32
+}
+
+/// Transfer `amount` out of the crash vault, signed by the game authority PDA.
+fn game_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    game_authority: AccountInfo<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to,
+        authority: game_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Tunable game parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct CrashParams {
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Cap on both the live multiplier and the crash point
+    pub max_multiplier_bps: u32,
+    pub growth_bps_per_slot: u32,
+    /// Slots a round accepts wagers after opening
+    pub join_slots: u64,
+    /// Slots after locking before an unrevealed round may be voided, forfeiting its wagers
+    pub reveal_timeout_slots: u64,
+}
+
+impl CrashParams {
+    pub const LEN: usize = 2 // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 4  // max_multiplier_bps
+        + 4  // growth_bps_per_slot
+        + 8  // join_slots
+        + 8; // reveal_timeout_slots
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (self.house_edge_bps as u64) < BPS_DENOMINATOR
+                && self.min_bet > 0
+                && self.min_bet <= self.max_bet
+                && self.max_multiplier_bps as u64 > BPS_DENOMINATOR
+                && self.growth_bps_per_slot > 0
+                && self.join_slots > 0
+                && self.reveal_timeout_slots > 0,
+            CrashError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct CrashConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    /// MUTR escrow for round wagers, owned by `game_authority`
+    pub crash_vault: Pubkey,
+    pub params: CrashParams,
+    pub randomness_provider: RandomnessProvider,
+    pub round_count: u64,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl CrashConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 32 // crash_vault
+        + CrashParams::LEN // params
+        + 1  // randomness_provider
+        + 8  // round_count
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundStatus {
+    Open,
+    Locked,
+    Crashed,
+    Settled,
+    Voided,
+}
+
+#[account]
+pub struct Round {
+    pub round_id: u64,
+    pub status: RoundStatus,
+    pub open_slot: u64,
+    pub lock_slot: u64,
+    pub randomness_account: Pubkey,
+    /// Fixed once the round crashes
+    pub crash_multiplier_bps: u32,
+    pub entry_count: u32,
+    pub evaluated_entries: u32,
+    pub total_wagered: u64,
+    /// Sum of winning payouts, known once every entry is evaluated
+    pub total_payout: u64,
+    pub bump: u8,
+}
+
+impl Round {
+    pub const LEN: usize = 8 // round_id
+        + 1  // status
+        + 8  // open_slot
+        + 8  // lock_slot
+        + 32 // randomness_account
+        + 4  // crash_multiplier_bps
+        + 4  // entry_count
+        + 4  // evaluated_entries
+        + 8  // total_wagered
+        + 8  // total_payout
+        + 1; // bump
+}
+
+/// A player's wager in a round.
+#[account]
+pub struct Entry {
+    pub round: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    /// Multiplier locked in by `cash_out`; 0 while still riding
+    pub cashout_multiplier_bps: u32,
+    pub payout: u64,
+    pub evaluated: bool,
+    pub bump: u8,
+}
+
+impl Entry {
+    pub const LEN: usize = 32 // round
+        + 32 // player
+        + 8  // amount
+        + 4  // cashout_multiplier_bps
+        + 8  // payout
+        + 1  // evaluated
+        + 1; // bump
+}
+
+#[event]
+pub struct RoundCrashed {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub round_id: u64,
+    pub crash_multiplier_bps: u32,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeCrash<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CrashConfig::LEN,
+        seeds = [b"crash_config"],
+        bump
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer registered as the crash game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(address = clr_state.load()?.mutr_mint @ CrashError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"crash_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = game_authority
+    )]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCrashConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = authority @ CrashError::Unauthorized
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"crash_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Round::LEN,
+        seeds = [b"round", config.round_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct JoinRound<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = clr_state @ CrashError::InvalidClrState,
+        has_one = crash_vault @ CrashError::InvalidVault
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer registered as the crash game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CLR config of the game; checked by the CLR
+    #[account(mut)]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + Entry::LEN,
+        seeds = [b"entry", round.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, Entry>,
+
+    #[account(mut)]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = crash_vault.mint @ CrashError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinRound<'info> {
+    /// Run the wager through the CLR's player checks.
+    fn record_wager(&self, authority_bump: u8, amount: u64) -> Result<()> {
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::RecordGameWager {
+            state: self.clr_state.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game_ledger: self.clr_game_ledger.to_account_info(),
+            player_state: self.clr_player_state.to_account_info(),
+            user_stats: self
+                .clr_user_stats
+                .as_ref()
+                .map(|stats| stats.to_account_info()),
+            player: self.player.to_account_info(),
+            game: self.game_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::record_game_wager(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct LockRound<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct CashOut<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    #[account(
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        seeds = [b"entry", round.key().as_ref(), player.key().as_ref()],
+        bump = entry.bump
+    )]
+    pub entry: Account<'info, Entry>,
+
+    /// CHECK: the request the round is committed to; parsed by `randomness`
+    #[account(address = round.randomness_account @ CrashError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct CrashRound<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = clr_state @ CrashError::InvalidClrState
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer registered as the crash game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: the request the round is committed to; parsed by `randomness`
+    #[account(address = round.randomness_account @ CrashError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// Numbers the round's event (see `mutr_clr::events`)
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Registry entry of the crash `game_authority` in the CLR
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct SettleEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        has_one = round @ CrashError::InvalidEntry
+    )]
+    pub entry: Account<'info, Entry>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct SettleRound<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = clr_state @ CrashError::InvalidClrState,
+        has_one = crash_vault @ CrashError::InvalidVault
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer registered as the crash game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(mut)]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ CrashError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// Registry entry of the crash `game_authority` in the CLR
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the game's CLR `PrizeVesting`; created and checked by the CLR when a net
+    /// win is over its vesting threshold
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    /// Funds the game's `PrizeVesting` when a net win opens it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleRound<'info> {
+    /// Feed the CLR progressive jackpot its share of the round's wagers.
+    /// The jackpot accounts are required once the jackpot exists.
+    fn contribute_to_jackpot(&self, authority_bump: u8, wager: u64) -> Result<()> {
+        let (Some(jackpot), Some(jackpot_vault)) = (&self.clr_jackpot, &self.clr_jackpot_vault)
+        else {
+            require!(
+                self.clr_state.load()?.jackpot_enabled == 0,
+                CrashError::MissingJackpotAccounts
+            );
+            return Ok(());
+        };
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ContributeJackpot {
+            state: self.clr_state.to_account_info(),
+            mutr_mint: self.mutr_mint.to_account_info(),
+            jackpot: jackpot.to_account_info(),
+            clr_vault: self.clr_vault.to_account_info(),
+            jackpot_vault: jackpot_vault.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game: self.game_authority.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::contribute_jackpot(cpi_ctx, wager)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct VoidRound<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = clr_state @ CrashError::InvalidClrState,
+        has_one = crash_vault @ CrashError::InvalidVault
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer owning the crash vault
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(mut)]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ CrashError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = crash_vault.mint @ CrashError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct ClaimEntry<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = crash_vault @ CrashError::InvalidVault
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer owning the crash vault
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        seeds = [b"entry", round.key().as_ref(), player.key().as_ref()],
+        bump = entry.bump,
+        has_one = round @ CrashError::InvalidEntry,
+        close = player
+    )]
+    pub entry: Account<'info, Entry>,
+
+    #[account(mut)]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = crash_vault.mint @ CrashError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = player_mutr_account.owner == player.key() @ CrashError::Unauthorized
+    )]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: receives the entry account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CollectVestedPrize<'info> {
+    #[account(
+        seeds = [b"crash_config"],
+        bump = config.bump,
+        has_one = clr_state @ CrashError::InvalidClrState,
+        has_one = crash_vault @ CrashError::InvalidVault
+    )]
+    pub config: Account<'info, CrashConfig>,
+
+    /// CHECK: PDA signer owning the crash vault and the streamed net wins
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub crash_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ CrashError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: the game's CLR `PrizeVesting`; checked by the CLR
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[error_code]
+pub enum CrashError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Round is not in the required status")]
+    InvalidRoundStatus,
+    #[msg("Join window is closed")]
+    JoinWindowClosed,
+    #[msg("Join window is still open")]
+    JoinWindowOpen,
+    #[msg("Entry already cashed out")]
+    AlreadyCashedOut,
+    #[msg("Multiplier has not started climbing")]
+    MultiplierNotStarted,
+    #[msg("Entry already evaluated")]
+    EntryAlreadyEvaluated,
+    #[msg("Not every entry has been evaluated")]
+    EntriesNotEvaluated,
+    #[msg("Entry does not belong to the round")]
+    InvalidEntry,
+    #[msg("Round randomness reveal timed out")]
+    RoundTimedOut,
+    #[msg("Round has not timed out")]
+    RoundNotTimedOut,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpotAccounts,
+}
@@ -0,0 +1,596 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{Bet, GameConfig, GlobalState, UserStats, VipConfig};
+
+declare_id!("DiceRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Rolls are drawn from `0..ROLL_RANGE`
+const ROLL_RANGE: u64 = 100;
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod mutr_dice {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR (provider `None`: dice settles bets from its own randomness).
+    pub fn initialize_dice(
+        ctx: Context<InitializeDice>,
+        house_edge_bps: u16,
+        min_bet: u64,
+        max_bet: u64,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            (house_edge_bps as u64) < BPS_DENOMINATOR
+                && min_bet > 0
+                && min_bet <= max_bet
+                && randomness_provider != RandomnessProvider::None,
+            DiceError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.house_edge_bps = house_edge_bps;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: change the house edge and bet limits.
+    pub fn update_dice_config(
+        ctx: Context<UpdateDiceConfig>,
+        house_edge_bps: u16,
+        min_bet: u64,
+        max_bet: u64,
+    ) -> Result<()> {
+        require!(
+            (house_edge_bps as u64) < BPS_DENOMINATOR && min_bet > 0 && min_bet <= max_bet,
+            DiceError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.house_edge_bps = house_edge_bps;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        Ok(())
+    }
+
+    /// Wager `amount` that the roll lands under `target` (or over it with `roll_over`).
+    /// The wager is escrowed as a CLR bet; the payout multiplier is the fair odds with
+    /// the house edge taken off, discounted by the player's CLR VIP tier when its accounts
+    /// are passed. A fresh randomness request must accompany the roll, which the CLR
+    /// rejects once past `deadline_slot`, when given.
+    pub fn roll(
+        ctx: Context<Roll>,
+        nonce: u64,
+        amount: u64,
+        target: u8,
+        roll_over: bool,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            amount >= config.min_bet && amount <= config.max_bet,
+            DiceError::BetOutOfRange
+        );
+        let house_edge_bps = mutr_clr::vip::discounted_bps(
+            ctx.accounts.clr_vip_config.as_deref(),
+            ctx.accounts.clr_user_stats.as_deref(),
+            config.house_edge_bps,
+        );
+        let multiplier_bps = dice_multiplier_bps(target, roll_over, house_edge_bps)?;
+
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let cpi_accounts = mutr_clr::cpi::accounts::PlaceBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            randomness_account: None,
+            jackpot: ctx
+                .accounts
+                .clr_jackpot
+                .as_ref()
+                .map(|jackpot| jackpot.to_account_info()),
+            jackpot_vault: ctx
+                .accounts
+                .clr_jackpot_vault
+                .as_ref()
+                .map(|jackpot_vault| jackpot_vault.to_account_info()),
+            usd_pricing: ctx
+                .accounts
+                .clr_usd_pricing
+                .as_ref()
+                .map(|usd_pricing| usd_pricing.to_account_info()),
+            price_update: ctx
+                .accounts
+                .clr_price_update
+                .as_ref()
+                .map(|price_update| price_update.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            referral_config: None,
+            referrer: None,
+            referral_vault: None,
+            player_state: ctx.accounts.clr_player_state.to_account_info(),
+            session: ctx
+                .accounts
+                .clr_session
+                .as_ref()
+                .map(|session| session.to_account_info()),
+            player: ctx.accounts.player.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.clr_program.to_account_info(), cpi_accounts);
+        mutr_clr::cpi::place_bet(cpi_ctx, nonce, amount, multiplier_bps, deadline_slot)?;
+
+        let dice_roll = &mut ctx.accounts.dice_roll;
+        dice_roll.player = ctx.accounts.player.key();
+        dice_roll.bet = ctx.accounts.bet.key();
+        dice_roll.target = target;
+        dice_roll.roll_over = roll_over;
+        dice_roll.randomness_account = ctx.accounts.randomness_account.key();
+        dice_roll.placed_slot = slot;
+        dice_roll.bump = ctx.bumps.dice_roll;
+        Ok(())
+    }
+
+    /// Permissionless: read the revealed randomness, roll the die and settle the CLR
+    /// bet. The roll account is closed back to the player.
+    pub fn settle_roll(ctx: Context<SettleRoll>) -> Result<()> {
+        let dice_roll = &ctx.accounts.dice_roll;
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            dice_roll.placed_slot,
+        )?;
+        let mut roll_bytes = [0u8; 8];
+        roll_bytes.copy_from_slice(&value[..8]);
+        let roll = (u64::from_le_bytes(roll_bytes) % ROLL_RANGE) as u8;
+        let won = if dice_roll.roll_over {
+            roll > dice_roll.target
+        } else {
+            roll < dice_roll.target
+        };
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(RollSettled {
+            event_nonce,
+            player: dice_roll.player,
+            bet: dice_roll.bet,
+            roll,
+            target: dice_roll.target,
+            roll_over: dice_roll.roll_over,
+            won,
+        });
+
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[ctx.accounts.config.authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ResolveBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            stats: ctx.accounts.clr_stats.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            usd_pricing: None,
+            price_update: None,
+            leaderboard: ctx
+                .accounts
+                .clr_leaderboard
+                .as_ref()
+                .map(|leaderboard| leaderboard.to_account_info()),
+            season: ctx
+                .accounts
+                .clr_season
+                .as_ref()
+                .map(|season| season.to_account_info()),
+            season_player: ctx
+                .accounts
+                .clr_season_player
+                .as_ref()
+                .map(|season_player| season_player.to_account_info()),
+            season_leaderboard: ctx
+                .accounts
+                .clr_season_leaderboard
+                .as_ref()
+                .map(|season_leaderboard| season_leaderboard.to_account_info()),
+            loyalty_config: ctx
+                .accounts
+                .clr_loyalty_config
+                .as_ref()
+                .map(|loyalty_config| loyalty_config.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            rakeback_config: ctx
+                .accounts
+                .clr_rakeback_config
+                .as_ref()
+                .map(|rakeback_config| rakeback_config.to_account_info()),
+            game: ctx.accounts.game_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::resolve_bet(cpi_ctx, won)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Payout multiplier for a target: `(1 - edge) * ROLL_RANGE / winning_rolls`, in bps.
+fn dice_multiplier_bps(target: u8, roll_over: bool, house_edge_bps: u16) -> Result<u32> {
+    let winning_rolls = if roll_over {
+        (ROLL_RANGE - 1).checked_sub(target as u64)
+    } else {
+        Some(target as u64)
+    }
+    .ok_or(DiceError::InvalidTarget)?;
+    require!(
+        (1..ROLL_RANGE - 1).contains(&winning_rolls),
+        DiceError::InvalidTarget
+    );
+
+    let multiplier = (BPS_DENOMINATOR - house_edge_bps as u64) * ROLL_RANGE / winning_rolls;
+    Ok(multiplier as u32)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct DiceConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl DiceConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 2  // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// An open roll, waiting for its randomness to be revealed.
+#[account]
+pub struct DiceRoll {
+    pub player: Pubkey,
+    /// CLR `Bet` escrowing the wager
+    pub bet: Pubkey,
+    pub target: u8,
+    pub roll_over: bool,
+    pub randomness_account: Pubkey,
+    pub placed_slot: u64,
+    pub bump: u8,
+}
+
+impl DiceRoll {
+    pub const LEN: usize = 32 // player
+        + 32 // bet
+        + 1  // target
+        + 1  // roll_over
+        + 32 // randomness_account
+        + 8  // placed_slot
+        + 1; // bump
+}
+
+#[event]
+pub struct RollSettled {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub bet: Pubkey,
+    pub roll: u8,
+    pub target: u8,
+    pub roll_over: bool,
+    pub won: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeDice<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DiceConfig::LEN,
+        seeds = [b"dice_config"],
+        bump
+    )]
+    pub config: Account<'info, DiceConfig>,
+
+    /// CHECK: PDA signer registered as the dice game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDiceConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"dice_config"],
+        bump = config.bump,
+        has_one = authority @ DiceError::Unauthorized
+    )]
+    pub config: Account<'info, DiceConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Roll<'info> {
+    #[account(
+        seeds = [b"dice_config"],
+        bump = config.bump,
+        has_one = clr_state @ DiceError::InvalidClrState
+    )]
+    pub config: Account<'info, DiceConfig>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Registry entry of the dice `game_authority` in the CLR
+    #[account(mut)]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: created by the CLR `place_bet`
+    #[account(mut)]
+    pub bet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DiceRoll::LEN,
+        seeds = [b"roll", bet.key().as_ref()],
+        bump
+    )]
+    pub dice_roll: Account<'info, DiceRoll>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR USD limits; required with the price update while they are enabled
+    pub clr_usd_pricing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price update; checked by the CLR
+    pub clr_price_update: Option<UncheckedAccount<'info>>,
+
+    /// CLR VIP config and the player's stats; the player's tier discounts the house edge
+    /// when both are passed. The stats are required by the CLR daily wager and rate limits
+    pub clr_vip_config: Option<Account<'info, VipConfig>>,
+
+    #[account(
+        mut,
+        constraint = clr_user_stats.owner == player.key() @ DiceError::Unauthorized
+    )]
+    pub clr_user_stats: Option<Account<'info, UserStats>>,
+
+    /// CHECK: the player's first CLR position, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: CLR session whose key signs in the player's place; checked by the CLR
+    #[account(mut)]
+    pub clr_session: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the bettor; the CLR requires their signature unless a session of theirs
+    /// is passed
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// Pays for the bet accounts: the player, or their session key
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleRoll<'info> {
+    #[account(
+        seeds = [b"dice_config"],
+        bump = config.bump,
+        has_one = clr_state @ DiceError::InvalidClrState
+    )]
+    pub config: Account<'info, DiceConfig>,
+
+    /// CHECK: PDA signer registered as the dice game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    /// CHECK: CLR winners leaderboard; a winning bet is offered to it when passed
+    #[account(mut)]
+    pub clr_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR season accounts; the bet counts toward the season when all are passed
+    #[account(mut)]
+    pub clr_season: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_player: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR loyalty config and the player's stats; the wager earns loyalty points
+    /// when both are passed
+    pub clr_loyalty_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR rakeback config; the bet's rakeback accrues to the player's stats when
+    /// both are passed
+    pub clr_rakeback_config: Option<UncheckedAccount<'info>>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roll", bet.key().as_ref()],
+        bump = dice_roll.bump,
+        has_one = bet @ DiceError::InvalidBet,
+        has_one = player @ DiceError::InvalidBet,
+        close = player
+    )]
+    pub dice_roll: Account<'info, DiceRoll>,
+
+    #[account(mut)]
+    pub bet: AccountLoader<'info, Bet>,
+
+    /// CHECK: must be the request committed at roll time; parsed by `randomness`
+    #[account(address = dice_roll.randomness_account @ DiceError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: receives the roll account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum DiceError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Invalid roll target")]
+    InvalidTarget,
+    #[msg("Roll does not match the bet")]
+    InvalidBet,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+}
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("DistRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Upper bound on leaves per distributor, so the claim bitmap fits in a single
+/// account created through CPI (10 KiB limit).
+pub const MAX_NUM_NODES: u64 = 64_000;
+
+#[program]
+pub mod mutr_distributor {
+    use super::*;
+
+    /// Create a distributor for `merkle_root` and fund its vault with `max_total_claim`
+    /// tokens of `mint` (MUTR or xMUTR). Leaves are
+    /// `keccak(index_le || claimant || amount_le)`.
+    pub fn initialize_distributor(
+        ctx: Context<InitializeDistributor>,
+        distributor_id: u64,
+        merkle_root: [u8; 32],
+        max_total_claim: u64,
+        max_num_nodes: u64,
+    ) -> Result<()> {
+        require!(max_total_claim > 0, DistributorError::InvalidAmount);
+        require!(
+            max_num_nodes > 0 && max_num_nodes <= MAX_NUM_NODES,
+            DistributorError::InvalidNodeCount
+        );
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, max_total_claim)?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.authority = ctx.accounts.authority.key();
+        distributor.distributor_id = distributor_id;
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.vault = ctx.accounts.vault.key();
+        distributor.merkle_root = merkle_root;
+        distributor.max_total_claim = max_total_claim;
+        distributor.max_num_nodes = max_num_nodes;
+        distributor.total_amount_claimed = 0;
+        distributor.num_nodes_claimed = 0;
+        distributor.bump = ctx.bumps.distributor;
+        distributor.claimed_bitmap = vec![0u8; bitmap_len(max_num_nodes)];
+        Ok(())
+    }
+
+    /// Claim `amount` for leaf `index` by proving membership in the distributor's root.
+    pub fn claim(ctx: Context<Claim>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(index < distributor.max_num_nodes, DistributorError::InvalidIndex);
+        require!(!distributor.is_claimed(index), DistributorError::AlreadyClaimed);
+
+        let leaf = keccak::hashv(&[
+            &index.to_le_bytes(),
+            ctx.accounts.claimant.key().as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .0;
+        require!(
+            verify_proof(&proof, distributor.merkle_root, leaf),
+            DistributorError::InvalidProof
+        );
+
+        let total_amount_claimed = distributor
+            .total_amount_claimed
+            .checked_add(amount)
+            .ok_or(DistributorError::MathOverflow)?;
+        require!(
+            total_amount_claimed <= distributor.max_total_claim,
+            DistributorError::ExceededMaxClaim
+        );
+
+        let distributor_id = distributor.distributor_id.to_le_bytes();
+        let distributor_seeds: &[&[u8]] = &[b"distributor", &distributor_id, &[distributor.bump]];
+        let signer_seeds = &[distributor_seeds];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.distributor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.set_claimed(index);
+        distributor.total_amount_claimed = total_amount_claimed;
+        distributor.num_nodes_claimed = distributor
+            .num_nodes_claimed
+            .checked_add(1)
+            .ok_or(DistributorError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+fn bitmap_len(max_num_nodes: u64) -> usize {
+    max_num_nodes.div_ceil(8) as usize
+}
+
+/// Verify a Merkle proof using sorted-pair keccak hashing.
+fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).0
+        } else {
+            keccak::hashv(&[node, &computed]).0
+        };
+    }
+    computed == root
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct MerkleDistributor {
+    pub authority: Pubkey,
+    pub distributor_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub merkle_root: [u8; 32],
+
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub total_amount_claimed: u64,
+    pub num_nodes_claimed: u64,
+
+    pub bump: u8,
+
+    /// One bit per leaf index; set once that leaf has been claimed.
+    pub claimed_bitmap: Vec<u8>,
+}
+
+impl MerkleDistributor {
+    pub fn space(max_num_nodes: u64) -> usize {
+        8 // discriminator
+            + 32 // authority
+            + 8  // distributor_id
+            + 32 // mint
+            + 32 // vault
+            + 32 // merkle_root
+            + 8  // max_total_claim
+            + 8  // max_num_nodes
+            + 8  // total_amount_claimed
+            + 8  // num_nodes_claimed
+            + 1  // bump
+            + 4 + bitmap_len(max_num_nodes) // claimed_bitmap
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    fn set_claimed(&mut self, index: u64) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(distributor_id: u64, merkle_root: [u8; 32], max_total_claim: u64, max_num_nodes: u64)]
+pub struct InitializeDistributor<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = MerkleDistributor::space(max_num_nodes),
+        seeds = [b"distributor".as_ref(), &distributor_id.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Airdropped mint (MUTR or xMUTR)
+    pub mint: Account<'info, Mint>,
+
+    /// Distributor vault, owned by the `distributor` PDA
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distributor
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == mint.key() @ DistributorError::InvalidMint,
+        constraint = funder_token_account.owner == authority.key() @ DistributorError::Unauthorized
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        seeds = [b"distributor".as_ref(), &distributor.distributor_id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = vault @ DistributorError::InvalidVault
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == distributor.mint @ DistributorError::InvalidMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub claimant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum DistributorError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Invalid distributor vault")]
+    InvalidVault,
+    #[msg("Invalid number of Merkle leaves")]
+    InvalidNodeCount,
+    #[msg("Leaf index out of range")]
+    InvalidIndex,
+    #[msg("Invalid Merkle proof")]
+    InvalidProof,
+    #[msg("Leaf already claimed")]
+    AlreadyClaimed,
+    #[msg("Claim exceeds distributor funding")]
+    ExceededMaxClaim,
+}
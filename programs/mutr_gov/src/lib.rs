@@ -0,0 +1,701 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::{ConfigUpdate, GameConfig, GameLedger, GlobalState};
+
+declare_id!("GovRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Space reserved for a serialized `ProposalAction`
+const MAX_ACTION_LEN: usize = 256;
+
+#[program]
+pub mod mutr_gov {
+    use super::*;
+
+    /// One-time initializer, signed by the CLR `authority`. That role must afterwards be
+    /// handed to the `gov_authority` PDA (via `mutr_clr::set_authority`) for proposals
+    /// to execute.
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        voting_period_secs: i64,
+        quorum_votes: u64,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        require!(voting_period_secs > 0, GovError::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.xmutr_mint = ctx.accounts.xmutr_mint.key();
+        config.vote_vault = ctx.accounts.vote_vault.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.voting_period_secs = voting_period_secs;
+        config.quorum_votes = quorum_votes;
+        config.proposal_threshold = proposal_threshold;
+        config.proposal_count = 0;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.gov_authority;
+        Ok(())
+    }
+
+    /// Deposit xMUTR as voting power. Deposits stay locked while a vote using them is open.
+    pub fn deposit_votes(ctx: Context<DepositVotes>, amount: u64) -> Result<()> {
+        require!(amount > 0, GovError::InvalidAmount);
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.voter_xmutr_account.to_account_info(),
+            mint: ctx.accounts.xmutr_mint.to_account_info(),
+            to: ctx.accounts.vote_vault.to_account_info(),
+            authority: ctx.accounts.voter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.xmutr_mint.decimals)?;
+
+        let record = &mut ctx.accounts.voter_record;
+        if record.owner == Pubkey::default() {
+            record.owner = ctx.accounts.voter.key();
+        }
+        record.deposited = record
+            .deposited
+            .checked_add(amount)
+            .ok_or(GovError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Withdraw deposited xMUTR once every vote it was used in has closed.
+    pub fn withdraw_votes(ctx: Context<WithdrawVotes>, amount: u64) -> Result<()> {
+        require!(amount > 0, GovError::InvalidAmount);
+
+        let record = &mut ctx.accounts.voter_record;
+        require!(
+            Clock::get()?.unix_timestamp >= record.locked_until,
+            GovError::VotesLocked
+        );
+        record.deposited = record
+            .deposited
+            .checked_sub(amount)
+            .ok_or(GovError::InsufficientVotes)?;
+
+        let config = &ctx.accounts.config;
+        let authority_seeds: &[&[u8]] = &[
+            b"gov_authority",
+            &[config.authority_bump],
+        ];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vote_vault.to_account_info(),
+            mint: ctx.accounts.xmutr_mint.to_account_info(),
+            to: ctx.accounts.voter_xmutr_account.to_account_info(),
+            authority: ctx.accounts.gov_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.xmutr_mint.decimals)?;
+        Ok(())
+    }
+
+    /// Open a proposal. The proposer needs at least `proposal_threshold` deposited votes.
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        require!(
+            action.try_to_vec()?.len() <= MAX_ACTION_LEN,
+            GovError::ActionTooLarge
+        );
+        let config = &mut ctx.accounts.config;
+        require!(
+            ctx.accounts.voter_record.deposited >= config.proposal_threshold,
+            GovError::InsufficientVotes
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = config.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.action = action;
+        proposal.created_at = now;
+        proposal.voting_ends_at = now
+            .checked_add(config.voting_period_secs)
+            .ok_or(GovError::MathOverflow)?;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.proposal;
+
+        config.proposal_count = config
+            .proposal_count
+            .checked_add(1)
+            .ok_or(GovError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Vote with the full deposited balance; locks the deposit until voting ends.
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+            GovError::VotingClosed
+        );
+
+        let record = &mut ctx.accounts.voter_record;
+        let weight = record.deposited;
+        require!(weight > 0, GovError::InsufficientVotes);
+
+        if support {
+            proposal.yes_votes = proposal
+                .yes_votes
+                .checked_add(weight)
+                .ok_or(GovError::MathOverflow)?;
+        } else {
+            proposal.no_votes = proposal
+                .no_votes
+                .checked_add(weight)
+                .ok_or(GovError::MathOverflow)?;
+        }
+        record.locked_until = record.locked_until.max(proposal.voting_ends_at);
+
+        let receipt = &mut ctx.accounts.vote_receipt;
+        receipt.proposal = proposal.key();
+        receipt.voter = ctx.accounts.voter.key();
+        receipt.support = support;
+        receipt.weight = weight;
+        Ok(())
+    }
+
+    /// Permissionless: execute a passed proposal by CPI into the CLR admin instructions.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, GovError::AlreadyExecuted);
+        require!(
+            Clock::get()?.unix_timestamp >= proposal.voting_ends_at,
+            GovError::VotingOpen
+        );
+        let turnout = proposal
+            .yes_votes
+            .checked_add(proposal.no_votes)
+            .ok_or(GovError::MathOverflow)?;
+        require!(
+            proposal.yes_votes > proposal.no_votes && turnout >= config.quorum_votes,
+            GovError::ProposalNotPassed
+        );
+        proposal.executed = true;
+
+        let authority_seeds: &[&[u8]] = &[
+            b"gov_authority",
+            &[config.authority_bump],
+        ];
+        let signer_seeds = &[authority_seeds];
+
+        match proposal.action.clone() {
+            ProposalAction::UpdateConfig(update) => {
+                let cpi_accounts = mutr_clr::cpi::accounts::UpdateConfig {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::update_config(cpi_ctx, update)?;
+            }
+            ProposalAction::SetAuthority { new_authority } => {
+                let cpi_accounts = mutr_clr::cpi::accounts::SetAuthority {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::set_authority(cpi_ctx, new_authority)?;
+            }
+            ProposalAction::TreasurySpend { amount, recipient } => {
+                let spend_proposal = ctx
+                    .accounts
+                    .spend_proposal
+                    .as_ref()
+                    .ok_or(GovError::MissingAccount)?;
+                let cpi_accounts = mutr_clr::cpi::accounts::ProposeSpend {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    spend_proposal: spend_proposal.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                    payer: ctx.accounts.executor.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::propose_spend(cpi_ctx, amount, recipient)?;
+            }
+            ProposalAction::RegisterGame {
+                game,
+                max_multiplier_bps,
+                bet_ttl_slots,
+            } => {
+                let game_config = ctx
+                    .accounts
+                    .game_config
+                    .as_ref()
+                    .ok_or(GovError::MissingAccount)?;
+                let game_ledger = ctx
+                    .accounts
+                    .game_ledger
+                    .as_ref()
+                    .ok_or(GovError::MissingAccount)?;
+
+                // The CLR charges the rent to its authority; the executor fronts it
+                let rent = Rent::get()?;
+                let lamports = rent
+                    .minimum_balance(8 + GameConfig::LEN)
+                    .checked_add(rent.minimum_balance(8 + GameLedger::LEN))
+                    .ok_or(GovError::MathOverflow)?;
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.executor.to_account_info(),
+                        to: ctx.accounts.gov_authority.to_account_info(),
+                    },
+                );
+                anchor_lang::system_program::transfer(cpi_ctx, lamports)?;
+
+                let cpi_accounts = mutr_clr::cpi::accounts::RegisterGame {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    game_config: game_config.to_account_info(),
+                    game_ledger: game_ledger.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::register_game(cpi_ctx, game, max_multiplier_bps, bet_ttl_slots)?;
+            }
+            ProposalAction::SetPauseFlags { flags } => {
+                let cpi_accounts = mutr_clr::cpi::accounts::SetPauseFlags {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::set_pause_flags(cpi_ctx, flags)?;
+            }
+            ProposalAction::SetGameOracle { game, oracle } => {
+                let game_config = ctx
+                    .accounts
+                    .game_config
+                    .as_ref()
+                    .ok_or(GovError::MissingAccount)?;
+                // The CLR takes any registered game; hold it to the one voted on
+                let (expected, _) =
+                    Pubkey::find_program_address(&[b"game", game.as_ref()], &mutr_clr::ID);
+                require_keys_eq!(game_config.key(), expected, GovError::InvalidGameConfig);
+
+                let cpi_accounts = mutr_clr::cpi::accounts::SetGameEnabled {
+                    state: ctx.accounts.clr_state.to_account_info(),
+                    game_config: game_config.to_account_info(),
+                    admin_log: ctx.accounts.admin_log.to_account_info(),
+                    authority: ctx.accounts.gov_authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.clr_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                );
+                mutr_clr::cpi::set_game_oracle(cpi_ctx, oracle)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Change a proposal applies to the CLR when executed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ProposalAction {
+    UpdateConfig(ConfigUpdate),
+    SetAuthority { new_authority: Pubkey },
+    /// Queues a CLR treasury spend; it pays out after the treasury timelock
+    TreasurySpend { amount: u64, recipient: Pubkey },
+    /// Registers a game with the CLR; the executor pays the rent of its accounts
+    RegisterGame {
+        game: Pubkey,
+        max_multiplier_bps: u32,
+        bet_ttl_slots: u64,
+    },
+    /// Sets or clears the CLR pause flags
+    SetPauseFlags { flags: u8 },
+    /// Sets the Ed25519 oracle key that settles a registered game's bets
+    SetGameOracle { game: Pubkey, oracle: Pubkey },
+}
+
+#[account]
+pub struct GovConfig {
+    pub xmutr_mint: Pubkey,
+    pub vote_vault: Pubkey,
+    pub clr_state: Pubkey,
+
+    pub voting_period_secs: i64,
+    pub quorum_votes: u64,
+    pub proposal_threshold: u64,
+    pub proposal_count: u64,
+
+    pub bump: u8,
+    pub authority_bump: u8,
+}
+
+impl GovConfig {
+    pub const LEN: usize = 32 // xmutr_mint
+        + 32 // vote_vault
+        + 32 // clr_state
+        + 8  // voting_period_secs
+        + 8  // quorum_votes
+        + 8  // proposal_threshold
+        + 8  // proposal_count
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+#[account]
+pub struct VoterRecord {
+    pub owner: Pubkey,
+    pub deposited: u64,
+    /// Deposits cannot be withdrawn before the last voted proposal closes
+    pub locked_until: i64,
+}
+
+impl VoterRecord {
+    pub const LEN: usize = 32 // owner
+        + 8  // deposited
+        + 8; // locked_until
+}
+
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 // id
+        + 32 // proposer
+        + MAX_ACTION_LEN // action
+        + 8  // created_at
+        + 8  // voting_ends_at
+        + 8  // yes_votes
+        + 8  // no_votes
+        + 1  // executed
+        + 1; // bump
+}
+
+/// One per (proposal, voter); its existence prevents double voting.
+#[account]
+pub struct VoteReceipt {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+}
+
+impl VoteReceipt {
+    pub const LEN: usize = 32 // proposal
+        + 32 // voter
+        + 1  // support
+        + 8; // weight
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"gov_config"],
+        bump,
+        space = 8 + GovConfig::LEN
+    )]
+    pub config: Account<'info, GovConfig>,
+
+    /// CHECK: PDA that signs CPIs into the CLR and owns the vote vault
+    #[account(
+        seeds = [b"gov_authority"],
+        bump
+    )]
+    pub gov_authority: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = xmutr_mint.key() == clr_state.load()?.xmutr_mint @ GovError::InvalidMint
+    )]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vote_vault"],
+        bump,
+        token::mint = xmutr_mint,
+        token::authority = gov_authority
+    )]
+    pub vote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(address = clr_state.load()?.authority @ GovError::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositVotes<'info> {
+    #[account(
+        seeds = [b"gov_config"],
+        bump = config.bump,
+        has_one = vote_vault @ GovError::InvalidVault
+    )]
+    pub config: Account<'info, GovConfig>,
+
+    #[account(address = config.xmutr_mint @ GovError::InvalidMint)]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = voter_xmutr_account.mint == config.xmutr_mint @ GovError::InvalidMint,
+        constraint = voter_xmutr_account.owner == voter.key() @ GovError::Unauthorized
+    )]
+    pub voter_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoterRecord::LEN,
+        seeds = [b"voter", voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVotes<'info> {
+    #[account(
+        seeds = [b"gov_config"],
+        bump = config.bump,
+        has_one = vote_vault @ GovError::InvalidVault
+    )]
+    pub config: Account<'info, GovConfig>,
+
+    /// CHECK: PDA signer for the vote vault
+    #[account(
+        seeds = [b"gov_authority"],
+        bump = config.authority_bump
+    )]
+    pub gov_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.xmutr_mint @ GovError::InvalidMint)]
+    pub xmutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = voter_xmutr_account.mint == config.xmutr_mint @ GovError::InvalidMint
+    )]
+    pub voter_xmutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    pub voter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"gov_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GovConfig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::LEN,
+        seeds = [b"proposal", config.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"voter", proposer.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteReceipt::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        seeds = [b"gov_config"],
+        bump = config.bump,
+        has_one = clr_state @ GovError::InvalidClrState
+    )]
+    pub config: Account<'info, GovConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: PDA holding the CLR authority role
+    #[account(
+        mut,
+        seeds = [b"gov_authority"],
+        bump = config.authority_bump
+    )]
+    pub gov_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR `AdminLog` PDA the executed action is recorded in, validated by the CLR
+    #[account(mut)]
+    pub admin_log: UncheckedAccount<'info>,
+
+    /// CHECK: CLR `SpendProposal` PDA to create; only for `TreasurySpend`, validated by the CLR
+    #[account(mut)]
+    pub spend_proposal: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR `GameConfig` PDA; only for `RegisterGame` and `SetGameOracle`, validated by the CLR
+    #[account(mut)]
+    pub game_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR `GameLedger` PDA to create; only for `RegisterGame`, validated by the CLR
+    #[account(mut)]
+    pub game_ledger: Option<UncheckedAccount<'info>>,
+
+    /// Pays rent for accounts the executed action creates
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum GovError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Invalid vote vault")]
+    InvalidVault,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Insufficient deposited votes")]
+    InsufficientVotes,
+    #[msg("Deposited votes are locked by an open vote")]
+    VotesLocked,
+    #[msg("Proposal action too large")]
+    ActionTooLarge,
+    #[msg("Voting has closed")]
+    VotingClosed,
+    #[msg("Voting is still open")]
+    VotingOpen,
+    #[msg("Proposal did not pass")]
+    ProposalNotPassed,
+    #[msg("Proposal already executed")]
+    AlreadyExecuted,
+    #[msg("Account required by the proposal action is missing")]
+    MissingAccount,
+    #[msg("Game config does not match the proposal")]
+    InvalidGameConfig,
+}
@@ -0,0 +1,841 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::{GameConfig, GlobalState};
+use mutr_clr_cpi::{ClrVaults, PayPrize};
+
+declare_id!("Hi1oRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Cards in the deck; card `c` has rank `c % 13` (0 = two .. 12 = ace)
+pub const DECK_SIZE: usize = 52;
+
+/// Ranks per suit
+const RANKS: u8 = 13;
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Hi-lo over a committed deck.
+///
+/// The house operator co-signs `start_session` with `keccak(session || deck || salt)`
+/// for a shuffled deck and deals its first card. The player guesses whether the next
+/// card is higher-or-same or lower-or-same; the operator deals it. Every correct guess
+/// grows the multiplier by `remaining / favorable` over the cards still in the deck. A
+/// bust or a cash-out reveals the deck, which must match the commitment and every card
+/// dealt so far. Wagers go into the CLR vault at start; a cash-out is paid from it
+/// through `send_prize`, a bust leaves the wager with the house. If the operator stops
+/// dealing, the player can claim the running payout once the session expires.
+#[program]
+pub mod mutr_hilo {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR, which pays cash-outs through `send_prize`.
+    pub fn initialize_hilo(
+        ctx: Context<InitializeHilo>,
+        operator: Pubkey,
+        params: HiloParams,
+    ) -> Result<()> {
+        params.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.operator = operator;
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.params = params;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: replace the operator key and game parameters.
+    pub fn update_hilo_config(
+        ctx: Context<UpdateHiloConfig>,
+        operator: Pubkey,
+        params: HiloParams,
+    ) -> Result<()> {
+        params.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.operator = operator;
+        config.params = params;
+        Ok(())
+    }
+
+    /// Start a session escrowing `amount` in the CLR vault. Co-signed by the operator,
+    /// who commits to the deck and deals `first_card`.
+    pub fn start_session(
+        ctx: Context<StartSession>,
+        nonce: u64,
+        amount: u64,
+        commitment: [u8; 32],
+        first_card: u8,
+    ) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        require!(
+            amount >= params.min_bet && amount <= params.max_bet,
+            HiloError::BetOutOfRange
+        );
+        require!((first_card as usize) < DECK_SIZE, HiloError::InvalidCard);
+
+        let clr_vault_before = ctx.accounts.clr_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.clr_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // Credit what reached the vault, net of any transfer fee
+        ctx.accounts.clr_vault.reload()?;
+        let amount = ctx
+            .accounts
+            .clr_vault
+            .amount
+            .checked_sub(clr_vault_before)
+            .ok_or(HiloError::MathOverflow)?;
+        ctx.accounts
+            .record_wager(ctx.accounts.config.authority_bump, amount)?;
+        ctx.accounts
+            .contribute_to_jackpot(ctx.accounts.config.authority_bump, amount)?;
+
+        let session = &mut ctx.accounts.session;
+        session.player = ctx.accounts.player.key();
+        session.nonce = nonce;
+        session.amount = amount;
+        session.commitment = commitment;
+        session.cards = vec![first_card];
+        session.pending_guess = Guess::None;
+        session.multiplier_bps = BPS_DENOMINATOR as u32;
+        session.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(params.session_ttl_slots)
+            .ok_or(HiloError::MathOverflow)?;
+        session.bump = ctx.bumps.session;
+        Ok(())
+    }
+
+    /// Guess the next card. The operator answers with `deal_card` or `bust`.
+    pub fn place_guess(ctx: Context<PlaceGuess>, guess: Guess) -> Result<()> {
+        let session = &mut ctx.accounts.session;
+        require!(
+            session.pending_guess == Guess::None,
+            HiloError::GuessPending
+        );
+        require!(guess != Guess::None, HiloError::InvalidGuess);
+        let (favorable, _) = session.odds(guess);
+        require!(favorable > 0, HiloError::InvalidGuess);
+
+        session.pending_guess = guess;
+        session.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(ctx.accounts.config.params.session_ttl_slots)
+            .ok_or(HiloError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Operator: deal the next card for a correct guess and grow the multiplier.
+    pub fn deal_card(ctx: Context<DealCard>, card: u8) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        let session = &mut ctx.accounts.session;
+        let guess = session.pending_guess;
+        require!(guess != Guess::None, HiloError::NoGuessPending);
+        require!(
+            (card as usize) < DECK_SIZE && !session.cards.contains(&card),
+            HiloError::InvalidCard
+        );
+        require!(session.guess_wins(guess, card), HiloError::LosingCard);
+
+        let (favorable, remaining) = session.odds(guess);
+        let next = (session.multiplier_bps as u64)
+            .checked_mul(remaining)
+            .ok_or(HiloError::MathOverflow)?
+            / favorable;
+        session.multiplier_bps = next.min(params.max_multiplier_bps as u64) as u32;
+        session.cards.push(card);
+        session.pending_guess = Guess::None;
+        session.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(params.session_ttl_slots)
+            .ok_or(HiloError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Operator: the next card loses the pending guess. The revealed deck must match
+    /// the commitment; the wager stays in the CLR and the session is closed.
+    pub fn bust(ctx: Context<Bust>, deck: [u8; DECK_SIZE], salt: [u8; 32]) -> Result<()> {
+        let session = &ctx.accounts.session;
+        let guess = session.pending_guess;
+        require!(guess != Guess::None, HiloError::NoGuessPending);
+        verify_deck(&session.key(), session, &deck, &salt)?;
+        require!(
+            !session.guess_wins(guess, deck[session.cards.len()]),
+            HiloError::DeckMismatch
+        );
+
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(SessionFinished {
+            event_nonce,
+            player: session.player,
+            session: session.key(),
+            amount: session.amount,
+            cards: deck[..=session.cards.len()].to_vec(),
+            payout: 0,
+        });
+        Ok(())
+    }
+
+    /// Cash out the running multiplier, co-signed by the operator revealing the deck.
+    pub fn cash_out(ctx: Context<CashOut>, deck: [u8; DECK_SIZE], salt: [u8; 32]) -> Result<()> {
+        let session = &ctx.accounts.session;
+        require!(
+            session.pending_guess == Guess::None,
+            HiloError::GuessPending
+        );
+        require!(session.cards.len() > 1, HiloError::NothingWon);
+        verify_deck(&session.key(), session, &deck, &salt)?;
+
+        let payout = session_payout(session, ctx.accounts.config.params.house_edge_bps)?;
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(SessionFinished {
+            event_nonce,
+            player: session.player,
+            session: session.key(),
+            amount: session.amount,
+            cards: session.cards.clone(),
+            payout,
+        });
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            payout,
+        )
+    }
+
+    /// The operator stopped dealing: once the session expires the player is paid the
+    /// running payout (the wager back if no guess was won).
+    pub fn claim_expired_session(ctx: Context<ClaimExpiredSession>) -> Result<()> {
+        let session = &ctx.accounts.session;
+        require!(
+            Clock::get()?.slot > session.expiry_slot,
+            HiloError::SessionNotExpired
+        );
+        let payout = if session.cards.len() == 1 {
+            session.amount
+        } else {
+            session_payout(session, ctx.accounts.config.params.house_edge_bps)?
+        };
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            payout,
+        )
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// `amount * multiplier` with the house edge taken off.
+fn session_payout(session: &HiloSession, house_edge_bps: u16) -> Result<u64> {
+    let gross = mutr_clr::bet_payout(session.amount, session.multiplier_bps)?;
+    let edge = (gross as u128 * house_edge_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    Ok(gross - edge)
+}
+
+/// Check the revealed deck against the commitment and the cards dealt so far.
+fn verify_deck(
+    session_key: &Pubkey,
+    session: &HiloSession,
+    deck: &[u8; DECK_SIZE],
+    salt: &[u8; 32],
+) -> Result<()> {
+    let hash = keccak::hashv(&[session_key.as_ref(), deck, salt]);
+    require!(hash.0 == session.commitment, HiloError::DeckMismatch);
+
+    let mut seen = 0u64;
+    for &card in deck {
+        require!(
+            (card as usize) < DECK_SIZE && seen & (1 << card) == 0,
+            HiloError::DeckMismatch
+        );
+        seen |= 1 << card;
+    }
+    require!(
+        deck[..session.cards.len()] == session.cards[..],
+        HiloError::DeckMismatch
+    );
+    Ok(())
+}
+
+/// Pay `amount` from the CLR vault to the player, streamed when it is over the CLR's
+/// vesting threshold (see `mutr_clr_cpi::PayPrize`).
+fn send_from_clr(accounts: &ClrPrize, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let vaults = ClrVaults {
+        mutr_mint: accounts.mutr_mint.key(),
+        clr_vault: accounts.clr_vault.key(),
+        token_program: accounts.token_program.key(),
+    };
+    PayPrize {
+        game: accounts.game_authority.key(),
+        winner: accounts.player_mutr_account.owner,
+        winner_mutr_account: accounts.player_mutr_account.key(),
+        payer: accounts.payer.key(),
+        amount,
+    }
+    .invoke_signed(
+        &vaults,
+        &accounts.clr_state,
+        accounts.clr_vault.amount,
+        &accounts.to_account_infos(),
+        &[authority_seeds],
+    )
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Tunable game parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct HiloParams {
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Cap on the running multiplier
+    pub max_multiplier_bps: u32,
+    /// Slots the operator has to deal before the player may claim the session
+    pub session_ttl_slots: u64,
+}
+
+impl HiloParams {
+    pub const LEN: usize = 2 // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 4  // max_multiplier_bps
+        + 8; // session_ttl_slots
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (self.house_edge_bps as u64) < BPS_DENOMINATOR
+                && self.min_bet > 0
+                && self.min_bet <= self.max_bet
+                && self.max_multiplier_bps as u64 > BPS_DENOMINATOR
+                && self.session_ttl_slots > 0,
+            HiloError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct HiloConfig {
+    pub authority: Pubkey,
+    /// House key that commits to decks and deals cards
+    pub operator: Pubkey,
+    pub clr_state: Pubkey,
+    pub params: HiloParams,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl HiloConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // operator
+        + 32 // clr_state
+        + HiloParams::LEN // params
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Guess {
+    None,
+    /// Next card ranks higher than or the same as the current one
+    Higher,
+    /// Next card ranks lower than or the same as the current one
+    Lower,
+}
+
+/// A session in progress.
+#[account]
+pub struct HiloSession {
+    pub player: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    /// `keccak(session || deck || salt)`
+    pub commitment: [u8; 32],
+    /// Cards dealt so far, in deck order
+    pub cards: Vec<u8>,
+    pub pending_guess: Guess,
+    pub multiplier_bps: u32,
+    /// Slot after which the player may claim the session if the operator is silent
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+impl HiloSession {
+    pub const LEN: usize = 32 // player
+        + 8  // nonce
+        + 8  // amount
+        + 32 // commitment
+        + 4 + DECK_SIZE // cards
+        + 1  // pending_guess
+        + 4  // multiplier_bps
+        + 8  // expiry_slot
+        + 1; // bump
+
+    fn current_rank(&self) -> u8 {
+        self.cards.last().map_or(0, |card| card % RANKS)
+    }
+
+    fn guess_wins(&self, guess: Guess, card: u8) -> bool {
+        let rank = card % RANKS;
+        match guess {
+            Guess::None => false,
+            Guess::Higher => rank >= self.current_rank(),
+            Guess::Lower => rank <= self.current_rank(),
+        }
+    }
+
+    /// `(favorable, remaining)` cards left in the deck for `guess`.
+    fn odds(&self, guess: Guess) -> (u64, u64) {
+        let remaining = (DECK_SIZE - self.cards.len()) as u64;
+        let favorable = (0..DECK_SIZE as u8)
+            .filter(|card| !self.cards.contains(card) && self.guess_wins(guess, *card))
+            .count() as u64;
+        (favorable, remaining)
+    }
+}
+
+/// A finished session with the cards dealt, for the frontend and for verification.
+#[event]
+pub struct SessionFinished {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub session: Pubkey,
+    pub amount: u64,
+    pub cards: Vec<u8>,
+    pub payout: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeHilo<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HiloConfig::LEN,
+        seeds = [b"hilo_config"],
+        bump
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    /// CHECK: PDA signer registered as the hi-lo game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateHiloConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        has_one = authority @ HiloError::Unauthorized
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct StartSession<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        has_one = clr_state @ HiloError::InvalidClrState,
+        has_one = operator @ HiloError::Unauthorized
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    /// CHECK: PDA signer registered as the hi-lo game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Sessions only start while the game is enabled in the CLR
+    #[account(
+        mut,
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ HiloError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ HiloError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + HiloSession::LEN,
+        seeds = [b"hilo", player.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, HiloSession>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub operator: Signer<'info>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StartSession<'info> {
+    /// Feed the CLR progressive jackpot its share of the wager.
+    /// The jackpot accounts are required once the jackpot exists.
+    fn contribute_to_jackpot(&self, authority_bump: u8, wager: u64) -> Result<()> {
+        let (Some(jackpot), Some(jackpot_vault)) = (&self.clr_jackpot, &self.clr_jackpot_vault)
+        else {
+            require!(
+                self.clr_state.load()?.jackpot_enabled == 0,
+                HiloError::MissingJackpotAccounts
+            );
+            return Ok(());
+        };
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ContributeJackpot {
+            state: self.clr_state.to_account_info(),
+            mutr_mint: self.mutr_mint.to_account_info(),
+            jackpot: jackpot.to_account_info(),
+            clr_vault: self.clr_vault.to_account_info(),
+            jackpot_vault: jackpot_vault.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game: self.game_authority.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::contribute_jackpot(cpi_ctx, wager)?;
+        Ok(())
+    }
+
+    /// Run the wager through the CLR's player checks.
+    fn record_wager(&self, authority_bump: u8, amount: u64) -> Result<()> {
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::RecordGameWager {
+            state: self.clr_state.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game_ledger: self.clr_game_ledger.to_account_info(),
+            player_state: self.clr_player_state.to_account_info(),
+            user_stats: self
+                .clr_user_stats
+                .as_ref()
+                .map(|stats| stats.to_account_info()),
+            player: self.player.to_account_info(),
+            game: self.game_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::record_game_wager(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct PlaceGuess<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ HiloError::Unauthorized
+    )]
+    pub session: Account<'info, HiloSession>,
+
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DealCard<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        has_one = operator @ HiloError::Unauthorized
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    #[account(mut)]
+    pub session: Account<'info, HiloSession>,
+
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Bust<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        has_one = operator @ HiloError::Unauthorized,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ HiloError::InvalidClrState
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ HiloError::Unauthorized,
+        close = player
+    )]
+    pub session: Account<'info, HiloSession>,
+
+    pub clr_accounts: ClrEvent<'info>,
+
+    /// CHECK: receives the session account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub operator: Signer<'info>,
+}
+
+/// CLR accounts for numbering an event through `next_event_nonce`.
+#[derive(Accounts)]
+pub struct ClrEvent<'info> {
+    /// CHECK: PDA signer registered as the hi-lo game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+}
+
+/// CLR accounts for paying a player through `send_prize` or `send_vested_prize`.
+#[derive(Accounts)]
+pub struct ClrPrize<'info> {
+    /// CHECK: PDA signer registered as the hi-lo game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the player's CLR `PrizeVesting`; created and checked by the CLR when a prize
+    /// is over its vesting threshold
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    /// Funds the player's `PrizeVesting` when a prize opens it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CashOut<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        has_one = operator @ HiloError::Unauthorized,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ HiloError::InvalidClrState
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ HiloError::Unauthorized,
+        constraint = clr_accounts.player_mutr_account.owner == session.player @ HiloError::Unauthorized,
+        close = player
+    )]
+    pub session: Account<'info, HiloSession>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimExpiredSession<'info> {
+    #[account(
+        seeds = [b"hilo_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ HiloError::InvalidClrState
+    )]
+    pub config: Account<'info, HiloConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ HiloError::Unauthorized,
+        constraint = clr_accounts.player_mutr_account.owner == session.player @ HiloError::Unauthorized,
+        close = player
+    )]
+    pub session: Account<'info, HiloSession>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[error_code]
+pub enum HiloError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Invalid card")]
+    InvalidCard,
+    #[msg("Guess cannot win")]
+    InvalidGuess,
+    #[msg("A guess is waiting for the operator")]
+    GuessPending,
+    #[msg("No guess is waiting for the operator")]
+    NoGuessPending,
+    #[msg("Card loses the guess; reveal the deck with bust")]
+    LosingCard,
+    #[msg("No guess has been won")]
+    NothingWon,
+    #[msg("Revealed deck does not match the commitment")]
+    DeckMismatch,
+    #[msg("Session has not expired")]
+    SessionNotExpired,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpotAccounts,
+}
@@ -0,0 +1,602 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{risk, Bet, GameConfig, GlobalState, UserStats, VipConfig};
+
+declare_id!("LimboRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Resolution of the result draw: `r` is drawn from `0..ROLL_RANGE`
+const ROLL_RANGE: u64 = 1_000_000;
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod mutr_limbo {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR (provider `None`: limbo settles bets from its own randomness).
+    pub fn initialize_limbo(
+        ctx: Context<InitializeLimbo>,
+        params: LimboParams,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        params.validate()?;
+        require!(
+            randomness_provider != RandomnessProvider::None,
+            LimboError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.params = params;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: replace the game parameters.
+    pub fn update_limbo_config(ctx: Context<UpdateLimboConfig>, params: LimboParams) -> Result<()> {
+        params.validate()?;
+        ctx.accounts.config.params = params;
+        Ok(())
+    }
+
+    /// Wager `amount` that the result reaches `target_multiplier_bps`, paying the target
+    /// on a win. The target is capped by the game maximum and by the CLR risk limit for
+    /// the current vault size. The player's CLR VIP tier discounts the house edge when its
+    /// accounts are passed. A fresh randomness request must accompany the bet, which the
+    /// CLR rejects once past `deadline_slot`, when given.
+    pub fn play(
+        ctx: Context<Play>,
+        nonce: u64,
+        amount: u64,
+        target_multiplier_bps: u32,
+        deadline_slot: Option<u64>,
+    ) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        require!(
+            amount >= params.min_bet && amount <= params.max_bet,
+            LimboError::BetOutOfRange
+        );
+        let max_target_bps = params.max_target_bps.min(risk::max_multiplier_bps(
+            &*ctx.accounts.clr_state.load()?,
+            ctx.accounts.clr_vault.amount,
+            amount,
+        ));
+        require!(
+            target_multiplier_bps as u64 > BPS_DENOMINATOR
+                && target_multiplier_bps <= max_target_bps,
+            LimboError::InvalidTarget
+        );
+
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let cpi_accounts = mutr_clr::cpi::accounts::PlaceBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            randomness_account: None,
+            jackpot: ctx
+                .accounts
+                .clr_jackpot
+                .as_ref()
+                .map(|jackpot| jackpot.to_account_info()),
+            jackpot_vault: ctx
+                .accounts
+                .clr_jackpot_vault
+                .as_ref()
+                .map(|jackpot_vault| jackpot_vault.to_account_info()),
+            usd_pricing: ctx
+                .accounts
+                .clr_usd_pricing
+                .as_ref()
+                .map(|usd_pricing| usd_pricing.to_account_info()),
+            price_update: ctx
+                .accounts
+                .clr_price_update
+                .as_ref()
+                .map(|price_update| price_update.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            referral_config: None,
+            referrer: None,
+            referral_vault: None,
+            player_state: ctx.accounts.clr_player_state.to_account_info(),
+            session: ctx
+                .accounts
+                .clr_session
+                .as_ref()
+                .map(|session| session.to_account_info()),
+            player: ctx.accounts.player.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.clr_program.to_account_info(), cpi_accounts);
+        mutr_clr::cpi::place_bet(cpi_ctx, nonce, amount, target_multiplier_bps, deadline_slot)?;
+
+        let limbo_bet = &mut ctx.accounts.limbo_bet;
+        limbo_bet.player = ctx.accounts.player.key();
+        limbo_bet.bet = ctx.accounts.bet.key();
+        limbo_bet.target_multiplier_bps = target_multiplier_bps;
+        limbo_bet.house_edge_bps = mutr_clr::vip::discounted_bps(
+            ctx.accounts.clr_vip_config.as_deref(),
+            ctx.accounts.clr_user_stats.as_deref(),
+            ctx.accounts.config.params.house_edge_bps,
+        );
+        limbo_bet.randomness_account = ctx.accounts.randomness_account.key();
+        limbo_bet.placed_slot = slot;
+        limbo_bet.bump = ctx.bumps.limbo_bet;
+        Ok(())
+    }
+
+    /// Permissionless: draw the result multiplier from the revealed randomness and
+    /// settle the CLR bet. The limbo bet account is closed back to the player.
+    pub fn settle_play(ctx: Context<SettlePlay>) -> Result<()> {
+        let limbo_bet = &ctx.accounts.limbo_bet;
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            limbo_bet.placed_slot,
+        )?;
+        let result_multiplier_bps = result_multiplier_bps(&value, limbo_bet.house_edge_bps);
+        let won = result_multiplier_bps >= limbo_bet.target_multiplier_bps as u64;
+
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(LimboSettled {
+            event_nonce,
+            player: limbo_bet.player,
+            bet: limbo_bet.bet,
+            target_multiplier_bps: limbo_bet.target_multiplier_bps,
+            result_multiplier_bps,
+            won,
+        });
+
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[ctx.accounts.config.authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ResolveBet {
+            state: ctx.accounts.clr_state.to_account_info(),
+            stats: ctx.accounts.clr_stats.to_account_info(),
+            mutr_mint: ctx.accounts.mutr_mint.to_account_info(),
+            game_config: ctx.accounts.clr_game_config.to_account_info(),
+            game_ledger: ctx.accounts.clr_game_ledger.to_account_info(),
+            bet: ctx.accounts.bet.to_account_info(),
+            clr_vault: ctx.accounts.clr_vault.to_account_info(),
+            player_mutr_account: ctx.accounts.player_mutr_account.to_account_info(),
+            usd_pricing: None,
+            price_update: None,
+            leaderboard: ctx
+                .accounts
+                .clr_leaderboard
+                .as_ref()
+                .map(|leaderboard| leaderboard.to_account_info()),
+            season: ctx
+                .accounts
+                .clr_season
+                .as_ref()
+                .map(|season| season.to_account_info()),
+            season_player: ctx
+                .accounts
+                .clr_season_player
+                .as_ref()
+                .map(|season_player| season_player.to_account_info()),
+            season_leaderboard: ctx
+                .accounts
+                .clr_season_leaderboard
+                .as_ref()
+                .map(|season_leaderboard| season_leaderboard.to_account_info()),
+            loyalty_config: ctx
+                .accounts
+                .clr_loyalty_config
+                .as_ref()
+                .map(|loyalty_config| loyalty_config.to_account_info()),
+            user_stats: ctx
+                .accounts
+                .clr_user_stats
+                .as_ref()
+                .map(|user_stats| user_stats.to_account_info()),
+            rakeback_config: ctx
+                .accounts
+                .clr_rakeback_config
+                .as_ref()
+                .map(|rakeback_config| rakeback_config.to_account_info()),
+            game: ctx.accounts.game_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::resolve_bet(cpi_ctx, won)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Result multiplier `(1 - edge) / (1 - r)` for `r` uniform in `[0, 1)`, so a target
+/// `t` is reached with probability `(1 - edge) / t`.
+fn result_multiplier_bps(value: &[u8; 32], house_edge_bps: u16) -> u64 {
+    let mut roll_bytes = [0u8; 8];
+    roll_bytes.copy_from_slice(&value[..8]);
+    let r = u64::from_le_bytes(roll_bytes) % ROLL_RANGE;
+    (BPS_DENOMINATOR - house_edge_bps as u64) * ROLL_RANGE / (ROLL_RANGE - r)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Tunable game parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct LimboParams {
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Highest target a player may pick, before the CLR risk limit
+    pub max_target_bps: u32,
+}
+
+impl LimboParams {
+    pub const LEN: usize = 2 // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 4; // max_target_bps
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (self.house_edge_bps as u64) < BPS_DENOMINATOR
+                && self.min_bet > 0
+                && self.min_bet <= self.max_bet
+                && self.max_target_bps as u64 > BPS_DENOMINATOR,
+            LimboError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct LimboConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    pub params: LimboParams,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl LimboConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + LimboParams::LEN // params
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// An open bet, waiting for its randomness to be revealed.
+#[account]
+pub struct LimboBet {
+    pub player: Pubkey,
+    /// CLR `Bet` escrowing the wager
+    pub bet: Pubkey,
+    pub target_multiplier_bps: u32,
+    /// House edge of the draw, discounted by the player's VIP tier when the bet was placed
+    pub house_edge_bps: u16,
+    pub randomness_account: Pubkey,
+    pub placed_slot: u64,
+    pub bump: u8,
+}
+
+impl LimboBet {
+    pub const LEN: usize = 32 // player
+        + 32 // bet
+        + 4  // target_multiplier_bps
+        + 2  // house_edge_bps
+        + 32 // randomness_account
+        + 8  // placed_slot
+        + 1; // bump
+}
+
+#[event]
+pub struct LimboSettled {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub bet: Pubkey,
+    pub target_multiplier_bps: u32,
+    pub result_multiplier_bps: u64,
+    pub won: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeLimbo<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LimboConfig::LEN,
+        seeds = [b"limbo_config"],
+        bump
+    )]
+    pub config: Account<'info, LimboConfig>,
+
+    /// CHECK: PDA signer registered as the limbo game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLimboConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"limbo_config"],
+        bump = config.bump,
+        has_one = authority @ LimboError::Unauthorized
+    )]
+    pub config: Account<'info, LimboConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Play<'info> {
+    #[account(
+        seeds = [b"limbo_config"],
+        bump = config.bump,
+        has_one = clr_state @ LimboError::InvalidClrState
+    )]
+    pub config: Account<'info, LimboConfig>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Registry entry of the limbo `game_authority` in the CLR
+    #[account(mut)]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: created by the CLR `place_bet`
+    #[account(mut)]
+    pub bet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LimboBet::LEN,
+        seeds = [b"limbo", bet.key().as_ref()],
+        bump
+    )]
+    pub limbo_bet: Account<'info, LimboBet>,
+
+    /// Sizes the risk limit on the target
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ LimboError::InvalidVault
+    )]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR USD limits; required with the price update while they are enabled
+    pub clr_usd_pricing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price update; checked by the CLR
+    pub clr_price_update: Option<UncheckedAccount<'info>>,
+
+    /// CLR VIP config and the player's stats; the player's tier discounts the house edge
+    /// when both are passed. The stats are required by the CLR daily wager and rate limits
+    pub clr_vip_config: Option<Account<'info, VipConfig>>,
+
+    #[account(
+        mut,
+        constraint = clr_user_stats.owner == player.key() @ LimboError::Unauthorized
+    )]
+    pub clr_user_stats: Option<Account<'info, UserStats>>,
+
+    /// CHECK: the player's first CLR position, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: CLR session whose key signs in the player's place; checked by the CLR
+    #[account(mut)]
+    pub clr_session: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the bettor; the CLR requires their signature unless a session of theirs
+    /// is passed
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// Pays for the bet accounts: the player, or their session key
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettlePlay<'info> {
+    #[account(
+        seeds = [b"limbo_config"],
+        bump = config.bump,
+        has_one = clr_state @ LimboError::InvalidClrState
+    )]
+    pub config: Account<'info, LimboConfig>,
+
+    /// CHECK: PDA signer registered as the limbo game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    /// CHECK: CLR winners leaderboard; a winning bet is offered to it when passed
+    #[account(mut)]
+    pub clr_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR season accounts; the bet counts toward the season when all are passed
+    #[account(mut)]
+    pub clr_season: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_player: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_season_leaderboard: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR loyalty config and the player's stats; the wager earns loyalty points
+    /// when both are passed
+    pub clr_loyalty_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the CLR
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR rakeback config; the bet's rakeback accrues to the player's stats when
+    /// both are passed
+    pub clr_rakeback_config: Option<UncheckedAccount<'info>>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: CLR ledger of the game's bets; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"limbo", bet.key().as_ref()],
+        bump = limbo_bet.bump,
+        has_one = bet @ LimboError::InvalidBet,
+        has_one = player @ LimboError::InvalidBet,
+        close = player
+    )]
+    pub limbo_bet: Account<'info, LimboBet>,
+
+    #[account(mut)]
+    pub bet: AccountLoader<'info, Bet>,
+
+    /// CHECK: must be the request committed at bet time; parsed by `randomness`
+    #[account(address = limbo_bet.randomness_account @ LimboError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub player_mutr_account: Account<'info, TokenAccount>,
+
+    /// CHECK: receives the limbo bet account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum LimboError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Target multiplier outside the allowed range")]
+    InvalidTarget,
+    #[msg("Limbo bet does not match the CLR bet")]
+    InvalidBet,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+}
@@ -0,0 +1,1032 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+
+declare_id!("LotteryRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum number of prize tiers
+pub const MAX_TIERS: usize = 4;
+
+/// Maximum number of winning tickets drawn per round, across all tiers
+pub const MAX_WINNERS: usize = 32;
+
+/// Lottery rounds.
+///
+/// A round sells tickets for `sale_slots`. Once its draw slot has passed anyone may
+/// request the draw with a fresh randomness request; the CLR cut of the ticket revenue
+/// is deposited into the CLR as profit at that point, and the rest, plus whatever rolled
+/// over from earlier rounds, is the prize pool. The reveal picks the winning ticket of
+/// every prize slot: each tier splits its share of the pool evenly between its winners.
+/// Winners claim within `claim_window_slots`; what is left unclaimed then rolls over into
+/// the next round.
+///
+/// Selling -> Drawing -> Drawn -> Closed. A round that sold nothing rolls its whole
+/// pool over when drawn.
+#[program]
+pub mod mutr_lottery {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR so draws can deposit the CLR cut.
+    pub fn initialize_lottery(
+        ctx: Context<InitializeLottery>,
+        params: LotteryParams,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        params.validate()?;
+        require!(
+            randomness_provider != RandomnessProvider::None,
+            LotteryError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.lottery_vault = ctx.accounts.lottery_vault.key();
+        config.params = params;
+        config.randomness_provider = randomness_provider;
+        config.round_count = 0;
+        config.selling = false;
+        config.rollover = 0;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: replace the lottery parameters. Rounds opened afterwards pick them up.
+    pub fn update_lottery_config(
+        ctx: Context<UpdateLotteryConfig>,
+        params: LotteryParams,
+    ) -> Result<()> {
+        params.validate()?;
+        ctx.accounts.config.params = params;
+        Ok(())
+    }
+
+    /// Permissionless: open the next round once the previous one has stopped selling.
+    /// The round snapshots the current ticket price, CLR cut and prize tiers.
+    pub fn open_round(ctx: Context<OpenRound>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.selling, LotteryError::RoundStillSelling);
+
+        let slot = Clock::get()?.slot;
+        let round = &mut ctx.accounts.round;
+        round.id = config.round_count;
+        round.ticket_price = config.params.ticket_price;
+        round.clr_cut_bps = config.params.clr_cut_bps;
+        round.tier_count = config.params.tier_count;
+        round.tiers = config.params.tiers;
+        round.draw_slot = slot
+            .checked_add(config.params.sale_slots)
+            .ok_or(LotteryError::MathOverflow)?;
+        round.status = RoundStatus::Selling;
+        round.bump = ctx.bumps.round;
+
+        config.round_count = config
+            .round_count
+            .checked_add(1)
+            .ok_or(LotteryError::MathOverflow)?;
+        config.selling = true;
+        Ok(())
+    }
+
+    /// Buy `count` consecutive tickets in a selling round.
+    pub fn buy_tickets(ctx: Context<BuyTickets>, round_id: u64, count: u32) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Selling,
+            LotteryError::RoundNotSelling
+        );
+        require!(
+            Clock::get()?.slot < round.draw_slot,
+            LotteryError::SalesClosed
+        );
+        require!(count > 0, LotteryError::InvalidTicketCount);
+
+        let cost = round
+            .ticket_price
+            .checked_mul(count as u64)
+            .ok_or(LotteryError::MathOverflow)?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_mutr_account.to_account_info(),
+            to: ctx.accounts.lottery_vault.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, cost)?;
+
+        let tickets = &mut ctx.accounts.tickets;
+        tickets.owner = ctx.accounts.buyer.key();
+        tickets.round_id = round_id;
+        tickets.first_ticket = round.ticket_count;
+        tickets.count = count;
+        tickets.bump = ctx.bumps.tickets;
+
+        let round = &mut ctx.accounts.round;
+        round.ticket_count = round
+            .ticket_count
+            .checked_add(count)
+            .ok_or(LotteryError::MathOverflow)?;
+        round.revenue = round
+            .revenue
+            .checked_add(cost)
+            .ok_or(LotteryError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Permissionless: close sales once the draw slot has passed and commit the draw to
+    /// a fresh randomness request. The first request also fixes the prize pool and
+    /// deposits the CLR cut; a draw whose request was never revealed can be requested
+    /// again after `draw_ttl_slots`.
+    pub fn request_draw(ctx: Context<RequestDraw>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let round = &ctx.accounts.round;
+        let slot = Clock::get()?.slot;
+        match round.status {
+            RoundStatus::Selling => {
+                require!(slot >= round.draw_slot, LotteryError::DrawNotDue)
+            }
+            RoundStatus::Drawing => require!(
+                slot > round
+                    .request_slot
+                    .saturating_add(config.params.draw_ttl_slots),
+                LotteryError::DrawPending
+            ),
+            _ => return err!(LotteryError::RoundNotSelling),
+        }
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        if round.status == RoundStatus::Selling {
+            let clr_cut = (round.revenue as u128 * round.clr_cut_bps as u128
+                / BPS_DENOMINATOR as u128) as u64;
+            if clr_cut > 0 {
+                deposit_clr_cut(&ctx.accounts.clr_accounts, config.authority_bump, clr_cut)?;
+            }
+
+            let prize_pool = round
+                .revenue
+                .checked_sub(clr_cut)
+                .and_then(|pool| pool.checked_add(config.rollover))
+                .ok_or(LotteryError::MathOverflow)?;
+            let round = &mut ctx.accounts.round;
+            round.clr_cut = clr_cut;
+            round.prize_pool = prize_pool;
+            round.status = RoundStatus::Drawing;
+
+            let config = &mut ctx.accounts.config;
+            config.rollover = 0;
+            config.selling = false;
+        }
+
+        let round = &mut ctx.accounts.round;
+        round.randomness_account = ctx.accounts.randomness_account.key();
+        round.request_slot = slot;
+        Ok(())
+    }
+
+    /// Permissionless: pick the winning ticket of every prize slot from the revealed
+    /// randomness. Winners are drawn independently, so one ticket may win more than one
+    /// slot.
+    pub fn draw(ctx: Context<Draw>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let round = &ctx.accounts.round;
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            round.request_slot,
+        )?;
+
+        if round.ticket_count == 0 {
+            let prize_pool = round.prize_pool;
+            let config = &mut ctx.accounts.config;
+            config.rollover = config
+                .rollover
+                .checked_add(prize_pool)
+                .ok_or(LotteryError::MathOverflow)?;
+            let round = &mut ctx.accounts.round;
+            round.status = RoundStatus::Closed;
+            let clr = &ctx.accounts.clr_accounts;
+            let event_nonce = next_event_nonce(
+                &clr.clr_program,
+                &clr.clr_state,
+                &clr.clr_game_config,
+                &clr.game_authority,
+                ctx.accounts.config.authority_bump,
+            )?;
+            emit!(RoundDrawn {
+                event_nonce,
+                round_id: round.id,
+                ticket_count: 0,
+                prize_pool,
+                winning_tickets: [0; MAX_WINNERS],
+                winner_count: 0,
+            });
+            return Ok(());
+        }
+
+        let claim_window_slots = ctx.accounts.config.params.claim_window_slots;
+        let round = &mut ctx.accounts.round;
+        let winner_count = round.winner_count();
+        for index in 0..winner_count {
+            let hash = keccak::hashv(&[value.as_ref(), &[index as u8]]).to_bytes();
+            let mut draw_bytes = [0u8; 8];
+            draw_bytes.copy_from_slice(&hash[..8]);
+            round.winning_tickets[index] =
+                (u64::from_le_bytes(draw_bytes) % round.ticket_count as u64) as u32;
+        }
+        round.status = RoundStatus::Drawn;
+        round.claim_deadline_slot = slot
+            .checked_add(claim_window_slots)
+            .ok_or(LotteryError::MathOverflow)?;
+
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(RoundDrawn {
+            event_nonce,
+            round_id: round.id,
+            ticket_count: round.ticket_count,
+            prize_pool: round.prize_pool,
+            winning_tickets: round.winning_tickets,
+            winner_count: winner_count as u8,
+        });
+        Ok(())
+    }
+
+    /// Claim the prize of `winner_slot` with the ticket batch holding its winning ticket.
+    pub fn claim_prize(ctx: Context<ClaimPrize>, winner_slot: u8) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Drawn,
+            LotteryError::RoundNotDrawn
+        );
+        require!(
+            Clock::get()?.slot <= round.claim_deadline_slot,
+            LotteryError::ClaimWindowClosed
+        );
+        let index = winner_slot as usize;
+        require!(
+            index < round.winner_count(),
+            LotteryError::InvalidWinnerSlot
+        );
+        require!(
+            round.claimed_mask & (1 << index) == 0,
+            LotteryError::AlreadyClaimed
+        );
+        let winning_ticket = round.winning_tickets[index];
+        require!(
+            ctx.accounts.tickets.holds(winning_ticket),
+            LotteryError::NotAWinner
+        );
+
+        let prize = round.slot_prize(index)?;
+        game_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.lottery_vault.to_account_info(),
+            ctx.accounts.owner_mutr_account.to_account_info(),
+            ctx.accounts.game_authority.to_account_info(),
+            ctx.accounts.config.authority_bump,
+            prize,
+        )?;
+
+        let round = &mut ctx.accounts.round;
+        round.claimed_mask |= 1 << index;
+        round.claimed_total = round
+            .claimed_total
+            .checked_add(prize)
+            .ok_or(LotteryError::MathOverflow)?;
+
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(PrizeClaimed {
+            event_nonce,
+            round_id: round.id,
+            owner: ctx.accounts.owner.key(),
+            winner_slot,
+            ticket: winning_ticket,
+            prize,
+        });
+        Ok(())
+    }
+
+    /// Permissionless: close a drawn round after its claim window and roll what was not
+    /// claimed (rounding dust included) into the next round's pool.
+    pub fn close_round(ctx: Context<CloseRound>) -> Result<()> {
+        let round = &ctx.accounts.round;
+        require!(
+            round.status == RoundStatus::Drawn,
+            LotteryError::RoundNotDrawn
+        );
+        require!(
+            Clock::get()?.slot > round.claim_deadline_slot,
+            LotteryError::ClaimWindowOpen
+        );
+
+        let unclaimed = round
+            .prize_pool
+            .checked_sub(round.claimed_total)
+            .ok_or(LotteryError::MathOverflow)?;
+        let config = &mut ctx.accounts.config;
+        config.rollover = config
+            .rollover
+            .checked_add(unclaimed)
+            .ok_or(LotteryError::MathOverflow)?;
+        ctx.accounts.round.status = RoundStatus::Closed;
+        Ok(())
+    }
+
+    /// Close a ticket batch of a closed round and refund its rent to the owner.
+    pub fn close_tickets(ctx: Context<CloseTickets>) -> Result<()> {
+        require!(
+            ctx.accounts.round.status == RoundStatus::Closed,
+            LotteryError::RoundNotClosed
+        );
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Move the CLR cut of a round's revenue from the lottery vault into the CLR as profit.
+fn deposit_clr_cut(accounts: &ClrDeposit, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::DepositGameProfit {
+        state: accounts.clr_state.to_account_info(),
+        stats: accounts.clr_stats.to_account_info(),
+        mutr_mint: accounts.mutr_mint.to_account_info(),
+        clr_vault: accounts.clr_vault.to_account_info(),
+        source: accounts.lottery_vault.to_account_info(),
+        game_config: accounts.clr_game_config.to_account_info(),
+        game: accounts.game_authority.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.clr_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    mutr_clr::cpi::deposit_game_profit(cpi_ctx, amount)
+}
+
+/// Transfer `amount` out of the lottery vault, signed by the game authority PDA.
+fn game_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    game_authority: AccountInfo<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = Transfer {
+        from,
+        to,
+        authority: game_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// A prize tier: `share_bps` of the pool split evenly between `winners` tickets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PrizeTier {
+    pub share_bps: u16,
+    pub winners: u8,
+}
+
+impl PrizeTier {
+    pub const LEN: usize = 2 // share_bps
+        + 1; // winners
+}
+
+/// Tunable lottery parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct LotteryParams {
+    pub ticket_price: u64,
+    /// Share of ticket revenue deposited into the CLR as profit
+    pub clr_cut_bps: u16,
+    /// Slots a round sells tickets after opening
+    pub sale_slots: u64,
+    /// Slots winners have to claim after the draw
+    pub claim_window_slots: u64,
+    /// Slots after a draw request before an unrevealed draw may be requested again
+    pub draw_ttl_slots: u64,
+    pub tier_count: u8,
+    /// Tiers from the top prize down; only the first `tier_count` are used
+    pub tiers: [PrizeTier; MAX_TIERS],
+}
+
+impl LotteryParams {
+    pub const LEN: usize = 8 // ticket_price
+        + 2  // clr_cut_bps
+        + 8  // sale_slots
+        + 8  // claim_window_slots
+        + 8  // draw_ttl_slots
+        + 1  // tier_count
+        + PrizeTier::LEN * MAX_TIERS; // tiers
+
+    /// Tier shares must add up to the whole pool and every tier needs a winner.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.ticket_price > 0
+                && self.clr_cut_bps as u64 <= BPS_DENOMINATOR
+                && self.sale_slots > 0
+                && self.claim_window_slots > 0
+                && self.draw_ttl_slots > 0
+                && (1..=MAX_TIERS).contains(&(self.tier_count as usize)),
+            LotteryError::InvalidConfig
+        );
+
+        let tiers = &self.tiers[..self.tier_count as usize];
+        let total_share: u64 = tiers.iter().map(|tier| tier.share_bps as u64).sum();
+        let total_winners: usize = tiers.iter().map(|tier| tier.winners as usize).sum();
+        require!(
+            tiers.iter().all(|tier| tier.winners > 0)
+                && total_share == BPS_DENOMINATOR
+                && total_winners <= MAX_WINNERS,
+            LotteryError::InvalidPrizeTiers
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct LotteryConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    /// MUTR vault holding ticket revenue and prize pools, owned by `game_authority`
+    pub lottery_vault: Pubkey,
+    pub params: LotteryParams,
+    pub randomness_provider: RandomnessProvider,
+    pub round_count: u64,
+    /// The latest round is still selling tickets
+    pub selling: bool,
+    /// Unclaimed prizes waiting to join the next drawn round's pool
+    pub rollover: u64,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl LotteryConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 32 // lottery_vault
+        + LotteryParams::LEN // params
+        + 1  // randomness_provider
+        + 8  // round_count
+        + 1  // selling
+        + 8  // rollover
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundStatus {
+    Selling,
+    Drawing,
+    Drawn,
+    Closed,
+}
+
+#[account]
+pub struct Round {
+    pub id: u64,
+    pub ticket_price: u64,
+    pub clr_cut_bps: u16,
+    pub tier_count: u8,
+    pub tiers: [PrizeTier; MAX_TIERS],
+    /// First slot the draw may be requested; sales end here
+    pub draw_slot: u64,
+    pub ticket_count: u32,
+    pub revenue: u64,
+    /// Deposited into the CLR when the draw was requested
+    pub clr_cut: u64,
+    /// Revenue after the CLR cut plus the rollover taken in at the draw request
+    pub prize_pool: u64,
+    pub status: RoundStatus,
+    pub randomness_account: Pubkey,
+    pub request_slot: u64,
+    /// Winning ticket of each prize slot, tiers in order
+    pub winning_tickets: [u32; MAX_WINNERS],
+    /// Bit `i` is set once prize slot `i` has been claimed
+    pub claimed_mask: u32,
+    pub claimed_total: u64,
+    pub claim_deadline_slot: u64,
+    pub bump: u8,
+}
+
+impl Round {
+    pub const LEN: usize = 8 // id
+        + 8  // ticket_price
+        + 2  // clr_cut_bps
+        + 1  // tier_count
+        + PrizeTier::LEN * MAX_TIERS // tiers
+        + 8  // draw_slot
+        + 4  // ticket_count
+        + 8  // revenue
+        + 8  // clr_cut
+        + 8  // prize_pool
+        + 1  // status
+        + 32 // randomness_account
+        + 8  // request_slot
+        + 4 * MAX_WINNERS // winning_tickets
+        + 4  // claimed_mask
+        + 8  // claimed_total
+        + 8  // claim_deadline_slot
+        + 1; // bump
+
+    fn tiers(&self) -> &[PrizeTier] {
+        &self.tiers[..self.tier_count as usize]
+    }
+
+    /// Number of prize slots across all tiers.
+    pub fn winner_count(&self) -> usize {
+        self.tiers().iter().map(|tier| tier.winners as usize).sum()
+    }
+
+    /// Prize of slot `index`: its tier's share of the pool over the tier's winners.
+    pub fn slot_prize(&self, index: usize) -> Result<u64> {
+        let mut first = 0;
+        for tier in self.tiers() {
+            let winners = tier.winners as usize;
+            if index < first + winners {
+                let share = self.prize_pool as u128 * tier.share_bps as u128
+                    / BPS_DENOMINATOR as u128
+                    / winners as u128;
+                return u64::try_from(share).map_err(|_| error!(LotteryError::MathOverflow));
+            }
+            first += winners;
+        }
+        err!(LotteryError::InvalidWinnerSlot)
+    }
+}
+
+/// A batch of consecutive tickets bought in one purchase.
+#[account]
+pub struct TicketBatch {
+    pub owner: Pubkey,
+    pub round_id: u64,
+    pub first_ticket: u32,
+    pub count: u32,
+    pub bump: u8,
+}
+
+impl TicketBatch {
+    pub const LEN: usize = 32 // owner
+        + 8  // round_id
+        + 4  // first_ticket
+        + 4  // count
+        + 1; // bump
+
+    pub fn holds(&self, ticket: u32) -> bool {
+        ticket >= self.first_ticket && ticket - self.first_ticket < self.count
+    }
+}
+
+/// Winning tickets of a drawn round, for the frontend.
+#[event]
+pub struct RoundDrawn {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub round_id: u64,
+    pub ticket_count: u32,
+    pub prize_pool: u64,
+    pub winning_tickets: [u32; MAX_WINNERS],
+    /// Number of prize slots; zero when nothing was sold and the pool rolled over
+    pub winner_count: u8,
+}
+
+#[event]
+pub struct PrizeClaimed {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub round_id: u64,
+    pub owner: Pubkey,
+    pub winner_slot: u8,
+    pub ticket: u32,
+    pub prize: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeLottery<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LotteryConfig::LEN,
+        seeds = [b"lottery_config"],
+        bump
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    /// CHECK: PDA signer registered as the lottery game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(address = clr_state.load()?.mutr_mint @ LotteryError::InvalidMint)]
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lottery_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = game_authority
+    )]
+    pub lottery_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLotteryConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery_config"],
+        bump = config.bump,
+        has_one = authority @ LotteryError::Unauthorized
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    /// CHECK: PDA signer registered as the lottery game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    /// Rounds are only opened while the game is enabled in the CLR
+    #[account(
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ LotteryError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Round::LEN,
+        seeds = [b"round", config.round_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct BuyTickets<'info> {
+    #[account(
+        seeds = [b"lottery_config"],
+        bump = config.bump,
+        has_one = lottery_vault @ LotteryError::InvalidVault
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round_id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + TicketBatch::LEN,
+        seeds = [
+            b"tickets",
+            round_id.to_le_bytes().as_ref(),
+            round.ticket_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    #[account(mut)]
+    pub lottery_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer_mutr_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// CLR accounts for depositing the CLR cut through `deposit_game_profit`.
+#[derive(Accounts)]
+pub struct ClrDeposit<'info> {
+    /// CHECK: PDA signer registered as the lottery game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub lottery_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: Account<'info, TokenAccount>,
+
+    pub mutr_mint: Account<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// CLR accounts for numbering an event through `next_event_nonce`.
+#[derive(Accounts)]
+pub struct ClrEvent<'info> {
+    /// CHECK: PDA signer registered as the lottery game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+}
+
+#[derive(Accounts)]
+pub struct RequestDraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery_config"],
+        bump = config.bump,
+        constraint = config.lottery_vault == clr_accounts.lottery_vault.key() @ LotteryError::InvalidVault,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ LotteryError::InvalidClrState
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub clr_accounts: ClrDeposit<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Draw<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ LotteryError::InvalidClrState
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status == RoundStatus::Drawing @ LotteryError::DrawNotRequested
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: must be the request committed for the draw; parsed by `randomness`
+    #[account(address = round.randomness_account @ LotteryError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub clr_accounts: ClrEvent<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        seeds = [b"lottery_config"],
+        bump = config.bump,
+        has_one = lottery_vault @ LotteryError::InvalidVault,
+        has_one = clr_state @ LotteryError::InvalidClrState
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    /// CHECK: PDA signer that owns the lottery vault and is registered in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        has_one = owner @ LotteryError::Unauthorized,
+        constraint = tickets.round_id == round.id @ LotteryError::NotAWinner
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    #[account(mut)]
+    pub lottery_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_mutr_account.mint == lottery_vault.mint @ LotteryError::InvalidMint
+    )]
+    pub owner_mutr_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, LotteryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTickets<'info> {
+    #[account(
+        seeds = [b"round", round.id.to_le_bytes().as_ref()],
+        bump = round.bump
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        has_one = owner @ LotteryError::Unauthorized,
+        constraint = tickets.round_id == round.id @ LotteryError::InvalidRound,
+        close = owner
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[error_code]
+pub enum LotteryError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Prize tier shares must add up to 100% and every tier needs a winner")]
+    InvalidPrizeTiers,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("The current round is still selling tickets")]
+    RoundStillSelling,
+    #[msg("Round is not selling tickets")]
+    RoundNotSelling,
+    #[msg("Ticket sales have closed")]
+    SalesClosed,
+    #[msg("Invalid ticket count")]
+    InvalidTicketCount,
+    #[msg("Draw slot has not been reached")]
+    DrawNotDue,
+    #[msg("Draw request is still pending")]
+    DrawPending,
+    #[msg("Draw has not been requested")]
+    DrawNotRequested,
+    #[msg("Round has not been drawn")]
+    RoundNotDrawn,
+    #[msg("Round is not closed")]
+    RoundNotClosed,
+    #[msg("Ticket batch belongs to another round")]
+    InvalidRound,
+    #[msg("Invalid prize slot")]
+    InvalidWinnerSlot,
+    #[msg("Prize already claimed")]
+    AlreadyClaimed,
+    #[msg("Ticket batch does not hold the winning ticket")]
+    NotAWinner,
+    #[msg("Claim window has closed")]
+    ClaimWindowClosed,
+    #[msg("Claim window is still open")]
+    ClaimWindowOpen,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,811 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::{GameConfig, GlobalState};
+use mutr_clr_cpi::{ClrVaults, PayPrize};
+
+declare_id!("MinesRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Tiles on the 5x5 board
+pub const TILE_COUNT: u8 = 25;
+
+/// `pending_tile` value when no pick is waiting for the operator
+const NO_PICK: u8 = u8::MAX;
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Mines with a committed bomb layout.
+///
+/// The house operator co-signs `start_game` with `keccak(game || mine_mask_le || salt)`.
+/// The player picks tiles one at a time and the operator answers each pick; every safe
+/// tile grows the multiplier by `remaining_tiles / remaining_safe_tiles`. A bust or a
+/// cash-out reveals the layout, which must match the commitment and every answer given
+/// so far. Wagers go into the CLR vault at start; a cash-out is paid from it through
+/// `send_prize`, a bust leaves the wager with the house. If the operator stops
+/// answering, the player can claim the running payout once the game expires.
+#[program]
+pub mod mutr_mines {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR, which pays cash-outs through `send_prize`.
+    pub fn initialize_mines(
+        ctx: Context<InitializeMines>,
+        operator: Pubkey,
+        params: MinesParams,
+    ) -> Result<()> {
+        params.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.operator = operator;
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.params = params;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: replace the operator key and game parameters.
+    pub fn update_mines_config(
+        ctx: Context<UpdateMinesConfig>,
+        operator: Pubkey,
+        params: MinesParams,
+    ) -> Result<()> {
+        params.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.operator = operator;
+        config.params = params;
+        Ok(())
+    }
+
+    /// Start a game on a board with `mine_count` mines, escrowing `amount` in the CLR
+    /// vault. Co-signed by the operator, who commits to the layout.
+    pub fn start_game(
+        ctx: Context<StartGame>,
+        nonce: u64,
+        amount: u64,
+        mine_count: u8,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        require!(
+            amount >= params.min_bet && amount <= params.max_bet,
+            MinesError::BetOutOfRange
+        );
+        require!(
+            (1..TILE_COUNT).contains(&mine_count),
+            MinesError::InvalidMineCount
+        );
+
+        let clr_vault_before = ctx.accounts.clr_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.clr_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // Credit what reached the vault, net of any transfer fee
+        ctx.accounts.clr_vault.reload()?;
+        let amount = ctx
+            .accounts
+            .clr_vault
+            .amount
+            .checked_sub(clr_vault_before)
+            .ok_or(MinesError::MathOverflow)?;
+        ctx.accounts
+            .record_wager(ctx.accounts.config.authority_bump, amount)?;
+        ctx.accounts
+            .contribute_to_jackpot(ctx.accounts.config.authority_bump, amount)?;
+
+        let game = &mut ctx.accounts.game;
+        game.player = ctx.accounts.player.key();
+        game.nonce = nonce;
+        game.amount = amount;
+        game.mine_count = mine_count;
+        game.commitment = commitment;
+        game.revealed_mask = 0;
+        game.pending_tile = NO_PICK;
+        game.multiplier_bps = BPS_DENOMINATOR as u32;
+        game.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(params.game_ttl_slots)
+            .ok_or(MinesError::MathOverflow)?;
+        game.bump = ctx.bumps.game;
+        Ok(())
+    }
+
+    /// Pick the next tile to uncover. The operator answers with `reveal_safe_tile` or
+    /// `bust`.
+    pub fn pick_tile(ctx: Context<PickTile>, tile: u8) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        require!(game.pending_tile == NO_PICK, MinesError::PickPending);
+        require!(
+            tile < TILE_COUNT && game.revealed_mask & (1 << tile) == 0,
+            MinesError::InvalidTile
+        );
+        game.pending_tile = tile;
+        game.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(ctx.accounts.config.params.game_ttl_slots)
+            .ok_or(MinesError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Operator: the pending tile is safe. Grows the multiplier along the payout curve.
+    pub fn reveal_safe_tile(ctx: Context<RevealTile>) -> Result<()> {
+        let params = &ctx.accounts.config.params;
+        let game = &mut ctx.accounts.game;
+        require!(game.pending_tile != NO_PICK, MinesError::NoPickPending);
+
+        game.multiplier_bps = next_multiplier_bps(game, params.max_multiplier_bps)?;
+        game.revealed_mask |= 1 << game.pending_tile;
+        game.pending_tile = NO_PICK;
+        game.expiry_slot = Clock::get()?
+            .slot
+            .checked_add(params.game_ttl_slots)
+            .ok_or(MinesError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Operator: the pending tile is a mine. The revealed layout must match the
+    /// commitment; the wager stays in the CLR and the game is closed.
+    pub fn bust(ctx: Context<Bust>, mine_mask: u32, salt: [u8; 32]) -> Result<()> {
+        let game = &ctx.accounts.game;
+        require!(game.pending_tile != NO_PICK, MinesError::NoPickPending);
+        verify_layout(&game.key(), game, mine_mask, &salt)?;
+        require!(
+            mine_mask & (1 << game.pending_tile) != 0,
+            MinesError::LayoutMismatch
+        );
+
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(GameFinished {
+            event_nonce,
+            player: game.player,
+            game: game.key(),
+            amount: game.amount,
+            mine_mask,
+            revealed_mask: game.revealed_mask,
+            payout: 0,
+        });
+        Ok(())
+    }
+
+    /// Cash out the running multiplier, co-signed by the operator revealing the layout.
+    pub fn cash_out(ctx: Context<CashOut>, mine_mask: u32, salt: [u8; 32]) -> Result<()> {
+        let game = &ctx.accounts.game;
+        require!(game.pending_tile == NO_PICK, MinesError::PickPending);
+        require!(game.revealed_mask != 0, MinesError::NothingRevealed);
+        verify_layout(&game.key(), game, mine_mask, &salt)?;
+
+        let payout = game_payout(game, ctx.accounts.config.params.house_edge_bps)?;
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(GameFinished {
+            event_nonce,
+            player: game.player,
+            game: game.key(),
+            amount: game.amount,
+            mine_mask,
+            revealed_mask: game.revealed_mask,
+            payout,
+        });
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            payout,
+        )
+    }
+
+    /// The operator stopped answering: once the game expires the player is paid the
+    /// running payout (the wager back if no tile was uncovered).
+    pub fn claim_expired_game(ctx: Context<ClaimExpiredGame>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        require!(
+            Clock::get()?.slot > game.expiry_slot,
+            MinesError::GameNotExpired
+        );
+        let payout = if game.revealed_mask == 0 {
+            game.amount
+        } else {
+            game_payout(game, ctx.accounts.config.params.house_edge_bps)?
+        };
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            payout,
+        )
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Multiplier after one more safe tile: `multiplier * remaining / remaining_safe`,
+/// capped at `max_multiplier_bps`.
+fn next_multiplier_bps(game: &MinesGame, max_multiplier_bps: u32) -> Result<u32> {
+    let uncovered = game.revealed_mask.count_ones() as u64;
+    let remaining = TILE_COUNT as u64 - uncovered;
+    let remaining_safe = remaining
+        .checked_sub(game.mine_count as u64)
+        .filter(|&safe| safe > 0)
+        .ok_or(MinesError::InvalidTile)?;
+
+    let next = (game.multiplier_bps as u64)
+        .checked_mul(remaining)
+        .ok_or(MinesError::MathOverflow)?
+        / remaining_safe;
+    Ok(next.min(max_multiplier_bps as u64) as u32)
+}
+
+/// `amount * multiplier` with the house edge taken off.
+fn game_payout(game: &MinesGame, house_edge_bps: u16) -> Result<u64> {
+    let gross = mutr_clr::bet_payout(game.amount, game.multiplier_bps)?;
+    let edge = (gross as u128 * house_edge_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    Ok(gross - edge)
+}
+
+/// Check the revealed layout against the commitment, the mine count and every tile
+/// already answered as safe.
+fn verify_layout(
+    game_key: &Pubkey,
+    game: &MinesGame,
+    mine_mask: u32,
+    salt: &[u8; 32],
+) -> Result<()> {
+    let hash = keccak::hashv(&[game_key.as_ref(), &mine_mask.to_le_bytes(), salt]);
+    require!(hash.0 == game.commitment, MinesError::LayoutMismatch);
+    require!(
+        mine_mask >> TILE_COUNT == 0
+            && mine_mask.count_ones() == game.mine_count as u32
+            && mine_mask & game.revealed_mask == 0,
+        MinesError::LayoutMismatch
+    );
+    Ok(())
+}
+
+/// Pay `amount` from the CLR vault to the player, streamed when it is over the CLR's
+/// vesting threshold (see `mutr_clr_cpi::PayPrize`).
+fn send_from_clr(accounts: &ClrPrize, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let vaults = ClrVaults {
+        mutr_mint: accounts.mutr_mint.key(),
+        clr_vault: accounts.clr_vault.key(),
+        token_program: accounts.token_program.key(),
+    };
+    PayPrize {
+        game: accounts.game_authority.key(),
+        winner: accounts.player_mutr_account.owner,
+        winner_mutr_account: accounts.player_mutr_account.key(),
+        payer: accounts.payer.key(),
+        amount,
+    }
+    .invoke_signed(
+        &vaults,
+        &accounts.clr_state,
+        accounts.clr_vault.amount,
+        &accounts.to_account_infos(),
+        &[authority_seeds],
+    )
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+/// Tunable game parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct MinesParams {
+    pub house_edge_bps: u16,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Cap on the running multiplier
+    pub max_multiplier_bps: u32,
+    /// Slots the operator has to answer before the player may claim the game
+    pub game_ttl_slots: u64,
+}
+
+impl MinesParams {
+    pub const LEN: usize = 2 // house_edge_bps
+        + 8  // min_bet
+        + 8  // max_bet
+        + 4  // max_multiplier_bps
+        + 8; // game_ttl_slots
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (self.house_edge_bps as u64) < BPS_DENOMINATOR
+                && self.min_bet > 0
+                && self.min_bet <= self.max_bet
+                && self.max_multiplier_bps as u64 > BPS_DENOMINATOR
+                && self.game_ttl_slots > 0,
+            MinesError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct MinesConfig {
+    pub authority: Pubkey,
+    /// House key that commits to layouts and answers picks
+    pub operator: Pubkey,
+    pub clr_state: Pubkey,
+    pub params: MinesParams,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl MinesConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // operator
+        + 32 // clr_state
+        + MinesParams::LEN // params
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// A game in progress.
+#[account]
+pub struct MinesGame {
+    pub player: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub mine_count: u8,
+    /// `keccak(game || mine_mask_le || salt)`
+    pub commitment: [u8; 32],
+    /// Bit `i` set once tile `i` was answered safe
+    pub revealed_mask: u32,
+    /// Tile waiting for the operator's answer, `NO_PICK` if none
+    pub pending_tile: u8,
+    pub multiplier_bps: u32,
+    /// Slot after which the player may claim the game if the operator is silent
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+impl MinesGame {
+    pub const LEN: usize = 32 // player
+        + 8  // nonce
+        + 8  // amount
+        + 1  // mine_count
+        + 32 // commitment
+        + 4  // revealed_mask
+        + 1  // pending_tile
+        + 4  // multiplier_bps
+        + 8  // expiry_slot
+        + 1; // bump
+}
+
+/// A finished game with its revealed layout, for the frontend and for verification.
+#[event]
+pub struct GameFinished {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub game: Pubkey,
+    pub amount: u64,
+    pub mine_mask: u32,
+    pub revealed_mask: u32,
+    pub payout: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeMines<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MinesConfig::LEN,
+        seeds = [b"mines_config"],
+        bump
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    /// CHECK: PDA signer registered as the mines game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMinesConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        has_one = authority @ MinesError::Unauthorized
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct StartGame<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        has_one = clr_state @ MinesError::InvalidClrState,
+        has_one = operator @ MinesError::Unauthorized
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    /// CHECK: PDA signer registered as the mines game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Games only start while enabled in the CLR
+    #[account(
+        mut,
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ MinesError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ MinesError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + MinesGame::LEN,
+        seeds = [b"mines", player.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, MinesGame>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub operator: Signer<'info>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StartGame<'info> {
+    /// Feed the CLR progressive jackpot its share of the wager.
+    /// The jackpot accounts are required once the jackpot exists.
+    fn contribute_to_jackpot(&self, authority_bump: u8, wager: u64) -> Result<()> {
+        let (Some(jackpot), Some(jackpot_vault)) = (&self.clr_jackpot, &self.clr_jackpot_vault)
+        else {
+            require!(
+                self.clr_state.load()?.jackpot_enabled == 0,
+                MinesError::MissingJackpotAccounts
+            );
+            return Ok(());
+        };
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ContributeJackpot {
+            state: self.clr_state.to_account_info(),
+            mutr_mint: self.mutr_mint.to_account_info(),
+            jackpot: jackpot.to_account_info(),
+            clr_vault: self.clr_vault.to_account_info(),
+            jackpot_vault: jackpot_vault.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game: self.game_authority.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::contribute_jackpot(cpi_ctx, wager)?;
+        Ok(())
+    }
+
+    /// Run the wager through the CLR's player checks.
+    fn record_wager(&self, authority_bump: u8, amount: u64) -> Result<()> {
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::RecordGameWager {
+            state: self.clr_state.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game_ledger: self.clr_game_ledger.to_account_info(),
+            player_state: self.clr_player_state.to_account_info(),
+            user_stats: self
+                .clr_user_stats
+                .as_ref()
+                .map(|stats| stats.to_account_info()),
+            player: self.player.to_account_info(),
+            game: self.game_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::record_game_wager(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct PickTile<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ MinesError::Unauthorized
+    )]
+    pub game: Account<'info, MinesGame>,
+
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealTile<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        has_one = operator @ MinesError::Unauthorized
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    #[account(mut)]
+    pub game: Account<'info, MinesGame>,
+
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Bust<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        has_one = operator @ MinesError::Unauthorized,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ MinesError::InvalidClrState
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ MinesError::Unauthorized,
+        close = player
+    )]
+    pub game: Account<'info, MinesGame>,
+
+    pub clr_accounts: ClrEvent<'info>,
+
+    /// CHECK: receives the game account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub operator: Signer<'info>,
+}
+
+/// CLR accounts for numbering an event through `next_event_nonce`.
+#[derive(Accounts)]
+pub struct ClrEvent<'info> {
+    /// CHECK: PDA signer registered as the mines game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+}
+
+/// CLR accounts for paying a player through `send_prize` or `send_vested_prize`.
+#[derive(Accounts)]
+pub struct ClrPrize<'info> {
+    /// CHECK: PDA signer registered as the mines game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the player's CLR `PrizeVesting`; created and checked by the CLR when a prize
+    /// is over its vesting threshold
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    /// Funds the player's `PrizeVesting` when a prize opens it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CashOut<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        has_one = operator @ MinesError::Unauthorized,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ MinesError::InvalidClrState
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ MinesError::Unauthorized,
+        constraint = clr_accounts.player_mutr_account.owner == game.player @ MinesError::Unauthorized,
+        close = player
+    )]
+    pub game: Account<'info, MinesGame>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimExpiredGame<'info> {
+    #[account(
+        seeds = [b"mines_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ MinesError::InvalidClrState
+    )]
+    pub config: Account<'info, MinesConfig>,
+
+    #[account(
+        mut,
+        has_one = player @ MinesError::Unauthorized,
+        constraint = clr_accounts.player_mutr_account.owner == game.player @ MinesError::Unauthorized,
+        close = player
+    )]
+    pub game: Account<'info, MinesGame>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[error_code]
+pub enum MinesError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Invalid mine count")]
+    InvalidMineCount,
+    #[msg("Invalid tile")]
+    InvalidTile,
+    #[msg("A pick is waiting for the operator")]
+    PickPending,
+    #[msg("No pick is waiting for the operator")]
+    NoPickPending,
+    #[msg("No tile has been uncovered")]
+    NothingRevealed,
+    #[msg("Revealed layout does not match the commitment")]
+    LayoutMismatch,
+    #[msg("Game has not expired")]
+    GameNotExpired,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpotAccounts,
+}
@@ -0,0 +1,913 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+
+declare_id!("PvPRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest rake the authority may configure (10%)
+pub const MAX_RAKE_BPS: u16 = 1_000;
+
+/// Player-vs-player wagers. A creator escrows MUTR in a challenge, an opponent matches
+/// it, and the winner takes the pot less a rake, which is deposited into the CLR as
+/// profit. Challenges are decided either by the registered resolver (for matches played
+/// off-chain) or by a randomness request committed when the challenge is accepted.
+#[program]
+pub mod mutr_pvp {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR so it can deposit the rake.
+    pub fn initialize_pvp(
+        ctx: Context<InitializePvp>,
+        rake_bps: u16,
+        min_wager: u64,
+        resolve_ttl_slots: u64,
+        resolver: Pubkey,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            rake_bps <= MAX_RAKE_BPS && min_wager > 0 && resolve_ttl_slots > 0,
+            PvpError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.escrow_vault = ctx.accounts.escrow_vault.key();
+        config.resolver = resolver;
+        config.rake_bps = rake_bps;
+        config.min_wager = min_wager;
+        config.resolve_ttl_slots = resolve_ttl_slots;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: change the rake, minimum wager, resolution window and resolver. Accepted
+    /// challenges settle with the rake in force at settlement.
+    pub fn update_pvp_config(
+        ctx: Context<UpdatePvpConfig>,
+        rake_bps: u16,
+        min_wager: u64,
+        resolve_ttl_slots: u64,
+        resolver: Pubkey,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            rake_bps <= MAX_RAKE_BPS && min_wager > 0 && resolve_ttl_slots > 0,
+            PvpError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.rake_bps = rake_bps;
+        config.min_wager = min_wager;
+        config.resolve_ttl_slots = resolve_ttl_slots;
+        config.resolver = resolver;
+        config.randomness_provider = randomness_provider;
+        Ok(())
+    }
+
+    /// Escrow `amount` in a new challenge. `opponent` restricts who may accept it;
+    /// `terms_hash` commits to the off-chain match a resolver-decided challenge is about.
+    pub fn create_challenge(
+        ctx: Context<CreateChallenge>,
+        nonce: u64,
+        amount: u64,
+        opponent: Option<Pubkey>,
+        mode: ResolutionMode,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(amount >= config.min_wager, PvpError::WagerTooSmall);
+        if mode == ResolutionMode::Randomness {
+            require!(
+                config.randomness_provider != RandomnessProvider::None,
+                PvpError::RandomnessDisabled
+            );
+        }
+
+        let amount = escrow_wager(
+            &ctx.accounts.creator_mutr_account,
+            &mut ctx.accounts.escrow_vault,
+            &ctx.accounts.mutr_mint,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let challenge = &mut ctx.accounts.challenge;
+        challenge.creator = ctx.accounts.creator.key();
+        challenge.nonce = nonce;
+        challenge.amount = amount;
+        challenge.opponent = opponent.unwrap_or_default();
+        challenge.mode = mode;
+        challenge.terms_hash = terms_hash;
+        challenge.status = ChallengeStatus::Open;
+        challenge.created_slot = Clock::get()?.slot;
+        challenge.bump = ctx.bumps.challenge;
+        Ok(())
+    }
+
+    /// Creator: withdraw a challenge nobody has accepted yet.
+    pub fn cancel_challenge(ctx: Context<CancelChallenge>) -> Result<()> {
+        game_transfer(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.escrow_vault.to_account_info(),
+            ctx.accounts.creator_mutr_account.to_account_info(),
+            &ctx.accounts.mutr_mint,
+            ctx.accounts.game_authority.to_account_info(),
+            ctx.accounts.config.authority_bump,
+            ctx.accounts.challenge.amount,
+        )
+    }
+
+    /// Match an open challenge's wager. Randomness-decided challenges commit a fresh
+    /// request here, so neither side knows the outcome when the pot is locked.
+    pub fn accept_challenge(ctx: Context<AcceptChallenge>) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+        let acceptor = ctx.accounts.acceptor.key();
+        require!(
+            acceptor != challenge.creator,
+            PvpError::CannotAcceptOwnChallenge
+        );
+        require!(
+            challenge.opponent == Pubkey::default() || challenge.opponent == acceptor,
+            PvpError::NotTheOpponent
+        );
+
+        let slot = Clock::get()?.slot;
+        let randomness_account = if challenge.mode == ResolutionMode::Randomness {
+            let randomness_account = ctx
+                .accounts
+                .randomness_account
+                .as_ref()
+                .ok_or(PvpError::InvalidRandomness)?;
+            randomness::check_fresh_request(
+                ctx.accounts.config.randomness_provider,
+                randomness_account,
+                slot,
+            )?;
+            randomness_account.key()
+        } else {
+            Pubkey::default()
+        };
+
+        let matched_amount = escrow_wager(
+            &ctx.accounts.acceptor_mutr_account,
+            &mut ctx.accounts.escrow_vault,
+            &ctx.accounts.mutr_mint,
+            &ctx.accounts.acceptor,
+            &ctx.accounts.token_program,
+            challenge.amount,
+        )?;
+
+        let challenge = &mut ctx.accounts.challenge;
+        challenge.acceptor = acceptor;
+        challenge.matched_amount = matched_amount;
+        challenge.status = ChallengeStatus::Accepted;
+        challenge.randomness_account = randomness_account;
+        challenge.accepted_slot = slot;
+        challenge.expiry_slot = slot
+            .checked_add(ctx.accounts.config.resolve_ttl_slots)
+            .ok_or(PvpError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Resolver: decide a resolver-mode challenge. A draw returns both wagers without
+    /// taking a rake.
+    pub fn resolve_challenge(ctx: Context<ResolveChallenge>, outcome: Outcome) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+        require!(
+            challenge.mode == ResolutionMode::Resolver,
+            PvpError::WrongResolutionMode
+        );
+        require!(
+            Clock::get()?.slot <= challenge.expiry_slot,
+            PvpError::ChallengeExpired
+        );
+        settle(
+            &ctx.accounts.settlement,
+            &ctx.accounts.config,
+            challenge,
+            outcome,
+        )
+    }
+
+    /// Permissionless: decide a randomness-mode challenge from the revealed value.
+    pub fn settle_challenge_with_randomness(
+        ctx: Context<SettleChallengeWithRandomness>,
+    ) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+        require!(
+            challenge.mode == ResolutionMode::Randomness,
+            PvpError::WrongResolutionMode
+        );
+        let slot = Clock::get()?.slot;
+        require!(slot <= challenge.expiry_slot, PvpError::ChallengeExpired);
+
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            challenge.accepted_slot,
+        )?;
+        let outcome = if value[0] & 1 == 0 {
+            Outcome::CreatorWins
+        } else {
+            Outcome::AcceptorWins
+        };
+        settle(
+            &ctx.accounts.settlement,
+            &ctx.accounts.config,
+            challenge,
+            outcome,
+        )
+    }
+
+    /// Permissionless: return both wagers of an accepted challenge that was not decided
+    /// before its expiry slot.
+    pub fn refund_expired_challenge(ctx: Context<RefundExpiredChallenge>) -> Result<()> {
+        require!(
+            Clock::get()?.slot > ctx.accounts.challenge.expiry_slot,
+            PvpError::ChallengeNotExpired
+        );
+        settle(
+            &ctx.accounts.settlement,
+            &ctx.accounts.config,
+            &ctx.accounts.challenge,
+            Outcome::Draw,
+        )
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Pay out an accepted challenge: the pot less the rake to the winner and the rake into
+/// the CLR, or both wagers back on a draw.
+fn settle(
+    settlement: &Settlement,
+    config: &PvpConfig,
+    challenge: &Account<Challenge>,
+    outcome: Outcome,
+) -> Result<()> {
+    let pot = challenge
+        .amount
+        .checked_add(challenge.matched_amount)
+        .ok_or(PvpError::MathOverflow)?;
+    let (payout, rake) = match outcome {
+        Outcome::Draw => (challenge.amount, 0),
+        Outcome::CreatorWins | Outcome::AcceptorWins => {
+            let rake = (pot as u128 * config.rake_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+            (pot - rake, rake)
+        }
+    };
+
+    let escrow = settlement.escrow_vault.to_account_info();
+    let creator = settlement.creator_mutr_account.to_account_info();
+    let acceptor = settlement.acceptor_mutr_account.to_account_info();
+    let game_authority = settlement.game_authority.to_account_info();
+    let token_program = settlement.token_program.to_account_info();
+    let mint = &settlement.mutr_mint;
+    let bump = config.authority_bump;
+
+    match outcome {
+        Outcome::CreatorWins => game_transfer(
+            token_program,
+            escrow,
+            creator,
+            mint,
+            game_authority,
+            bump,
+            payout,
+        )?,
+        Outcome::AcceptorWins => game_transfer(
+            token_program,
+            escrow,
+            acceptor,
+            mint,
+            game_authority,
+            bump,
+            payout,
+        )?,
+        Outcome::Draw => {
+            game_transfer(
+                token_program.clone(),
+                escrow.clone(),
+                creator,
+                mint,
+                game_authority.clone(),
+                bump,
+                payout,
+            )?;
+            game_transfer(
+                token_program,
+                escrow,
+                acceptor,
+                mint,
+                game_authority,
+                bump,
+                challenge.matched_amount,
+            )?;
+        }
+    }
+
+    if rake > 0 {
+        deposit_rake(settlement, bump, rake)?;
+    }
+
+    let event_nonce = next_event_nonce(
+        &settlement.clr_program,
+        &settlement.clr_state,
+        &settlement.clr_game_config,
+        &settlement.game_authority,
+        bump,
+    )?;
+    emit!(ChallengeSettled {
+        event_nonce,
+        challenge: challenge.key(),
+        creator: challenge.creator,
+        acceptor: challenge.acceptor,
+        amount: challenge.amount,
+        matched_amount: challenge.matched_amount,
+        outcome,
+        payout,
+        rake,
+    });
+    Ok(())
+}
+
+/// Move the rake from the escrow into the CLR vault as profit.
+fn deposit_rake(settlement: &Settlement, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::DepositGameProfit {
+        state: settlement.clr_state.to_account_info(),
+        stats: settlement.clr_stats.to_account_info(),
+        mutr_mint: settlement.mutr_mint.to_account_info(),
+        clr_vault: settlement.clr_vault.to_account_info(),
+        source: settlement.escrow_vault.to_account_info(),
+        game_config: settlement.clr_game_config.to_account_info(),
+        game: settlement.game_authority.to_account_info(),
+        token_program: settlement.token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        settlement.clr_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    mutr_clr::cpi::deposit_game_profit(cpi_ctx, amount)
+}
+
+/// Transfer `amount` out of the escrow vault, signed by the game authority PDA.
+fn game_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    game_authority: AccountInfo<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to,
+        authority: game_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Move a player's wager into the escrow vault, returning what reached it net of any
+/// transfer fee.
+fn escrow_wager<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    escrow_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    player: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<u64> {
+    let escrow_before = escrow_vault.amount;
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: escrow_vault.to_account_info(),
+        authority: player.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+    escrow_vault.reload()?;
+    Ok(escrow_vault
+        .amount
+        .checked_sub(escrow_before)
+        .ok_or(PvpError::MathOverflow)?)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct PvpConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    /// MUTR escrow for challenge wagers, owned by `game_authority`
+    pub escrow_vault: Pubkey,
+    /// Decides resolver-mode challenges
+    pub resolver: Pubkey,
+    /// Share of the pot taken as CLR profit when a challenge is won
+    pub rake_bps: u16,
+    pub min_wager: u64,
+    /// Slots an accepted challenge may stay undecided before both sides are refunded
+    pub resolve_ttl_slots: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl PvpConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 32 // escrow_vault
+        + 32 // resolver
+        + 2  // rake_bps
+        + 8  // min_wager
+        + 8  // resolve_ttl_slots
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// How a challenge's winner is decided.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionMode {
+    /// The configured resolver reports the result of an off-chain match
+    Resolver,
+    /// A coin flip on the randomness request committed at acceptance
+    Randomness,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeStatus {
+    Open,
+    Accepted,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    CreatorWins,
+    AcceptorWins,
+    /// Both wagers are returned and no rake is taken
+    Draw,
+}
+
+/// A wager between two players, closed back to the creator once settled.
+#[account]
+pub struct Challenge {
+    pub creator: Pubkey,
+    pub nonce: u64,
+    /// Creator's wager as it reached the escrow
+    pub amount: u64,
+    /// Only this player may accept; default for an open challenge
+    pub opponent: Pubkey,
+    pub acceptor: Pubkey,
+    /// Acceptor's matching wager as it reached the escrow
+    pub matched_amount: u64,
+    pub mode: ResolutionMode,
+    /// Commitment to the off-chain match terms, for resolver-mode challenges
+    pub terms_hash: [u8; 32],
+    pub status: ChallengeStatus,
+    /// Request committed at acceptance, for randomness-mode challenges
+    pub randomness_account: Pubkey,
+    pub created_slot: u64,
+    pub accepted_slot: u64,
+    /// Last slot the challenge may be decided; refundable afterwards
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+impl Challenge {
+    pub const LEN: usize = 32 // creator
+        + 8  // nonce
+        + 8  // amount
+        + 32 // opponent
+        + 32 // acceptor
+        + 8  // matched_amount
+        + 1  // mode
+        + 32 // terms_hash
+        + 1  // status
+        + 32 // randomness_account
+        + 8  // created_slot
+        + 8  // accepted_slot
+        + 8  // expiry_slot
+        + 1; // bump
+}
+
+/// Result of a settled challenge, for the frontend.
+#[event]
+pub struct ChallengeSettled {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub challenge: Pubkey,
+    pub creator: Pubkey,
+    pub acceptor: Pubkey,
+    pub amount: u64,
+    pub matched_amount: u64,
+    pub outcome: Outcome,
+    /// Paid to the winner, or the creator's wager back on a draw
+    pub payout: u64,
+    pub rake: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializePvp<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PvpConfig::LEN,
+        seeds = [b"pvp_config"],
+        bump
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    /// CHECK: PDA signer registered as the PvP game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(address = clr_state.load()?.mutr_mint @ PvpError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pvp_escrow"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = game_authority
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePvpConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        has_one = authority @ PvpError::Unauthorized
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CreateChallenge<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        has_one = escrow_vault @ PvpError::InvalidVault
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    /// CHECK: PDA signer registered as the PvP game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    /// Challenges are only opened while the game is enabled in the CLR
+    #[account(
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ PvpError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(mut)]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_vault.mint @ PvpError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Challenge::LEN,
+        seeds = [b"challenge", creator.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(mut)]
+    pub creator_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChallenge<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        has_one = escrow_vault @ PvpError::InvalidVault
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    /// CHECK: PDA signer that owns the escrow vault
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_vault.mint @ PvpError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = creator @ PvpError::Unauthorized,
+        constraint = challenge.status == ChallengeStatus::Open @ PvpError::ChallengeNotOpen,
+        close = creator
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(
+        mut,
+        constraint = creator_mutr_account.mint == escrow_vault.mint @ PvpError::InvalidMint
+    )]
+    pub creator_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptChallenge<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        has_one = escrow_vault @ PvpError::InvalidVault
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    /// CHECK: PDA signer registered as the PvP game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    /// Challenges are only accepted while the game is enabled in the CLR
+    #[account(
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ PvpError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(mut)]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_vault.mint @ PvpError::InvalidMint)]
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = challenge.status == ChallengeStatus::Open @ PvpError::ChallengeNotOpen
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(mut)]
+    pub acceptor_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: fresh request for randomness-mode challenges; parsed by `randomness`
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+
+    pub acceptor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Escrow, player and CLR accounts for paying out an accepted challenge.
+#[derive(Accounts)]
+pub struct Settlement<'info> {
+    /// CHECK: PDA signer registered as the PvP game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub acceptor_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        has_one = resolver @ PvpError::Unauthorized,
+        constraint = config.escrow_vault == settlement.escrow_vault.key() @ PvpError::InvalidVault,
+        constraint = config.clr_state == settlement.clr_state.key() @ PvpError::InvalidClrState
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    #[account(
+        mut,
+        has_one = creator @ PvpError::InvalidChallenge,
+        constraint = challenge.status == ChallengeStatus::Accepted @ PvpError::ChallengeNotAccepted,
+        constraint = settlement.creator_mutr_account.owner == challenge.creator @ PvpError::InvalidChallenge,
+        constraint = settlement.acceptor_mutr_account.owner == challenge.acceptor @ PvpError::InvalidChallenge,
+        close = creator
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub settlement: Settlement<'info>,
+
+    pub resolver: Signer<'info>,
+
+    /// CHECK: receives the challenge account's rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleChallengeWithRandomness<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        constraint = config.escrow_vault == settlement.escrow_vault.key() @ PvpError::InvalidVault,
+        constraint = config.clr_state == settlement.clr_state.key() @ PvpError::InvalidClrState
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    #[account(
+        mut,
+        has_one = creator @ PvpError::InvalidChallenge,
+        constraint = challenge.status == ChallengeStatus::Accepted @ PvpError::ChallengeNotAccepted,
+        constraint = settlement.creator_mutr_account.owner == challenge.creator @ PvpError::InvalidChallenge,
+        constraint = settlement.acceptor_mutr_account.owner == challenge.acceptor @ PvpError::InvalidChallenge,
+        close = creator
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// CHECK: must be the request committed at acceptance; parsed by `randomness`
+    #[account(address = challenge.randomness_account @ PvpError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub settlement: Settlement<'info>,
+
+    /// CHECK: receives the challenge account's rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundExpiredChallenge<'info> {
+    #[account(
+        seeds = [b"pvp_config"],
+        bump = config.bump,
+        constraint = config.escrow_vault == settlement.escrow_vault.key() @ PvpError::InvalidVault,
+        constraint = config.clr_state == settlement.clr_state.key() @ PvpError::InvalidClrState
+    )]
+    pub config: Account<'info, PvpConfig>,
+
+    #[account(
+        mut,
+        has_one = creator @ PvpError::InvalidChallenge,
+        constraint = challenge.status == ChallengeStatus::Accepted @ PvpError::ChallengeNotAccepted,
+        constraint = settlement.creator_mutr_account.owner == challenge.creator @ PvpError::InvalidChallenge,
+        constraint = settlement.acceptor_mutr_account.owner == challenge.acceptor @ PvpError::InvalidChallenge,
+        close = creator
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub settlement: Settlement<'info>,
+
+    /// CHECK: receives the challenge account's rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum PvpError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("Wager below the minimum")]
+    WagerTooSmall,
+    #[msg("Randomness-decided challenges are not enabled")]
+    RandomnessDisabled,
+    #[msg("Challenge is not open")]
+    ChallengeNotOpen,
+    #[msg("Challenge has not been accepted")]
+    ChallengeNotAccepted,
+    #[msg("Cannot accept your own challenge")]
+    CannotAcceptOwnChallenge,
+    #[msg("Challenge is reserved for another opponent")]
+    NotTheOpponent,
+    #[msg("Challenge is decided another way")]
+    WrongResolutionMode,
+    #[msg("Accounts do not match the challenge")]
+    InvalidChallenge,
+    #[msg("Challenge has expired")]
+    ChallengeExpired,
+    #[msg("Challenge has not expired")]
+    ChallengeNotExpired,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
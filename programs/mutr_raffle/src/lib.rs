@@ -0,0 +1,1007 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+
+declare_id!("RaffRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Basis-point denominator (10_000 = 1x)
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest fee the authority may take from ticket revenue (20%)
+pub const MAX_FEE_BPS: u16 = 2_000;
+
+/// Raffles for token and NFT prizes.
+///
+/// The authority, or a creator it whitelisted, escrows a prize (any SPL token; an NFT is
+/// an amount of 1) and sells tickets in MUTR until the end slot or until sold out. Anyone
+/// may then request the draw with a fresh randomness request, and the reveal picks the
+/// winning ticket. At the draw the ticket revenue is released: `fee_bps` of it goes to
+/// the CLR treasury, the rest to the creator. The winner claims the prize afterwards.
+///
+/// Selling -> Drawing -> Drawn -> Claimed. A raffle that sold no tickets can be
+/// cancelled by its creator, which returns the prize.
+#[program]
+pub mod mutr_raffle {
+    use super::*;
+
+    /// One-time initializer for the raffle program. The `game_authority` PDA must then be
+    /// registered as a game in the CLR, which numbers the raffle's events.
+    pub fn initialize_raffles(
+        ctx: Context<InitializeRaffles>,
+        fee_bps: u16,
+        draw_ttl_slots: u64,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            fee_bps <= MAX_FEE_BPS
+                && draw_ttl_slots > 0
+                && randomness_provider != RandomnessProvider::None,
+            RaffleError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.raffle_vault = ctx.accounts.raffle_vault.key();
+        config.fee_bps = fee_bps;
+        config.draw_ttl_slots = draw_ttl_slots;
+        config.randomness_provider = randomness_provider;
+        config.raffle_count = 0;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+        Ok(())
+    }
+
+    /// Admin: change the fee and draw retry window. Raffles keep the fee they were
+    /// created with.
+    pub fn update_raffle_config(
+        ctx: Context<UpdateRaffleConfig>,
+        fee_bps: u16,
+        draw_ttl_slots: u64,
+    ) -> Result<()> {
+        require!(
+            fee_bps <= MAX_FEE_BPS && draw_ttl_slots > 0,
+            RaffleError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps;
+        config.draw_ttl_slots = draw_ttl_slots;
+        Ok(())
+    }
+
+    /// Admin: whitelist `creator` to run raffles.
+    pub fn add_creator(ctx: Context<AddCreator>, creator: Pubkey) -> Result<()> {
+        let allowance = &mut ctx.accounts.creator_allowance;
+        allowance.creator = creator;
+        allowance.bump = ctx.bumps.creator_allowance;
+        Ok(())
+    }
+
+    /// Admin: remove a creator from the whitelist. Their open raffles run to completion.
+    pub fn remove_creator(_ctx: Context<RemoveCreator>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Escrow `prize_amount` of the prize mint and open a raffle selling up to
+    /// `max_tickets` tickets at `ticket_price` MUTR for `sale_slots`.
+    pub fn create_raffle(
+        ctx: Context<CreateRaffle>,
+        prize_amount: u64,
+        ticket_price: u64,
+        max_tickets: u32,
+        sale_slots: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let creator = ctx.accounts.creator.key();
+        require!(
+            creator == config.authority || ctx.accounts.creator_allowance.is_some(),
+            RaffleError::CreatorNotWhitelisted
+        );
+        require!(
+            prize_amount > 0 && ticket_price > 0 && max_tickets > 0 && sale_slots > 0,
+            RaffleError::InvalidRaffle
+        );
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.creator_prize_account.to_account_info(),
+            to: ctx.accounts.prize_escrow.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, prize_amount)?;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.id = config.raffle_count;
+        raffle.creator = creator;
+        raffle.prize_mint = ctx.accounts.prize_mint.key();
+        raffle.prize_amount = prize_amount;
+        raffle.ticket_price = ticket_price;
+        raffle.max_tickets = max_tickets;
+        raffle.fee_bps = config.fee_bps;
+        raffle.end_slot = Clock::get()?
+            .slot
+            .checked_add(sale_slots)
+            .ok_or(RaffleError::MathOverflow)?;
+        raffle.status = RaffleStatus::Selling;
+        raffle.bump = ctx.bumps.raffle;
+        raffle.escrow_bump = ctx.bumps.prize_escrow;
+
+        let config = &mut ctx.accounts.config;
+        config.raffle_count = config
+            .raffle_count
+            .checked_add(1)
+            .ok_or(RaffleError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Buy `count` consecutive tickets in a selling raffle.
+    pub fn buy_tickets(ctx: Context<BuyTickets>, raffle_id: u64, count: u32) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        require!(
+            raffle.status == RaffleStatus::Selling,
+            RaffleError::RaffleNotSelling
+        );
+        require!(
+            Clock::get()?.slot < raffle.end_slot,
+            RaffleError::SalesClosed
+        );
+        let ticket_count = raffle
+            .ticket_count
+            .checked_add(count)
+            .ok_or(RaffleError::MathOverflow)?;
+        require!(
+            count > 0 && ticket_count <= raffle.max_tickets,
+            RaffleError::InvalidTicketCount
+        );
+
+        let cost = raffle
+            .ticket_price
+            .checked_mul(count as u64)
+            .ok_or(RaffleError::MathOverflow)?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_mutr_account.to_account_info(),
+            to: ctx.accounts.raffle_vault.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, cost)?;
+
+        let tickets = &mut ctx.accounts.tickets;
+        tickets.owner = ctx.accounts.buyer.key();
+        tickets.raffle_id = raffle_id;
+        tickets.first_ticket = raffle.ticket_count;
+        tickets.count = count;
+        tickets.bump = ctx.bumps.tickets;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.ticket_count = ticket_count;
+        raffle.revenue = raffle
+            .revenue
+            .checked_add(cost)
+            .ok_or(RaffleError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Permissionless: close sales once the raffle has ended or sold out and commit the
+    /// draw to a fresh randomness request. A draw whose request was never revealed can be
+    /// requested again after `draw_ttl_slots`.
+    pub fn request_draw(ctx: Context<RequestDraw>) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        let slot = Clock::get()?.slot;
+        match raffle.status {
+            RaffleStatus::Selling => {
+                require!(raffle.ticket_count > 0, RaffleError::NoTicketsSold);
+                require!(
+                    slot >= raffle.end_slot || raffle.ticket_count == raffle.max_tickets,
+                    RaffleError::DrawNotDue
+                );
+            }
+            RaffleStatus::Drawing => require!(
+                slot > raffle
+                    .request_slot
+                    .saturating_add(ctx.accounts.config.draw_ttl_slots),
+                RaffleError::DrawPending
+            ),
+            _ => return err!(RaffleError::RaffleNotSelling),
+        }
+        randomness::check_fresh_request(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.status = RaffleStatus::Drawing;
+        raffle.randomness_account = ctx.accounts.randomness_account.key();
+        raffle.request_slot = slot;
+        Ok(())
+    }
+
+    /// Permissionless: pick the winning ticket from the revealed randomness and release
+    /// the ticket revenue to the CLR treasury and the creator.
+    pub fn draw(ctx: Context<Draw>) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            raffle.request_slot,
+        )?;
+
+        let hash = keccak::hashv(&[value.as_ref()]).to_bytes();
+        let mut draw_bytes = [0u8; 8];
+        draw_bytes.copy_from_slice(&hash[..8]);
+        let winning_ticket = (u64::from_le_bytes(draw_bytes) % raffle.ticket_count as u64) as u32;
+
+        let fee =
+            (raffle.revenue as u128 * raffle.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let creator_proceeds = raffle.revenue - fee;
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let raffle_vault = ctx.accounts.raffle_vault.to_account_info();
+        let game_authority = ctx.accounts.game_authority.to_account_info();
+        let authority_bump = ctx.accounts.config.authority_bump;
+        game_transfer(
+            token_program.clone(),
+            raffle_vault.clone(),
+            ctx.accounts.treasury_vault.to_account_info(),
+            game_authority.clone(),
+            authority_bump,
+            fee,
+        )?;
+        game_transfer(
+            token_program,
+            raffle_vault,
+            ctx.accounts.creator_mutr_account.to_account_info(),
+            game_authority,
+            authority_bump,
+            creator_proceeds,
+        )?;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.winning_ticket = winning_ticket;
+        raffle.status = RaffleStatus::Drawn;
+
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(RaffleDrawn {
+            event_nonce,
+            raffle_id: raffle.id,
+            ticket_count: raffle.ticket_count,
+            winning_ticket,
+            revenue: raffle.revenue,
+            fee,
+        });
+        Ok(())
+    }
+
+    /// Claim the prize with the ticket batch holding the winning ticket. The emptied
+    /// prize escrow is closed back to the creator.
+    pub fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        require!(
+            raffle.status == RaffleStatus::Drawn,
+            RaffleError::RaffleNotDrawn
+        );
+        require!(
+            ctx.accounts.tickets.holds(raffle.winning_ticket),
+            RaffleError::NotAWinner
+        );
+
+        release_prize(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.winner_prize_account,
+            &ctx.accounts.game_authority,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.accounts.config.authority_bump,
+            raffle.prize_amount,
+        )?;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.status = RaffleStatus::Claimed;
+        let event_nonce = next_event_nonce(
+            &ctx.accounts.clr_program,
+            &ctx.accounts.clr_state,
+            &ctx.accounts.clr_game_config,
+            &ctx.accounts.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(RafflePrizeClaimed {
+            event_nonce,
+            raffle_id: raffle.id,
+            winner: ctx.accounts.owner.key(),
+            prize_mint: raffle.prize_mint,
+            prize_amount: raffle.prize_amount,
+        });
+        Ok(())
+    }
+
+    /// Creator: cancel a raffle nobody bought into and take the prize back.
+    pub fn cancel_raffle(ctx: Context<CancelRaffle>) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        require!(
+            raffle.status == RaffleStatus::Selling && raffle.ticket_count == 0,
+            RaffleError::CannotCancel
+        );
+        release_prize(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.creator_prize_account,
+            &ctx.accounts.game_authority,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.accounts.config.authority_bump,
+            raffle.prize_amount,
+        )
+    }
+
+    /// Close a ticket batch once the prize has been claimed and refund its rent.
+    pub fn close_tickets(ctx: Context<CloseTickets>) -> Result<()> {
+        require!(
+            ctx.accounts.raffle.status == RaffleStatus::Claimed,
+            RaffleError::RaffleNotClaimed
+        );
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Transfer `amount` out of a game-owned vault, signed by the game authority PDA.
+fn game_transfer<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    game_authority: AccountInfo<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = Transfer {
+        from,
+        to,
+        authority: game_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+/// Send the escrowed prize to `to` and close the escrow, refunding its rent to the
+/// creator.
+fn release_prize<'info>(
+    prize_escrow: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    game_authority: &UncheckedAccount<'info>,
+    creator: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    game_transfer(
+        token_program.to_account_info(),
+        prize_escrow.to_account_info(),
+        to.to_account_info(),
+        game_authority.to_account_info(),
+        authority_bump,
+        amount,
+    )?;
+
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+    let cpi_accounts = CloseAccount {
+        account: prize_escrow.to_account_info(),
+        destination: creator.clone(),
+        authority: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::close_account(cpi_ctx)
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct RaffleConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    /// MUTR vault holding ticket revenue until the draw, owned by `game_authority`
+    pub raffle_vault: Pubkey,
+    /// Share of ticket revenue sent to the CLR treasury
+    pub fee_bps: u16,
+    /// Slots after a draw request before an unrevealed draw may be requested again
+    pub draw_ttl_slots: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub raffle_count: u64,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA that owns the vaults
+    pub authority_bump: u8,
+}
+
+impl RaffleConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 32 // raffle_vault
+        + 2  // fee_bps
+        + 8  // draw_ttl_slots
+        + 1  // randomness_provider
+        + 8  // raffle_count
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// Marks a creator the authority allows to run raffles.
+#[account]
+pub struct CreatorAllowance {
+    pub creator: Pubkey,
+    pub bump: u8,
+}
+
+impl CreatorAllowance {
+    pub const LEN: usize = 32 // creator
+        + 1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RaffleStatus {
+    Selling,
+    Drawing,
+    Drawn,
+    Claimed,
+}
+
+#[account]
+pub struct Raffle {
+    pub id: u64,
+    pub creator: Pubkey,
+    pub prize_mint: Pubkey,
+    pub prize_amount: u64,
+    pub ticket_price: u64,
+    pub max_tickets: u32,
+    pub ticket_count: u32,
+    pub revenue: u64,
+    /// Treasury fee fixed at creation
+    pub fee_bps: u16,
+    /// Sales end here, or earlier once sold out
+    pub end_slot: u64,
+    pub status: RaffleStatus,
+    pub randomness_account: Pubkey,
+    pub request_slot: u64,
+    pub winning_ticket: u32,
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+impl Raffle {
+    pub const LEN: usize = 8 // id
+        + 32 // creator
+        + 32 // prize_mint
+        + 8  // prize_amount
+        + 8  // ticket_price
+        + 4  // max_tickets
+        + 4  // ticket_count
+        + 8  // revenue
+        + 2  // fee_bps
+        + 8  // end_slot
+        + 1  // status
+        + 32 // randomness_account
+        + 8  // request_slot
+        + 4  // winning_ticket
+        + 1  // bump
+        + 1; // escrow_bump
+}
+
+/// A batch of consecutive tickets bought in one purchase.
+#[account]
+pub struct TicketBatch {
+    pub owner: Pubkey,
+    pub raffle_id: u64,
+    pub first_ticket: u32,
+    pub count: u32,
+    pub bump: u8,
+}
+
+impl TicketBatch {
+    pub const LEN: usize = 32 // owner
+        + 8  // raffle_id
+        + 4  // first_ticket
+        + 4  // count
+        + 1; // bump
+
+    pub fn holds(&self, ticket: u32) -> bool {
+        ticket >= self.first_ticket && ticket - self.first_ticket < self.count
+    }
+}
+
+#[event]
+pub struct RaffleDrawn {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub raffle_id: u64,
+    pub ticket_count: u32,
+    pub winning_ticket: u32,
+    pub revenue: u64,
+    /// Sent to the CLR treasury; the rest of the revenue went to the creator
+    pub fee: u64,
+}
+
+#[event]
+pub struct RafflePrizeClaimed {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub raffle_id: u64,
+    pub winner: Pubkey,
+    pub prize_mint: Pubkey,
+    pub prize_amount: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeRaffles<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RaffleConfig::LEN,
+        seeds = [b"raffle_config"],
+        bump
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    /// CHECK: PDA signer that owns the raffle vaults
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(address = clr_state.load()?.mutr_mint @ RaffleError::InvalidMint)]
+    pub mutr_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"raffle_vault"],
+        bump,
+        token::mint = mutr_mint,
+        token::authority = game_authority
+    )]
+    pub raffle_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRaffleConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = authority @ RaffleError::Unauthorized
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddCreator<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = authority @ RaffleError::Unauthorized
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CreatorAllowance::LEN,
+        seeds = [b"creator", creator.as_ref()],
+        bump
+    )]
+    pub creator_allowance: Account<'info, CreatorAllowance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCreator<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = authority @ RaffleError::Unauthorized
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"creator", creator_allowance.creator.as_ref()],
+        bump = creator_allowance.bump,
+        close = authority
+    )]
+    pub creator_allowance: Account<'info, CreatorAllowance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    /// Required unless the creator is the authority
+    #[account(
+        seeds = [b"creator", creator.key().as_ref()],
+        bump = creator_allowance.bump
+    )]
+    pub creator_allowance: Option<Account<'info, CreatorAllowance>>,
+
+    /// CHECK: PDA signer that owns the raffle vaults
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Raffle::LEN,
+        seeds = [b"raffle", config.raffle_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    pub prize_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"prize_escrow", config.raffle_count.to_le_bytes().as_ref()],
+        bump,
+        token::mint = prize_mint,
+        token::authority = game_authority
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator_prize_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(raffle_id: u64)]
+pub struct BuyTickets<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = raffle_vault @ RaffleError::InvalidVault
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + TicketBatch::LEN,
+        seeds = [
+            b"tickets",
+            raffle_id.to_le_bytes().as_ref(),
+            raffle.ticket_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    #[account(mut)]
+    pub raffle_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer_mutr_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestDraw<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Draw<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = clr_state @ RaffleError::InvalidClrState,
+        has_one = raffle_vault @ RaffleError::InvalidVault
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+        constraint = raffle.status == RaffleStatus::Drawing @ RaffleError::DrawNotRequested
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    /// CHECK: must be the request committed for the draw; parsed by `randomness`
+    #[account(address = raffle.randomness_account @ RaffleError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// CHECK: PDA signer that owns the raffle vaults and is registered in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(mut)]
+    pub raffle_vault: Account<'info, TokenAccount>,
+
+    /// Receives the fee
+    #[account(
+        mut,
+        address = clr_state.load()?.treasury_vault @ RaffleError::InvalidVault
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_mutr_account.owner == raffle.creator @ RaffleError::Unauthorized,
+        constraint = creator_mutr_account.mint == raffle_vault.mint @ RaffleError::InvalidMint
+    )]
+    pub creator_mutr_account: Account<'info, TokenAccount>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump,
+        has_one = clr_state @ RaffleError::InvalidClrState
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+        has_one = creator @ RaffleError::Unauthorized
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        has_one = owner @ RaffleError::Unauthorized,
+        constraint = tickets.raffle_id == raffle.id @ RaffleError::NotAWinner
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    /// CHECK: PDA signer that owns the raffle vaults and is registered in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_escrow", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.escrow_bump
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = winner_prize_account.mint == raffle.prize_mint @ RaffleError::InvalidMint
+    )]
+    pub winner_prize_account: Account<'info, TokenAccount>,
+
+    /// CHECK: receives the prize escrow's rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    pub owner: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRaffle<'info> {
+    #[account(
+        seeds = [b"raffle_config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, RaffleConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump,
+        has_one = creator @ RaffleError::Unauthorized,
+        close = creator
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    /// CHECK: PDA signer that owns the raffle vaults
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_escrow", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.escrow_bump
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_prize_account.mint == raffle.prize_mint @ RaffleError::InvalidMint
+    )]
+    pub creator_prize_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTickets<'info> {
+    #[account(
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        mut,
+        has_one = owner @ RaffleError::Unauthorized,
+        constraint = tickets.raffle_id == raffle.id @ RaffleError::InvalidRaffle,
+        close = owner
+    )]
+    pub tickets: Account<'info, TicketBatch>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[error_code]
+pub enum RaffleError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Creator is not whitelisted")]
+    CreatorNotWhitelisted,
+    #[msg("Invalid raffle")]
+    InvalidRaffle,
+    #[msg("Raffle is not selling tickets")]
+    RaffleNotSelling,
+    #[msg("Ticket sales have closed")]
+    SalesClosed,
+    #[msg("Invalid ticket count")]
+    InvalidTicketCount,
+    #[msg("No tickets were sold")]
+    NoTicketsSold,
+    #[msg("Raffle has not ended")]
+    DrawNotDue,
+    #[msg("Draw request is still pending")]
+    DrawPending,
+    #[msg("Draw has not been requested")]
+    DrawNotRequested,
+    #[msg("Raffle has not been drawn")]
+    RaffleNotDrawn,
+    #[msg("Prize has not been claimed")]
+    RaffleNotClaimed,
+    #[msg("Only a raffle without tickets can be cancelled")]
+    CannotCancel,
+    #[msg("Ticket batch does not hold the winning ticket")]
+    NotAWinner,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,771 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use mutr_clr::program::MutrClr;
+use mutr_clr::randomness::{self, RandomnessProvider};
+use mutr_clr::{GameConfig, GlobalState};
+use mutr_clr_cpi::{ClrVaults, PayPrize};
+
+declare_id!("S1otsRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+/// Number of reels
+pub const REEL_COUNT: usize = 3;
+
+/// Number of distinct symbols a reel strip can carry
+pub const MAX_SYMBOLS: usize = 8;
+
+/// Maximum number of paytable lines
+pub const MAX_PAYTABLE_ENTRIES: usize = 16;
+
+#[program]
+pub mod mutr_slots {
+    use super::*;
+
+    /// One-time initializer. The `game_authority` PDA must then be registered as a
+    /// game in the CLR, which pays every win through `send_prize`.
+    pub fn initialize_slots(
+        ctx: Context<InitializeSlots>,
+        min_bet: u64,
+        max_bet: u64,
+        spin_ttl_slots: u64,
+        randomness_provider: RandomnessProvider,
+    ) -> Result<()> {
+        require!(
+            min_bet > 0
+                && min_bet <= max_bet
+                && spin_ttl_slots > 0
+                && randomness_provider != RandomnessProvider::None,
+            SlotsError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.clr_state = ctx.accounts.clr_state.key();
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.spin_ttl_slots = spin_ttl_slots;
+        config.randomness_provider = randomness_provider;
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.game_authority;
+
+        ctx.accounts.reel_strips.bump = ctx.bumps.reel_strips;
+        ctx.accounts.paytable.bump = ctx.bumps.paytable;
+        Ok(())
+    }
+
+    /// Admin: change the bet limits and spin expiry.
+    pub fn update_slots_config(
+        ctx: Context<UpdateSlotsConfig>,
+        min_bet: u64,
+        max_bet: u64,
+        spin_ttl_slots: u64,
+    ) -> Result<()> {
+        require!(
+            min_bet > 0 && min_bet <= max_bet && spin_ttl_slots > 0,
+            SlotsError::InvalidConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.min_bet = min_bet;
+        config.max_bet = max_bet;
+        config.spin_ttl_slots = spin_ttl_slots;
+        Ok(())
+    }
+
+    /// Admin: replace the symbol weights of every reel. `weights[reel][symbol]` is the
+    /// number of stops the symbol occupies on that reel; each reel needs at least one.
+    pub fn set_reel_strips(
+        ctx: Context<SetReelStrips>,
+        weights: [[u16; MAX_SYMBOLS]; REEL_COUNT],
+    ) -> Result<()> {
+        for reel in &weights {
+            require!(
+                reel.iter().any(|&weight| weight > 0),
+                SlotsError::InvalidReelStrips
+            );
+        }
+        ctx.accounts.reel_strips.weights = weights;
+        Ok(())
+    }
+
+    /// Admin: replace the paytable.
+    pub fn set_paytable(ctx: Context<SetPaytable>, entries: Vec<PaytableEntry>) -> Result<()> {
+        require!(
+            entries.len() <= MAX_PAYTABLE_ENTRIES,
+            SlotsError::InvalidPaytable
+        );
+        for entry in &entries {
+            require!(
+                (entry.symbol as usize) < MAX_SYMBOLS
+                    && entry.count >= 1
+                    && entry.count as usize <= REEL_COUNT
+                    && entry.multiplier_bps > 0,
+                SlotsError::InvalidPaytable
+            );
+        }
+        ctx.accounts.paytable.entries = entries;
+        Ok(())
+    }
+
+    /// Wager `amount` on a spin. The wager goes straight into the CLR vault; a fresh
+    /// randomness request must accompany the spin.
+    pub fn spin(ctx: Context<Spin>, nonce: u64, amount: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            amount >= config.min_bet && amount <= config.max_bet,
+            SlotsError::BetOutOfRange
+        );
+
+        let slot = Clock::get()?.slot;
+        randomness::check_fresh_request(
+            config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            slot,
+        )?;
+
+        let clr_vault_before = ctx.accounts.clr_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_mutr_account.to_account_info(),
+            mint: ctx.accounts.mutr_mint.to_account_info(),
+            to: ctx.accounts.clr_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mutr_mint.decimals)?;
+        // Credit what reached the vault, net of any transfer fee
+        ctx.accounts.clr_vault.reload()?;
+        let amount = ctx
+            .accounts
+            .clr_vault
+            .amount
+            .checked_sub(clr_vault_before)
+            .ok_or(SlotsError::MathOverflow)?;
+        ctx.accounts.record_wager(config.authority_bump, amount)?;
+        ctx.accounts
+            .contribute_to_jackpot(config.authority_bump, amount)?;
+
+        let spin = &mut ctx.accounts.spin;
+        spin.player = ctx.accounts.player.key();
+        spin.nonce = nonce;
+        spin.amount = amount;
+        spin.randomness_account = ctx.accounts.randomness_account.key();
+        spin.placed_slot = slot;
+        spin.expiry_slot = slot
+            .checked_add(config.spin_ttl_slots)
+            .ok_or(SlotsError::MathOverflow)?;
+        spin.bump = ctx.bumps.spin;
+        Ok(())
+    }
+
+    /// Permissionless: stop the reels from the revealed randomness, pay the paytable
+    /// multiplier from the CLR and emit the full result. The spin is closed back to
+    /// the player.
+    pub fn settle_spin(ctx: Context<SettleSpin>) -> Result<()> {
+        let spin = &ctx.accounts.spin;
+        let slot = Clock::get()?.slot;
+        require!(slot <= spin.expiry_slot, SlotsError::SpinExpired);
+
+        let value = randomness::revealed_value(
+            ctx.accounts.config.randomness_provider,
+            &ctx.accounts.randomness_account,
+            spin.placed_slot,
+        )?;
+        let reels = ctx.accounts.reel_strips.stop(&value);
+        let multiplier_bps = ctx.accounts.paytable.multiplier_bps(&reels);
+        let payout = if multiplier_bps > 0 {
+            mutr_clr::bet_payout(spin.amount, multiplier_bps)?
+        } else {
+            0
+        };
+
+        let clr = &ctx.accounts.clr_accounts;
+        let event_nonce = next_event_nonce(
+            &clr.clr_program,
+            &clr.clr_state,
+            &clr.clr_game_config,
+            &clr.game_authority,
+            ctx.accounts.config.authority_bump,
+        )?;
+        emit!(SpinSettled {
+            event_nonce,
+            player: spin.player,
+            spin: spin.key(),
+            nonce: spin.nonce,
+            amount: spin.amount,
+            reels,
+            multiplier_bps,
+            payout,
+        });
+
+        if payout == 0 {
+            return Ok(());
+        }
+        send_from_clr(
+            &ctx.accounts.clr_accounts,
+            ctx.accounts.config.authority_bump,
+            payout,
+        )
+    }
+
+    /// Permissionless: close a spin left unsettled past its expiry slot. The wager is
+    /// forfeited to the CLR: anyone can settle a spin once its value is revealed, so
+    /// only a player holding back a losing draw lets it expire.
+    pub fn close_expired_spin(ctx: Context<CloseExpiredSpin>) -> Result<()> {
+        require!(
+            Clock::get()?.slot > ctx.accounts.spin.expiry_slot,
+            SlotsError::SpinNotExpired
+        );
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Pay `amount` from the CLR vault to the player, streamed when it is over the CLR's
+/// vesting threshold (see `mutr_clr_cpi::PayPrize`).
+fn send_from_clr(accounts: &ClrPrize, authority_bump: u8, amount: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let vaults = ClrVaults {
+        mutr_mint: accounts.mutr_mint.key(),
+        clr_vault: accounts.clr_vault.key(),
+        token_program: accounts.token_program.key(),
+    };
+    PayPrize {
+        game: accounts.game_authority.key(),
+        winner: accounts.player_mutr_account.owner,
+        winner_mutr_account: accounts.player_mutr_account.key(),
+        payer: accounts.payer.key(),
+        amount,
+    }
+    .invoke_signed(
+        &vaults,
+        &accounts.clr_state,
+        accounts.clr_vault.amount,
+        &accounts.to_account_infos(),
+        &[authority_seeds],
+    )
+}
+
+/// Take the next CLR event sequence number, for an event about to be emitted.
+fn next_event_nonce<'info>(
+    clr_program: &Program<'info, MutrClr>,
+    clr_state: &AccountLoader<'info, GlobalState>,
+    clr_game_config: &AccountLoader<'info, GameConfig>,
+    game_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+) -> Result<u64> {
+    let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+    let signer_seeds = &[authority_seeds];
+
+    let cpi_accounts = mutr_clr::cpi::accounts::NextEventNonce {
+        state: clr_state.to_account_info(),
+        game_config: clr_game_config.to_account_info(),
+        game: game_authority.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(clr_program.to_account_info(), cpi_accounts, signer_seeds);
+    Ok(mutr_clr::cpi::next_event_nonce(cpi_ctx)?.get())
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct SlotsConfig {
+    pub authority: Pubkey,
+    pub clr_state: Pubkey,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    /// Slots a spin may stay unsettled before it can be closed as forfeited
+    pub spin_ttl_slots: u64,
+    pub randomness_provider: RandomnessProvider,
+    pub bump: u8,
+    /// Bump of the `game_authority` PDA registered in the CLR
+    pub authority_bump: u8,
+}
+
+impl SlotsConfig {
+    pub const LEN: usize = 32 // authority
+        + 32 // clr_state
+        + 8  // min_bet
+        + 8  // max_bet
+        + 8  // spin_ttl_slots
+        + 1  // randomness_provider
+        + 1  // bump
+        + 1; // authority_bump
+}
+
+/// Weighted reel strips.
+#[account]
+pub struct ReelStrips {
+    /// `weights[reel][symbol]`: stops the symbol occupies on the reel
+    pub weights: [[u16; MAX_SYMBOLS]; REEL_COUNT],
+    pub bump: u8,
+}
+
+impl ReelStrips {
+    pub const LEN: usize = 2 * MAX_SYMBOLS * REEL_COUNT // weights
+        + 1; // bump
+
+    /// Stop every reel on a symbol drawn from its own 8 bytes of `value`, with
+    /// probability proportional to the symbol's weight.
+    pub fn stop(&self, value: &[u8; 32]) -> [u8; REEL_COUNT] {
+        let mut reels = [0u8; REEL_COUNT];
+        for (reel, weights) in self.weights.iter().enumerate() {
+            let total: u64 = weights.iter().map(|&weight| weight as u64).sum();
+            let mut draw_bytes = [0u8; 8];
+            draw_bytes.copy_from_slice(&value[reel * 8..reel * 8 + 8]);
+            let mut draw = u64::from_le_bytes(draw_bytes) % total.max(1);
+
+            for (symbol, &weight) in weights.iter().enumerate() {
+                if draw < weight as u64 {
+                    reels[reel] = symbol as u8;
+                    break;
+                }
+                draw -= weight as u64;
+            }
+        }
+        reels
+    }
+}
+
+/// Pays `multiplier_bps` when `symbol` lands on at least `count` consecutive reels
+/// from the left.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct PaytableEntry {
+    pub symbol: u8,
+    pub count: u8,
+    pub multiplier_bps: u32,
+}
+
+impl PaytableEntry {
+    pub const LEN: usize = 1 // symbol
+        + 1  // count
+        + 4; // multiplier_bps
+}
+
+#[account]
+pub struct Paytable {
+    pub entries: Vec<PaytableEntry>,
+    pub bump: u8,
+}
+
+impl Paytable {
+    pub const LEN: usize = 4 + PaytableEntry::LEN * MAX_PAYTABLE_ENTRIES // entries
+        + 1; // bump
+
+    /// Best multiplier the stopped reels pay; 0 on a losing spin.
+    pub fn multiplier_bps(&self, reels: &[u8; REEL_COUNT]) -> u32 {
+        let symbol = reels[0];
+        let run = reels.iter().take_while(|&&reel| reel == symbol).count();
+        self.entries
+            .iter()
+            .filter(|entry| entry.symbol == symbol && entry.count as usize <= run)
+            .map(|entry| entry.multiplier_bps)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A spin waiting for its randomness to be revealed.
+#[account]
+pub struct SpinState {
+    pub player: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub randomness_account: Pubkey,
+    pub placed_slot: u64,
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+impl SpinState {
+    pub const LEN: usize = 32 // player
+        + 8  // nonce
+        + 8  // amount
+        + 32 // randomness_account
+        + 8  // placed_slot
+        + 8  // expiry_slot
+        + 1; // bump
+}
+
+/// Full result of a settled spin, for the frontend.
+#[event]
+pub struct SpinSettled {
+    /// CLR event sequence number (see `mutr_clr::events`)
+    pub event_nonce: u64,
+    pub player: Pubkey,
+    pub spin: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub reels: [u8; REEL_COUNT],
+    pub multiplier_bps: u32,
+    pub payout: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeSlots<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SlotsConfig::LEN,
+        seeds = [b"slots_config"],
+        bump
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReelStrips::LEN,
+        seeds = [b"reel_strips"],
+        bump
+    )]
+    pub reel_strips: Account<'info, ReelStrips>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Paytable::LEN,
+        seeds = [b"paytable"],
+        bump
+    )]
+    pub paytable: Account<'info, Paytable>,
+
+    /// CHECK: PDA signer registered as the slots game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSlotsConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"slots_config"],
+        bump = config.bump,
+        has_one = authority @ SlotsError::Unauthorized
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetReelStrips<'info> {
+    #[account(
+        seeds = [b"slots_config"],
+        bump = config.bump,
+        has_one = authority @ SlotsError::Unauthorized
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"reel_strips"],
+        bump = reel_strips.bump
+    )]
+    pub reel_strips: Account<'info, ReelStrips>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaytable<'info> {
+    #[account(
+        seeds = [b"slots_config"],
+        bump = config.bump,
+        has_one = authority @ SlotsError::Unauthorized
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"paytable"],
+        bump = paytable.bump
+    )]
+    pub paytable: Account<'info, Paytable>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct Spin<'info> {
+    #[account(
+        seeds = [b"slots_config"],
+        bump = config.bump,
+        has_one = clr_state @ SlotsError::InvalidClrState
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    /// CHECK: PDA signer registered as the slots game in the CLR
+    #[account(
+        seeds = [b"game_authority"],
+        bump = config.authority_bump
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// Spins are only taken while the game is enabled in the CLR
+    #[account(
+        mut,
+        seeds = [b"game", game_authority.key().as_ref()],
+        bump = clr_game_config.load()?.bump,
+        seeds::program = mutr_clr::ID,
+        constraint = clr_game_config.load()?.is_enabled() @ SlotsError::GameDisabled
+    )]
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    #[account(
+        mut,
+        address = clr_state.load()?.clr_vault @ SlotsError::InvalidVault
+    )]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + SpinState::LEN,
+        seeds = [b"spin", player.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub spin: Account<'info, SpinState>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: randomness request from the configured provider; parsed by `randomness`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR position 0, holding their responsible-gaming limits;
+    /// checked by the CLR
+    #[account(mut)]
+    pub clr_player_state: UncheckedAccount<'info>,
+
+    /// CHECK: the player's CLR `UserStats`; required by the CLR while its daily wager
+    /// limit or the game's player rate limit is on
+    #[account(mut)]
+    pub clr_user_stats: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR progressive jackpot; required with its vault once the jackpot exists
+    #[account(mut)]
+    pub clr_jackpot: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CLR jackpot vault; checked by the CLR
+    #[account(mut)]
+    pub clr_jackpot_vault: Option<UncheckedAccount<'info>>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Spin<'info> {
+    /// Feed the CLR progressive jackpot its share of the wager.
+    /// The jackpot accounts are required once the jackpot exists.
+    fn contribute_to_jackpot(&self, authority_bump: u8, wager: u64) -> Result<()> {
+        let (Some(jackpot), Some(jackpot_vault)) = (&self.clr_jackpot, &self.clr_jackpot_vault)
+        else {
+            require!(
+                self.clr_state.load()?.jackpot_enabled == 0,
+                SlotsError::MissingJackpotAccounts
+            );
+            return Ok(());
+        };
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::ContributeJackpot {
+            state: self.clr_state.to_account_info(),
+            mutr_mint: self.mutr_mint.to_account_info(),
+            jackpot: jackpot.to_account_info(),
+            clr_vault: self.clr_vault.to_account_info(),
+            jackpot_vault: jackpot_vault.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game: self.game_authority.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::contribute_jackpot(cpi_ctx, wager)?;
+        Ok(())
+    }
+
+    /// Run the wager through the CLR's player checks.
+    fn record_wager(&self, authority_bump: u8, amount: u64) -> Result<()> {
+        let authority_seeds: &[&[u8]] = &[b"game_authority", &[authority_bump]];
+        let signer_seeds = &[authority_seeds];
+
+        let cpi_accounts = mutr_clr::cpi::accounts::RecordGameWager {
+            state: self.clr_state.to_account_info(),
+            game_config: self.clr_game_config.to_account_info(),
+            game_ledger: self.clr_game_ledger.to_account_info(),
+            player_state: self.clr_player_state.to_account_info(),
+            user_stats: self
+                .clr_user_stats
+                .as_ref()
+                .map(|stats| stats.to_account_info()),
+            player: self.player.to_account_info(),
+            game: self.game_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.clr_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        mutr_clr::cpi::record_game_wager(cpi_ctx, amount)
+    }
+}
+
+/// CLR accounts for paying a player through `send_prize` or `send_vested_prize`.
+#[derive(Accounts)]
+pub struct ClrPrize<'info> {
+    /// CHECK: PDA signer registered as the slots game in the CLR
+    #[account(seeds = [b"game_authority"], bump)]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_state: AccountLoader<'info, GlobalState>,
+
+    /// CHECK: CLR protocol stats; checked by the CLR
+    #[account(mut)]
+    pub clr_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub clr_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mutr_mint: InterfaceAccount<'info, Mint>,
+
+    pub clr_game_config: AccountLoader<'info, GameConfig>,
+
+    /// CHECK: the game's CLR `GameLedger`; checked by the CLR
+    #[account(mut)]
+    pub clr_game_ledger: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player_mutr_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the player's CLR `PrizeVesting`; created and checked by the CLR when a prize
+    /// is over its vesting threshold
+    #[account(mut)]
+    pub prize_vesting: UncheckedAccount<'info>,
+
+    /// Funds the player's `PrizeVesting` when a prize opens it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub clr_program: Program<'info, MutrClr>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleSpin<'info> {
+    #[account(
+        seeds = [b"slots_config"],
+        bump = config.bump,
+        constraint = config.clr_state == clr_accounts.clr_state.key() @ SlotsError::InvalidClrState
+    )]
+    pub config: Account<'info, SlotsConfig>,
+
+    #[account(
+        seeds = [b"reel_strips"],
+        bump = reel_strips.bump
+    )]
+    pub reel_strips: Account<'info, ReelStrips>,
+
+    #[account(
+        seeds = [b"paytable"],
+        bump = paytable.bump
+    )]
+    pub paytable: Account<'info, Paytable>,
+
+    #[account(
+        mut,
+        has_one = player @ SlotsError::InvalidSpin,
+        constraint = clr_accounts.player_mutr_account.owner == spin.player @ SlotsError::InvalidSpin,
+        close = player
+    )]
+    pub spin: Account<'info, SpinState>,
+
+    /// CHECK: must be the request committed at spin time; parsed by `randomness`
+    #[account(address = spin.randomness_account @ SlotsError::InvalidRandomness)]
+    pub randomness_account: UncheckedAccount<'info>,
+
+    pub clr_accounts: ClrPrize<'info>,
+
+    /// CHECK: receives the spin account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredSpin<'info> {
+    #[account(
+        mut,
+        has_one = player @ SlotsError::InvalidSpin,
+        close = player
+    )]
+    pub spin: Account<'info, SpinState>,
+
+    /// CHECK: receives the spin account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum SlotsError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+    #[msg("Invalid CLR state")]
+    InvalidClrState,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Game is disabled in the CLR")]
+    GameDisabled,
+    #[msg("Bet amount outside the game limits")]
+    BetOutOfRange,
+    #[msg("Every reel needs at least one weighted symbol")]
+    InvalidReelStrips,
+    #[msg("Invalid paytable")]
+    InvalidPaytable,
+    #[msg("Spin does not belong to the player")]
+    InvalidSpin,
+    #[msg("Spin has expired")]
+    SpinExpired,
+    #[msg("Spin has not expired")]
+    SpinNotExpired,
+    #[msg("Invalid randomness account")]
+    InvalidRandomness,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Jackpot accounts are required once the jackpot exists")]
+    MissingJackpotAccounts,
+}
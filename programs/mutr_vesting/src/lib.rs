@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("VestRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR");
+
+#[program]
+pub mod mutr_vesting {
+    use super::*;
+
+    /// Create a vesting schedule for `beneficiary` and fund it with `total_amount` MUTR.
+    /// Nothing vests before `cliff_ts`; after that the schedule vests linearly from
+    /// `start_ts` to `end_ts`. Use `cliff_ts == start_ts` for a plain linear schedule
+    /// and `cliff_ts == end_ts` for a pure cliff.
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        schedule_id: u64,
+        beneficiary: Pubkey,
+        total_amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        require!(total_amount > 0, VestingError::InvalidAmount);
+        require!(
+            start_ts <= cliff_ts && cliff_ts <= end_ts && start_ts < end_ts,
+            VestingError::InvalidSchedule
+        );
+        require_keys_neq!(beneficiary, Pubkey::default(), VestingError::InvalidSchedule);
+
+        // Fund the schedule vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder_mutr_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, total_amount)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.authority = ctx.accounts.authority.key();
+        vesting.beneficiary = beneficiary;
+        vesting.mint = ctx.accounts.mint.key();
+        vesting.vault = ctx.accounts.vault.key();
+        vesting.schedule_id = schedule_id;
+        vesting.total_amount = total_amount;
+        vesting.claimed_amount = 0;
+        vesting.start_ts = start_ts;
+        vesting.cliff_ts = cliff_ts;
+        vesting.end_ts = end_ts;
+        vesting.bump = ctx.bumps.vesting;
+        Ok(())
+    }
+
+    /// Claim everything vested so far.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        let vested = vested_amount(vesting, Clock::get()?.unix_timestamp)?;
+        let claimable = vested
+            .checked_sub(vesting.claimed_amount)
+            .ok_or(VestingError::MathOverflow)?;
+        require!(claimable > 0, VestingError::NothingToClaim);
+
+        let schedule_id = vesting.schedule_id.to_le_bytes();
+        let vesting_seeds: &[&[u8]] = &[
+            b"vesting",
+            vesting.beneficiary.as_ref(),
+            &schedule_id,
+            &[vesting.bump],
+        ];
+        let signer_seeds = &[vesting_seeds];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vesting.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, claimable)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.claimed_amount = vesting
+            .claimed_amount
+            .checked_add(claimable)
+            .ok_or(VestingError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper functions
+// -----------------------------------------------------------------------------
+
+/// Total amount vested at `now` (including what was already claimed).
+fn vested_amount(vesting: &VestingAccount, now: i64) -> Result<u64> {
+    if now < vesting.cliff_ts {
+        return Ok(0);
+    }
+    if now >= vesting.end_ts {
+        return Ok(vesting.total_amount);
+    }
+    let elapsed = (now - vesting.start_ts) as u128;
+    let duration = (vesting.end_ts - vesting.start_ts) as u128;
+    let vested = (vesting.total_amount as u128)
+        .checked_mul(elapsed)
+        .ok_or(VestingError::MathOverflow)?
+        .checked_div(duration)
+        .ok_or(VestingError::MathOverflow)?;
+    Ok(vested as u64)
+}
+
+// -----------------------------------------------------------------------------
+// Data structures & error types
+// -----------------------------------------------------------------------------
+
+#[account]
+pub struct VestingAccount {
+    pub authority: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub schedule_id: u64,
+
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    pub const LEN: usize = 32 // authority
+        + 32 // beneficiary
+        + 32 // mint
+        + 32 // vault
+        + 8  // schedule_id
+        + 8  // total_amount
+        + 8  // claimed_amount
+        + 8  // start_ts
+        + 8  // cliff_ts
+        + 8  // end_ts
+        + 1; // bump
+}
+
+// -----------------------------------------------------------------------------
+// Accounts
+// -----------------------------------------------------------------------------
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64, beneficiary: Pubkey)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingAccount::LEN,
+        seeds = [b"vesting", beneficiary.as_ref(), &schedule_id.to_le_bytes()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    /// MUTR mint
+    pub mint: Account<'info, Mint>,
+
+    /// Schedule vault, owned by the `vesting` PDA
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_vault", vesting.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_mutr_account.mint == mint.key() @ VestingError::InvalidMint,
+        constraint = funder_mutr_account.owner == authority.key() @ VestingError::Unauthorized
+    )]
+    pub funder_mutr_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", beneficiary.key().as_ref(), &vesting.schedule_id.to_le_bytes()],
+        bump = vesting.bump,
+        has_one = beneficiary @ VestingError::Unauthorized,
+        has_one = vault @ VestingError::InvalidVault
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == vesting.mint @ VestingError::InvalidMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub beneficiary: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Invalid vesting vault")]
+    InvalidVault,
+    #[msg("Invalid vesting schedule")]
+    InvalidSchedule,
+    #[msg("Nothing vested to claim")]
+    NothingToClaim,
+}